@@ -0,0 +1,900 @@
+//! Analytics derived from time series, computed by the API or locally
+use crate::calendar;
+use crate::parsing::ParseWarning;
+use crate::returns::ReturnSeries;
+use crate::time_series::{OutputSize, TimeSeries};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime};
+use chrono_tz::Tz;
+use chrono_tz::US::Eastern;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Number of trading days in a year, used to annualize daily volatilities.
+const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+
+/// Represents a trailing window of time analytics are computed over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Window {
+    /// Last 30 days.
+    ThirtyDays,
+    /// Last 90 days.
+    NinetyDays,
+    /// Last year.
+    OneYear,
+}
+
+impl Window {
+    /// Length of the window, in calendar days.
+    pub fn days(self) -> i64 {
+        match self {
+            Window::ThirtyDays => 30,
+            Window::NinetyDays => 90,
+            Window::OneYear => 365,
+        }
+    }
+
+    /// Amount of daily entries to retrieve to compute analytics over the window locally.
+    pub fn output_size(self) -> OutputSize {
+        match self {
+            Window::ThirtyDays | Window::NinetyDays => OutputSize::Compact,
+            Window::OneYear => OutputSize::Full,
+        }
+    }
+
+    /// Range of the window in `ANALYTICS_FIXED_WINDOW` requests.
+    pub fn range(self) -> &'static str {
+        match self {
+            Window::ThirtyDays => "30day",
+            Window::NinetyDays => "90day",
+            Window::OneYear => "1year",
+        }
+    }
+}
+
+/// Represents the range of time `ANALYTICS_FIXED_WINDOW` computes analytics over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Range {
+    /// Trailing window.
+    Window(Window),
+    /// Trailing number of days.
+    Days(u32),
+    /// Trailing number of months.
+    Months(u32),
+    /// Trailing number of years.
+    Years(u32),
+    /// Whole history of the symbols.
+    Full,
+}
+
+impl Range {
+    /// Value of the `RANGE` parameter.
+    pub fn to_param(self) -> String {
+        match self {
+            Range::Window(window) => window.range().to_string(),
+            Range::Days(days) => format!("{}day", days),
+            Range::Months(months) => format!("{}month", months),
+            Range::Years(years) => format!("{}year", years),
+            Range::Full => "full".to_string(),
+        }
+    }
+}
+
+/// Represents the value of each entry returns are computed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PriceField {
+    /// Open value.
+    Open,
+    /// High value.
+    High,
+    /// Low value.
+    Low,
+    /// Close value. Default.
+    #[default]
+    Close,
+}
+
+impl PriceField {
+    /// Value of the `OHLC` parameter.
+    pub fn to_string(self) -> &'static str {
+        match self {
+            PriceField::Open => "open",
+            PriceField::High => "high",
+            PriceField::Low => "low",
+            PriceField::Close => "close",
+        }
+    }
+}
+
+/// Represents a calculation over the returns of the symbols of an `ANALYTICS_FIXED_WINDOW`
+/// request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Calculation {
+    /// Minimum return.
+    Min,
+    /// Maximum return.
+    Max,
+    /// Mean return.
+    Mean,
+    /// Median return.
+    Median,
+    /// Return over the whole range.
+    CumulativeReturn,
+    /// Variance of the returns.
+    Variance,
+    /// Annualized variance of the returns.
+    AnnualizedVariance,
+    /// Standard deviation of the returns.
+    StdDev,
+    /// Annualized standard deviation of the returns.
+    AnnualizedStdDev,
+    /// Largest drop from a peak.
+    MaxDrawdown,
+    /// Correlation of the returns of each pair of symbols.
+    Correlation,
+    /// Covariance of the returns of each pair of symbols.
+    Covariance,
+    /// Annualized covariance of the returns of each pair of symbols.
+    AnnualizedCovariance,
+}
+
+impl Calculation {
+    /// Name of the calculation in the `CALCULATIONS` parameter.
+    pub fn to_string(self) -> &'static str {
+        use self::Calculation::*;
+        match self {
+            Min => "MIN",
+            Max => "MAX",
+            Mean => "MEAN",
+            Median => "MEDIAN",
+            CumulativeReturn => "CUMULATIVE_RETURN",
+            Variance => "VARIANCE",
+            AnnualizedVariance => "VARIANCE(annualized=True)",
+            StdDev => "STDDEV",
+            AnnualizedStdDev => "STDDEV(annualized=True)",
+            MaxDrawdown => "MAX_DRAWDOWN",
+            Correlation => "CORRELATION",
+            Covariance => "COVARIANCE",
+            AnnualizedCovariance => "COVARIANCE(annualized=True)",
+        }
+    }
+
+    /// Whether the calculation yields a value for each pair of symbols rather than for each
+    /// symbol.
+    pub fn is_pairwise(self) -> bool {
+        use self::Calculation::*;
+        matches!(self, Correlation | Covariance | AnnualizedCovariance)
+    }
+
+    /// Key of the calculation in [`FixedWindowAnalytics`].
+    fn key(self) -> String {
+        self.to_string().to_ascii_uppercase()
+    }
+}
+
+/// Represents a calculation over each pair of a set of symbols.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolMatrix {
+    /// Symbols, indexing both the rows and the columns of the matrix.
+    pub symbols: Vec<String>,
+    /// Value for the symbols of each row and column, symmetric.
+    pub values: Vec<Vec<f64>>,
+}
+
+impl SymbolMatrix {
+    /// Value for the pair of symbols `a` and `b`, if both are part of the matrix.
+    pub fn get(&self, a: &str, b: &str) -> Option<f64> {
+        let index = |symbol: &str| {
+            self.symbols
+                .iter()
+                .position(|s| s.eq_ignore_ascii_case(symbol))
+        };
+        Some(self.values[index(a)?][index(b)?])
+    }
+}
+
+/// Represents the analytics computed by `ANALYTICS_FIXED_WINDOW` over the returns of a set of
+/// symbols.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedWindowAnalytics {
+    /// Symbols the analytics refer to.
+    pub symbols: Vec<String>,
+    /// First date of the range the analytics were computed over.
+    pub start: NaiveDate,
+    /// Last date of the range the analytics were computed over.
+    pub end: NaiveDate,
+    /// Calculations yielding a value for each symbol, keyed by their uppercase name as returned
+    /// by the API, e.g. `STDDEV(ANNUALIZED=TRUE)`, then by symbol.
+    pub metrics: BTreeMap<String, BTreeMap<String, f64>>,
+    /// Calculations yielding a value for each pair of symbols, keyed by their uppercase name.
+    pub matrices: BTreeMap<String, SymbolMatrix>,
+    /// Malformed calculations skipped while leniently parsing the analytics.
+    pub warnings: Vec<ParseWarning>,
+}
+
+impl FixedWindowAnalytics {
+    /// Value of `calculation` for `symbol`, if it was computed.
+    pub fn metric(&self, calculation: Calculation, symbol: &str) -> Option<f64> {
+        self.metrics
+            .get(&calculation.key())?
+            .iter()
+            .find(|(s, _)| s.eq_ignore_ascii_case(symbol))
+            .map(|(_, value)| *value)
+    }
+
+    /// Value of the pairwise `calculation` for the symbols `a` and `b`, if it was computed.
+    pub fn pairwise(&self, calculation: Calculation, a: &str, b: &str) -> Option<f64> {
+        self.matrices.get(&calculation.key())?.get(a, b)
+    }
+}
+
+/// Represents the value of a calculation over the window ending at a date.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RollingValue {
+    /// Date of the last entry of the window, at midnight for daily and coarser intervals.
+    pub date: NaiveDateTime,
+    /// Value.
+    pub value: f64,
+}
+
+/// Represents the analytics computed by `ANALYTICS_SLIDING_WINDOW` over windows sliding across
+/// the returns of a set of symbols.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlidingWindowAnalytics {
+    /// Symbols the analytics refer to.
+    pub symbols: Vec<String>,
+    /// Number of entries in each window.
+    pub window_size: u32,
+    /// First date of the range the analytics were computed over.
+    pub start: NaiveDate,
+    /// Last date of the range the analytics were computed over.
+    pub end: NaiveDate,
+    /// Calculations yielding a value for each symbol, keyed by their uppercase name then by
+    /// symbol, with values sorted by ascending dates.
+    pub series: BTreeMap<String, BTreeMap<String, Vec<RollingValue>>>,
+    /// Calculations yielding a value for each pair of symbols, keyed by their uppercase name
+    /// then by pair of symbols as returned by the API, with values sorted by ascending dates.
+    pub pairs: BTreeMap<String, BTreeMap<(String, String), Vec<RollingValue>>>,
+    /// Malformed calculations skipped while leniently parsing the analytics.
+    pub warnings: Vec<ParseWarning>,
+}
+
+impl SlidingWindowAnalytics {
+    /// Values of `calculation` for `symbol`, if it was computed.
+    pub fn metric(&self, calculation: Calculation, symbol: &str) -> Option<&[RollingValue]> {
+        self.series
+            .get(&calculation.key())?
+            .iter()
+            .find(|(s, _)| s.eq_ignore_ascii_case(symbol))
+            .map(|(_, values)| values.as_slice())
+    }
+
+    /// Values of the pairwise `calculation` for the symbols `a` and `b`, in either order, if it
+    /// was computed.
+    pub fn pairwise(&self, calculation: Calculation, a: &str, b: &str) -> Option<&[RollingValue]> {
+        self.pairs
+            .get(&calculation.key())?
+            .iter()
+            .find(|((x, y), _)| {
+                (x.eq_ignore_ascii_case(a) && y.eq_ignore_ascii_case(b))
+                    || (x.eq_ignore_ascii_case(b) && y.eq_ignore_ascii_case(a))
+            })
+            .map(|(_, values)| values.as_slice())
+    }
+}
+
+/// Controls where a volatility is computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VolatilityMode {
+    /// Compute the volatility locally when the daily time series it's derived from is cached,
+    /// which doesn't use any of the API quota, or when the tier or the remaining quota of the
+    /// client doesn't allow requesting it from the API right away. Request it from the API
+    /// otherwise, falling back to computing it locally if the API fails to. Default.
+    #[default]
+    Auto,
+    /// Request the volatility from the `ANALYTICS_FIXED_WINDOW` function.
+    Server,
+    /// Compute the volatility locally from the daily adjusted time series.
+    Local,
+}
+
+/// Represents where a volatility was computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// Computed by the API.
+    Server,
+    /// Computed locally from the daily adjusted time series.
+    Local,
+}
+
+/// Represents the annualized realized volatility of a symbol over a trailing window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Volatility {
+    /// Symbol the volatility refers to.
+    pub symbol: String,
+    /// Window the volatility was computed over.
+    pub window: Window,
+    /// Annualized standard deviation of the daily returns, as a fraction.
+    pub annualized: f64,
+    /// Where the volatility was computed.
+    pub source: Source,
+}
+
+/// Compute the annualized realized volatility of the daily total returns of `time_series` over
+/// the trailing `window`, ending with its latest entry.
+///
+/// Returns `None` when the window holds less than two returns.
+pub fn realized_volatility(time_series: &TimeSeries, window: Window) -> Option<f64> {
+    let last = time_series.entries.last()?.date;
+    let start = last - Duration::days(window.days());
+    let returns = ReturnSeries::total_return(time_series);
+    let returns: Vec<f64> = returns
+        .entries
+        .iter()
+        .skip(1)
+        .filter(|e| e.date > start)
+        .map(|e| e.period_return)
+        .collect();
+    if returns.len() < 2 {
+        return None;
+    }
+    let n = returns.len() as f64;
+    let mean = returns.iter().sum::<f64>() / n;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    Some((variance * TRADING_DAYS_PER_YEAR).sqrt())
+}
+
+/// Represents the volume weighted average price of a symbol at a given time of a trading
+/// session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VwapEntry {
+    /// Date.
+    pub date: DateTime<Tz>,
+    /// Volume weighted average of the typical prices (average of the high, low and close
+    /// values) since the start of the session.
+    pub vwap: f64,
+}
+
+/// Controls which hours of a trading day a session covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SessionHours {
+    /// Regular hours only, from 9:30 to 16:00.
+    Regular,
+    /// Regular hours along with the pre-market and after hours, from 4:00 to 20:00. Default.
+    #[default]
+    Extended,
+}
+
+impl SessionHours {
+    /// Whether entries made during `session` are covered.
+    fn covers(self, session: calendar::Session) -> bool {
+        match self {
+            SessionHours::Regular => session == calendar::Session::Regular,
+            SessionHours::Extended => session != calendar::Session::Closed,
+        }
+    }
+}
+
+/// Compute the volume weighted average price at each entry of an intraday `time_series`,
+/// resetting at the start of each trading session.
+///
+/// A session covers the given `hours` of a trading day, in US/Eastern time (see
+/// [`calendar::session`]). Entries outside of a session or made before any volume was traded in
+/// their session are skipped.
+pub fn session_vwap(time_series: &TimeSeries, hours: SessionHours) -> Vec<VwapEntry> {
+    let mut entries = vec![];
+    let mut session = None;
+    let (mut value, mut volume) = (0.0, 0.0);
+    for entry in &time_series.entries {
+        if !hours.covers(calendar::session(&entry.date)) {
+            continue;
+        }
+        let day = entry.date.with_timezone(&Eastern).date_naive();
+        if session != Some(day) {
+            session = Some(day);
+            value = 0.0;
+            volume = 0.0;
+        }
+        value += entry.typical_price() * entry.volume as f64;
+        volume += entry.volume as f64;
+        if volume > 0.0 {
+            entries.push(VwapEntry {
+                date: entry.date,
+                vwap: value / volume,
+            });
+        }
+    }
+    entries
+}
+
+pub mod parser {
+    use super::*;
+    use crate::deserialize::DATE_FORMAT;
+    use crate::error::Error;
+    use crate::parsing::Parser;
+    use serde_json::Value;
+    use std::io::Read;
+
+    #[derive(Debug, Deserialize)]
+    struct FixedWindowHelper {
+        #[serde(rename = "Error Message")]
+        error: Option<String>,
+        meta_data: Option<MetaDataHelper>,
+        payload: Option<PayloadHelper>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct MetaDataHelper {
+        symbols: String,
+        window_size: Option<u32>,
+        min_dt: String,
+        max_dt: String,
+        #[allow(dead_code)]
+        ohlc: Option<String>,
+        #[allow(dead_code)]
+        interval: Option<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct PayloadHelper {
+        #[serde(rename = "RETURNS_CALCULATIONS")]
+        calculations: BTreeMap<String, Value>,
+    }
+
+    /// Represents the value of a single calculation.
+    enum Values {
+        Metric(BTreeMap<String, f64>),
+        Matrix(SymbolMatrix),
+    }
+
+    /// Parse a date of the metadata, which includes a time for intraday intervals.
+    fn parse_date(value: &str) -> Result<NaiveDate, Error> {
+        let date = value.split(' ').next().unwrap_or_default();
+        Ok(NaiveDate::parse_from_str(date, DATE_FORMAT)?)
+    }
+
+    /// Parse the date of a rolling value, which includes a time for intraday intervals.
+    fn parse_date_time(value: &str) -> Result<NaiveDateTime, Error> {
+        match NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S") {
+            Ok(date) => Ok(date),
+            Err(_) => Ok(NaiveDate::parse_from_str(value, DATE_FORMAT)?
+                .and_hms_opt(0, 0, 0)
+                .unwrap_or_default()),
+        }
+    }
+
+    /// Parse the values of a calculation by date.
+    fn parse_rolling(values: &serde_json::Map<String, Value>) -> Result<Vec<RollingValue>, Error> {
+        let mut values = values
+            .iter()
+            .map(|(date, value)| {
+                Ok(RollingValue {
+                    date: parse_date_time(date)?,
+                    value: value
+                        .as_f64()
+                        .ok_or_else(|| Error::ParsingError(format!("invalid value {}", value)))?,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        values.sort_by_key(|v| v.date);
+        Ok(values)
+    }
+
+    /// Represents the values of a single calculation over sliding windows.
+    enum RollingValues {
+        Series(BTreeMap<String, Vec<RollingValue>>),
+        Pairs(BTreeMap<(String, String), Vec<RollingValue>>),
+    }
+
+    /// Parse the values of a calculation over sliding windows, nested under a `RUNNING_` key
+    /// then by symbol, and for pairwise calculations by second symbol, before being keyed by
+    /// date.
+    fn parse_rolling_values(value: &Value) -> Result<RollingValues, Error> {
+        let object = |value: &Value| {
+            value
+                .as_object()
+                .cloned()
+                .ok_or_else(|| Error::ParsingError(format!("invalid calculation {}", value)))
+        };
+        let mut symbols = object(value)?;
+        if symbols.len() == 1 && symbols.keys().all(|k| k.starts_with("RUNNING_")) {
+            let (_, running) = symbols.into_iter().next().unwrap_or_default();
+            symbols = object(&running)?;
+        }
+        let pairwise = symbols
+            .values()
+            .filter_map(Value::as_object)
+            .flat_map(|values| values.keys())
+            .any(|key| parse_date_time(key).is_err());
+        if !pairwise {
+            let mut series = BTreeMap::new();
+            for (symbol, values) in &symbols {
+                series.insert(symbol.clone(), parse_rolling(&object(values)?)?);
+            }
+            return Ok(RollingValues::Series(series));
+        }
+        let mut pairs = BTreeMap::new();
+        for (a, others) in &symbols {
+            for (b, values) in object(others)? {
+                pairs.insert((a.clone(), b), parse_rolling(&object(&values)?)?);
+            }
+        }
+        Ok(RollingValues::Pairs(pairs))
+    }
+
+    /// Parse the values of the calculation `name`.
+    ///
+    /// Pairwise calculations hold the lower triangle of their matrix under the lowercase name of
+    /// the calculation, indexed by `index`. Calculations returning several values per symbol,
+    /// like `MAX_DRAWDOWN`, hold the main one under their lowercase name.
+    fn parse_values(name: &str, value: &Value) -> Result<Values, Error> {
+        let field = name
+            .split('(')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let number = |value: &Value| {
+            value
+                .as_f64()
+                .ok_or_else(|| Error::ParsingError(format!("invalid value {}", value)))
+        };
+        let object = value
+            .as_object()
+            .ok_or_else(|| Error::ParsingError(format!("invalid calculation {}", value)))?;
+        if let Some(index) = object.get("index") {
+            let symbols: Vec<String> = serde_json::from_value(index.clone())?;
+            let rows: Vec<Vec<f64>> = object
+                .get(&field)
+                .ok_or_else(|| Error::ParsingError(format!("missing {} matrix", field)))
+                .and_then(|rows| Ok(serde_json::from_value(rows.clone())?))?;
+            if rows.len() != symbols.len() || rows.iter().enumerate().any(|(i, r)| r.len() != i + 1)
+            {
+                return Err(Error::ParsingError(format!("malformed {} matrix", field)));
+            }
+            let values = (0..symbols.len())
+                .map(|i| {
+                    (0..symbols.len())
+                        .map(|j| if j <= i { rows[i][j] } else { rows[j][i] })
+                        .collect()
+                })
+                .collect();
+            return Ok(Values::Matrix(SymbolMatrix { symbols, values }));
+        }
+        let mut metric = BTreeMap::new();
+        for (symbol, value) in object {
+            let value = match value {
+                Value::Object(fields) => fields
+                    .get(&field)
+                    .ok_or_else(|| Error::ParsingError(format!("missing {} of {}", field, symbol)))
+                    .and_then(number)?,
+                value => number(value)?,
+            };
+            metric.insert(symbol.clone(), value);
+        }
+        Ok(Values::Metric(metric))
+    }
+
+    pub fn parse_fixed_window(
+        mut parser: Parser,
+        reader: impl Read,
+    ) -> Result<FixedWindowAnalytics, Error> {
+        let helper: FixedWindowHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let meta_data = helper
+            .meta_data
+            .ok_or_else(|| Error::ParsingError("missing meta data".into()))?;
+        let payload = helper
+            .payload
+            .ok_or_else(|| Error::ParsingError("missing payload".into()))?;
+
+        let mut metrics = BTreeMap::new();
+        let mut matrices = BTreeMap::new();
+        for (name, value) in &payload.calculations {
+            let path = format!("payload.RETURNS_CALCULATIONS.{}", name);
+            let key = name.to_ascii_uppercase();
+            match parser.entry(&path, parse_values(name, value))? {
+                Some(Values::Metric(metric)) => {
+                    metrics.insert(key, metric);
+                }
+                Some(Values::Matrix(matrix)) => {
+                    matrices.insert(key, matrix);
+                }
+                None => {}
+            }
+        }
+
+        Ok(FixedWindowAnalytics {
+            symbols: meta_data
+                .symbols
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .collect(),
+            start: parse_date(&meta_data.min_dt)?,
+            end: parse_date(&meta_data.max_dt)?,
+            metrics,
+            matrices,
+            warnings: parser.finish(),
+        })
+    }
+
+    pub fn parse_sliding_window(
+        mut parser: Parser,
+        reader: impl Read,
+    ) -> Result<SlidingWindowAnalytics, Error> {
+        let helper: FixedWindowHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let meta_data = helper
+            .meta_data
+            .ok_or_else(|| Error::ParsingError("missing meta data".into()))?;
+        let window_size = meta_data
+            .window_size
+            .ok_or_else(|| Error::ParsingError("missing window size".into()))?;
+        let payload = helper
+            .payload
+            .ok_or_else(|| Error::ParsingError("missing payload".into()))?;
+
+        let mut series = BTreeMap::new();
+        let mut pairs = BTreeMap::new();
+        for (name, value) in &payload.calculations {
+            let path = format!("payload.RETURNS_CALCULATIONS.{}", name);
+            let key = name.to_ascii_uppercase();
+            match parser.entry(&path, parse_rolling_values(value))? {
+                Some(RollingValues::Series(values)) => {
+                    series.insert(key, values);
+                }
+                Some(RollingValues::Pairs(values)) => {
+                    pairs.insert(key, values);
+                }
+                None => {}
+            }
+        }
+
+        Ok(SlidingWindowAnalytics {
+            symbols: meta_data
+                .symbols
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .collect(),
+            window_size,
+            start: parse_date(&meta_data.min_dt)?,
+            end: parse_date(&meta_data.max_dt)?,
+            series,
+            pairs,
+            warnings: parser.finish(),
+        })
+    }
+
+    /// Parse the annualized standard deviation of `symbol` from a response to
+    /// `ANALYTICS_FIXED_WINDOW`.
+    pub fn parse_volatility(parser: Parser, symbol: &str, reader: impl Read) -> Result<f64, Error> {
+        let analytics = parse_fixed_window(parser, reader)?;
+        analytics
+            .metrics
+            .iter()
+            .find(|(name, _)| name.starts_with("STDDEV"))
+            .and_then(|(_, values)| values.get(symbol))
+            .copied()
+            .ok_or_else(|| Error::ParsingError("missing standard deviation".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deserialize::parse_date;
+    use crate::parsing::{ParseMode, Parser};
+    use crate::time_series::Entry;
+    use chrono_tz::US::Eastern;
+    use std::io::BufReader;
+
+    fn time_series(closes: &[(&str, f64)]) -> TimeSeries {
+        let entries: Vec<_> = closes
+            .iter()
+            .map(|(date, close)| (*date, *close, 0))
+            .collect();
+        time_series_with_volumes(&entries)
+    }
+
+    fn time_series_with_volumes(entries: &[(&str, f64, u64)]) -> TimeSeries {
+        TimeSeries {
+            symbol: "AAPL".to_string(),
+            last_refreshed: parse_date(entries[entries.len() - 1].0, Eastern).unwrap(),
+            entries: entries
+                .iter()
+                .map(|(date, close, volume)| Entry {
+                    date: parse_date(date, Eastern).unwrap(),
+                    open: *close,
+                    high: *close,
+                    low: *close,
+                    close: *close,
+                    volume: *volume,
+                    adjusted_close: None,
+                    dividend_amount: None,
+                    split_coefficient: None,
+                })
+                .collect(),
+            warnings: vec![],
+        }
+    }
+
+    #[test]
+    fn volatility() {
+        let series = time_series(&[
+            ("2024-01-02", 100.0),
+            ("2024-03-01", 120.0),
+            ("2024-04-30", 121.2),
+            ("2024-05-01", 120.0),
+            ("2024-05-02", 121.2),
+        ]);
+        // The return on 2024-03-01 falls outside of the window.
+        let expected = {
+            let returns = [
+                121.2 / 120.0 - 1.0,
+                120.0 / 121.2 - 1.0,
+                121.2 / 120.0 - 1.0,
+            ];
+            let mean = returns.iter().sum::<f64>() / 3.0;
+            let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / 2.0;
+            (variance * 252.0).sqrt()
+        };
+        let volatility = realized_volatility(&series, Window::ThirtyDays).unwrap();
+        assert!((volatility - expected).abs() < 1e-12);
+        assert_eq!(
+            realized_volatility(&time_series(&[("2024-05-02", 1.0)]), Window::OneYear),
+            None
+        );
+    }
+
+    #[test]
+    fn vwap() {
+        let series = time_series_with_volumes(&[
+            ("2024-05-02 15:55:00", 100.0, 100),
+            ("2024-05-02 16:00:00", 103.0, 200),
+            ("2024-05-03 09:30:00", 110.0, 0),
+            ("2024-05-03 09:35:00", 110.0, 50),
+            ("2024-05-03 09:40:00", 120.0, 50),
+            // Saturday, not a trading day.
+            ("2024-05-04 10:00:00", 90.0, 10),
+        ]);
+        let vwap: Vec<f64> = session_vwap(&series, SessionHours::Extended)
+            .iter()
+            .map(|e| e.vwap)
+            .collect();
+        assert_eq!(vwap, vec![100.0, 102.0, 110.0, 115.0]);
+        // The close at 16:00 is part of the after hours.
+        let vwap: Vec<f64> = session_vwap(&series, SessionHours::Regular)
+            .iter()
+            .map(|e| e.vwap)
+            .collect();
+        assert_eq!(vwap, vec![100.0, 110.0, 115.0]);
+    }
+
+    #[test]
+    fn parse_volatility() {
+        let data: &[u8] = include_bytes!("../../tests/json/analytics_fixed_window.json");
+        let volatility =
+            parser::parse_volatility(Parser::default(), "AAPL", BufReader::new(data)).unwrap();
+        assert_eq!(volatility, 0.2461);
+        assert!(parser::parse_volatility(Parser::default(), "MSFT", BufReader::new(data)).is_err());
+    }
+
+    #[test]
+    fn parse_fixed_window() {
+        let data: &[u8] = include_bytes!("../../tests/json/analytics_fixed_window_multi.json");
+        let analytics =
+            parser::parse_fixed_window(Parser::default(), BufReader::new(data)).unwrap();
+        assert_eq!(analytics.symbols, vec!["AAPL", "MSFT", "IBM"]);
+        assert_eq!(
+            analytics.start,
+            NaiveDate::from_ymd_opt(2024, 4, 3).unwrap()
+        );
+        assert_eq!(analytics.end, NaiveDate::from_ymd_opt(2024, 5, 3).unwrap());
+        assert_eq!(analytics.metric(Calculation::Mean, "msft"), Some(0.0012));
+        assert_eq!(
+            analytics.metric(Calculation::AnnualizedStdDev, "IBM"),
+            Some(0.1893)
+        );
+        assert_eq!(
+            analytics.metric(Calculation::MaxDrawdown, "AAPL"),
+            Some(-0.0862)
+        );
+        assert_eq!(analytics.metric(Calculation::Median, "AAPL"), None);
+
+        assert_eq!(
+            analytics.pairwise(Calculation::Correlation, "AAPL", "IBM"),
+            Some(0.2134)
+        );
+        assert_eq!(
+            analytics.pairwise(Calculation::Correlation, "IBM", "AAPL"),
+            Some(0.2134)
+        );
+        assert_eq!(
+            analytics.pairwise(Calculation::Correlation, "MSFT", "MSFT"),
+            Some(1.0)
+        );
+        assert_eq!(
+            analytics.pairwise(Calculation::Correlation, "MSFT", "GOOGL"),
+            None
+        );
+
+        // The volatility of any of the symbols can be retrieved from the same response.
+        let volatility =
+            parser::parse_volatility(Parser::default(), "MSFT", BufReader::new(data)).unwrap();
+        assert_eq!(volatility, 0.2317);
+    }
+
+    #[test]
+    fn malformed_calculations() {
+        let data = br#"{
+            "meta_data": {"symbols": "AAPL,MSFT", "min_dt": "2024-04-03", "max_dt": "2024-05-03"},
+            "payload": {"RETURNS_CALCULATIONS": {
+                "MEAN": {"AAPL": 0.001, "MSFT": 0.002},
+                "CORRELATION": {"index": ["AAPL", "MSFT"], "correlation": [[1.0, 0.5]]}
+            }}
+        }"#;
+        assert!(parser::parse_fixed_window(Parser::default(), &data[..]).is_err());
+
+        let analytics =
+            parser::parse_fixed_window(Parser::new(ParseMode::Lenient), &data[..]).unwrap();
+        assert_eq!(analytics.metric(Calculation::Mean, "MSFT"), Some(0.002));
+        assert!(analytics.matrices.is_empty());
+        assert_eq!(analytics.warnings.len(), 1);
+        assert_eq!(
+            analytics.warnings[0].path,
+            "payload.RETURNS_CALCULATIONS.CORRELATION"
+        );
+    }
+
+    #[test]
+    fn parse_sliding_window() {
+        let data: &[u8] = include_bytes!("../../tests/json/analytics_sliding_window.json");
+        let analytics =
+            parser::parse_sliding_window(Parser::default(), BufReader::new(data)).unwrap();
+        assert_eq!(analytics.symbols, vec!["AAPL", "IBM"]);
+        assert_eq!(analytics.window_size, 10);
+        assert_eq!(analytics.end, NaiveDate::from_ymd_opt(2024, 5, 3).unwrap());
+
+        let mean = analytics.metric(Calculation::Mean, "ibm").unwrap();
+        let dates: Vec<String> = mean.iter().map(|v| v.date.to_string()).collect();
+        assert_eq!(
+            dates,
+            vec![
+                "2024-05-01 00:00:00",
+                "2024-05-02 00:00:00",
+                "2024-05-03 00:00:00"
+            ]
+        );
+        assert_eq!(mean[2].value, -0.0015);
+        assert_eq!(analytics.metric(Calculation::StdDev, "AAPL"), None);
+
+        let correlation = analytics
+            .pairwise(Calculation::Correlation, "IBM", "AAPL")
+            .unwrap();
+        assert_eq!(correlation.len(), 3);
+        assert_eq!(correlation[0].value, 0.3121);
+        assert_eq!(
+            analytics.pairwise(Calculation::Correlation, "AAPL", "MSFT"),
+            None
+        );
+    }
+
+    #[test]
+    fn params() {
+        assert_eq!(Range::Window(Window::NinetyDays).to_param(), "90day");
+        assert_eq!(Range::Months(6).to_param(), "6month");
+        assert_eq!(Range::Full.to_param(), "full");
+        assert_eq!(
+            Calculation::AnnualizedCovariance.to_string(),
+            "COVARIANCE(annualized=True)"
+        );
+        assert!(Calculation::Covariance.is_pairwise());
+        assert!(!Calculation::MaxDrawdown.is_pairwise());
+    }
+}
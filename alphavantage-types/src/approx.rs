@@ -0,0 +1,201 @@
+//! Approximate comparison of parsed values, for tests
+//!
+//! Floats parsed from responses or derived from them can differ in their last digits from the
+//! values written in a test. Wrapping either side of an assertion in [`Approx`] compares floats
+//! within an epsilon while every other field must still be equal:
+//!
+//! ```
+//! use alphavantage_types::approx::Approx;
+//! use alphavantage_types::exchange_rate::{Currency, ExchangeRate};
+//! use chrono::TimeZone;
+//! use chrono_tz::UTC;
+//!
+//! let currency = |code: &str| Currency {
+//!     name: code.to_string(),
+//!     code: code.to_string(),
+//! };
+//! let rate = ExchangeRate {
+//!     from: currency("USD"),
+//!     to: currency("EUR"),
+//!     rate: 0.1 + 0.2,
+//!     date: UTC.with_ymd_and_hms(2024, 5, 3, 10, 0, 0).unwrap(),
+//!     warnings: vec![],
+//! };
+//! let expected = ExchangeRate { rate: 0.3, ..rate.clone() };
+//! assert_ne!(rate, expected);
+//! assert_eq!(Approx::new(rate), expected);
+//! ```
+use crate::exchange_rate::ExchangeRate;
+use crate::time_series::{Entry, TimeSeries};
+
+/// Epsilon used by [`Approx::new`].
+pub const DEFAULT_EPSILON: f64 = 1e-9;
+
+/// Represents a value which can be compared to another one within an epsilon.
+pub trait ApproxEq {
+    /// Whether this value is equal to `other`, comparing floats within `epsilon`.
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool;
+}
+
+/// Whether `a` and `b` are within `epsilon` of each other, relative to their magnitude when it's
+/// above one, e.g. for volumes or market capitalizations.
+pub fn approx_eq(a: f64, b: f64, epsilon: f64) -> bool {
+    if a == b || (a.is_nan() && b.is_nan()) {
+        return true;
+    }
+    (a - b).abs() <= epsilon * a.abs().max(b.abs()).max(1.0)
+}
+
+impl ApproxEq for f64 {
+    fn approx_eq(&self, other: &f64, epsilon: f64) -> bool {
+        approx_eq(*self, *other, epsilon)
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for Option<T> {
+    fn approx_eq(&self, other: &Option<T>, epsilon: f64) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a.approx_eq(b, epsilon),
+            (a, b) => a.is_none() && b.is_none(),
+        }
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for [T] {
+    fn approx_eq(&self, other: &[T], epsilon: f64) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a.approx_eq(b, epsilon))
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for Vec<T> {
+    fn approx_eq(&self, other: &Vec<T>, epsilon: f64) -> bool {
+        self.as_slice().approx_eq(other.as_slice(), epsilon)
+    }
+}
+
+impl ApproxEq for Entry {
+    fn approx_eq(&self, other: &Entry, epsilon: f64) -> bool {
+        self.date == other.date
+            && self.volume == other.volume
+            && self.open.approx_eq(&other.open, epsilon)
+            && self.high.approx_eq(&other.high, epsilon)
+            && self.low.approx_eq(&other.low, epsilon)
+            && self.close.approx_eq(&other.close, epsilon)
+            && self
+                .adjusted_close
+                .approx_eq(&other.adjusted_close, epsilon)
+            && self
+                .dividend_amount
+                .approx_eq(&other.dividend_amount, epsilon)
+            && self
+                .split_coefficient
+                .approx_eq(&other.split_coefficient, epsilon)
+    }
+}
+
+impl ApproxEq for TimeSeries {
+    fn approx_eq(&self, other: &TimeSeries, epsilon: f64) -> bool {
+        self.symbol == other.symbol
+            && self.last_refreshed == other.last_refreshed
+            && self.warnings == other.warnings
+            && self.entries.approx_eq(&other.entries, epsilon)
+    }
+}
+
+impl ApproxEq for ExchangeRate {
+    fn approx_eq(&self, other: &ExchangeRate, epsilon: f64) -> bool {
+        self.from == other.from
+            && self.to == other.to
+            && self.date == other.date
+            && self.warnings == other.warnings
+            && self.rate.approx_eq(&other.rate, epsilon)
+    }
+}
+
+/// Wrapper comparing a value to others with [`ApproxEq`], e.g. in `assert_eq!`.
+#[derive(Debug, Clone)]
+pub struct Approx<T> {
+    /// Wrapped value.
+    pub value: T,
+    /// Epsilon floats are compared within.
+    pub epsilon: f64,
+}
+
+impl<T> Approx<T> {
+    /// Wrap `value`, comparing floats within [`DEFAULT_EPSILON`].
+    pub fn new(value: T) -> Approx<T> {
+        Approx {
+            value,
+            epsilon: DEFAULT_EPSILON,
+        }
+    }
+
+    /// Compare floats within `epsilon` instead.
+    pub fn epsilon(mut self, epsilon: f64) -> Approx<T> {
+        self.epsilon = epsilon;
+        self
+    }
+}
+
+impl<T: ApproxEq> PartialEq<T> for Approx<T> {
+    fn eq(&self, other: &T) -> bool {
+        self.value.approx_eq(other, self.epsilon)
+    }
+}
+
+impl<T: ApproxEq> PartialEq for Approx<T> {
+    /// Compare within the larger epsilon of both wrappers.
+    fn eq(&self, other: &Approx<T>) -> bool {
+        self.value
+            .approx_eq(&other.value, self.epsilon.max(other.epsilon))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deserialize::parse_date;
+    use chrono_tz::US::Eastern;
+
+    #[test]
+    fn floats() {
+        assert!(approx_eq(0.1 + 0.2, 0.3, DEFAULT_EPSILON));
+        assert!(!approx_eq(0.3001, 0.3, DEFAULT_EPSILON));
+        assert!(approx_eq(0.3001, 0.3, 1e-3));
+        assert!(approx_eq(28232197.0, 28232197.01, DEFAULT_EPSILON));
+        assert!(approx_eq(f64::NAN, f64::NAN, DEFAULT_EPSILON));
+        assert!(Some(1.0).approx_eq(&Some(1.0 + 1e-12), DEFAULT_EPSILON));
+        assert!(!Some(1.0).approx_eq(&None, DEFAULT_EPSILON));
+        assert!(!vec![1.0].approx_eq(&vec![1.0, 2.0], DEFAULT_EPSILON));
+    }
+
+    #[test]
+    fn time_series() {
+        let entry = |close: f64| {
+            Entry::builder(parse_date("2024-05-03", Eastern).unwrap())
+                .price(close)
+                .adjusted_close(close)
+                .volume(100)
+                .build()
+        };
+        let time_series = TimeSeries {
+            symbol: "MSFT".to_string(),
+            last_refreshed: parse_date("2024-05-03", Eastern).unwrap(),
+            entries: vec![entry(0.1 + 0.2)],
+            warnings: vec![],
+        };
+        let expected = TimeSeries {
+            entries: vec![entry(0.3)],
+            ..time_series.clone()
+        };
+        assert_eq!(Approx::new(time_series.clone()), expected);
+        assert_eq!(Approx::new(entry(402.28)).epsilon(1e-4), entry(402.2801));
+        assert_ne!(Approx::new(entry(402.28)), entry(402.2801));
+
+        let other = TimeSeries {
+            symbol: "IBM".to_string(),
+            ..expected
+        };
+        assert_ne!(Approx::new(time_series), other);
+    }
+}
@@ -0,0 +1,239 @@
+//! Types of assets and their price or value history across endpoint families
+pub use crate::calendar::DateRange;
+pub use crate::commodities::Commodity;
+use crate::crypto::Market;
+use crate::economic::Indicator;
+use crate::exchange_rate::CurrencyPair;
+use crate::time_series::Interval;
+use chrono::{Datelike, Duration, NaiveDateTime};
+use std::collections::BTreeMap;
+
+/// Represents the type of an asset, which determines the family of functions retrieving its
+/// history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetType {
+    /// Shares of a company.
+    Equity,
+    /// Exchange traded fund.
+    Etf,
+    /// Digital currency.
+    Crypto,
+    /// Pair of physical currencies.
+    ForexPair,
+    /// Commodity, e.g. crude oil or wheat.
+    Commodity,
+    /// Economic indicator, e.g. the consumer price index.
+    EconomicSeries,
+}
+
+impl AssetType {
+    /// Asset type named `name` in listings, symbol searches and company overviews (e.g. `Stock`,
+    /// `Equity`, `Common Stock` or `ETF`), ignoring case.
+    pub fn from_name(name: &str) -> Option<AssetType> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "stock" | "equity" | "common stock" => Some(AssetType::Equity),
+            "etf" => Some(AssetType::Etf),
+            "digital currency" | "crypto" => Some(AssetType::Crypto),
+            "forex" | "physical currency" => Some(AssetType::ForexPair),
+            "commodity" => Some(AssetType::Commodity),
+            _ => None,
+        }
+    }
+}
+
+/// Represents an asset whose history can be retrieved.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Asset {
+    /// Shares of the company with the given symbol.
+    Equity(String),
+    /// ETF with the given symbol.
+    Etf(String),
+    /// Digital currency, quoted in a market.
+    Crypto {
+        /// Code of the digital currency.
+        symbol: String,
+        /// Market the prices are quoted in.
+        market: Market,
+    },
+    /// Exchange rate of a currency pair.
+    ForexPair(CurrencyPair),
+    /// Prices of a commodity.
+    Commodity(Commodity),
+    /// Values of an economic indicator.
+    EconomicSeries(Indicator),
+}
+
+impl Asset {
+    /// Type of the asset.
+    pub fn asset_type(&self) -> AssetType {
+        match self {
+            Asset::Equity(_) => AssetType::Equity,
+            Asset::Etf(_) => AssetType::Etf,
+            Asset::Crypto { .. } => AssetType::Crypto,
+            Asset::ForexPair(_) => AssetType::ForexPair,
+            Asset::Commodity(_) => AssetType::Commodity,
+            Asset::EconomicSeries(_) => AssetType::EconomicSeries,
+        }
+    }
+
+    /// Asset of the given `asset_type` traded under `symbol`, for the types identified by a
+    /// single symbol (equities and ETFs).
+    pub fn listed(symbol: &str, asset_type: AssetType) -> Option<Asset> {
+        match asset_type {
+            AssetType::Equity => Some(Asset::Equity(symbol.to_string())),
+            AssetType::Etf => Some(Asset::Etf(symbol.to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// Represents the granularity of the history of an asset.
+pub type Granularity = Interval;
+
+/// Represents the value of an asset at a date.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryPoint {
+    /// Date, in the time zone the values are reported in, at midnight for daily and coarser
+    /// granularities.
+    pub date: NaiveDateTime,
+    /// Close price or exchange rate, or value of the commodity or indicator.
+    pub value: f64,
+}
+
+/// Represents the history of an asset over a range of dates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct History {
+    /// Asset the history refers to.
+    pub asset: Asset,
+    /// Granularity of the values, which is never finer than the one the asset is reported at.
+    pub granularity: Granularity,
+    /// Values, sorted by ascending dates.
+    pub points: Vec<HistoryPoint>,
+}
+
+impl History {
+    /// Create the history of `asset` from `values` within `range`, at the given `granularity`.
+    ///
+    /// Values are grouped by period of the granularity, keeping the last value of each period
+    /// at the date it was reported at, e.g. the close of the last intraday entry of each day or
+    /// of the last daily entry of each week.
+    pub fn new(
+        asset: Asset,
+        granularity: Granularity,
+        values: impl IntoIterator<Item = (NaiveDateTime, f64)>,
+        range: &DateRange,
+    ) -> History {
+        let mut periods: BTreeMap<NaiveDateTime, (NaiveDateTime, f64)> = BTreeMap::new();
+        for (date, value) in values {
+            if !range.contains(date.date()) {
+                continue;
+            }
+            let day = date.date();
+            let period = match granularity {
+                Interval::Intraday(_) => date,
+                Interval::Daily => day.into(),
+                Interval::Weekly => {
+                    (day - Duration::days(day.weekday().num_days_from_monday().into())).into()
+                }
+                Interval::Monthly => day.with_day(1).unwrap_or(day).into(),
+            };
+            match periods.get(&period) {
+                Some((last, _)) if *last > date => {}
+                _ => {
+                    periods.insert(period, (date, value));
+                }
+            }
+        }
+        let points = periods
+            .into_values()
+            .map(|(date, value)| HistoryPoint {
+                date: match granularity {
+                    Interval::Intraday(_) => date,
+                    _ => date.date().into(),
+                },
+                value,
+            })
+            .collect();
+        History {
+            asset,
+            granularity,
+            points,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commodities;
+    use crate::economic::Maturity;
+    use crate::time_series::IntradayInterval;
+    use chrono::NaiveDate;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 5, day).unwrap()
+    }
+
+    #[test]
+    fn asset_types() {
+        assert_eq!(AssetType::from_name("Stock"), Some(AssetType::Equity));
+        assert_eq!(
+            AssetType::from_name("Common Stock"),
+            Some(AssetType::Equity)
+        );
+        assert_eq!(AssetType::from_name("etf"), Some(AssetType::Etf));
+        assert_eq!(AssetType::from_name("Mutual Fund"), None);
+
+        let asset = Asset::listed("SPY", AssetType::Etf).unwrap();
+        assert_eq!(asset, Asset::Etf("SPY".to_string()));
+        assert_eq!(asset.asset_type(), AssetType::Etf);
+        assert_eq!(Asset::listed("EUR", AssetType::ForexPair), None);
+        assert_eq!(
+            Asset::EconomicSeries(Indicator::TreasuryYield(Maturity::TenYears)).asset_type(),
+            AssetType::EconomicSeries
+        );
+        assert_eq!(Commodity::NaturalGas.function(), "NATURAL_GAS");
+        assert_eq!(
+            Commodity::Coffee.finest_interval(),
+            commodities::Interval::Monthly
+        );
+    }
+
+    #[test]
+    fn history() {
+        let at = |day: u32, hour: u32| date(day).and_hms_opt(hour, 0, 0).unwrap();
+        let values = vec![
+            (at(6, 10), 3.0),
+            (at(2, 10), 1.0),
+            (at(3, 16), 2.5),
+            (at(3, 10), 2.0),
+            (at(1, 10), 0.5),
+        ];
+        let range = DateRange::new(date(2), date(5));
+        let asset = Asset::Equity("IBM".to_string());
+        let history = History::new(asset.clone(), Interval::Daily, values.clone(), &range);
+        assert_eq!(
+            history.points,
+            vec![
+                HistoryPoint {
+                    date: at(2, 0),
+                    value: 1.0
+                },
+                HistoryPoint {
+                    date: at(3, 0),
+                    value: 2.5
+                },
+            ]
+        );
+
+        let hourly = Interval::Intraday(IntradayInterval::SixtyMinutes);
+        let history = History::new(asset.clone(), hourly, values.clone(), &range);
+        assert_eq!(history.points.len(), 3);
+
+        // 2024-05-06 is the Monday after 2024-05-03.
+        let range = DateRange::new(date(1), date(6));
+        let history = History::new(asset, Interval::Weekly, values, &range);
+        let points: Vec<_> = history.points.iter().map(|p| (p.date, p.value)).collect();
+        assert_eq!(points, vec![(at(3, 0), 2.5), (at(6, 0), 3.0)]);
+    }
+}
@@ -0,0 +1,436 @@
+//! US equity market trading calendar
+//!
+//! Trading days follow the regular NYSE holiday schedule. Unscheduled closures (e.g. national
+//! days of mourning or weather events) are not taken into account.
+use crate::earnings::CalendarEntry;
+use crate::listing::IpoEntry;
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::US::Eastern;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::ops::{RangeFrom, RangeFull, RangeInclusive, RangeToInclusive};
+
+/// Represents a trading session of a trading day.
+///
+/// Sessions follow the US/Eastern time, early closes aren't taken into account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Session {
+    /// From 4:00 to 9:30.
+    PreMarket,
+    /// From 9:30 to 16:00.
+    Regular,
+    /// From 16:00 to 20:00.
+    AfterHours,
+    /// Outside of the other sessions, or on days which aren't trading days.
+    Closed,
+}
+
+impl Session {
+    /// Whether the session is outside of regular trading hours but still trading.
+    pub fn is_extended(self) -> bool {
+        matches!(self, Session::PreMarket | Session::AfterHours)
+    }
+}
+
+/// Session the market is in at `time`.
+pub fn session<Tz: TimeZone>(time: &DateTime<Tz>) -> Session {
+    let time = time.with_timezone(&Eastern);
+    if !is_trading_day(time.date_naive()) {
+        return Session::Closed;
+    }
+    let hm = |hour, minute| NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+    match time.time() {
+        t if t < hm(4, 0) => Session::Closed,
+        t if t < hm(9, 30) => Session::PreMarket,
+        t if t < hm(16, 0) => Session::Regular,
+        t if t < hm(20, 0) => Session::AfterHours,
+        _ => Session::Closed,
+    }
+}
+
+/// Whether the market is closed for a holiday on `date`.
+pub fn is_holiday(date: NaiveDate) -> bool {
+    holidays(date.year()).contains(&date)
+}
+
+/// Whether the market is open on `date`.
+pub fn is_trading_day(date: NaiveDate) -> bool {
+    !is_weekend(date) && !is_holiday(date)
+}
+
+/// First trading day after `date`.
+pub fn next_trading_day(date: NaiveDate) -> NaiveDate {
+    let mut next = date + Duration::days(1);
+    while !is_trading_day(next) {
+        next += Duration::days(1);
+    }
+    next
+}
+
+/// Last trading day before `date`.
+pub fn previous_trading_day(date: NaiveDate) -> NaiveDate {
+    let mut previous = date - Duration::days(1);
+    while !is_trading_day(previous) {
+        previous -= Duration::days(1);
+    }
+    previous
+}
+
+/// Trading days between `start` and `end`, both inclusive.
+pub fn trading_days(start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+    let mut days = vec![];
+    let mut date = start;
+    while date <= end {
+        if is_trading_day(date) {
+            days.push(date);
+        }
+        date += Duration::days(1);
+    }
+    days
+}
+
+/// Represents a range of dates, both ends included, which may be open on either end.
+///
+/// Ranges convert from the ranges of the standard library, e.g. `start..=end` or `start..`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct DateRange {
+    /// First date of the range, `None` from the earliest date available.
+    pub start: Option<NaiveDate>,
+    /// Last date of the range, `None` up to the latest date available.
+    pub end: Option<NaiveDate>,
+}
+
+impl DateRange {
+    /// Create a range from `start` to `end`, both included.
+    pub fn new(start: NaiveDate, end: NaiveDate) -> DateRange {
+        DateRange {
+            start: Some(start),
+            end: Some(end),
+        }
+    }
+
+    /// Create a range from `start`, included, to the latest date available.
+    pub fn since(start: NaiveDate) -> DateRange {
+        DateRange {
+            start: Some(start),
+            end: None,
+        }
+    }
+
+    /// Create a range from the earliest date available to `end`, included.
+    pub fn until(end: NaiveDate) -> DateRange {
+        DateRange {
+            start: None,
+            end: Some(end),
+        }
+    }
+
+    /// Create a range covering every date available.
+    pub fn all() -> DateRange {
+        DateRange::default()
+    }
+
+    /// Create a range covering the last `days` calendar days, up to today in US/Eastern time.
+    pub fn last_n_days(days: u32) -> DateRange {
+        let today = Utc::now().with_timezone(&Eastern).date_naive();
+        let days = i64::from(days.max(1)) - 1;
+        DateRange::new(today - Duration::days(days), today)
+    }
+
+    /// Create a range covering the calendar `year`.
+    pub fn year(year: i32) -> DateRange {
+        DateRange::new(ymd(year, 1, 1), ymd(year, 12, 31))
+    }
+
+    /// Whether `date` falls within the range.
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start.is_none_or(|start| start <= date) && self.end.is_none_or(|end| date <= end)
+    }
+
+    /// First and last dates of the range, bounding an open start by `first` and an open end by
+    /// `last`.
+    pub fn bounds(&self, first: NaiveDate, last: NaiveDate) -> (NaiveDate, NaiveDate) {
+        (self.start.unwrap_or(first), self.end.unwrap_or(last))
+    }
+
+    /// Months overlapping the range, formatted as `YYYY-MM`, e.g. to backfill intraday time
+    /// series one month at a time. Open ends are bounded by `first` and `last`.
+    pub fn months(&self, first: NaiveDate, last: NaiveDate) -> Vec<String> {
+        let (start, end) = self.bounds(first, last);
+        let mut months = vec![];
+        let mut month = start.with_day(1).unwrap_or(start);
+        while month <= end {
+            months.push(month.format("%Y-%m").to_string());
+            month = month + Months::new(1);
+        }
+        months
+    }
+}
+
+impl From<RangeInclusive<NaiveDate>> for DateRange {
+    fn from(range: RangeInclusive<NaiveDate>) -> DateRange {
+        DateRange::new(*range.start(), *range.end())
+    }
+}
+
+impl From<RangeFrom<NaiveDate>> for DateRange {
+    fn from(range: RangeFrom<NaiveDate>) -> DateRange {
+        DateRange::since(range.start)
+    }
+}
+
+impl From<RangeToInclusive<NaiveDate>> for DateRange {
+    fn from(range: RangeToInclusive<NaiveDate>) -> DateRange {
+        DateRange::until(range.end)
+    }
+}
+
+impl From<RangeFull> for DateRange {
+    fn from(_: RangeFull) -> DateRange {
+        DateRange::all()
+    }
+}
+
+/// Represents a change of an entry between two snapshots of a calendar, e.g. the earnings or IPO
+/// calendar.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalendarEvent<T> {
+    /// An entry was added to the calendar.
+    Added(T),
+    /// An entry was removed from the calendar, e.g. once its date passed or it was cancelled.
+    Removed(T),
+    /// An entry was moved to another date.
+    Rescheduled {
+        /// Date the entry was previously expected on.
+        previous_date: NaiveDate,
+        /// The entry, as of the later snapshot.
+        entry: T,
+    },
+}
+
+/// Represents the changes of the earnings and IPO calendars between two polls.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CalendarChanges {
+    /// Changes of the earnings calendar.
+    pub earnings: Vec<CalendarEvent<CalendarEntry>>,
+    /// Changes of the IPO calendar.
+    pub ipos: Vec<CalendarEvent<IpoEntry>>,
+}
+
+impl CalendarChanges {
+    /// Whether neither calendar changed.
+    pub fn is_empty(&self) -> bool {
+        self.earnings.is_empty() && self.ipos.is_empty()
+    }
+}
+
+/// Changes from the `before` entries of a calendar to the `after` ones, matching entries by
+/// `key` and comparing their `date`.
+///
+/// Added and rescheduled entries come first, in the order of `after`, followed by removed entries
+/// in the order of `before`.
+pub(crate) fn diff<T: Clone, K: Eq + Hash>(
+    before: &[T],
+    after: &[T],
+    key: impl Fn(&T) -> K,
+    date: impl Fn(&T) -> NaiveDate,
+) -> Vec<CalendarEvent<T>> {
+    let previous: HashMap<K, &T> = before.iter().map(|e| (key(e), e)).collect();
+    let current: HashSet<K> = after.iter().map(&key).collect();
+    let mut events = vec![];
+    for entry in after {
+        match previous.get(&key(entry)) {
+            None => events.push(CalendarEvent::Added(entry.clone())),
+            Some(previous) if date(previous) != date(entry) => {
+                events.push(CalendarEvent::Rescheduled {
+                    previous_date: date(previous),
+                    entry: entry.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    for entry in before {
+        if !current.contains(&key(entry)) {
+            events.push(CalendarEvent::Removed(entry.clone()));
+        }
+    }
+    events
+}
+
+/// Market holidays observed in `year`, sorted by date.
+pub fn holidays(year: i32) -> Vec<NaiveDate> {
+    let mut holidays = vec![];
+
+    // New Year's Day isn't observed on the preceding Friday when it falls on a Saturday.
+    let new_year = ymd(year, 1, 1);
+    match new_year.weekday() {
+        Weekday::Sat => {}
+        Weekday::Sun => holidays.push(ymd(year, 1, 2)),
+        _ => holidays.push(new_year),
+    }
+    if year >= 1998 {
+        holidays.push(nth_weekday(year, 1, Weekday::Mon, 3));
+    }
+    holidays.push(nth_weekday(year, 2, Weekday::Mon, 3));
+    holidays.push(easter(year) - Duration::days(2));
+    holidays.push(last_weekday(year, 5, Weekday::Mon));
+    if year >= 2022 {
+        holidays.push(observed(ymd(year, 6, 19)));
+    }
+    holidays.push(observed(ymd(year, 7, 4)));
+    holidays.push(nth_weekday(year, 9, Weekday::Mon, 1));
+    holidays.push(nth_weekday(year, 11, Weekday::Thu, 4));
+    holidays.push(observed(ymd(year, 12, 25)));
+
+    holidays.sort();
+    holidays
+}
+
+fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).expect("invalid calendar date")
+}
+
+/// Holidays falling on a Saturday are observed on Friday, and on a Sunday on Monday.
+fn observed(date: NaiveDate) -> NaiveDate {
+    match date.weekday() {
+        Weekday::Sat => date - Duration::days(1),
+        Weekday::Sun => date + Duration::days(1),
+        _ => date,
+    }
+}
+
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: u32) -> NaiveDate {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, n as u8)
+        .expect("invalid weekday of month")
+}
+
+fn last_weekday(year: i32, month: u32, weekday: Weekday) -> NaiveDate {
+    let mut date = if month == 12 {
+        ymd(year + 1, 1, 1)
+    } else {
+        ymd(year, month + 1, 1)
+    } - Duration::days(1);
+    while date.weekday() != weekday {
+        date -= Duration::days(1);
+    }
+    date
+}
+
+/// Date of Easter Sunday using the anonymous Gregorian algorithm.
+fn easter(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    ymd(year, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deserialize::parse_date;
+
+    #[test]
+    fn holidays_2018() {
+        let expected = vec![
+            ymd(2018, 1, 1),
+            ymd(2018, 1, 15),
+            ymd(2018, 2, 19),
+            ymd(2018, 3, 30),
+            ymd(2018, 5, 28),
+            ymd(2018, 7, 4),
+            ymd(2018, 9, 3),
+            ymd(2018, 11, 22),
+            ymd(2018, 12, 25),
+        ];
+        assert_eq!(holidays(2018), expected);
+    }
+
+    #[test]
+    fn observed_holidays() {
+        // New Year's Day on a Saturday isn't observed, Christmas on a Saturday is.
+        assert!(!is_holiday(ymd(2021, 12, 31)));
+        assert!(is_holiday(ymd(2021, 12, 24)));
+        assert!(is_holiday(ymd(2023, 1, 2)));
+        assert!(is_holiday(ymd(2022, 6, 20)));
+    }
+
+    #[test]
+    fn sessions() {
+        let at = |date: &str| parse_date(&format!("{}:00", date), Eastern).unwrap();
+        assert_eq!(session(&at("2024-05-03 03:59")), Session::Closed);
+        assert_eq!(session(&at("2024-05-03 04:00")), Session::PreMarket);
+        assert_eq!(session(&at("2024-05-03 09:30")), Session::Regular);
+        assert_eq!(session(&at("2024-05-03 16:00")), Session::AfterHours);
+        assert_eq!(session(&at("2024-05-03 20:00")), Session::Closed);
+        assert_eq!(session(&at("2024-05-04 10:00")), Session::Closed);
+        let utc = at("2024-05-03 19:00").with_timezone(&chrono::Utc);
+        assert_eq!(session(&utc), Session::AfterHours);
+        assert!(Session::PreMarket.is_extended());
+    }
+
+    #[test]
+    fn trading_days_range() {
+        let days = trading_days(ymd(2018, 5, 25), ymd(2018, 6, 1));
+        assert_eq!(
+            days,
+            vec![
+                ymd(2018, 5, 25),
+                ymd(2018, 5, 29),
+                ymd(2018, 5, 30),
+                ymd(2018, 5, 31),
+                ymd(2018, 6, 1),
+            ]
+        );
+        assert_eq!(next_trading_day(ymd(2018, 5, 25)), ymd(2018, 5, 29));
+        assert_eq!(previous_trading_day(ymd(2018, 5, 29)), ymd(2018, 5, 25));
+    }
+
+    #[test]
+    fn date_range() {
+        let range = DateRange::from(ymd(2023, 11, 15)..=ymd(2024, 5, 3));
+        assert!(range.contains(ymd(2024, 5, 3)));
+        assert!(!range.contains(ymd(2024, 5, 4)));
+        let months = range.months(ymd(2000, 1, 1), ymd(2024, 5, 8));
+        assert_eq!(
+            months,
+            vec!["2023-11", "2023-12", "2024-01", "2024-02", "2024-03", "2024-04", "2024-05"]
+        );
+
+        let since = DateRange::from(ymd(2024, 3, 15)..);
+        assert_eq!(since, DateRange::since(ymd(2024, 3, 15)));
+        assert!(since.contains(ymd(2100, 1, 1)));
+        assert_eq!(
+            since.bounds(ymd(2000, 1, 1), ymd(2024, 5, 8)),
+            (ymd(2024, 3, 15), ymd(2024, 5, 8))
+        );
+        assert_eq!(
+            since.months(ymd(2000, 1, 1), ymd(2024, 5, 8)),
+            vec!["2024-03", "2024-04", "2024-05"]
+        );
+        let until = DateRange::from(..=ymd(2024, 3, 15));
+        assert!(until.contains(ymd(1999, 1, 1)) && !until.contains(ymd(2024, 3, 16)));
+        assert!(DateRange::from(..).contains(ymd(2024, 3, 16)));
+
+        let year = DateRange::year(2020);
+        assert_eq!(year, DateRange::new(ymd(2020, 1, 1), ymd(2020, 12, 31)));
+        let last_week = DateRange::last_n_days(7);
+        let (start, end) = last_week.bounds(ymd(2000, 1, 1), ymd(2000, 1, 1));
+        assert_eq!((end - start).num_days(), 6);
+    }
+}
@@ -0,0 +1,264 @@
+//! Commodities related operations
+//!
+//! Every commodity is retrieved the same way, so supporting another one only takes a
+//! [`Commodity`] variant along with its function and intervals.
+use crate::economic::{self, Unit};
+use crate::parsing::ParseWarning;
+use chrono::NaiveDate;
+
+/// Represents a commodity whose prices are reported by the API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Commodity {
+    /// West Texas Intermediate crude oil, in dollars per barrel.
+    Wti,
+    /// Brent crude oil, in dollars per barrel.
+    Brent,
+    /// Henry Hub natural gas, in dollars per million BTU.
+    NaturalGas,
+    /// Copper, in dollars per metric ton.
+    Copper,
+    /// Aluminum, in dollars per metric ton.
+    Aluminum,
+    /// Wheat, in dollars per metric ton.
+    Wheat,
+    /// Corn, in dollars per metric ton.
+    Corn,
+    /// Cotton, in cents per pound.
+    Cotton,
+    /// Sugar, in cents per pound.
+    Sugar,
+    /// Coffee, in cents per pound.
+    Coffee,
+}
+
+const COMMODITIES: [Commodity; 10] = [
+    Commodity::Wti,
+    Commodity::Brent,
+    Commodity::NaturalGas,
+    Commodity::Copper,
+    Commodity::Aluminum,
+    Commodity::Wheat,
+    Commodity::Corn,
+    Commodity::Cotton,
+    Commodity::Sugar,
+    Commodity::Coffee,
+];
+
+impl Commodity {
+    /// Commodity whose prices are retrieved by `function`, e.g. `WHEAT`.
+    pub fn from_function(function: &str) -> Option<Commodity> {
+        COMMODITIES
+            .iter()
+            .find(|c| c.function() == function)
+            .copied()
+    }
+
+    /// Every commodity whose prices are reported by the API.
+    pub fn all() -> &'static [Commodity] {
+        &COMMODITIES
+    }
+
+    /// Function retrieving the prices of the commodity.
+    pub fn function(self) -> &'static str {
+        use self::Commodity::*;
+        match self {
+            Wti => "WTI",
+            Brent => "BRENT",
+            NaturalGas => "NATURAL_GAS",
+            Copper => "COPPER",
+            Aluminum => "ALUMINUM",
+            Wheat => "WHEAT",
+            Corn => "CORN",
+            Cotton => "COTTON",
+            Sugar => "SUGAR",
+            Coffee => "COFFEE",
+        }
+    }
+
+    /// Finest interval the prices of the commodity are reported at: daily for energy, monthly
+    /// otherwise.
+    pub fn finest_interval(self) -> Interval {
+        use self::Commodity::*;
+        match self {
+            Wti | Brent | NaturalGas => Interval::Daily,
+            _ => Interval::Monthly,
+        }
+    }
+
+    /// Intervals the prices of the commodity can be retrieved at.
+    pub fn intervals(self) -> &'static [Interval] {
+        use self::Commodity::*;
+        match self {
+            Wti | Brent | NaturalGas => &[Interval::Daily, Interval::Weekly, Interval::Monthly],
+            _ => &[Interval::Monthly, Interval::Quarterly, Interval::Annual],
+        }
+    }
+}
+
+/// Represents the interval between the prices of a commodity.
+///
+/// Crude oil and natural gas prices are reported daily, weekly or monthly, other commodities
+/// monthly, quarterly or annually, see [`Commodity::intervals`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Interval {
+    /// Daily prices.
+    Daily,
+    /// Weekly prices.
+    Weekly,
+    /// Monthly prices.
+    Monthly,
+    /// Quarterly prices.
+    Quarterly,
+    /// Annual prices.
+    Annual,
+}
+
+impl Interval {
+    /// Name of the interval in requests.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Interval::Daily => "daily",
+            Interval::Weekly => "weekly",
+            Interval::Monthly => "monthly",
+            Interval::Quarterly => "quarterly",
+            Interval::Annual => "annual",
+        }
+    }
+}
+
+/// Represents the price of a commodity at a date.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Price {
+    /// Date the price refers to.
+    pub date: NaiveDate,
+    /// Price, `None` when the API doesn't report any for the date.
+    pub value: Option<f64>,
+}
+
+/// Represents the prices of a commodity over time.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CommoditySeries {
+    /// Name of the commodity's prices, e.g. `Crude Oil Prices WTI`.
+    pub name: String,
+    /// Interval between the prices.
+    pub interval: Interval,
+    /// Unit of the prices, e.g. dollars per barrel.
+    pub unit: Unit,
+    /// Prices, sorted by ascending dates, including the dates without a reported price.
+    pub data: Vec<Price>,
+    /// Malformed prices skipped while leniently parsing the series.
+    pub warnings: Vec<ParseWarning>,
+}
+
+impl CommoditySeries {
+    /// Latest reported price on or before `date`.
+    pub fn price_on(&self, date: NaiveDate) -> Option<&Price> {
+        self.data
+            .iter()
+            .rev()
+            .find(|price| price.date <= date && price.value.is_some())
+    }
+}
+
+pub mod parser {
+    use super::*;
+    use crate::error::Error;
+    use crate::parsing::Parser;
+    use std::io::Read;
+
+    /// Parse a response to a commodity function, e.g. `WTI`, `COPPER` or `COFFEE`, whose interval
+    /// must be daily, weekly, monthly, quarterly or annual.
+    pub fn parse(parser: Parser, reader: impl Read) -> Result<CommoditySeries, Error> {
+        let (series, values) = economic::parser::parse_values(parser, reader)?;
+        let interval = match series.interval {
+            economic::Interval::Daily => Interval::Daily,
+            economic::Interval::Weekly => Interval::Weekly,
+            economic::Interval::Monthly => Interval::Monthly,
+            economic::Interval::Quarterly => Interval::Quarterly,
+            economic::Interval::Annual => Interval::Annual,
+            interval => {
+                return Err(Error::ParsingError(format!(
+                    "unsupported interval {}",
+                    interval.as_str()
+                )))
+            }
+        };
+        Ok(CommoditySeries {
+            name: series.name,
+            interval,
+            unit: series.unit,
+            data: values
+                .into_iter()
+                .map(|(date, value)| Price { date, value })
+                .collect(),
+            warnings: series.warnings,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+    use std::io::BufReader;
+
+    #[test]
+    fn parse() {
+        let data: &[u8] = include_bytes!("../../tests/json/wti.json");
+        let series = parser::parse(Parser::default(), BufReader::new(data))
+            .expect("failed to parse commodity prices");
+        assert_eq!(series.name, "Crude Oil Prices WTI");
+        assert_eq!(series.interval, Interval::Weekly);
+        assert_eq!(series.unit, Unit::Other("dollars per barrel".to_string()));
+        let prices: Vec<Option<f64>> = series.data.iter().map(|p| p.value).collect();
+        assert_eq!(prices, vec![Some(82.63), Some(82.86), Some(80.26)]);
+        let date = NaiveDate::from_ymd_opt(2024, 4, 30).unwrap();
+        assert_eq!(series.price_on(date).unwrap().value, Some(82.86));
+        assert_eq!(Interval::Monthly.as_str(), "monthly");
+
+        let data: &[u8] = include_bytes!("../../tests/json/natural_gas.json");
+        let series = parser::parse(Parser::default(), BufReader::new(data)).unwrap();
+        assert_eq!(series.interval, Interval::Monthly);
+        assert_eq!(
+            series.unit,
+            Unit::Other("dollars per million BTU".to_string())
+        );
+        assert_eq!(series.data.last().unwrap().value, Some(1.60));
+
+        let data: &[u8] = include_bytes!("../../tests/json/treasury_yield.json");
+        let mut data = String::from_utf8(data.to_vec()).unwrap();
+        data = data.replace("\"daily\"", "\"semiannual\"");
+        assert!(parser::parse(Parser::default(), data.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn parse_missing_prices() {
+        let data: &[u8] = include_bytes!("../../tests/json/copper.json");
+        let series = parser::parse(Parser::default(), BufReader::new(data))
+            .expect("failed to parse commodity prices");
+        assert_eq!(series.interval, Interval::Quarterly);
+        assert_eq!(
+            series.unit,
+            Unit::Other("dollars per metric ton".to_string())
+        );
+        let prices: Vec<Option<f64>> = series.data.iter().map(|p| p.value).collect();
+        assert_eq!(prices, vec![Some(8474.22), Some(8315.35), None]);
+        let date = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+        assert_eq!(series.price_on(date).unwrap().value, Some(8315.35));
+    }
+
+    #[test]
+    fn commodities() {
+        assert_eq!(Commodity::from_function("COFFEE"), Some(Commodity::Coffee));
+        assert_eq!(Commodity::from_function("TREASURY_YIELD"), None);
+        assert_eq!(Commodity::all().len(), 10);
+        assert!(Commodity::NaturalGas.intervals().contains(&Interval::Daily));
+        assert!(!Commodity::Wheat.intervals().contains(&Interval::Daily));
+
+        let data: &[u8] = include_bytes!("../../tests/json/coffee.json");
+        let series = parser::parse(Parser::default(), BufReader::new(data)).unwrap();
+        assert_eq!(series.interval, Interval::Monthly);
+        assert_eq!(series.unit, Unit::Other("cents per pound".to_string()));
+        assert_eq!(series.data.last().unwrap().value, Some(239.86));
+    }
+}
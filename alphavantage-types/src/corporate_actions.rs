@@ -0,0 +1,391 @@
+//! Corporate action related operations
+use crate::parsing::ParseWarning;
+use crate::time_series::{Entry, TimeSeries};
+use chrono::{DateTime, Months, NaiveDate};
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+/// Represents a dividend paid by a company.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Dividend {
+    /// Date from which the equity trades without the dividend.
+    pub ex_dividend_date: NaiveDate,
+    /// Date the dividend was declared, if known.
+    pub declaration_date: Option<NaiveDate>,
+    /// Date shareholders must be on record to receive the dividend, if known.
+    pub record_date: Option<NaiveDate>,
+    /// Date the dividend was paid, if known.
+    pub payment_date: Option<NaiveDate>,
+    /// Amount paid per share.
+    pub amount: f64,
+}
+
+/// Represents the dividend history for a given symbol.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Dividends {
+    /// Symbol the dividends refer to.
+    pub symbol: String,
+    /// Dividends paid, sorted by ascending ex-dividend dates.
+    pub dividends: Vec<Dividend>,
+    /// Malformed dividends skipped while leniently parsing the dividend history.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Represents a split of the shares of a company.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Split {
+    /// Date from which the equity trades split.
+    pub effective_date: NaiveDate,
+    /// Number of shares each share was split into, e.g. `2.0` for a 2-for-1 split or `0.1` for a
+    /// 1-for-10 reverse split.
+    pub split_factor: f64,
+}
+
+/// Represents the split history for a given symbol.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Splits {
+    /// Symbol the splits refer to.
+    pub symbol: String,
+    /// Splits, sorted by ascending effective dates.
+    pub splits: Vec<Split>,
+    /// Malformed splits skipped while leniently parsing the split history.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Represents the trailing twelve month dividend yield for a given period in a time series.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DividendYield {
+    /// Date.
+    pub date: DateTime<Tz>,
+    /// Close value for the period.
+    pub close: f64,
+    /// Dividends with an ex-dividend date in the twelve months up to the period.
+    pub trailing_dividends: f64,
+    /// Trailing dividends as a fraction of the close value.
+    pub dividend_yield: f64,
+}
+
+/// Represents the dividend yield history for a given symbol.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DividendYieldSeries {
+    /// Symbol the dividend yields refer to.
+    pub symbol: String,
+    /// Entries in the series, sorted by ascending dates.
+    pub entries: Vec<DividendYield>,
+}
+
+impl Dividends {
+    /// Compute the trailing twelve month dividend yield for each entry in `time_series`.
+    pub fn trailing_yield(&self, time_series: &TimeSeries) -> DividendYieldSeries {
+        let entries = time_series
+            .entries
+            .iter()
+            .map(|entry| {
+                let date = entry.date.date_naive();
+                let since = date.checked_sub_months(Months::new(12)).unwrap_or(date);
+                let trailing_dividends: f64 = self
+                    .dividends
+                    .iter()
+                    .filter(|d| d.ex_dividend_date > since && d.ex_dividend_date <= date)
+                    .map(|d| d.amount)
+                    .sum();
+                DividendYield {
+                    date: entry.date,
+                    close: entry.close,
+                    trailing_dividends,
+                    dividend_yield: trailing_dividends / entry.close,
+                }
+            })
+            .collect();
+        DividendYieldSeries {
+            symbol: time_series.symbol.clone(),
+            entries,
+        }
+    }
+}
+
+impl Splits {
+    /// Adjust the values of a raw `time_series` for the splits, so values before each split are
+    /// comparable with values after it.
+    ///
+    /// Open, high, low and close values of entries dated before a split are divided by its
+    /// factor, and volumes multiplied by it. Adjusted close values are left as is.
+    pub fn adjust(&self, time_series: &TimeSeries) -> TimeSeries {
+        let entries = time_series
+            .entries
+            .iter()
+            .map(|entry| {
+                let date = entry.date.date_naive();
+                let factor: f64 = self
+                    .splits
+                    .iter()
+                    .filter(|s| s.effective_date > date)
+                    .map(|s| s.split_factor)
+                    .product();
+                Entry {
+                    open: entry.open / factor,
+                    high: entry.high / factor,
+                    low: entry.low / factor,
+                    close: entry.close / factor,
+                    volume: (entry.volume as f64 * factor).round() as u64,
+                    ..entry.clone()
+                }
+            })
+            .collect();
+        TimeSeries {
+            entries,
+            ..time_series.clone()
+        }
+    }
+}
+
+pub mod parser {
+    use super::*;
+    use crate::deserialize::{from_str, parse_optional_date, DATE_FORMAT};
+    use crate::error::Error;
+    use crate::parsing::Parser;
+    use std::io::Read;
+
+    #[derive(Debug, Deserialize)]
+    struct DividendsHelper {
+        #[serde(rename = "Error Message")]
+        error: Option<String>,
+        symbol: Option<String>,
+        data: Option<Vec<serde_json::Value>>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct DividendHelper {
+        ex_dividend_date: String,
+        declaration_date: String,
+        record_date: String,
+        payment_date: String,
+        #[serde(deserialize_with = "from_str")]
+        amount: f64,
+    }
+
+    pub fn parse_dividends(mut parser: Parser, reader: impl Read) -> Result<Dividends, Error> {
+        let helper: DividendsHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let symbol = helper
+            .symbol
+            .ok_or_else(|| Error::ParsingError("missing symbol".into()))?;
+
+        let data = helper
+            .data
+            .ok_or_else(|| Error::ParsingError("missing dividend data".into()))?;
+
+        let mut dividends = vec![];
+        for (i, d) in data.iter().enumerate() {
+            let path = format!("data.{}", i);
+            let dividend = parser.parse_value(&path, d).and_then(|d: DividendHelper| {
+                Ok(Dividend {
+                    ex_dividend_date: NaiveDate::parse_from_str(&d.ex_dividend_date, DATE_FORMAT)?,
+                    declaration_date: parse_optional_date(&d.declaration_date)?,
+                    record_date: parse_optional_date(&d.record_date)?,
+                    payment_date: parse_optional_date(&d.payment_date)?,
+                    amount: d.amount,
+                })
+            });
+            if let Some(dividend) = parser.entry(&path, dividend)? {
+                dividends.push(dividend);
+            }
+        }
+
+        dividends.sort_by_key(|d| d.ex_dividend_date);
+
+        Ok(Dividends {
+            symbol,
+            dividends,
+            warnings: parser.finish(),
+        })
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct SplitHelper {
+        effective_date: String,
+        #[serde(deserialize_with = "from_str")]
+        split_factor: f64,
+    }
+
+    pub fn parse_splits(mut parser: Parser, reader: impl Read) -> Result<Splits, Error> {
+        let helper: DividendsHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let symbol = helper
+            .symbol
+            .ok_or_else(|| Error::ParsingError("missing symbol".into()))?;
+
+        let data = helper
+            .data
+            .ok_or_else(|| Error::ParsingError("missing split data".into()))?;
+
+        let mut splits = vec![];
+        for (i, s) in data.iter().enumerate() {
+            let path = format!("data.{}", i);
+            let split = parser.parse_value(&path, s).and_then(|s: SplitHelper| {
+                Ok(Split {
+                    effective_date: NaiveDate::parse_from_str(&s.effective_date, DATE_FORMAT)?,
+                    split_factor: s.split_factor,
+                })
+            });
+            if let Some(split) = parser.entry(&path, split)? {
+                splits.push(split);
+            }
+        }
+
+        splits.sort_by_key(|s| s.effective_date);
+
+        Ok(Splits {
+            symbol,
+            splits,
+            warnings: parser.finish(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deserialize::parse_date;
+    use crate::parsing::Parser;
+    use crate::time_series::Entry;
+    use chrono_tz::US::Eastern;
+    use std::io::BufReader;
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn parse_dividends() {
+        let data: &[u8] = include_bytes!("../../tests/json/dividends.json");
+        let dividends = parser::parse_dividends(Parser::default(), BufReader::new(data))
+            .expect("failed to parse dividends");
+        assert_eq!(dividends.symbol, "MSFT");
+        assert_eq!(dividends.dividends.len(), 5);
+        assert_eq!(
+            dividends.dividends[0],
+            Dividend {
+                ex_dividend_date: ymd(2017, 5, 16),
+                declaration_date: None,
+                record_date: None,
+                payment_date: None,
+                amount: 0.39,
+            }
+        );
+        assert_eq!(
+            dividends.dividends[4],
+            Dividend {
+                ex_dividend_date: ymd(2018, 5, 16),
+                declaration_date: Some(ymd(2018, 3, 14)),
+                record_date: Some(ymd(2018, 5, 17)),
+                payment_date: Some(ymd(2018, 6, 14)),
+                amount: 0.42,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_splits() {
+        let data: &[u8] = include_bytes!("../../tests/json/splits.json");
+        let splits = parser::parse_splits(Parser::default(), BufReader::new(data))
+            .expect("failed to parse splits");
+        assert_eq!(splits.symbol, "IBM");
+        assert_eq!(
+            splits.splits,
+            vec![
+                Split {
+                    effective_date: ymd(1997, 5, 28),
+                    split_factor: 2.0,
+                },
+                Split {
+                    effective_date: ymd(1999, 5, 27),
+                    split_factor: 2.0,
+                },
+            ]
+        );
+
+        let data: &[u8] = br#"{"Error Message": "Invalid API call."}"#;
+        assert!(parser::parse_splits(Parser::default(), data).is_err());
+    }
+
+    #[test]
+    fn adjust_for_splits() {
+        let splits = Splits {
+            symbol: "IBM".to_string(),
+            splits: vec![
+                Split {
+                    effective_date: ymd(1997, 5, 28),
+                    split_factor: 2.0,
+                },
+                Split {
+                    effective_date: ymd(1999, 5, 27),
+                    split_factor: 2.0,
+                },
+            ],
+            warnings: vec![],
+        };
+        let entry = |date: &str, close: f64, volume: u64| {
+            Entry::builder(parse_date(date, Eastern).unwrap())
+                .price(close)
+                .volume(volume)
+                .build()
+        };
+        let time_series = TimeSeries {
+            symbol: "IBM".to_string(),
+            last_refreshed: parse_date("1999-05-27", Eastern).unwrap(),
+            entries: vec![
+                entry("1997-05-27", 400.0, 10),
+                entry("1998-05-27", 200.0, 10),
+                entry("1999-05-27", 100.0, 10),
+            ],
+            warnings: vec![],
+        };
+        let adjusted: Vec<_> = splits
+            .adjust(&time_series)
+            .entries
+            .iter()
+            .map(|e| (e.open, e.close, e.volume))
+            .collect();
+        assert_eq!(
+            adjusted,
+            vec![(100.0, 100.0, 40), (100.0, 100.0, 20), (100.0, 100.0, 10)]
+        );
+    }
+
+    #[test]
+    fn trailing_yield() {
+        let data: &[u8] = include_bytes!("../../tests/json/dividends.json");
+        let dividends = parser::parse_dividends(Parser::default(), BufReader::new(data)).unwrap();
+        let entry = |date: &str, close: f64| Entry {
+            date: parse_date(date, Eastern).unwrap(),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 0,
+            adjusted_close: None,
+            dividend_amount: None,
+            split_coefficient: None,
+        };
+        let time_series = TimeSeries {
+            symbol: "MSFT".to_string(),
+            last_refreshed: parse_date("2018-05-16", Eastern).unwrap(),
+            entries: vec![entry("2018-05-15", 100.0), entry("2018-05-16", 100.0)],
+            warnings: vec![],
+        };
+        let series = dividends.trailing_yield(&time_series);
+        assert_eq!(series.entries.len(), 2);
+        assert!((series.entries[0].trailing_dividends - 1.62).abs() < 1e-9);
+        assert!((series.entries[1].trailing_dividends - 1.65).abs() < 1e-9);
+        assert!((series.entries[1].dividend_yield - 0.0165).abs() < 1e-9);
+    }
+}
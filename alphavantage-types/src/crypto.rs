@@ -0,0 +1,492 @@
+//! Digital currencies related operations
+use crate::exchange_rate::Currency;
+use crate::parsing::ParseWarning;
+use crate::time_series::{Interval, IntradayInterval};
+use chrono::DateTime;
+use chrono_tz::Tz;
+use serde::Deserialize;
+use std::fmt;
+
+/// Represents the market a digital currency is traded in, i.e. the physical currency its prices
+/// are quoted in.
+///
+/// Clients validating currencies check markets against the physical currency list before
+/// issuing requests.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Market(String);
+
+impl Market {
+    /// Create a market from the code of a physical currency (e.g. `USD`), ignoring case.
+    pub fn new(code: &str) -> Market {
+        Market(code.trim().to_ascii_uppercase())
+    }
+
+    /// Code of the physical currency of the market.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&Currency> for Market {
+    fn from(currency: &Currency) -> Market {
+        Market::new(&currency.code)
+    }
+}
+
+impl fmt::Display for Market {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Represents an intraday time series of a digital currency in a given market.
+#[derive(Debug, Clone)]
+pub struct CryptoSeries {
+    /// Code of the digital currency.
+    pub symbol: String,
+    /// Name of the digital currency.
+    pub name: String,
+    /// Market the prices are quoted in.
+    pub market: Market,
+    /// Name of the market's currency.
+    pub market_name: String,
+    /// Interval between the entries.
+    pub interval: IntradayInterval,
+    /// Date the information was last refreshed at.
+    pub last_refreshed: DateTime<Tz>,
+    /// Time zone of the dates of the time series.
+    pub time_zone: Tz,
+    /// Entries in the time series, sorted by ascending dates.
+    pub entries: Vec<CryptoEntry>,
+    /// Malformed entries skipped while leniently parsing the time series.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Represents a set of values for a digital currency for a given period in the time series.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CryptoEntry {
+    /// Date.
+    pub date: DateTime<Tz>,
+    /// Open value.
+    pub open: f64,
+    /// High value.
+    pub high: f64,
+    /// Low value.
+    pub low: f64,
+    /// Close value.
+    pub close: f64,
+    /// Traded volume, in units of the digital currency.
+    pub volume: f64,
+}
+
+/// Represents the daily, weekly or monthly time series of a digital currency in a given market.
+#[derive(Debug, Clone)]
+pub struct DigitalCurrencySeries {
+    /// Code of the digital currency.
+    pub symbol: String,
+    /// Name of the digital currency.
+    pub name: String,
+    /// Market the prices are quoted in.
+    pub market: Market,
+    /// Name of the market's currency.
+    pub market_name: String,
+    /// Interval between the entries, either daily, weekly or monthly.
+    pub interval: Interval,
+    /// Date the information was last refreshed at.
+    pub last_refreshed: DateTime<Tz>,
+    /// Time zone of the dates of the time series.
+    pub time_zone: Tz,
+    /// Entries in the time series, sorted by ascending dates.
+    pub entries: Vec<DigitalCurrencyEntry>,
+    /// Malformed entries skipped while leniently parsing the time series.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Represents the values of a digital currency for a given period, quoted in the market and in US
+/// dollars.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DigitalCurrencyEntry {
+    /// Values quoted in the market's currency.
+    pub market: CryptoEntry,
+    /// Values quoted in US dollars, if the response includes them.
+    pub usd: Option<CryptoEntry>,
+    /// Market capitalization in US dollars, if the response includes it.
+    pub market_cap: Option<f64>,
+}
+
+impl DigitalCurrencyEntry {
+    /// Date.
+    pub fn date(&self) -> DateTime<Tz> {
+        self.market.date
+    }
+}
+
+pub mod parser {
+    use super::*;
+    use crate::deserialize::{from_str, join_path, parse_date};
+    use crate::error::Error;
+    use crate::parsing::Parser;
+    use std::collections::HashMap;
+    use std::io::Read;
+
+    #[derive(Debug, Deserialize)]
+    struct EntryHelper {
+        #[serde(rename = "1. open", deserialize_with = "from_str")]
+        open: f64,
+        #[serde(rename = "2. high", deserialize_with = "from_str")]
+        high: f64,
+        #[serde(rename = "3. low", deserialize_with = "from_str")]
+        low: f64,
+        #[serde(rename = "4. close", deserialize_with = "from_str")]
+        close: f64,
+        #[serde(rename = "5. volume", deserialize_with = "from_str")]
+        volume: f64,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct CryptoSeriesHelper {
+        #[serde(rename = "Error Message")]
+        error: Option<String>,
+        #[serde(rename = "Meta Data")]
+        metadata: Option<HashMap<String, String>>,
+        #[serde(flatten)]
+        time_series: HashMap<String, serde_json::Value>,
+    }
+
+    pub fn parse_intraday(mut parser: Parser, reader: impl Read) -> Result<CryptoSeries, Error> {
+        let helper: CryptoSeriesHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let metadata = helper
+            .metadata
+            .ok_or_else(|| Error::ParsingError("missing metadata".into()))?;
+        let field = |key: &str, name: &str| {
+            metadata
+                .get(key)
+                .ok_or_else(|| Error::ParsingError(format!("missing {}", name)))
+        };
+
+        let interval = field("7. Interval", "interval")?;
+        let interval = IntradayInterval::from_name(interval)
+            .ok_or_else(|| Error::ParsingError(format!("unknown interval {}", interval)))?;
+
+        let time_zone = field("9. Time Zone", "time zone")?;
+        let time_zone = parser.time_zone("9. Time Zone", time_zone, Tz::UTC)?;
+
+        let last_refreshed = parse_date(field("6. Last Refreshed", "last refreshed")?, time_zone)?;
+        parser.check_freshness("6. Last Refreshed", &last_refreshed);
+
+        let time_series_key = format!("Time Series Crypto ({})", interval.to_string());
+        let time_series = helper
+            .time_series
+            .get(&time_series_key)
+            .ok_or_else(|| Error::ParsingError("missing requested time series".into()))?;
+        let time_series: HashMap<String, serde_json::Value> =
+            parser.parse_value(&time_series_key, time_series)?;
+
+        let mut entries = vec![];
+        for (d, v) in time_series.iter() {
+            let path = join_path(&time_series_key, d);
+            let entry = parse_date(d, time_zone).and_then(|date| {
+                let v: EntryHelper = parser.parse_value(&path, v)?;
+                Ok(CryptoEntry {
+                    date,
+                    open: v.open,
+                    high: v.high,
+                    low: v.low,
+                    close: v.close,
+                    volume: v.volume,
+                })
+            });
+            if let Some(entry) = parser.entry(&path, entry)? {
+                entries.push(entry);
+            }
+        }
+
+        entries.sort_by_key(|e| e.date);
+
+        Ok(CryptoSeries {
+            symbol: field("2. Digital Currency Code", "digital currency code")?.to_string(),
+            name: field("3. Digital Currency Name", "digital currency name")?.to_string(),
+            market: Market::new(field("4. Market Code", "market code")?),
+            market_name: field("5. Market Name", "market name")?.to_string(),
+            interval,
+            last_refreshed,
+            time_zone,
+            entries,
+            warnings: parser.finish(),
+        })
+    }
+
+    /// Value of the column named `name` in a daily `entry`, quoted in `currency` (e.g. `1a. open
+    /// (EUR)`) or, when `currency` is `None`, without a currency (e.g. `1. open`).
+    fn column(
+        entry: &HashMap<String, String>,
+        name: &str,
+        currency: Option<&str>,
+    ) -> Option<Result<f64, Error>> {
+        let label = match currency {
+            Some(currency) => format!("{} ({})", name, currency),
+            None => name.to_string(),
+        };
+        entry
+            .iter()
+            .find(|(key, _)| key.split_once(". ").map(|(_, l)| l) == Some(label.as_str()))
+            .map(|(key, value)| {
+                value
+                    .trim()
+                    .parse()
+                    .map_err(|_| Error::ParsingError(format!("invalid {}: {}", key, value)))
+            })
+    }
+
+    /// Open, high, low and close values of a daily `entry` quoted in `currency`, if present.
+    fn prices(
+        entry: &HashMap<String, String>,
+        currency: Option<&str>,
+    ) -> Option<Result<[f64; 4], Error>> {
+        let mut prices = [0.0; 4];
+        for (price, name) in prices.iter_mut().zip(["open", "high", "low", "close"]) {
+            match column(entry, name, currency)? {
+                Ok(value) => *price = value,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        Some(Ok(prices))
+    }
+
+    /// Parse a response to `DIGITAL_CURRENCY_DAILY`.
+    ///
+    /// Both the dual-denominated columns (e.g. `1a. open (EUR)` and `1b. open (USD)`) and the
+    /// single-denominated ones (e.g. `1. open`) are supported, in which case
+    /// [`DigitalCurrencyEntry::usd`] is `None`.
+    pub fn parse_daily(parser: Parser, reader: impl Read) -> Result<DigitalCurrencySeries, Error> {
+        parse_series(parser, reader, Interval::Daily)
+    }
+
+    /// Parse a response to `DIGITAL_CURRENCY_WEEKLY`, like [`parse_daily`].
+    pub fn parse_weekly(parser: Parser, reader: impl Read) -> Result<DigitalCurrencySeries, Error> {
+        parse_series(parser, reader, Interval::Weekly)
+    }
+
+    /// Parse a response to `DIGITAL_CURRENCY_MONTHLY`, like [`parse_daily`].
+    pub fn parse_monthly(
+        parser: Parser,
+        reader: impl Read,
+    ) -> Result<DigitalCurrencySeries, Error> {
+        parse_series(parser, reader, Interval::Monthly)
+    }
+
+    fn parse_series(
+        mut parser: Parser,
+        reader: impl Read,
+        interval: Interval,
+    ) -> Result<DigitalCurrencySeries, Error> {
+        let helper: CryptoSeriesHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let metadata = helper
+            .metadata
+            .ok_or_else(|| Error::ParsingError("missing metadata".into()))?;
+        let field = |key: &str, name: &str| {
+            metadata
+                .get(key)
+                .ok_or_else(|| Error::ParsingError(format!("missing {}", name)))
+        };
+
+        let market = Market::new(field("4. Market Code", "market code")?);
+
+        let time_zone = field("7. Time Zone", "time zone")?;
+        let time_zone = parser.time_zone("7. Time Zone", time_zone, Tz::UTC)?;
+
+        let last_refreshed = parse_date(field("6. Last Refreshed", "last refreshed")?, time_zone)?;
+        parser.check_freshness("6. Last Refreshed", &last_refreshed);
+
+        let time_series_key = match interval {
+            Interval::Weekly => "Time Series (Digital Currency Weekly)",
+            Interval::Monthly => "Time Series (Digital Currency Monthly)",
+            _ => "Time Series (Digital Currency Daily)",
+        };
+        let time_series = helper
+            .time_series
+            .get(time_series_key)
+            .ok_or_else(|| Error::ParsingError("missing requested time series".into()))?;
+        let time_series: HashMap<String, serde_json::Value> =
+            parser.parse_value(time_series_key, time_series)?;
+
+        let mut entries = vec![];
+        for (d, v) in time_series.iter() {
+            let path = join_path(time_series_key, d);
+            let entry = parse_date(d, time_zone).and_then(|date| {
+                let v: HashMap<String, String> = parser.parse_value(&path, v)?;
+                let volume = column(&v, "volume", None)
+                    .ok_or_else(|| Error::ParsingError("missing volume".into()))??;
+                let entry = |[open, high, low, close]: [f64; 4]| CryptoEntry {
+                    date,
+                    open,
+                    high,
+                    low,
+                    close,
+                    volume,
+                };
+                let quoted = prices(&v, Some(market.as_str()))
+                    .or_else(|| prices(&v, None))
+                    .ok_or_else(|| Error::ParsingError("missing prices".into()))??;
+                Ok(DigitalCurrencyEntry {
+                    market: entry(quoted),
+                    usd: prices(&v, Some("USD")).transpose()?.map(entry),
+                    market_cap: column(&v, "market cap", Some("USD")).transpose()?,
+                })
+            });
+            if let Some(entry) = parser.entry(&path, entry)? {
+                entries.push(entry);
+            }
+        }
+
+        entries.sort_by_key(|e| e.date());
+
+        Ok(DigitalCurrencySeries {
+            symbol: field("2. Digital Currency Code", "digital currency code")?.to_string(),
+            name: field("3. Digital Currency Name", "digital currency name")?.to_string(),
+            market,
+            market_name: field("5. Market Name", "market name")?.to_string(),
+            interval,
+            last_refreshed,
+            time_zone,
+            entries,
+            warnings: parser.finish(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deserialize::parse_date;
+    use crate::parsing::{ParseMode, Parser};
+    use std::io::BufReader;
+
+    #[test]
+    fn market() {
+        assert_eq!(Market::new(" usd").as_str(), "USD");
+        let currency = Currency {
+            name: "Euro".to_string(),
+            code: "EUR".to_string(),
+        };
+        assert_eq!(Market::from(&currency).to_string(), "EUR");
+    }
+
+    #[test]
+    fn parse_intraday() {
+        let data: &[u8] = include_bytes!("../../tests/json/crypto_intraday.json");
+        let series = parser::parse_intraday(Parser::default(), BufReader::new(data))
+            .expect("failed to parse crypto series");
+        assert_eq!(series.symbol, "ETH");
+        assert_eq!(series.name, "Ethereum");
+        assert_eq!(series.market, Market::new("USD"));
+        assert_eq!(series.market_name, "United States Dollar");
+        assert_eq!(series.interval, IntradayInterval::FiveMinutes);
+        assert_eq!(series.time_zone, Tz::UTC);
+        assert_eq!(
+            series.last_refreshed,
+            parse_date("2024-05-03 14:45:00", Tz::UTC).unwrap()
+        );
+        assert_eq!(series.entries.len(), 2);
+        assert_eq!(
+            series.entries[1],
+            CryptoEntry {
+                date: parse_date("2024-05-03 14:45:00", Tz::UTC).unwrap(),
+                open: 3072.53,
+                high: 3074.99,
+                low: 3070.12,
+                close: 3073.1,
+                volume: 184.25,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_daily() {
+        let data: &[u8] = include_bytes!("../../tests/json/digital_currency_daily.json");
+        let series = parser::parse_daily(Parser::default(), BufReader::new(data))
+            .expect("failed to parse crypto series");
+        assert_eq!(series.symbol, "BTC");
+        assert_eq!(series.market, Market::new("EUR"));
+        assert_eq!(series.market_name, "Euro");
+        assert_eq!(series.interval, Interval::Daily);
+        assert_eq!(series.entries.len(), 2);
+        let date = parse_date("2024-05-03", Tz::UTC).unwrap();
+        let entry = &series.entries[1];
+        assert_eq!(entry.date(), date);
+        assert_eq!(
+            entry.market,
+            CryptoEntry {
+                date,
+                open: 55190.12,
+                high: 55955.0,
+                low: 54512.37,
+                close: 55770.4,
+                volume: 1502.36,
+            }
+        );
+        assert_eq!(entry.usd.as_ref().map(|usd| usd.close), Some(59823.45));
+        assert_eq!(entry.market_cap, Some(89880000.0));
+
+        let data = br#"{
+            "Meta Data": {
+                "2. Digital Currency Code": "BTC",
+                "3. Digital Currency Name": "Bitcoin",
+                "4. Market Code": "EUR",
+                "5. Market Name": "Euro",
+                "6. Last Refreshed": "2024-05-03 00:00:00",
+                "7. Time Zone": "UTC"
+            },
+            "Time Series (Digital Currency Daily)": {
+                "2024-05-03": {
+                    "1. open": "55190.12",
+                    "2. high": "55955.00",
+                    "3. low": "54512.37",
+                    "4. close": "55770.40",
+                    "5. volume": "1502.36"
+                },
+                "2024-05-02": {"1. open": "55190.12"}
+            }
+        }"#;
+        let series = parser::parse_daily(Parser::new(ParseMode::Lenient), &data[..]).unwrap();
+        assert_eq!(series.entries.len(), 1);
+        assert_eq!(series.entries[0].market.close, 55770.4);
+        assert_eq!(series.entries[0].usd, None);
+        assert_eq!(series.entries[0].market_cap, None);
+        assert_eq!(series.warnings.len(), 1);
+    }
+
+    #[test]
+    fn parse_weekly_and_monthly() {
+        let data: &[u8] = include_bytes!("../../tests/json/digital_currency_weekly.json");
+        let weekly = parser::parse_weekly(Parser::default(), BufReader::new(data)).unwrap();
+        assert_eq!(weekly.interval, Interval::Weekly);
+        assert_eq!(weekly.market, Market::new("USD"));
+        let closes: Vec<f64> = weekly.entries.iter().map(|e| e.market.close).collect();
+        assert_eq!(closes, vec![63118.62, 59823.45]);
+        assert_eq!(weekly.entries[1].market.volume, 18544.73);
+
+        let data: &[u8] = include_bytes!("../../tests/json/digital_currency_monthly.json");
+        let monthly = parser::parse_monthly(Parser::default(), BufReader::new(data)).unwrap();
+        assert_eq!(monthly.interval, Interval::Monthly);
+        assert_eq!(monthly.entries.len(), 3);
+        assert_eq!(
+            monthly.entries[0].date(),
+            parse_date("2024-03-31", Tz::UTC).unwrap()
+        );
+
+        // A weekly response isn't a monthly one.
+        let data: &[u8] = include_bytes!("../../tests/json/digital_currency_weekly.json");
+        assert!(parser::parse_monthly(Parser::default(), BufReader::new(data)).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+//! Lists of the physical and digital currencies supported by the API
+use crate::error::Error;
+use crate::exchange_rate::Currency;
+use crate::matching::{normalize, score};
+use crate::parsing::ParseWarning;
+
+/// Maximum number of close matches reported for an unknown currency.
+const MAX_CLOSE_MATCHES: usize = 5;
+
+/// Minimum score for a currency to be reported as a close match.
+const MIN_CLOSE_MATCH_SCORE: f64 = 0.6;
+
+/// Represents a list of currencies, e.g. every digital currency supported by the API.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct CurrencyList {
+    /// Currencies, in the order they're listed.
+    pub currencies: Vec<Currency>,
+    /// Malformed currencies skipped while leniently parsing the list.
+    pub warnings: Vec<ParseWarning>,
+}
+
+impl CurrencyList {
+    /// Currency listed with the given `code`, ignoring case.
+    pub fn get(&self, code: &str) -> Option<&Currency> {
+        self.currencies
+            .iter()
+            .find(|c| c.code.eq_ignore_ascii_case(code))
+    }
+
+    /// Whether a currency is listed with the given `code`, ignoring case.
+    pub fn contains(&self, code: &str) -> bool {
+        self.get(code).is_some()
+    }
+
+    /// Listed currencies whose code or name closely match `code`, best matches first.
+    pub fn close_matches(&self, code: &str) -> Vec<&Currency> {
+        let query = normalize(code);
+        let mut matches: Vec<(f64, &Currency)> = self
+            .currencies
+            .iter()
+            .map(|c| (score(&query, &c.code, &c.name), c))
+            .filter(|(score, _)| *score >= MIN_CLOSE_MATCH_SCORE)
+            .collect();
+        matches.sort_by(|a, b| {
+            b.0.partial_cmp(&a.0)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.1.code.cmp(&b.1.code))
+        });
+        matches
+            .into_iter()
+            .take(MAX_CLOSE_MATCHES)
+            .map(|(_, c)| c)
+            .collect()
+    }
+}
+
+pub mod parser {
+    use super::*;
+    use crate::parsing::Parser;
+    use serde::Deserialize;
+    use std::io::Read;
+
+    #[derive(Debug, Deserialize)]
+    struct CurrencyHelper {
+        #[serde(rename = "currency code")]
+        code: String,
+        #[serde(rename = "currency name")]
+        name: String,
+    }
+
+    const COLUMNS: [&str; 2] = ["currency code", "currency name"];
+
+    pub fn parse(mut parser: Parser, reader: impl Read) -> Result<CurrencyList, Error> {
+        let mut reader = csv::Reader::from_reader(reader);
+        parser.check_columns(reader.headers()?, &COLUMNS)?;
+        let mut currencies = vec![];
+        for (i, record) in reader.deserialize().enumerate() {
+            let path = format!("line {}", i + 2);
+            let currency = record
+                .map(|c: CurrencyHelper| Currency {
+                    name: c.name,
+                    code: c.code,
+                })
+                .map_err(Error::from);
+            if let Some(currency) = parser.entry(&path, currency)? {
+                currencies.push(currency);
+            }
+        }
+        Ok(CurrencyList {
+            currencies,
+            warnings: parser.finish(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+
+    fn digital() -> CurrencyList {
+        let data: &[u8] = include_bytes!("../../tests/csv/digital_currency_list.csv");
+        parser::parse(Parser::default(), data).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let list = digital();
+        assert_eq!(list.currencies.len(), 7);
+        assert_eq!(
+            list.get("btc"),
+            Some(&Currency {
+                name: "Bitcoin".to_string(),
+                code: "BTC".to_string(),
+            })
+        );
+        assert!(!list.contains("USD"));
+    }
+
+    #[test]
+    fn close_matches() {
+        let list = digital();
+        let codes: Vec<&str> = list
+            .close_matches("BT")
+            .iter()
+            .map(|c| c.code.as_str())
+            .collect();
+        assert_eq!(codes, vec!["BTC", "BTG"]);
+        let codes: Vec<&str> = list
+            .close_matches("bitcoin")
+            .iter()
+            .map(|c| c.code.as_str())
+            .collect();
+        assert_eq!(codes, vec!["BTC", "BCH", "BTG"]);
+        assert!(list.close_matches("XYZ").is_empty());
+    }
+}
@@ -0,0 +1,214 @@
+use crate::error::Error;
+use chrono::prelude::*;
+use chrono_tz::Tz;
+use serde::de::{self, Deserialize, DeserializeOwned, Deserializer};
+use std::fmt::Display;
+use std::io::Read;
+use std::str::FromStr;
+
+pub const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Number of bytes of the payload reported on each side of a deserialization error.
+const EXCERPT_RADIUS: usize = 40;
+
+/// Deserialize a JSON response, reporting the path of the offending field and an excerpt of
+/// the payload around it on errors.
+///
+/// The paths of fields ignored during deserialization are added to `unknown`.
+pub(crate) fn from_reader<T: DeserializeOwned>(
+    mut reader: impl Read,
+    unknown: &mut Vec<String>,
+) -> Result<T, Error> {
+    let mut body = vec![];
+    reader
+        .read_to_end(&mut body)
+        .map_err(|e| Error::ParsingError(e.to_string()))?;
+    let deserializer = &mut serde_json::Deserializer::from_slice(&body);
+    let mut track = |path: serde_ignored::Path| unknown.push(ignored_path(&path));
+    let deserializer = serde_ignored::Deserializer::new(deserializer, &mut track);
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        let path = e.path().to_string();
+        let inner = e.into_inner();
+        match excerpt(&body, inner.line(), inner.column()) {
+            Some(excerpt) => {
+                Error::ParsingError(format!("{} at `{}` near `{}`", inner, path, excerpt))
+            }
+            None => Error::ParsingError(format!("{} at `{}`", inner, path)),
+        }
+    })
+}
+
+/// Deserialize an already parsed JSON `value` found at `path` in a response, reporting the
+/// path of the offending field and its value on errors.
+///
+/// The paths of fields ignored during deserialization are added to `unknown`.
+pub(crate) fn from_value<T: DeserializeOwned>(
+    path: &str,
+    value: &serde_json::Value,
+    unknown: &mut Vec<String>,
+) -> Result<T, Error> {
+    let mut track = |ignored: serde_ignored::Path| {
+        unknown.push(join_path(path, &ignored_path(&ignored)));
+    };
+    let deserializer = serde_ignored::Deserializer::new(value, &mut track);
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        let mut offending = Some(value);
+        for segment in e.path().iter() {
+            offending = offending.and_then(|v| match segment {
+                serde_path_to_error::Segment::Map { key } => v.get(key),
+                serde_path_to_error::Segment::Seq { index } => v.get(index),
+                _ => None,
+            });
+        }
+        let path = join_path(path, &e.path().to_string());
+        let inner = e.into_inner();
+        match offending {
+            Some(offending) => {
+                let excerpt = truncate(&offending.to_string());
+                Error::ParsingError(format!("{} at `{}` near `{}`", inner, path, excerpt))
+            }
+            None => Error::ParsingError(format!("{} at `{}`", inner, path)),
+        }
+    })
+}
+
+/// Join the path of a value in a response with a `path` relative to that value.
+pub(crate) fn join_path(parent: &str, path: &str) -> String {
+    match path {
+        "." | "" => parent.to_string(),
+        path if parent.is_empty() => path.to_string(),
+        path => format!("{}.{}", parent, path),
+    }
+}
+
+/// Path of an ignored field, leaving out the segments for options and newtypes.
+fn ignored_path(path: &serde_ignored::Path) -> String {
+    match path {
+        serde_ignored::Path::Root => String::new(),
+        serde_ignored::Path::Seq { parent, index } => {
+            join_path(&ignored_path(parent), &index.to_string())
+        }
+        serde_ignored::Path::Map { parent, key } => join_path(&ignored_path(parent), key),
+        serde_ignored::Path::Some { parent }
+        | serde_ignored::Path::NewtypeStruct { parent }
+        | serde_ignored::Path::NewtypeVariant { parent } => ignored_path(parent),
+    }
+}
+
+/// Excerpt of `body` around the 1-based `line` and `column` where deserialization failed.
+fn excerpt(body: &[u8], line: usize, column: usize) -> Option<String> {
+    if line == 0 {
+        return None;
+    }
+    let line_start: usize = body
+        .split(|b| *b == b'\n')
+        .take(line - 1)
+        .map(|l| l.len() + 1)
+        .sum();
+    let offset = (line_start + column.saturating_sub(1)).min(body.len());
+    let start = offset.saturating_sub(EXCERPT_RADIUS);
+    let end = (offset + EXCERPT_RADIUS).min(body.len());
+    let excerpt = String::from_utf8_lossy(&body[start..end]);
+    Some(excerpt.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+fn truncate(value: &str) -> String {
+    match value.char_indices().nth(2 * EXCERPT_RADIUS) {
+        Some((i, _)) => format!("{}...", &value[..i]),
+        None => value.to_string(),
+    }
+}
+
+pub(crate) fn from_str<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    T::from_str(&s).map_err(de::Error::custom)
+}
+
+pub(crate) fn from_str_opt<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    T::from_str(&s).map(Some).map_err(de::Error::custom)
+}
+
+/// Deserialize a value which the API reports as `"None"`, `"-"` or `"n/a"` when missing.
+pub(crate) fn from_str_or_none<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    match s.as_str() {
+        "None" | "-" | "n/a" | "" => Ok(None),
+        s => T::from_str(s).map(Some).map_err(de::Error::custom),
+    }
+}
+
+/// Deserialize a percentage formatted as a string with a `%` suffix (e.g. `"1.23%"`).
+pub(crate) fn from_percent_str<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    s.trim_end_matches('%').parse().map_err(de::Error::custom)
+}
+
+/// Deserialize an object which the API returns empty (`{}`) when there is no data as `None`.
+pub(crate) fn empty_as_none<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: DeserializeOwned,
+    D: Deserializer<'de>,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    match value {
+        serde_json::Value::Object(ref map) if map.is_empty() => Ok(None),
+        value => T::deserialize(value).map(Some).map_err(de::Error::custom),
+    }
+}
+
+pub fn parse_date(value: &str, time_zone: Tz) -> Result<DateTime<Tz>, Error> {
+    if value.contains(':') {
+        let datetime = NaiveDateTime::parse_from_str(value, DATETIME_FORMAT)?;
+        time_zone
+            .from_local_datetime(&datetime)
+            .single()
+            .ok_or_else(|| Error::ParsingError("unable to parse datetime".into()))
+    } else {
+        let datetime = NaiveDate::parse_from_str(value, DATE_FORMAT)?
+            .and_hms_opt(0, 0, 0)
+            .ok_or_else(|| Error::ParsingError("unable to parse date".into()))?;
+        time_zone
+            .from_local_datetime(&datetime)
+            .single()
+            .ok_or_else(|| Error::ParsingError("unable to parse date".into()))
+    }
+}
+
+/// Parse a datetime followed by the name of its time zone (e.g. `"2024-05-03 16:15:59 US/Eastern"`).
+pub fn parse_date_with_time_zone(value: &str) -> Result<DateTime<Tz>, Error> {
+    let (datetime, time_zone) = value
+        .rsplit_once(' ')
+        .ok_or_else(|| Error::ParsingError("missing time zone".into()))?;
+    let time_zone: Tz = time_zone
+        .parse()
+        .map_err(|_| Error::ParsingError("error parsing time zone".into()))?;
+    parse_date(datetime, time_zone)
+}
+
+/// Parse a date which the API reports as `"None"` or `"null"` when missing.
+pub(crate) fn parse_optional_date(value: &str) -> Result<Option<NaiveDate>, Error> {
+    match value {
+        "None" | "null" | "n/a" | "" => Ok(None),
+        value => Ok(Some(NaiveDate::parse_from_str(value, DATE_FORMAT)?)),
+    }
+}
@@ -0,0 +1,766 @@
+//! Earnings related operations
+use crate::calendar::{self, CalendarEvent, DateRange};
+use crate::parsing::ParseWarning;
+use crate::time_series::{Entry, TimeSeries};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::cmp::Ordering;
+
+/// Represents the earnings per share reported for a fiscal year.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AnnualEarnings {
+    /// Last day of the fiscal year.
+    pub fiscal_date_ending: NaiveDate,
+    /// Reported earnings per share.
+    pub reported_eps: Option<f64>,
+}
+
+/// Represents the earnings per share reported for a fiscal quarter.
+#[derive(Debug, PartialEq, Clone)]
+pub struct QuarterlyEarnings {
+    /// Last day of the fiscal quarter.
+    pub fiscal_date_ending: NaiveDate,
+    /// Date the earnings were reported on.
+    pub reported_date: NaiveDate,
+    /// Reported earnings per share.
+    pub reported_eps: Option<f64>,
+    /// Earnings per share estimated by analysts.
+    pub estimated_eps: Option<f64>,
+    /// Difference between the reported and estimated earnings per share.
+    pub surprise: Option<f64>,
+    /// Surprise as a percentage of the estimated earnings per share.
+    pub surprise_percentage: Option<f64>,
+    /// Time of the day the earnings were reported at (e.g. `pre-market` or `post-market`), if
+    /// known.
+    pub report_time: Option<String>,
+}
+
+/// Represents how reported earnings per share compare to the analyst estimate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    /// Reported earnings above the estimate.
+    Beat,
+    /// Reported earnings matching the estimate.
+    InLine,
+    /// Reported earnings below the estimate.
+    Miss,
+}
+
+impl QuarterlyEarnings {
+    /// How the reported earnings per share compare to the estimate, if both are known.
+    pub fn outcome(&self) -> Option<Outcome> {
+        let (reported, estimated) = (self.reported_eps?, self.estimated_eps?);
+        Some(match reported.partial_cmp(&estimated)? {
+            Ordering::Greater => Outcome::Beat,
+            Ordering::Equal => Outcome::InLine,
+            Ordering::Less => Outcome::Miss,
+        })
+    }
+}
+
+/// Represents the earnings history for a given symbol.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Earnings {
+    /// Symbol the earnings refer to.
+    pub symbol: String,
+    /// Annual earnings, sorted by ascending fiscal dates.
+    pub annual: Vec<AnnualEarnings>,
+    /// Quarterly earnings, sorted by ascending report dates.
+    pub quarterly: Vec<QuarterlyEarnings>,
+    /// Malformed earnings skipped while leniently parsing the earnings history.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Represents the estimates of a value by analysts.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Estimate {
+    /// Average estimate.
+    pub average: Option<f64>,
+    /// Highest estimate.
+    pub high: Option<f64>,
+    /// Lowest estimate.
+    pub low: Option<f64>,
+    /// Number of analysts the estimates were made by.
+    pub analyst_count: Option<u32>,
+}
+
+/// Represents the estimates of the earnings and revenue of a company for a fiscal period.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EarningsEstimate {
+    /// Last day of the fiscal period.
+    pub date: NaiveDate,
+    /// Fiscal period relative to the current one (e.g. `next fiscal quarter` or
+    /// `current fiscal year`).
+    pub horizon: String,
+    /// Estimates of the earnings per share.
+    pub eps: Estimate,
+    /// Estimates of the revenue.
+    pub revenue: Estimate,
+    /// Average estimate of the earnings per share 7 days ago.
+    pub eps_average_7_days_ago: Option<f64>,
+    /// Average estimate of the earnings per share 30 days ago.
+    pub eps_average_30_days_ago: Option<f64>,
+    /// Average estimate of the earnings per share 60 days ago.
+    pub eps_average_60_days_ago: Option<f64>,
+    /// Average estimate of the earnings per share 90 days ago.
+    pub eps_average_90_days_ago: Option<f64>,
+    /// Number of upward revisions of the earnings per share estimates over the last 7 days.
+    pub eps_revisions_up_7_days: Option<u32>,
+    /// Number of downward revisions of the earnings per share estimates over the last 7 days.
+    pub eps_revisions_down_7_days: Option<u32>,
+    /// Number of upward revisions of the earnings per share estimates over the last 30 days.
+    pub eps_revisions_up_30_days: Option<u32>,
+    /// Number of downward revisions of the earnings per share estimates over the last 30 days.
+    pub eps_revisions_down_30_days: Option<u32>,
+}
+
+/// Represents the earnings estimates for a given symbol.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EarningsEstimates {
+    /// Symbol the estimates refer to.
+    pub symbol: String,
+    /// Estimates, sorted by ascending dates.
+    pub estimates: Vec<EarningsEstimate>,
+    /// Malformed estimates skipped while leniently parsing the earnings estimates.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Represents the entries of a daily time series around an earnings report.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EventWindow {
+    /// The earnings report.
+    pub report: QuarterlyEarnings,
+    /// Entries of the window, sorted by ascending dates.
+    pub entries: Vec<WindowEntry>,
+}
+
+/// Represents an entry of a time series within an [`EventWindow`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct WindowEntry {
+    /// Offset of the entry from the report, in trading days: `0` for the first entry on or
+    /// after the report date, negative before it.
+    pub offset: i64,
+    /// The time series entry.
+    pub entry: Entry,
+}
+
+/// Represents how far ahead an earnings calendar looks.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Horizon {
+    /// The next 3 months.
+    #[default]
+    ThreeMonths,
+    /// The next 6 months.
+    SixMonths,
+    /// The next 12 months.
+    TwelveMonths,
+}
+
+impl Horizon {
+    /// Value of the `horizon` parameter.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Horizon::ThreeMonths => "3month",
+            Horizon::SixMonths => "6month",
+            Horizon::TwelveMonths => "12month",
+        }
+    }
+}
+
+/// Represents an upcoming earnings report.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CalendarEntry {
+    /// Symbol reporting earnings.
+    pub symbol: String,
+    /// Name of the company.
+    pub name: String,
+    /// Date the earnings are expected to be reported on.
+    pub report_date: NaiveDate,
+    /// Last day of the fiscal quarter the earnings refer to.
+    pub fiscal_date_ending: NaiveDate,
+    /// Earnings per share estimated by analysts, if any.
+    pub estimate: Option<f64>,
+    /// Currency of the estimate.
+    pub currency: String,
+}
+
+/// Represents the earnings expected to be reported within a [`Horizon`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct EarningsCalendar {
+    /// How far ahead the calendar looks.
+    pub horizon: Horizon,
+    /// Upcoming earnings reports, sorted by ascending report dates.
+    pub entries: Vec<CalendarEntry>,
+    /// Malformed entries skipped while leniently parsing the calendar.
+    pub warnings: Vec<ParseWarning>,
+}
+
+impl EarningsCalendar {
+    /// Reports added to, removed from or rescheduled in the `later` calendar, matching reports
+    /// by symbol and fiscal quarter.
+    pub fn diff(&self, later: &EarningsCalendar) -> Vec<CalendarEvent<CalendarEntry>> {
+        calendar::diff(
+            &self.entries,
+            &later.entries,
+            |e| (e.symbol.clone(), e.fiscal_date_ending),
+            |e| e.report_date,
+        )
+    }
+
+    /// Earnings expected to be reported within `range`, e.g. `start..=end`.
+    pub fn between(&self, range: impl Into<DateRange>) -> Vec<&CalendarEntry> {
+        let range = range.into();
+        self.entries
+            .iter()
+            .filter(|e| range.contains(e.report_date))
+            .collect()
+    }
+}
+
+impl Earnings {
+    /// Align the quarterly reports with a daily `time_series`, producing a window of up to
+    /// `before` entries before and `after` entries after the first entry on or after each report
+    /// date.
+    ///
+    /// Windows are truncated at the ends of the time series. Reports dated before its first
+    /// entry or after its last one are left out.
+    pub fn windows(
+        &self,
+        time_series: &TimeSeries,
+        before: usize,
+        after: usize,
+    ) -> Vec<EventWindow> {
+        let entries = &time_series.entries;
+        let first = match entries.first() {
+            Some(first) => first.date.date_naive(),
+            None => return vec![],
+        };
+        self.quarterly
+            .iter()
+            .filter(|report| report.reported_date >= first)
+            .filter_map(|report| {
+                let event = entries
+                    .iter()
+                    .position(|e| e.date.date_naive() >= report.reported_date)?;
+                let start = event.saturating_sub(before);
+                let end = (event + after + 1).min(entries.len());
+                let entries = (start..end)
+                    .map(|i| WindowEntry {
+                        offset: i as i64 - event as i64,
+                        entry: entries[i].clone(),
+                    })
+                    .collect();
+                Some(EventWindow {
+                    report: report.clone(),
+                    entries,
+                })
+            })
+            .collect()
+    }
+}
+
+pub mod parser {
+    use super::*;
+    use crate::deserialize::{from_str_or_none, DATE_FORMAT};
+    use crate::error::Error;
+    use crate::parsing::Parser;
+    use std::io::Read;
+
+    #[derive(Debug, Deserialize)]
+    struct EarningsHelper {
+        #[serde(rename = "Error Message")]
+        error: Option<String>,
+        symbol: Option<String>,
+        #[serde(rename = "annualEarnings", default)]
+        annual: Vec<serde_json::Value>,
+        #[serde(rename = "quarterlyEarnings", default)]
+        quarterly: Vec<serde_json::Value>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct AnnualHelper {
+        #[serde(rename = "fiscalDateEnding")]
+        fiscal_date_ending: String,
+        #[serde(rename = "reportedEPS", default, deserialize_with = "from_str_or_none")]
+        reported_eps: Option<f64>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct QuarterlyHelper {
+        #[serde(rename = "fiscalDateEnding")]
+        fiscal_date_ending: String,
+        #[serde(rename = "reportedDate")]
+        reported_date: String,
+        #[serde(rename = "reportedEPS", default, deserialize_with = "from_str_or_none")]
+        reported_eps: Option<f64>,
+        #[serde(
+            rename = "estimatedEPS",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        estimated_eps: Option<f64>,
+        #[serde(default, deserialize_with = "from_str_or_none")]
+        surprise: Option<f64>,
+        #[serde(
+            rename = "surprisePercentage",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        surprise_percentage: Option<f64>,
+        #[serde(rename = "reportTime")]
+        report_time: Option<String>,
+    }
+
+    fn date(value: &str) -> Result<NaiveDate, Error> {
+        Ok(NaiveDate::parse_from_str(value, DATE_FORMAT)?)
+    }
+
+    pub fn parse(mut parser: Parser, reader: impl Read) -> Result<Earnings, Error> {
+        let helper: EarningsHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let symbol = helper
+            .symbol
+            .ok_or_else(|| Error::ParsingError("missing symbol".into()))?;
+
+        let mut annual = vec![];
+        for (i, value) in helper.annual.iter().enumerate() {
+            let path = format!("annualEarnings.{}", i);
+            let earnings = parser
+                .parse_value(&path, value)
+                .and_then(|a: AnnualHelper| {
+                    Ok(AnnualEarnings {
+                        fiscal_date_ending: date(&a.fiscal_date_ending)?,
+                        reported_eps: a.reported_eps,
+                    })
+                });
+            if let Some(earnings) = parser.entry(&path, earnings)? {
+                annual.push(earnings);
+            }
+        }
+        annual.sort_by_key(|a| a.fiscal_date_ending);
+
+        let mut quarterly = vec![];
+        for (i, value) in helper.quarterly.iter().enumerate() {
+            let path = format!("quarterlyEarnings.{}", i);
+            let earnings = parser
+                .parse_value(&path, value)
+                .and_then(|q: QuarterlyHelper| {
+                    Ok(QuarterlyEarnings {
+                        fiscal_date_ending: date(&q.fiscal_date_ending)?,
+                        reported_date: date(&q.reported_date)?,
+                        reported_eps: q.reported_eps,
+                        estimated_eps: q.estimated_eps,
+                        surprise: q.surprise,
+                        surprise_percentage: q.surprise_percentage,
+                        report_time: q.report_time.filter(|t| !t.is_empty()),
+                    })
+                });
+            if let Some(earnings) = parser.entry(&path, earnings)? {
+                quarterly.push(earnings);
+            }
+        }
+        quarterly.sort_by_key(|q| q.reported_date);
+
+        Ok(Earnings {
+            symbol,
+            annual,
+            quarterly,
+            warnings: parser.finish(),
+        })
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct EstimatesHelper {
+        #[serde(rename = "Error Message")]
+        error: Option<String>,
+        symbol: Option<String>,
+        #[serde(default)]
+        estimates: Vec<serde_json::Value>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct EstimateHelper {
+        date: String,
+        horizon: String,
+        #[serde(
+            rename = "eps_estimate_average",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        eps_average: Option<f64>,
+        #[serde(
+            rename = "eps_estimate_high",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        eps_high: Option<f64>,
+        #[serde(
+            rename = "eps_estimate_low",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        eps_low: Option<f64>,
+        #[serde(
+            rename = "eps_estimate_analyst_count",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        eps_analyst_count: Option<f64>,
+        #[serde(
+            rename = "eps_estimate_average_7_days_ago",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        eps_average_7_days_ago: Option<f64>,
+        #[serde(
+            rename = "eps_estimate_average_30_days_ago",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        eps_average_30_days_ago: Option<f64>,
+        #[serde(
+            rename = "eps_estimate_average_60_days_ago",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        eps_average_60_days_ago: Option<f64>,
+        #[serde(
+            rename = "eps_estimate_average_90_days_ago",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        eps_average_90_days_ago: Option<f64>,
+        #[serde(
+            rename = "eps_estimate_revision_up_trailing_7_days",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        eps_revisions_up_7_days: Option<f64>,
+        #[serde(
+            rename = "eps_estimate_revision_down_trailing_7_days",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        eps_revisions_down_7_days: Option<f64>,
+        #[serde(
+            rename = "eps_estimate_revision_up_trailing_30_days",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        eps_revisions_up_30_days: Option<f64>,
+        #[serde(
+            rename = "eps_estimate_revision_down_trailing_30_days",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        eps_revisions_down_30_days: Option<f64>,
+        #[serde(
+            rename = "revenue_estimate_average",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        revenue_average: Option<f64>,
+        #[serde(
+            rename = "revenue_estimate_high",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        revenue_high: Option<f64>,
+        #[serde(
+            rename = "revenue_estimate_low",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        revenue_low: Option<f64>,
+        #[serde(
+            rename = "revenue_estimate_analyst_count",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        revenue_analyst_count: Option<f64>,
+    }
+
+    /// Counts are reported as decimals, e.g. `18.0000`.
+    fn count(value: Option<f64>) -> Option<u32> {
+        value.map(|v| v.round() as u32)
+    }
+
+    pub fn parse_estimates(
+        mut parser: Parser,
+        reader: impl Read,
+    ) -> Result<EarningsEstimates, Error> {
+        let helper: EstimatesHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let symbol = helper
+            .symbol
+            .ok_or_else(|| Error::ParsingError("missing symbol".into()))?;
+
+        let mut estimates = vec![];
+        for (i, value) in helper.estimates.iter().enumerate() {
+            let path = format!("estimates.{}", i);
+            let estimate = parser
+                .parse_value(&path, value)
+                .and_then(|e: EstimateHelper| {
+                    Ok(EarningsEstimate {
+                        date: date(&e.date)?,
+                        horizon: e.horizon,
+                        eps: Estimate {
+                            average: e.eps_average,
+                            high: e.eps_high,
+                            low: e.eps_low,
+                            analyst_count: count(e.eps_analyst_count),
+                        },
+                        revenue: Estimate {
+                            average: e.revenue_average,
+                            high: e.revenue_high,
+                            low: e.revenue_low,
+                            analyst_count: count(e.revenue_analyst_count),
+                        },
+                        eps_average_7_days_ago: e.eps_average_7_days_ago,
+                        eps_average_30_days_ago: e.eps_average_30_days_ago,
+                        eps_average_60_days_ago: e.eps_average_60_days_ago,
+                        eps_average_90_days_ago: e.eps_average_90_days_ago,
+                        eps_revisions_up_7_days: count(e.eps_revisions_up_7_days),
+                        eps_revisions_down_7_days: count(e.eps_revisions_down_7_days),
+                        eps_revisions_up_30_days: count(e.eps_revisions_up_30_days),
+                        eps_revisions_down_30_days: count(e.eps_revisions_down_30_days),
+                    })
+                });
+            if let Some(estimate) = parser.entry(&path, estimate)? {
+                estimates.push(estimate);
+            }
+        }
+        estimates.sort_by_key(|e| e.date);
+
+        Ok(EarningsEstimates {
+            symbol,
+            estimates,
+            warnings: parser.finish(),
+        })
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct CalendarHelper {
+        symbol: String,
+        name: String,
+        #[serde(rename = "reportDate")]
+        report_date: String,
+        #[serde(rename = "fiscalDateEnding")]
+        fiscal_date_ending: String,
+        #[serde(deserialize_with = "from_str_or_none")]
+        estimate: Option<f64>,
+        currency: String,
+    }
+
+    const CALENDAR_COLUMNS: [&str; 6] = [
+        "symbol",
+        "name",
+        "reportDate",
+        "fiscalDateEnding",
+        "estimate",
+        "currency",
+    ];
+
+    fn calendar_entry(record: Result<CalendarHelper, csv::Error>) -> Result<CalendarEntry, Error> {
+        let c = record?;
+        Ok(CalendarEntry {
+            symbol: c.symbol,
+            name: c.name,
+            report_date: date(&c.report_date)?,
+            fiscal_date_ending: date(&c.fiscal_date_ending)?,
+            estimate: c.estimate,
+            currency: c.currency,
+        })
+    }
+
+    pub fn parse_calendar(
+        mut parser: Parser,
+        horizon: Horizon,
+        reader: impl Read,
+    ) -> Result<EarningsCalendar, Error> {
+        let mut reader = csv::Reader::from_reader(reader);
+        parser.check_columns(reader.headers()?, &CALENDAR_COLUMNS)?;
+        let mut entries = vec![];
+        for (i, record) in reader.deserialize().enumerate() {
+            let path = format!("line {}", i + 2);
+            if let Some(entry) = parser.entry(&path, calendar_entry(record))? {
+                entries.push(entry);
+            }
+        }
+        entries.sort_by_key(|e| e.report_date);
+        Ok(EarningsCalendar {
+            horizon,
+            entries,
+            warnings: parser.finish(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deserialize::parse_date;
+    use crate::parsing::Parser;
+    use chrono_tz::US::Eastern;
+    use std::io::BufReader;
+
+    fn ibm() -> Earnings {
+        let data: &[u8] = include_bytes!("../../tests/json/earnings.json");
+        parser::parse(Parser::default(), BufReader::new(data)).expect("failed to parse earnings")
+    }
+
+    fn entry(date: &str) -> Entry {
+        Entry::builder(parse_date(date, Eastern).unwrap())
+            .price(1.0)
+            .build()
+    }
+
+    #[test]
+    fn parse() {
+        let earnings = ibm();
+        assert_eq!(earnings.symbol, "IBM");
+        assert_eq!(earnings.annual[0].reported_eps, Some(9.61));
+        assert_eq!(earnings.quarterly.len(), 3);
+        let first = &earnings.quarterly[0];
+        assert_eq!(
+            first.reported_date,
+            NaiveDate::from_ymd_opt(1996, 4, 16).unwrap()
+        );
+        assert_eq!(first.estimated_eps, None);
+        assert_eq!(first.report_time.as_deref(), Some("pre-market"));
+        assert_eq!(earnings.quarterly[2].surprise_percentage, Some(5.0));
+        let outcomes: Vec<_> = earnings.quarterly.iter().map(|q| q.outcome()).collect();
+        assert_eq!(
+            outcomes,
+            vec![None, Some(Outcome::Beat), Some(Outcome::Beat)]
+        );
+    }
+
+    #[test]
+    fn parse_estimates() {
+        let data: &[u8] = include_bytes!("../../tests/json/earnings_estimates.json");
+        let estimates = parser::parse_estimates(Parser::default(), data)
+            .expect("failed to parse earnings estimates");
+        assert_eq!(estimates.symbol, "IBM");
+        assert_eq!(estimates.estimates.len(), 3);
+        let first = &estimates.estimates[0];
+        assert_eq!(first.date, NaiveDate::from_ymd_opt(2024, 6, 30).unwrap());
+        assert_eq!(first.horizon, "next fiscal quarter");
+        assert_eq!(
+            first.eps,
+            Estimate {
+                average: Some(2.1534),
+                high: Some(2.26),
+                low: Some(2.02),
+                analyst_count: Some(15),
+            }
+        );
+        assert_eq!(first.revenue.average, Some(15582614000.0));
+        assert_eq!(first.eps_revisions_up_30_days, Some(2));
+        assert_eq!(estimates.estimates[2].eps_average_90_days_ago, None);
+        assert!(estimates.warnings.is_empty());
+
+        let data: &[u8] = br#"{"Error Message": "Invalid API call."}"#;
+        assert!(parser::parse_estimates(Parser::default(), data).is_err());
+    }
+
+    #[test]
+    fn windows() {
+        let time_series = TimeSeries {
+            symbol: "IBM".to_string(),
+            last_refreshed: parse_date("2024-04-26", Eastern).unwrap(),
+            entries: [
+                "2024-04-22",
+                "2024-04-23",
+                "2024-04-24",
+                "2024-04-25",
+                "2024-04-26",
+            ]
+            .iter()
+            .map(|d| entry(d))
+            .collect(),
+            warnings: vec![],
+        };
+        let windows = ibm().windows(&time_series, 1, 3);
+        assert_eq!(windows.len(), 1);
+        let window = &windows[0];
+        assert_eq!(
+            window.report.reported_date,
+            NaiveDate::from_ymd_opt(2024, 4, 24).unwrap()
+        );
+        let offsets: Vec<i64> = window.entries.iter().map(|e| e.offset).collect();
+        assert_eq!(offsets, vec![-1, 0, 1, 2]);
+        assert_eq!(window.entries[1].entry, entry("2024-04-24"));
+    }
+
+    #[test]
+    fn parse_calendar() {
+        let data: &[u8] = include_bytes!("../../tests/csv/earnings_calendar.csv");
+        let calendar = parser::parse_calendar(
+            Parser::default(),
+            Horizon::ThreeMonths,
+            BufReader::new(data),
+        )
+        .expect("failed to parse earnings calendar");
+        assert_eq!(calendar.entries.len(), 4);
+        assert_eq!(
+            calendar.entries[0],
+            CalendarEntry {
+                symbol: "MSFT".to_string(),
+                name: "Microsoft Corporation".to_string(),
+                report_date: NaiveDate::from_ymd_opt(2024, 7, 23).unwrap(),
+                fiscal_date_ending: NaiveDate::from_ymd_opt(2024, 6, 30).unwrap(),
+                estimate: Some(2.94),
+                currency: "USD".to_string(),
+            }
+        );
+        assert_eq!(calendar.entries[3].estimate, None);
+
+        let july = calendar.between(
+            NaiveDate::from_ymd_opt(2024, 7, 1).unwrap()
+                ..=NaiveDate::from_ymd_opt(2024, 7, 31).unwrap(),
+        );
+        let symbols: Vec<&str> = july.iter().map(|e| e.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["MSFT", "IBM"]);
+    }
+
+    #[test]
+    fn diff_calendar() {
+        let data: &[u8] = include_bytes!("../../tests/csv/earnings_calendar.csv");
+        let calendar = parser::parse_calendar(
+            Parser::default(),
+            Horizon::ThreeMonths,
+            BufReader::new(data),
+        )
+        .unwrap();
+        assert!(calendar.diff(&calendar).is_empty());
+
+        let mut later = calendar.clone();
+        let msft = later.entries.remove(0);
+        later.entries[0].report_date = NaiveDate::from_ymd_opt(2024, 7, 25).unwrap();
+        later.entries.push(CalendarEntry {
+            symbol: "ZZZZ".to_string(),
+            fiscal_date_ending: NaiveDate::from_ymd_opt(2024, 10, 31).unwrap(),
+            ..later.entries[2].clone()
+        });
+        assert_eq!(
+            calendar.diff(&later),
+            vec![
+                CalendarEvent::Rescheduled {
+                    previous_date: NaiveDate::from_ymd_opt(2024, 7, 24).unwrap(),
+                    entry: later.entries[0].clone(),
+                },
+                CalendarEvent::Added(later.entries[3].clone()),
+                CalendarEvent::Removed(msft),
+            ]
+        );
+    }
+}
@@ -0,0 +1,500 @@
+//! Economic indicators related operations
+use crate::parsing::ParseWarning;
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+/// Represents the interval between the values of an economic indicator.
+///
+/// Each indicator only supports some of the intervals, requests for other intervals fail with
+/// [`Error::InvalidParams`](crate::Error::InvalidParams) without being issued.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Interval {
+    /// Daily values.
+    Daily,
+    /// Weekly values.
+    Weekly,
+    /// Monthly values.
+    Monthly,
+    /// Quarterly values.
+    Quarterly,
+    /// Semiannual values.
+    Semiannual,
+    /// Annual values.
+    Annual,
+}
+
+const INTERVALS: [Interval; 6] = [
+    Interval::Daily,
+    Interval::Weekly,
+    Interval::Monthly,
+    Interval::Quarterly,
+    Interval::Semiannual,
+    Interval::Annual,
+];
+
+impl Interval {
+    /// Name of the interval in requests.
+    pub fn as_str(self) -> &'static str {
+        use self::Interval::*;
+        match self {
+            Daily => "daily",
+            Weekly => "weekly",
+            Monthly => "monthly",
+            Quarterly => "quarterly",
+            Semiannual => "semiannual",
+            Annual => "annual",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Interval> {
+        INTERVALS.iter().find(|i| i.as_str() == name).copied()
+    }
+}
+
+/// Represents the unit of the values of an economic indicator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unit {
+    /// Percent.
+    Percent,
+    /// Billions of US dollars.
+    BillionsOfDollars,
+    /// Index relative to the given base (e.g. `1982-1984=100`).
+    Index(String),
+    /// Any other unit, as reported by the API.
+    Other(String),
+}
+
+impl From<&str> for Unit {
+    fn from(unit: &str) -> Unit {
+        match unit {
+            "percent" => Unit::Percent,
+            "billions of dollars" => Unit::BillionsOfDollars,
+            unit => match unit.strip_prefix("index") {
+                Some(base) => Unit::Index(base.trim().to_string()),
+                None => Unit::Other(unit.to_string()),
+            },
+        }
+    }
+}
+
+/// Represents the maturity of US treasuries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Maturity {
+    /// 3 months.
+    ThreeMonths,
+    /// 2 years.
+    TwoYears,
+    /// 5 years.
+    FiveYears,
+    /// 7 years.
+    SevenYears,
+    /// 10 years.
+    TenYears,
+    /// 30 years.
+    ThirtyYears,
+}
+
+const MATURITIES: [Maturity; 6] = [
+    Maturity::ThreeMonths,
+    Maturity::TwoYears,
+    Maturity::FiveYears,
+    Maturity::SevenYears,
+    Maturity::TenYears,
+    Maturity::ThirtyYears,
+];
+
+impl Maturity {
+    /// Every maturity yields are reported for, from the shortest to the longest.
+    pub fn all() -> &'static [Maturity] {
+        &MATURITIES
+    }
+
+    /// Name of the maturity in requests.
+    pub fn as_str(self) -> &'static str {
+        use self::Maturity::*;
+        match self {
+            ThreeMonths => "3month",
+            TwoYears => "2year",
+            FiveYears => "5year",
+            SevenYears => "7year",
+            TenYears => "10year",
+            ThirtyYears => "30year",
+        }
+    }
+
+    /// Tenor of the maturity, in years.
+    pub fn years(self) -> f64 {
+        use self::Maturity::*;
+        match self {
+            ThreeMonths => 0.25,
+            TwoYears => 2.0,
+            FiveYears => 5.0,
+            SevenYears => 7.0,
+            TenYears => 10.0,
+            ThirtyYears => 30.0,
+        }
+    }
+}
+
+/// Represents an economic indicator of the US.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Indicator {
+    /// Yields of treasuries of the given maturity.
+    TreasuryYield(Maturity),
+    /// Federal funds rate.
+    FederalFundsRate,
+    /// Real gross domestic product.
+    RealGdp,
+    /// Consumer price index.
+    Cpi,
+}
+
+impl Indicator {
+    /// Function retrieving the values of the indicator.
+    pub fn function(self) -> &'static str {
+        match self {
+            Indicator::TreasuryYield(_) => "TREASURY_YIELD",
+            Indicator::FederalFundsRate => "FEDERAL_FUNDS_RATE",
+            Indicator::RealGdp => "REAL_GDP",
+            Indicator::Cpi => "CPI",
+        }
+    }
+
+    /// Finest interval the values of the indicator are reported at.
+    pub fn finest_interval(self) -> Interval {
+        match self {
+            Indicator::TreasuryYield(_) | Indicator::FederalFundsRate => Interval::Daily,
+            Indicator::RealGdp => Interval::Quarterly,
+            Indicator::Cpi => Interval::Monthly,
+        }
+    }
+}
+
+/// Represents a value of an economic indicator.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DataPoint {
+    /// Date the value refers to.
+    pub date: NaiveDate,
+    /// Value.
+    pub value: f64,
+}
+
+/// Represents the values of an economic indicator over time.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EconomicSeries {
+    /// Name of the indicator.
+    pub name: String,
+    /// Interval between the values.
+    pub interval: Interval,
+    /// Unit of the values.
+    pub unit: Unit,
+    /// Values, sorted by ascending dates. Dates without a reported value are left out.
+    pub data: Vec<DataPoint>,
+    /// Malformed values skipped while leniently parsing the series.
+    pub warnings: Vec<ParseWarning>,
+}
+
+impl EconomicSeries {
+    /// Latest value on or before `date`.
+    pub fn value_on(&self, date: NaiveDate) -> Option<&DataPoint> {
+        self.data.iter().rev().find(|point| point.date <= date)
+    }
+}
+
+/// Represents the yield of treasuries of a maturity.
+#[derive(Debug, PartialEq, Clone)]
+pub struct YieldPoint {
+    /// Maturity of the treasuries.
+    pub maturity: Maturity,
+    /// Date the yield was reported on, which may precede the date of the curve.
+    pub date: NaiveDate,
+    /// Yield, in percent.
+    pub rate: f64,
+}
+
+/// Represents the yields of US treasuries across maturities on a date.
+#[derive(Debug, PartialEq, Clone)]
+pub struct YieldCurve {
+    /// Date of the curve.
+    pub date: NaiveDate,
+    /// Yields, from the shortest maturity to the longest one.
+    pub points: Vec<YieldPoint>,
+}
+
+impl YieldCurve {
+    /// Yield of treasuries of the given `maturity`, if reported.
+    pub fn rate(&self, maturity: Maturity) -> Option<f64> {
+        self.points
+            .iter()
+            .find(|p| p.maturity == maturity)
+            .map(|p| p.rate)
+    }
+
+    /// Yield at an arbitrary tenor, in `years`, linearly interpolated between the surrounding
+    /// maturities. `None` outside of the range of maturities of the curve.
+    pub fn rate_at(&self, years: f64) -> Option<f64> {
+        self.points.windows(2).find_map(|pair| {
+            let (short, long) = (&pair[0], &pair[1]);
+            let (start, end) = (short.maturity.years(), long.maturity.years());
+            if years < start || years > end {
+                return None;
+            }
+            let ratio = (years - start) / (end - start);
+            Some(short.rate + ratio * (long.rate - short.rate))
+        })
+    }
+
+    /// Difference between the yields of the `long` and `short` maturities, if both are reported.
+    pub fn spread(&self, short: Maturity, long: Maturity) -> Option<f64> {
+        Some(self.rate(long)? - self.rate(short)?)
+    }
+
+    /// Whether the 10 year yield is below the 2 year yield.
+    pub fn is_inverted(&self) -> bool {
+        self.spread(Maturity::TwoYears, Maturity::TenYears)
+            .is_some_and(|spread| spread < 0.0)
+    }
+
+    /// Pairs of consecutive maturities whose longer maturity yields less than the shorter one.
+    pub fn inversions(&self) -> Vec<(Maturity, Maturity)> {
+        self.points
+            .windows(2)
+            .filter(|pair| pair[1].rate < pair[0].rate)
+            .map(|pair| (pair[0].maturity, pair[1].maturity))
+            .collect()
+    }
+}
+
+/// Build the yield curve on `date`, or on the latest date every maturity was reported on, from
+/// the yield series of each maturity.
+pub fn yield_curve(
+    series: &[(Maturity, EconomicSeries)],
+    date: Option<NaiveDate>,
+) -> Option<YieldCurve> {
+    let date = match date {
+        Some(date) => date,
+        None => series
+            .iter()
+            .map(|(_, s)| s.data.last().map(|p| p.date))
+            .min()??,
+    };
+    let points = series
+        .iter()
+        .map(|(maturity, s)| {
+            s.value_on(date).map(|point| YieldPoint {
+                maturity: *maturity,
+                date: point.date,
+                rate: point.value,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(YieldCurve { date, points })
+}
+
+pub mod parser {
+    use super::*;
+    use crate::deserialize::DATE_FORMAT;
+    use crate::error::Error;
+    use crate::parsing::Parser;
+    use std::io::Read;
+
+    /// Value reported by the API for dates without data.
+    const MISSING_VALUE: &str = ".";
+
+    /// Values listed by a series, `None` for the dates without data.
+    pub(crate) type Values = Vec<(NaiveDate, Option<f64>)>;
+
+    #[derive(Debug, Deserialize)]
+    struct DataPointHelper {
+        date: String,
+        value: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct EconomicSeriesHelper {
+        #[serde(rename = "Error Message")]
+        error: Option<String>,
+        name: Option<String>,
+        interval: Option<String>,
+        unit: Option<String>,
+        data: Option<Vec<serde_json::Value>>,
+    }
+
+    fn data_point(
+        parser: &mut Parser,
+        path: &str,
+        value: &serde_json::Value,
+    ) -> Result<(NaiveDate, Option<f64>), Error> {
+        let p: DataPointHelper = parser.parse_value(path, value)?;
+        let date = NaiveDate::parse_from_str(&p.date, DATE_FORMAT)?;
+        if p.value == MISSING_VALUE {
+            return Ok((date, None));
+        }
+        let value = p
+            .value
+            .parse()
+            .map_err(|_| Error::ParsingError(format!("invalid value {}", p.value)))?;
+        Ok((date, Some(value)))
+    }
+
+    pub fn parse(parser: Parser, reader: impl Read) -> Result<EconomicSeries, Error> {
+        let (mut series, values) = parse_values(parser, reader)?;
+        series.data = values
+            .into_iter()
+            .filter_map(|(date, value)| {
+                Some(DataPoint {
+                    date,
+                    value: value?,
+                })
+            })
+            .collect();
+        Ok(series)
+    }
+
+    /// Parse a series along with every value it lists, sorted by ascending dates, including the
+    /// dates without data. The data points of the returned series are left empty.
+    pub(crate) fn parse_values(
+        mut parser: Parser,
+        reader: impl Read,
+    ) -> Result<(EconomicSeries, Values), Error> {
+        let helper: EconomicSeriesHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let data = helper
+            .data
+            .ok_or_else(|| Error::ParsingError("missing data".into()))?;
+
+        let mut points = vec![];
+        for (i, value) in data.iter().enumerate() {
+            let path = format!("data.{}", i);
+            let point = data_point(&mut parser, &path, value);
+            if let Some(point) = parser.entry(&path, point)? {
+                points.push(point);
+            }
+        }
+        points.sort_by_key(|p| p.0);
+
+        let interval = helper
+            .interval
+            .ok_or_else(|| Error::ParsingError("missing interval".into()))?;
+        let interval = Interval::from_name(&interval)
+            .ok_or_else(|| Error::ParsingError(format!("unknown interval {}", interval)))?;
+
+        let series = EconomicSeries {
+            name: helper.name.unwrap_or_default(),
+            interval,
+            unit: Unit::from(helper.unit.unwrap_or_default().as_str()),
+            data: vec![],
+            warnings: parser.finish(),
+        };
+        Ok((series, points))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::{ParseMode, ParseWarningKind, Parser};
+    use std::io::BufReader;
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn series(points: &[(NaiveDate, f64)]) -> EconomicSeries {
+        EconomicSeries {
+            name: String::new(),
+            interval: Interval::Daily,
+            unit: Unit::Percent,
+            data: points
+                .iter()
+                .map(|&(date, value)| DataPoint { date, value })
+                .collect(),
+            warnings: vec![],
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let data: &[u8] = include_bytes!("../../tests/json/treasury_yield.json");
+        let series = parser::parse(Parser::default(), BufReader::new(data))
+            .expect("failed to parse treasury yield");
+        assert_eq!(series.interval, Interval::Daily);
+        assert_eq!(series.unit, Unit::Percent);
+        assert_eq!(series.data.len(), 3);
+        assert_eq!(
+            series.data[0],
+            DataPoint {
+                date: ymd(2024, 4, 30),
+                value: 4.69,
+            }
+        );
+        assert_eq!(series.value_on(ymd(2024, 5, 1)).unwrap().value, 4.69);
+    }
+
+    #[test]
+    fn parse_lenient() {
+        let data = br#"{
+            "name": "Federal Funds Rate",
+            "interval": "monthly",
+            "unit": "percent",
+            "data": [
+                {"date": "2024-04-01", "value": "5.33"},
+                {"date": "2024-03-01", "value": "n/a"}
+            ]
+        }"#;
+        let series = parser::parse(Parser::new(ParseMode::Lenient), &data[..]).unwrap();
+        assert_eq!(series.data.len(), 1);
+        assert_eq!(series.warnings.len(), 1);
+        assert_eq!(series.warnings[0].kind, ParseWarningKind::SkippedEntry);
+        assert_eq!(series.warnings[0].path, "data.1");
+        assert!(parser::parse(Parser::default(), &data[..]).is_err());
+    }
+
+    #[test]
+    fn units() {
+        assert_eq!(Unit::from("billions of dollars"), Unit::BillionsOfDollars);
+        assert_eq!(
+            Unit::from("index 1982-1984=100"),
+            Unit::Index("1982-1984=100".to_string())
+        );
+        assert_eq!(
+            Unit::from("thousands"),
+            Unit::Other("thousands".to_string())
+        );
+    }
+
+    #[test]
+    fn yield_curve() {
+        let day = ymd(2024, 5, 3);
+        let rates = [5.45, 4.82, 4.48, 4.47, 4.50, 4.66];
+        let all: Vec<(Maturity, EconomicSeries)> = Maturity::all()
+            .iter()
+            .zip(rates.iter())
+            .map(|(m, &r)| (*m, series(&[(ymd(2024, 5, 2), r + 0.1), (day, r)])))
+            .collect();
+
+        let curve = super::yield_curve(&all, None).unwrap();
+        assert_eq!(curve.date, day);
+        assert_eq!(curve.rate(Maturity::TenYears), Some(4.50));
+        assert!((curve.rate_at(6.0).unwrap() - 4.475).abs() < 1e-9);
+        assert!((curve.rate_at(20.0).unwrap() - 4.58).abs() < 1e-9);
+        assert_eq!(curve.rate_at(40.0), None);
+        assert!(curve.is_inverted());
+        assert_eq!(
+            curve.inversions(),
+            vec![
+                (Maturity::ThreeMonths, Maturity::TwoYears),
+                (Maturity::TwoYears, Maturity::FiveYears),
+                (Maturity::FiveYears, Maturity::SevenYears),
+            ]
+        );
+
+        let curve = super::yield_curve(&all, Some(ymd(2024, 5, 2))).unwrap();
+        assert_eq!(curve.rate(Maturity::ThreeMonths), Some(5.55));
+        assert_eq!(super::yield_curve(&all, Some(ymd(2024, 5, 1))), None);
+    }
+}
@@ -0,0 +1,121 @@
+use derive_more::Display;
+use std::time::Duration;
+
+/// Set of errors which can occur when calling the API.
+#[derive(Display, Debug)]
+pub enum Error {
+    /// Error establishing a network connection.
+    #[display(fmt = "connection error: {}", _0)]
+    ConnectionError(String),
+    /// HTTP error returned by the API.
+    #[display(fmt = "server returned HTTP status code {}", _0)]
+    ServerError(u16),
+    /// Error parsing the API response.
+    #[display(fmt = "parsing error: {}", _0)]
+    ParsingError(String),
+    /// Error returned by the API.
+    #[display(fmt = "API error: {}", _0)]
+    APIError(String),
+    /// Error reading or writing a file.
+    #[display(fmt = "I/O error: {}", _0)]
+    IOError(String),
+    /// Currency which isn't listed by the API, along with the codes of the listed currencies
+    /// closely matching it.
+    #[display(fmt = "unknown currency {} (close matches: {:?})", code, close_matches)]
+    UnknownCurrency {
+        /// Code of the unknown currency.
+        code: String,
+        /// Codes of the listed currencies closely matching the unknown one, best matches first.
+        close_matches: Vec<String>,
+    },
+    /// API key which is malformed or was rejected by the API.
+    #[display(fmt = "invalid API key: {}", _0)]
+    InvalidApiKey(String),
+    /// Parameters of a request which would be rejected by the API, detected before issuing it.
+    #[display(fmt = "invalid parameters: {}", _0)]
+    InvalidParams(String),
+    /// Response to a request made by an offline client isn't in the cache.
+    #[display(fmt = "response not cached while offline: {}", _0)]
+    Offline(String),
+    /// Function which the tier the client is configured with can't call, detected before
+    /// issuing the request.
+    #[display(fmt = "premium tier required for {}", _0)]
+    PremiumRequired(String),
+    /// Request rejected by the API for exceeding the request frequency or quota of the key,
+    /// along with how long to wait before retrying it.
+    #[display(fmt = "throttled, retry after {:?}: {}", retry_after, message)]
+    Throttled {
+        /// Message returned by the API.
+        message: String,
+        /// Time until the start of the next period of the limit exceeded, plus some jitter.
+        retry_after: Duration,
+    },
+    /// Requests planned beyond the remaining quota of the client, detected before issuing them.
+    #[display(
+        fmt = "{} requests exceed the remaining quota of {}",
+        required,
+        remaining
+    )]
+    QuotaExceeded {
+        /// Number of requests required.
+        required: u32,
+        /// Number of requests remaining in the quota.
+        remaining: u32,
+    },
+    /// Data required to compute a result locally is missing from the responses retrieved, e.g.
+    /// the quote of a symbol held in a portfolio.
+    #[display(fmt = "missing data: {}", _0)]
+    MissingData(String),
+    /// Setting missing from the configuration of the client, required by an operation, e.g. a
+    /// cache to ingest files into.
+    #[display(fmt = "configuration error: {}", _0)]
+    Configuration(String),
+}
+
+impl Error {
+    /// Short name of the class of error, suitable for labelling metrics.
+    pub fn class(&self) -> &'static str {
+        match self {
+            Error::ConnectionError(_) => "connection",
+            Error::ServerError(_) => "server",
+            Error::ParsingError(_) => "parsing",
+            Error::APIError(_) => "api",
+            Error::IOError(_) => "io",
+            Error::UnknownCurrency { .. } => "validation",
+            Error::InvalidApiKey(_) => "api_key",
+            Error::InvalidParams(_) => "validation",
+            Error::Offline(_) => "offline",
+            Error::PremiumRequired(_) => "premium",
+            Error::Throttled { .. } => "throttled",
+            Error::QuotaExceeded { .. } => "quota",
+            Error::MissingData(_) => "missing_data",
+            Error::Configuration(_) => "configuration",
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(inner: std::io::Error) -> Error {
+        Error::IOError(inner.to_string())
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(inner: serde_json::Error) -> Error {
+        Error::ParsingError(inner.to_string())
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(inner: csv::Error) -> Error {
+        Error::ParsingError(inner.to_string())
+    }
+}
+
+impl From<chrono::format::ParseError> for Error {
+    fn from(inner: chrono::format::ParseError) -> Error {
+        Error::ParsingError(inner.to_string())
+    }
+}
@@ -0,0 +1,351 @@
+//! ETF related operations
+use crate::parsing::ParseWarning;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Represents the profile of an ETF along with its holdings.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EtfProfile {
+    /// Symbol the profile refers to.
+    pub symbol: String,
+    /// Net assets under management.
+    pub net_assets: Option<f64>,
+    /// Net expense ratio.
+    pub net_expense_ratio: Option<f64>,
+    /// Portfolio turnover.
+    pub portfolio_turnover: Option<f64>,
+    /// Dividend yield.
+    pub dividend_yield: Option<f64>,
+    /// Date the ETF was launched on.
+    pub inception_date: Option<NaiveDate>,
+    /// Whether the ETF is leveraged.
+    pub leveraged: bool,
+    /// Weight of each sector in the ETF.
+    pub sectors: Vec<SectorWeight>,
+    /// Holdings of the ETF, by descending weights.
+    pub holdings: Vec<Holding>,
+    /// Malformed holdings skipped while leniently parsing the profile.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Represents the weight of a sector in an ETF.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SectorWeight {
+    /// Name of the sector.
+    pub sector: String,
+    /// Weight of the sector, between 0 and 1.
+    pub weight: f64,
+}
+
+/// Represents a holding of an ETF.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Holding {
+    /// Symbol of the holding, `n/a` for holdings without a symbol (e.g. cash).
+    pub symbol: String,
+    /// Description of the holding.
+    pub description: String,
+    /// Weight of the holding in the ETF, between 0 and 1.
+    pub weight: f64,
+}
+
+/// Represents the holdings two ETFs have in common.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Overlap {
+    /// Share of the ETFs' weight held in common, between 0 and 1, i.e. the sum of the lowest
+    /// weight of each common holding.
+    pub weight: f64,
+    /// Holdings in common, by descending overlapping weights.
+    pub holdings: Vec<CommonHolding>,
+}
+
+/// Represents a holding two ETFs have in common.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CommonHolding {
+    /// Symbol of the holding.
+    pub symbol: String,
+    /// Weight of the holding in the first ETF.
+    pub weight: f64,
+    /// Weight of the holding in the second ETF.
+    pub other_weight: f64,
+}
+
+impl CommonHolding {
+    /// Weight of the holding in both ETFs.
+    pub fn overlapping_weight(&self) -> f64 {
+        self.weight.min(self.other_weight)
+    }
+}
+
+/// Represents a change in the weight of a holding between two profiles of an ETF.
+#[derive(Debug, PartialEq, Clone)]
+pub struct WeightChange {
+    /// The holding, with its later weight.
+    pub holding: Holding,
+    /// Earlier weight of the holding.
+    pub previous_weight: f64,
+}
+
+/// Represents the changes in the holdings of an ETF between two profiles.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct HoldingsDiff {
+    /// Holdings only in the later profile.
+    pub added: Vec<Holding>,
+    /// Holdings only in the earlier profile.
+    pub removed: Vec<Holding>,
+    /// Holdings whose weight changed.
+    pub changed: Vec<WeightChange>,
+}
+
+impl HoldingsDiff {
+    /// Whether the holdings didn't change.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Symbol reported for holdings without a symbol, which can't be matched across profiles.
+const NO_SYMBOL: &str = "n/a";
+
+impl EtfProfile {
+    /// Holdings with a symbol, by symbol.
+    fn holdings_by_symbol(&self) -> HashMap<&str, &Holding> {
+        self.holdings
+            .iter()
+            .filter(|h| h.symbol != NO_SYMBOL)
+            .map(|h| (h.symbol.as_str(), h))
+            .collect()
+    }
+
+    /// Compute the holdings this ETF has in common with `other`, weighted by the lowest weight
+    /// of each holding in both ETFs.
+    pub fn overlap(&self, other: &EtfProfile) -> Overlap {
+        let others = other.holdings_by_symbol();
+        let mut holdings: Vec<CommonHolding> = self
+            .holdings
+            .iter()
+            .filter(|h| h.symbol != NO_SYMBOL)
+            .filter_map(|h| {
+                others.get(h.symbol.as_str()).map(|o| CommonHolding {
+                    symbol: h.symbol.clone(),
+                    weight: h.weight,
+                    other_weight: o.weight,
+                })
+            })
+            .collect();
+        holdings.sort_by(|a, b| b.overlapping_weight().total_cmp(&a.overlapping_weight()));
+        Overlap {
+            weight: holdings.iter().map(CommonHolding::overlapping_weight).sum(),
+            holdings,
+        }
+    }
+
+    /// Compute the changes in holdings from this profile to a `later` profile of the same ETF.
+    ///
+    /// Holdings without a symbol are left out.
+    pub fn diff(&self, later: &EtfProfile) -> HoldingsDiff {
+        let earlier = self.holdings_by_symbol();
+        let later_holdings = later.holdings_by_symbol();
+        let mut diff = HoldingsDiff::default();
+        for holding in later.holdings.iter().filter(|h| h.symbol != NO_SYMBOL) {
+            match earlier.get(holding.symbol.as_str()) {
+                None => diff.added.push(holding.clone()),
+                Some(previous) if previous.weight != holding.weight => {
+                    diff.changed.push(WeightChange {
+                        holding: holding.clone(),
+                        previous_weight: previous.weight,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        for holding in self.holdings.iter().filter(|h| h.symbol != NO_SYMBOL) {
+            if !later_holdings.contains_key(holding.symbol.as_str()) {
+                diff.removed.push(holding.clone());
+            }
+        }
+        diff
+    }
+}
+
+pub mod parser {
+    use super::*;
+    use crate::deserialize::{from_str, from_str_or_none, parse_optional_date};
+    use crate::error::Error;
+    use crate::parsing::Parser;
+    use std::io::Read;
+
+    #[derive(Debug, Deserialize)]
+    struct SectorHelper {
+        sector: String,
+        #[serde(deserialize_with = "from_str")]
+        weight: f64,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct HoldingHelper {
+        symbol: String,
+        description: String,
+        #[serde(deserialize_with = "from_str")]
+        weight: f64,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct EtfProfileHelper {
+        #[serde(rename = "Error Message")]
+        error: Option<String>,
+        #[serde(default, deserialize_with = "from_str_or_none")]
+        net_assets: Option<f64>,
+        #[serde(default, deserialize_with = "from_str_or_none")]
+        net_expense_ratio: Option<f64>,
+        #[serde(default, deserialize_with = "from_str_or_none")]
+        portfolio_turnover: Option<f64>,
+        #[serde(default, deserialize_with = "from_str_or_none")]
+        dividend_yield: Option<f64>,
+        inception_date: Option<String>,
+        leveraged: Option<String>,
+        #[serde(default)]
+        sectors: Vec<SectorHelper>,
+        holdings: Option<Vec<serde_json::Value>>,
+    }
+
+    pub fn parse(mut parser: Parser, symbol: &str, reader: impl Read) -> Result<EtfProfile, Error> {
+        let helper: EtfProfileHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let holdings = helper
+            .holdings
+            .ok_or_else(|| Error::ParsingError("missing holdings".into()))?;
+
+        let mut parsed = vec![];
+        for (i, value) in holdings.iter().enumerate() {
+            let path = format!("holdings.{}", i);
+            let holding = parser
+                .parse_value(&path, value)
+                .map(|h: HoldingHelper| Holding {
+                    symbol: h.symbol,
+                    description: h.description,
+                    weight: h.weight,
+                });
+            if let Some(holding) = parser.entry(&path, holding)? {
+                parsed.push(holding);
+            }
+        }
+        parsed.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+
+        let inception_date = match &helper.inception_date {
+            Some(date) => parse_optional_date(date)?,
+            None => None,
+        };
+
+        let profile = EtfProfile {
+            symbol: symbol.to_string(),
+            net_assets: helper.net_assets,
+            net_expense_ratio: helper.net_expense_ratio,
+            portfolio_turnover: helper.portfolio_turnover,
+            dividend_yield: helper.dividend_yield,
+            inception_date,
+            leveraged: helper.leveraged.as_deref() == Some("YES"),
+            sectors: helper
+                .sectors
+                .into_iter()
+                .map(|s| SectorWeight {
+                    sector: s.sector,
+                    weight: s.weight,
+                })
+                .collect(),
+            holdings: parsed,
+            warnings: parser.finish(),
+        };
+        Ok(profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+    use std::io::BufReader;
+
+    fn qqq() -> EtfProfile {
+        let data: &[u8] = include_bytes!("../../tests/json/etf_profile.json");
+        parser::parse(Parser::default(), "QQQ", BufReader::new(data))
+            .expect("failed to parse ETF profile")
+    }
+
+    fn holding(symbol: &str, weight: f64) -> Holding {
+        Holding {
+            symbol: symbol.to_string(),
+            description: String::new(),
+            weight,
+        }
+    }
+
+    fn etf(symbol: &str, holdings: Vec<Holding>) -> EtfProfile {
+        EtfProfile {
+            symbol: symbol.to_string(),
+            holdings,
+            ..qqq()
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let profile = qqq();
+        assert_eq!(profile.symbol, "QQQ");
+        assert_eq!(profile.net_assets, Some(288700000000.0));
+        assert_eq!(profile.net_expense_ratio, Some(0.002));
+        assert_eq!(profile.inception_date, NaiveDate::from_ymd_opt(1999, 3, 10));
+        assert!(!profile.leveraged);
+        assert_eq!(profile.sectors.len(), 2);
+        assert_eq!(profile.holdings.len(), 4);
+        assert_eq!(profile.holdings[0].symbol, "AAPL");
+        assert_eq!(profile.holdings[0].weight, 0.0889);
+    }
+
+    #[test]
+    fn overlap() {
+        let qqq = etf(
+            "QQQ",
+            vec![
+                holding("AAPL", 0.09),
+                holding("MSFT", 0.08),
+                holding("n/a", 0.01),
+            ],
+        );
+        let spy = etf(
+            "SPY",
+            vec![
+                holding("MSFT", 0.07),
+                holding("AAPL", 0.06),
+                holding("n/a", 0.01),
+            ],
+        );
+        let overlap = qqq.overlap(&spy);
+        assert!((overlap.weight - 0.13).abs() < 1e-9);
+        assert_eq!(overlap.holdings.len(), 2);
+        assert_eq!(overlap.holdings[0].symbol, "MSFT");
+        assert_eq!(overlap.holdings[0].weight, 0.08);
+        assert_eq!(overlap.holdings[0].other_weight, 0.07);
+    }
+
+    #[test]
+    fn diff() {
+        let earlier = etf("QQQ", vec![holding("AAPL", 0.09), holding("MSFT", 0.08)]);
+        let later = etf("QQQ", vec![holding("AAPL", 0.1), holding("NVDA", 0.07)]);
+        let diff = earlier.diff(&later);
+        assert_eq!(diff.added, vec![holding("NVDA", 0.07)]);
+        assert_eq!(diff.removed, vec![holding("MSFT", 0.08)]);
+        assert_eq!(
+            diff.changed,
+            vec![WeightChange {
+                holding: holding("AAPL", 0.1),
+                previous_weight: 0.09,
+            }]
+        );
+        assert!(earlier.diff(&earlier).is_empty());
+    }
+}
@@ -0,0 +1,320 @@
+//! Exchange rate related operations
+use crate::error::Error;
+use crate::parsing::ParseWarning;
+use chrono::prelude::*;
+use chrono_tz::Tz;
+use serde::Deserialize;
+use std::fmt;
+
+/// Represents a currency.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Currency {
+    /// The currency's name.
+    pub name: String,
+    /// The currency's code. Can be a physical currency using ISO 4217 or a cryptocurrency.
+    pub code: String,
+}
+
+/// Represents the exchange rate for a currency pair.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ExchangeRate {
+    /// Currency to get the exchange rate for.
+    pub from: Currency,
+    /// Destination currency for the exchange rate.
+    pub to: Currency,
+    /// Value of the exchange rate.
+    pub rate: f64,
+    /// Date the exchange rate corresponds to.
+    pub date: DateTime<Tz>,
+    /// Issues found while parsing the exchange rate, e.g. data last refreshed longer ago than
+    /// the configured maximum age.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Represents a currency pair, e.g. `EUR/USD`.
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+pub struct CurrencyPair {
+    /// Code of the currency to get the exchange rate for.
+    pub from: String,
+    /// Code of the destination currency.
+    pub to: String,
+}
+
+impl CurrencyPair {
+    /// Create a pair from the codes of its currencies, ignoring case.
+    pub fn new(from: &str, to: &str) -> CurrencyPair {
+        CurrencyPair {
+            from: from.trim().to_ascii_uppercase(),
+            to: to.trim().to_ascii_uppercase(),
+        }
+    }
+}
+
+impl fmt::Display for CurrencyPair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.from, self.to)
+    }
+}
+
+/// Represents a cell of a [`RateMatrix`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct RateCell {
+    /// Value of the exchange rate.
+    pub rate: f64,
+    /// Date of the oldest rate the cell was triangulated from, `None` for the rate of a currency
+    /// to itself.
+    pub date: Option<DateTime<Tz>>,
+}
+
+/// Represents the exchange rates between every pair of a set of currencies.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RateMatrix {
+    /// Codes of the currencies, indexing both the rows and the columns of the matrix.
+    pub currencies: Vec<String>,
+    /// Rates from the currency of each row to the currency of each column.
+    pub rates: Vec<Vec<RateCell>>,
+}
+
+impl RateMatrix {
+    /// Triangulate the rates between `pivot` and the destination currencies of `rates`, which
+    /// are the exchange rates from `pivot` to each of them.
+    ///
+    /// The pivot is the first currency of the matrix, followed by the destination currencies
+    /// in order.
+    pub fn triangulate(pivot: &str, rates: &[ExchangeRate]) -> RateMatrix {
+        let legs: Vec<(String, f64, Option<DateTime<Tz>>)> =
+            std::iter::once((pivot.to_string(), 1.0, None))
+                .chain(
+                    rates
+                        .iter()
+                        .map(|r| (r.to.code.clone(), r.rate, Some(r.date))),
+                )
+                .collect();
+        let rates = legs
+            .iter()
+            .enumerate()
+            .map(|(i, (_, from_rate, from_date))| {
+                legs.iter()
+                    .enumerate()
+                    .map(|(j, (_, to_rate, to_date))| {
+                        if i == j {
+                            return RateCell {
+                                rate: 1.0,
+                                date: None,
+                            };
+                        }
+                        RateCell {
+                            rate: to_rate / from_rate,
+                            date: match (from_date, to_date) {
+                                (Some(from), Some(to)) => Some(*from.min(to)),
+                                (from, to) => from.or(*to),
+                            },
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        RateMatrix {
+            currencies: legs.into_iter().map(|(code, _, _)| code).collect(),
+            rates,
+        }
+    }
+
+    /// Exchange rate from the currency with code `from` to the currency with code `to`, if both
+    /// are part of the matrix.
+    pub fn get(&self, from: &str, to: &str) -> Option<&RateCell> {
+        let index = |code: &str| self.currencies.iter().position(|c| c == code);
+        Some(&self.rates[index(from)?][index(to)?])
+    }
+}
+
+/// Represents an update of a pair watched by `Client::fx_stream`.
+#[derive(Debug)]
+pub struct FxUpdate {
+    /// Pair the update refers to.
+    pub pair: CurrencyPair,
+    /// New exchange rate of the pair, or the error raised while retrieving it.
+    pub result: Result<ExchangeRate, Error>,
+}
+
+impl ExchangeRate {
+    /// Whether this rate differs from a `previous` rate of the same pair.
+    pub fn changed_from(&self, previous: Option<&ExchangeRate>) -> bool {
+        match previous {
+            Some(previous) => previous.rate != self.rate || previous.date != self.date,
+            None => true,
+        }
+    }
+}
+
+pub mod parser {
+    use super::*;
+    use crate::deserialize::{from_str, parse_date};
+    use crate::parsing::Parser;
+    use std::io::Read;
+
+    #[derive(Debug, Deserialize)]
+    struct ExchangeRateHelper {
+        #[serde(rename = "Error Message")]
+        error: Option<String>,
+        #[serde(rename = "Realtime Currency Exchange Rate")]
+        data: Option<RealtimeExchangeRate>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct RealtimeExchangeRate {
+        #[serde(rename = "1. From_Currency Code")]
+        from_code: String,
+        #[serde(rename = "2. From_Currency Name")]
+        from_name: String,
+        #[serde(rename = "3. To_Currency Code")]
+        to_code: String,
+        #[serde(rename = "4. To_Currency Name")]
+        to_name: String,
+        #[serde(rename = "5. Exchange Rate", deserialize_with = "from_str")]
+        rate: f64,
+        #[serde(rename = "6. Last Refreshed")]
+        last_refreshed: String,
+        #[serde(rename = "7. Time Zone")]
+        time_zone: String,
+    }
+
+    pub fn parse(mut parser: Parser, reader: impl Read) -> Result<ExchangeRate, Error> {
+        let helper: ExchangeRateHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let data = helper
+            .data
+            .ok_or_else(|| Error::ParsingError("missing exchange rate data".into()))?;
+
+        let time_zone = parser.time_zone(
+            "Realtime Currency Exchange Rate.7. Time Zone",
+            &data.time_zone,
+            Tz::UTC,
+        )?;
+
+        let date = parse_date(&data.last_refreshed, time_zone)?;
+        parser.check_freshness("Realtime Currency Exchange Rate.6. Last Refreshed", &date);
+
+        let exchange_rate = ExchangeRate {
+            from: Currency {
+                name: data.from_name,
+                code: data.from_code,
+            },
+            to: Currency {
+                name: data.to_name,
+                code: data.to_code,
+            },
+            rate: data.rate,
+            date,
+            warnings: parser.finish(),
+        };
+        Ok(exchange_rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deserialize::parse_date;
+    use crate::parsing::{ParseWarningKind, Parser};
+    use chrono_tz::UTC;
+    use std::io::BufReader;
+    use std::time::Duration;
+
+    #[test]
+    fn parse() {
+        let data: &[u8] = include_bytes!("../../tests/json/currency_exchange_rate.json");
+        let exchange_rate = parser::parse(Parser::default(), BufReader::new(data))
+            .expect("failed to parse exchange rate");
+        assert_eq!(
+            exchange_rate,
+            ExchangeRate {
+                from: Currency {
+                    name: "Euro".to_string(),
+                    code: "EUR".to_string(),
+                },
+                to: Currency {
+                    name: "United States Dollar".to_string(),
+                    code: "USD".to_string(),
+                },
+                rate: 1.16665014,
+                date: parse_date("2018-06-23 10:27:49", UTC).unwrap(),
+                warnings: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn parse_stale() {
+        let data: &[u8] = include_bytes!("../../tests/json/currency_exchange_rate.json");
+        let parser = Parser::default().stale_after(Duration::from_secs(24 * 60 * 60));
+        let exchange_rate =
+            parser::parse(parser, BufReader::new(data)).expect("failed to parse exchange rate");
+        assert_eq!(exchange_rate.warnings.len(), 1);
+        assert_eq!(exchange_rate.warnings[0].kind, ParseWarningKind::StaleData);
+        assert_eq!(
+            exchange_rate.warnings[0].path,
+            "Realtime Currency Exchange Rate.6. Last Refreshed"
+        );
+    }
+
+    #[test]
+    fn rate_matrix() {
+        let rate = |to: &str, rate: f64, date: &str| ExchangeRate {
+            from: Currency {
+                name: "United States Dollar".to_string(),
+                code: "USD".to_string(),
+            },
+            to: Currency {
+                name: to.to_string(),
+                code: to.to_string(),
+            },
+            rate,
+            date: parse_date(date, UTC).unwrap(),
+            warnings: vec![],
+        };
+        let rates = [
+            rate("EUR", 0.8, "2024-05-03 10:00:00"),
+            rate("JPY", 160.0, "2024-05-03 09:00:00"),
+        ];
+        let matrix = RateMatrix::triangulate("USD", &rates);
+        assert_eq!(matrix.currencies, vec!["USD", "EUR", "JPY"]);
+
+        let eur_jpy = matrix.get("EUR", "JPY").unwrap();
+        assert_eq!(eur_jpy.rate, 200.0);
+        assert_eq!(eur_jpy.date, Some(rates[1].date));
+        let eur_usd = matrix.get("EUR", "USD").unwrap();
+        assert_eq!(eur_usd.rate, 1.25);
+        assert_eq!(eur_usd.date, Some(rates[0].date));
+        assert_eq!(
+            matrix.get("JPY", "JPY"),
+            Some(&RateCell {
+                rate: 1.0,
+                date: None
+            })
+        );
+        assert_eq!(matrix.get("USD", "GBP"), None);
+    }
+
+    #[test]
+    fn currency_pair() {
+        let pair = CurrencyPair::new(" eur", "Usd");
+        assert_eq!(pair, CurrencyPair::new("EUR", "USD"));
+        assert_eq!(pair.to_string(), "EUR/USD");
+    }
+
+    #[test]
+    fn changed_from() {
+        let data: &[u8] = include_bytes!("../../tests/json/currency_exchange_rate.json");
+        let previous = parser::parse(Parser::default(), BufReader::new(data)).unwrap();
+        let mut rate = previous.clone();
+        assert!(rate.changed_from(None));
+        assert!(!rate.changed_from(Some(&previous)));
+        rate.rate = 1.17;
+        assert!(rate.changed_from(Some(&previous)));
+    }
+}
@@ -0,0 +1,393 @@
+//! Foreign exchange (FX) related operations
+use crate::exchange_rate::{Currency, ExchangeRate};
+use crate::parsing::ParseWarning;
+use crate::time_series::{Entry, IntradayInterval, TimeSeries};
+use chrono::{DateTime, NaiveDate};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Represents a time series of exchange rates for a currency pair.
+///
+/// Unlike [`TimeSeries`], entries don't have any volume, as none is reported for currency pairs.
+#[derive(Debug, Clone)]
+pub struct ForexSeries {
+    /// Currency the exchange rates are quoted for.
+    pub from_symbol: String,
+    /// Currency the exchange rates are quoted in.
+    pub to_symbol: String,
+    /// Date the information was last refreshed at.
+    pub last_refreshed: DateTime<Tz>,
+    /// Entries in the time series, sorted by ascending dates.
+    pub entries: Vec<ForexEntry>,
+    /// Malformed entries skipped while leniently parsing the time series.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Represents a set of exchange rates for a given period in the time series.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ForexEntry {
+    /// Date.
+    pub date: DateTime<Tz>,
+    /// Open value.
+    pub open: f64,
+    /// High value.
+    pub high: f64,
+    /// Low value.
+    pub low: f64,
+    /// Close value.
+    pub close: f64,
+}
+
+impl ForexSeries {
+    /// Close exchange rate on `date`, or on the most recent date before it.
+    pub fn rate_on(&self, date: NaiveDate) -> Option<ExchangeRate> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| e.date.date_naive() <= date)
+            .map(|e| self.exchange_rate(e))
+    }
+
+    /// Close exchange rates of every entry, by ascending dates.
+    ///
+    /// As the API doesn't report currency names in forex time series, currencies are named
+    /// after their codes.
+    pub fn exchange_rates(&self) -> impl Iterator<Item = ExchangeRate> + '_ {
+        self.entries.iter().map(move |e| self.exchange_rate(e))
+    }
+
+    fn exchange_rate(&self, entry: &ForexEntry) -> ExchangeRate {
+        let currency = |code: &str| Currency {
+            name: code.to_string(),
+            code: code.to_string(),
+        };
+        ExchangeRate {
+            from: currency(&self.from_symbol),
+            to: currency(&self.to_symbol),
+            rate: entry.close,
+            date: entry.date,
+            warnings: vec![],
+        }
+    }
+
+    /// Convert `time_series`, quoted in the currency this series is quoted for, into the currency
+    /// this series is quoted in.
+    ///
+    /// Entries are converted at the close rate of the same date, or of the most recent date
+    /// before it. Entries preceding every exchange rate in this series are left out.
+    pub fn convert(&self, time_series: &TimeSeries) -> TimeSeries {
+        let rates: BTreeMap<NaiveDate, f64> = self
+            .entries
+            .iter()
+            .map(|e| (e.date.date_naive(), e.close))
+            .collect();
+        let entries = time_series
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let (_, rate) = rates.range(..=entry.date.date_naive()).next_back()?;
+                Some(Entry {
+                    date: entry.date,
+                    open: entry.open * rate,
+                    high: entry.high * rate,
+                    low: entry.low * rate,
+                    close: entry.close * rate,
+                    volume: entry.volume,
+                    adjusted_close: entry.adjusted_close.map(|v| v * rate),
+                    dividend_amount: entry.dividend_amount.map(|v| v * rate),
+                    split_coefficient: entry.split_coefficient,
+                })
+            })
+            .collect();
+        TimeSeries {
+            symbol: time_series.symbol.clone(),
+            last_refreshed: time_series.last_refreshed,
+            entries,
+            warnings: time_series.warnings.clone(),
+        }
+    }
+}
+
+pub mod parser {
+    use super::*;
+    use crate::deserialize::{from_str, join_path, parse_date};
+    use crate::error::Error;
+    use crate::parsing::Parser;
+    use std::collections::HashMap;
+    use std::io::Read;
+
+    #[derive(Debug, Deserialize)]
+    struct EntryHelper {
+        #[serde(rename = "1. open", deserialize_with = "from_str")]
+        pub open: f64,
+        #[serde(rename = "2. high", deserialize_with = "from_str")]
+        pub high: f64,
+        #[serde(rename = "3. low", deserialize_with = "from_str")]
+        pub low: f64,
+        #[serde(rename = "4. close", deserialize_with = "from_str")]
+        pub close: f64,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct ForexSeriesHelper {
+        #[serde(rename = "Error Message")]
+        error: Option<String>,
+        #[serde(rename = "Meta Data")]
+        metadata: Option<HashMap<String, String>>,
+        #[serde(flatten)]
+        time_series: HashMap<String, serde_json::Value>,
+    }
+
+    /// Parse a response to `FX_DAILY`.
+    pub fn parse(parser: Parser, reader: impl Read) -> Result<ForexSeries, Error> {
+        parse_series(parser, reader, |_| Ok("Time Series FX (Daily)".to_string()))
+    }
+
+    /// Parse a response to `FX_WEEKLY`.
+    pub fn parse_weekly(parser: Parser, reader: impl Read) -> Result<ForexSeries, Error> {
+        parse_series(
+            parser,
+            reader,
+            |_| Ok("Time Series FX (Weekly)".to_string()),
+        )
+    }
+
+    /// Parse a response to `FX_MONTHLY`.
+    pub fn parse_monthly(parser: Parser, reader: impl Read) -> Result<ForexSeries, Error> {
+        parse_series(parser, reader, |_| {
+            Ok("Time Series FX (Monthly)".to_string())
+        })
+    }
+
+    /// Parse a response to `FX_INTRADAY`.
+    pub fn parse_intraday(parser: Parser, reader: impl Read) -> Result<ForexSeries, Error> {
+        parse_series(parser, reader, |metadata| {
+            let (_, interval) = metadata
+                .iter()
+                .find(|(key, _)| key.ends_with(". Interval"))
+                .ok_or_else(|| Error::ParsingError("missing interval".into()))?;
+            let interval = IntradayInterval::from_name(interval)
+                .ok_or_else(|| Error::ParsingError(format!("unknown interval {}", interval)))?;
+            Ok(format!("Time Series FX ({})", interval.to_string()))
+        })
+    }
+
+    /// Parse a forex time series, whose metadata fields are numbered differently depending on
+    /// the function, under the key returned by `time_series_key`.
+    fn parse_series(
+        mut parser: Parser,
+        reader: impl Read,
+        time_series_key: impl Fn(&HashMap<String, String>) -> Result<String, Error>,
+    ) -> Result<ForexSeries, Error> {
+        let helper: ForexSeriesHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let metadata = helper
+            .metadata
+            .ok_or_else(|| Error::ParsingError("missing metadata".into()))?;
+        let field = |name: &str| {
+            metadata
+                .iter()
+                .find(|(key, _)| key.split_once(". ").map(|(_, key)| key) == Some(name))
+                .ok_or_else(|| Error::ParsingError(format!("missing {}", name.to_lowercase())))
+        };
+
+        let from_symbol = field("From Symbol")?.1.to_string();
+        let to_symbol = field("To Symbol")?.1.to_string();
+
+        let (key, time_zone) = field("Time Zone")?;
+        let time_zone = parser.time_zone(key, time_zone, Tz::UTC)?;
+
+        let (key, last_refreshed) = field("Last Refreshed")?;
+        let last_refreshed = parse_date(last_refreshed, time_zone)?;
+        parser.check_freshness(key, &last_refreshed);
+
+        let time_series_key = time_series_key(&metadata)?;
+        let time_series = helper
+            .time_series
+            .get(&time_series_key)
+            .ok_or_else(|| Error::ParsingError("missing time series".into()))?;
+        let time_series: HashMap<String, serde_json::Value> =
+            parser.parse_value(&time_series_key, time_series)?;
+
+        let mut entries: Vec<ForexEntry> = vec![];
+
+        for (d, v) in time_series.iter() {
+            let path = join_path(&time_series_key, d);
+            let entry = parse_date(d, time_zone).and_then(|date| {
+                let v: EntryHelper = parser.parse_value(&path, v)?;
+                Ok(ForexEntry {
+                    date,
+                    open: v.open,
+                    high: v.high,
+                    low: v.low,
+                    close: v.close,
+                })
+            });
+            if let Some(entry) = parser.entry(&path, entry)? {
+                entries.push(entry);
+            }
+        }
+
+        entries.sort_by_key(|e| e.date);
+
+        let forex_series = ForexSeries {
+            from_symbol,
+            to_symbol,
+            last_refreshed,
+            entries,
+            warnings: parser.finish(),
+        };
+        Ok(forex_series)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deserialize::parse_date;
+    use crate::parsing::Parser;
+    use chrono_tz::US::Eastern;
+    use chrono_tz::UTC;
+    use std::io::BufReader;
+
+    #[test]
+    fn parse_daily() {
+        let data: &[u8] = include_bytes!("../../tests/json/fx_daily.json");
+        let forex_series = parser::parse(Parser::default(), BufReader::new(data))
+            .expect("failed to parse entries");
+        assert_eq!(forex_series.from_symbol, "USD");
+        assert_eq!(forex_series.to_symbol, "EUR");
+        assert_eq!(
+            forex_series.last_refreshed,
+            parse_date("2018-06-08 21:55:00", UTC).unwrap()
+        );
+        assert_eq!(forex_series.entries.len(), 5);
+        assert_eq!(
+            forex_series.entries[0],
+            ForexEntry {
+                date: parse_date("2018-06-04", UTC).unwrap(),
+                open: 0.85640,
+                high: 0.85790,
+                low: 0.85300,
+                close: 0.85470,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_intraday() {
+        let data: &[u8] = include_bytes!("../../tests/json/fx_intraday.json");
+        let forex_series = parser::parse_intraday(Parser::default(), BufReader::new(data))
+            .expect("failed to parse entries");
+        assert_eq!(forex_series.from_symbol, "EUR");
+        assert_eq!(forex_series.to_symbol, "USD");
+        assert_eq!(
+            forex_series.last_refreshed,
+            parse_date("2024-05-03 14:15:00", UTC).unwrap()
+        );
+        assert_eq!(forex_series.entries.len(), 4);
+        assert_eq!(
+            forex_series.entries[3],
+            ForexEntry {
+                date: parse_date("2024-05-03 14:15:00", UTC).unwrap(),
+                open: 1.07612,
+                high: 1.07655,
+                low: 1.07598,
+                close: 1.07641,
+            }
+        );
+        assert!(parser::parse(Parser::default(), BufReader::new(data)).is_err());
+    }
+
+    #[test]
+    fn parse_weekly_and_monthly() {
+        let data: &[u8] = include_bytes!("../../tests/json/fx_weekly.json");
+        let weekly = parser::parse_weekly(Parser::default(), BufReader::new(data)).unwrap();
+        assert_eq!(weekly.from_symbol, "EUR");
+        assert_eq!(weekly.entries.len(), 3);
+        assert_eq!(
+            weekly.entries[2],
+            ForexEntry {
+                date: parse_date("2024-05-03", UTC).unwrap(),
+                open: 1.07130,
+                high: 1.08120,
+                low: 1.06490,
+                close: 1.07610,
+            }
+        );
+        assert!(parser::parse_monthly(Parser::default(), BufReader::new(data)).is_err());
+
+        let data: &[u8] = include_bytes!("../../tests/json/fx_monthly.json");
+        let monthly = parser::parse_monthly(Parser::default(), BufReader::new(data)).unwrap();
+        assert_eq!(monthly.to_symbol, "USD");
+        assert_eq!(
+            monthly.last_refreshed,
+            parse_date("2024-05-03 20:55:00", UTC).unwrap()
+        );
+        let closes: Vec<f64> = monthly.entries.iter().map(|e| e.close).collect();
+        assert_eq!(closes, vec![1.07900, 1.06650, 1.07610]);
+    }
+
+    #[test]
+    fn rate_on() {
+        let data: &[u8] = include_bytes!("../../tests/json/fx_daily.json");
+        let forex_series = parser::parse(Parser::default(), BufReader::new(data)).unwrap();
+        let date = |value: &str| NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap();
+        let rate = forex_series.rate_on(date("2018-06-10")).unwrap();
+        assert_eq!(rate.from.code, "USD");
+        assert_eq!(rate.to.code, "EUR");
+        assert_eq!(rate.rate, 0.84880);
+        assert_eq!(rate.date, parse_date("2018-06-08", UTC).unwrap());
+        assert_eq!(
+            forex_series.rate_on(date("2018-06-04")).unwrap().rate,
+            0.85470
+        );
+        assert_eq!(forex_series.rate_on(date("2018-06-03")), None);
+
+        let rates: Vec<ExchangeRate> = forex_series.exchange_rates().collect();
+        assert_eq!(rates.len(), 5);
+        assert_eq!(rates[0].rate, 0.85470);
+        assert_eq!(rates[4], rate);
+    }
+
+    #[test]
+    fn convert() {
+        let data: &[u8] = include_bytes!("../../tests/json/fx_daily.json");
+        let forex_series = parser::parse(Parser::default(), BufReader::new(data)).unwrap();
+        let entry = |date: &str, close: f64| Entry {
+            date: parse_date(date, Eastern).unwrap(),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 100,
+            adjusted_close: Some(close),
+            dividend_amount: None,
+            split_coefficient: None,
+        };
+        let time_series = TimeSeries {
+            symbol: "MSFT".to_string(),
+            last_refreshed: parse_date("2018-06-09", Eastern).unwrap(),
+            entries: vec![
+                entry("2018-06-01", 100.0),
+                entry("2018-06-04", 100.0),
+                entry("2018-06-09", 200.0),
+            ],
+            warnings: vec![],
+        };
+        let converted = forex_series.convert(&time_series);
+        assert_eq!(converted.entries.len(), 2);
+        assert!((converted.entries[0].close - 85.47).abs() < 1e-9);
+        assert!((converted.entries[1].close - 169.76).abs() < 1e-9);
+        assert_eq!(
+            converted.entries[1].adjusted_close,
+            Some(converted.entries[1].close)
+        );
+        assert_eq!(converted.entries[1].volume, 100);
+    }
+}
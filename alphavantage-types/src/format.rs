@@ -0,0 +1,199 @@
+//! Formatting of prices, volumes and changes for display
+//!
+//! Values are formatted according to a [`Locale`], e.g. to show a quote in a UI:
+//!
+//! ```
+//! use alphavantage_types::exchange_rate::Currency;
+//! use alphavantage_types::format::Locale;
+//!
+//! let usd = Currency {
+//!     name: "United States Dollar".to_string(),
+//!     code: "USD".to_string(),
+//! };
+//! assert_eq!(Locale::EN_US.price(1234.5, &usd), "$1,234.50");
+//! assert_eq!(Locale::DE_DE.price(1234.5, &usd), "1.234,50 $");
+//! assert_eq!(Locale::EN_US.volume(28232197), "28,232,197");
+//! assert_eq!(Locale::EN_US.percent(1.254), "+1.25%");
+//! ```
+use crate::exchange_rate::Currency;
+
+/// Symbols of common currencies, by code.
+const SYMBOLS: [(&str, &str); 16] = [
+    ("USD", "$"),
+    ("EUR", "€"),
+    ("GBP", "£"),
+    ("JPY", "¥"),
+    ("CNY", "¥"),
+    ("INR", "₹"),
+    ("KRW", "₩"),
+    ("CAD", "CA$"),
+    ("AUD", "A$"),
+    ("HKD", "HK$"),
+    ("BRL", "R$"),
+    ("CHF", "CHF"),
+    ("RUB", "₽"),
+    ("ILS", "₪"),
+    ("BTC", "₿"),
+    ("ETH", "Ξ"),
+];
+
+/// Codes of the currencies without minor units.
+const ZERO_DECIMAL_CURRENCIES: [&str; 4] = ["JPY", "KRW", "ISK", "CLP"];
+
+/// Represents the conventions used to format numbers, e.g. `1,234.50` in the United States.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    /// Separator between the integer and fractional parts.
+    pub decimal: char,
+    /// Separator between groups of thousands.
+    pub thousands: char,
+    /// Whether currency symbols are placed before amounts, e.g. `$1.00` rather than `1,00 €`.
+    pub symbol_first: bool,
+}
+
+impl Default for Locale {
+    fn default() -> Locale {
+        Locale::EN_US
+    }
+}
+
+impl Locale {
+    /// English, as used in the United States.
+    pub const EN_US: Locale = Locale {
+        decimal: '.',
+        thousands: ',',
+        symbol_first: true,
+    };
+
+    /// German, as used in Germany.
+    pub const DE_DE: Locale = Locale {
+        decimal: ',',
+        thousands: '.',
+        symbol_first: false,
+    };
+
+    /// French, as used in France, grouping thousands with narrow no-break spaces.
+    pub const FR_FR: Locale = Locale {
+        decimal: ',',
+        thousands: '\u{202f}',
+        symbol_first: false,
+    };
+
+    /// Format `value` with the given number of `decimals` and grouped thousands.
+    pub fn number(&self, value: f64, decimals: usize) -> String {
+        let formatted = format!("{:.*}", decimals, value.abs());
+        let (integer, fraction) = match formatted.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (formatted.as_str(), None),
+        };
+        let mut number = String::new();
+        if value.is_sign_negative() && formatted.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+            number.push('-');
+        }
+        for (i, digit) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                number.push(self.thousands);
+            }
+            number.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            number.push(self.decimal);
+            number.push_str(fraction);
+        }
+        number
+    }
+
+    /// Format a price or rate quoted in `currency`, with its symbol when it's a common currency
+    /// and its code otherwise.
+    ///
+    /// Prices are shown with two decimals, none for currencies without minor units, and four
+    /// below one, e.g. for exchange rates or digital currencies.
+    pub fn price(&self, value: f64, currency: &Currency) -> String {
+        let decimals = if value.abs() < 1.0 {
+            4
+        } else if ZERO_DECIMAL_CURRENCIES.contains(&currency.code.as_str()) {
+            0
+        } else {
+            2
+        };
+        let number = self.number(value, decimals);
+        let (sign, number) = match number.strip_prefix('-') {
+            Some(number) => ("-", number),
+            None => ("", number.as_str()),
+        };
+        match (symbol(&currency.code), self.symbol_first) {
+            (Some(symbol), true) => format!("{}{}{}", sign, symbol, number),
+            (Some(symbol), false) => format!("{}{} {}", sign, number, symbol),
+            (None, _) => format!("{}{} {}", sign, number, currency.code),
+        }
+    }
+
+    /// Format a trading volume with grouped thousands.
+    pub fn volume(&self, volume: u64) -> String {
+        self.number(volume as f64, 0)
+    }
+
+    /// Format a change given as a percentage, e.g. a quote's change percentage, with two
+    /// decimals and an explicit sign.
+    pub fn percent(&self, change: f64) -> String {
+        let number = self.number(change, 2);
+        let zero = !number.chars().any(|c| ('1'..='9').contains(&c));
+        if number.starts_with('-') || zero {
+            format!("{}%", number)
+        } else {
+            format!("+{}%", number)
+        }
+    }
+}
+
+/// Symbol of the currency with the given `code`, ignoring case, if it's a common currency.
+pub fn symbol(code: &str) -> Option<&'static str> {
+    SYMBOLS
+        .iter()
+        .find(|(c, _)| c.eq_ignore_ascii_case(code))
+        .map(|(_, symbol)| *symbol)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn currency(code: &str) -> Currency {
+        Currency {
+            name: code.to_string(),
+            code: code.to_string(),
+        }
+    }
+
+    #[test]
+    fn numbers() {
+        let locale = Locale::default();
+        assert_eq!(locale.number(1234567.891, 2), "1,234,567.89");
+        assert_eq!(locale.number(-999.5, 0), "-1,000");
+        assert_eq!(locale.number(-0.001, 2), "0.00");
+        assert_eq!(Locale::DE_DE.number(1234.5, 1), "1.234,5");
+        assert_eq!(Locale::FR_FR.number(1234.5, 1), "1\u{202f}234,5");
+    }
+
+    #[test]
+    fn prices() {
+        let locale = Locale::EN_US;
+        assert_eq!(locale.price(-1234.5, &currency("USD")), "-$1,234.50");
+        assert_eq!(locale.price(15234.4, &currency("JPY")), "¥15,234");
+        assert_eq!(locale.price(0.84880, &currency("EUR")), "€0.8488");
+        assert_eq!(locale.price(12.0, &currency("SEK")), "12.00 SEK");
+        assert_eq!(Locale::DE_DE.price(1234.5, &currency("EUR")), "1.234,50 €");
+        assert_eq!(symbol("btc"), Some("₿"));
+    }
+
+    #[test]
+    fn volumes_and_percents() {
+        let locale = Locale::EN_US;
+        assert_eq!(locale.volume(999), "999");
+        assert_eq!(locale.volume(22165128), "22,165,128");
+        assert_eq!(locale.percent(0.5), "+0.50%");
+        assert_eq!(locale.percent(-2.346), "-2.35%");
+        assert_eq!(locale.percent(0.001), "0.00%");
+        assert_eq!(Locale::DE_DE.percent(1.5), "+1,50%");
+    }
+}
@@ -0,0 +1,381 @@
+//! Functions exposed by the API
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// Represents a function exposed by the API, as named in requests (e.g. `GLOBAL_QUOTE`).
+///
+/// Functions which aren't supported by the crate yet are represented as [`Function::Custom`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Function {
+    /// `TIME_SERIES_INTRADAY`.
+    TimeSeriesIntraday,
+    /// `TIME_SERIES_INTRADAY_EXTENDED`, the legacy function serving slices of the trailing two
+    /// years of intraday data as CSV.
+    TimeSeriesIntradayExtended,
+    /// `TIME_SERIES_DAILY`.
+    TimeSeriesDaily,
+    /// `TIME_SERIES_DAILY_ADJUSTED`.
+    TimeSeriesDailyAdjusted,
+    /// `TIME_SERIES_WEEKLY`.
+    TimeSeriesWeekly,
+    /// `TIME_SERIES_WEEKLY_ADJUSTED`.
+    TimeSeriesWeeklyAdjusted,
+    /// `TIME_SERIES_MONTHLY`.
+    TimeSeriesMonthly,
+    /// `TIME_SERIES_MONTHLY_ADJUSTED`.
+    TimeSeriesMonthlyAdjusted,
+    /// `CURRENCY_EXCHANGE_RATE`.
+    CurrencyExchangeRate,
+    /// `CRYPTO_INTRADAY`.
+    CryptoIntraday,
+    /// `DIGITAL_CURRENCY_DAILY`.
+    DigitalCurrencyDaily,
+    /// `DIGITAL_CURRENCY_WEEKLY`.
+    DigitalCurrencyWeekly,
+    /// `DIGITAL_CURRENCY_MONTHLY`.
+    DigitalCurrencyMonthly,
+    /// `FX_INTRADAY`.
+    FxIntraday,
+    /// `FX_DAILY`.
+    FxDaily,
+    /// `FX_WEEKLY`.
+    FxWeekly,
+    /// `FX_MONTHLY`.
+    FxMonthly,
+    /// `GLOBAL_QUOTE`.
+    GlobalQuote,
+    /// `REALTIME_BULK_QUOTES`.
+    RealtimeBulkQuotes,
+    /// `SYMBOL_SEARCH`.
+    SymbolSearch,
+    /// `OVERVIEW`.
+    Overview,
+    /// `ETF_PROFILE`.
+    EtfProfile,
+    /// `DIVIDENDS`.
+    Dividends,
+    /// `SPLITS`.
+    Splits,
+    /// `EARNINGS`.
+    Earnings,
+    /// `EARNINGS_ESTIMATES`.
+    EarningsEstimates,
+    /// `EARNINGS_CALENDAR`.
+    EarningsCalendar,
+    /// `IPO_CALENDAR`.
+    IpoCalendar,
+    /// `INCOME_STATEMENT`.
+    IncomeStatement,
+    /// `CASH_FLOW`.
+    CashFlow,
+    /// `WTI`.
+    Wti,
+    /// `BRENT`.
+    Brent,
+    /// `NATURAL_GAS`.
+    NaturalGas,
+    /// `COPPER`.
+    Copper,
+    /// `ALUMINUM`.
+    Aluminum,
+    /// `WHEAT`.
+    Wheat,
+    /// `CORN`.
+    Corn,
+    /// `COTTON`.
+    Cotton,
+    /// `SUGAR`.
+    Sugar,
+    /// `COFFEE`.
+    Coffee,
+    /// `TREASURY_YIELD`.
+    TreasuryYield,
+    /// `FEDERAL_FUNDS_RATE`.
+    FederalFundsRate,
+    /// `REAL_GDP`.
+    RealGdp,
+    /// `CPI`.
+    Cpi,
+    /// `ANALYTICS_FIXED_WINDOW`.
+    AnalyticsFixedWindow,
+    /// `ANALYTICS_SLIDING_WINDOW`.
+    AnalyticsSlidingWindow,
+    /// `TOP_GAINERS_LOSERS`.
+    TopGainersLosers,
+    /// `LISTING_STATUS`.
+    ListingStatus,
+    /// `NEWS_SENTIMENT`.
+    NewsSentiment,
+    /// `DIGITAL_CURRENCY_LIST`, the list of digital currencies served next to the query
+    /// endpoint.
+    DigitalCurrencyList,
+    /// `PHYSICAL_CURRENCY_LIST`, the list of physical currencies served next to the query
+    /// endpoint.
+    PhysicalCurrencyList,
+    /// Any other function, by name.
+    Custom(String),
+}
+
+const FUNCTIONS: [Function; 51] = [
+    Function::TimeSeriesIntraday,
+    Function::TimeSeriesIntradayExtended,
+    Function::TimeSeriesDaily,
+    Function::TimeSeriesDailyAdjusted,
+    Function::TimeSeriesWeekly,
+    Function::TimeSeriesWeeklyAdjusted,
+    Function::TimeSeriesMonthly,
+    Function::TimeSeriesMonthlyAdjusted,
+    Function::CurrencyExchangeRate,
+    Function::CryptoIntraday,
+    Function::DigitalCurrencyDaily,
+    Function::DigitalCurrencyWeekly,
+    Function::DigitalCurrencyMonthly,
+    Function::FxIntraday,
+    Function::FxDaily,
+    Function::FxWeekly,
+    Function::FxMonthly,
+    Function::GlobalQuote,
+    Function::RealtimeBulkQuotes,
+    Function::SymbolSearch,
+    Function::Overview,
+    Function::EtfProfile,
+    Function::Dividends,
+    Function::Splits,
+    Function::Earnings,
+    Function::EarningsEstimates,
+    Function::EarningsCalendar,
+    Function::IpoCalendar,
+    Function::IncomeStatement,
+    Function::CashFlow,
+    Function::Wti,
+    Function::Brent,
+    Function::NaturalGas,
+    Function::Copper,
+    Function::Aluminum,
+    Function::Wheat,
+    Function::Corn,
+    Function::Cotton,
+    Function::Sugar,
+    Function::Coffee,
+    Function::TreasuryYield,
+    Function::FederalFundsRate,
+    Function::RealGdp,
+    Function::Cpi,
+    Function::AnalyticsFixedWindow,
+    Function::AnalyticsSlidingWindow,
+    Function::TopGainersLosers,
+    Function::ListingStatus,
+    Function::NewsSentiment,
+    Function::DigitalCurrencyList,
+    Function::PhysicalCurrencyList,
+];
+
+/// Represents a function the API deprecated, renamed or removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deprecation {
+    /// Name of the deprecated function in requests.
+    pub function: &'static str,
+    /// Function replacing it, `None` if it was removed without any replacement.
+    pub replacement: Option<Function>,
+    /// Parameters added to requests for the replacement when they're missing, e.g. the interval
+    /// the deprecated function was reported at.
+    pub defaults: &'static [(&'static str, &'static str)],
+    /// Description of the change.
+    pub note: &'static str,
+}
+
+static DEPRECATIONS: [Deprecation; 3] = [
+    Deprecation {
+        function: "DIGITAL_CURRENCY_INTRADAY",
+        replacement: Some(Function::CryptoIntraday),
+        defaults: &[("interval", "5min")],
+        note: "replaced by CRYPTO_INTRADAY, which takes an interval",
+    },
+    Deprecation {
+        function: "SECTOR",
+        replacement: None,
+        defaults: &[],
+        note: "sector performances are no longer reported",
+    },
+    Deprecation {
+        function: "CRYPTO_RATING",
+        replacement: None,
+        defaults: &[],
+        note: "ratings of digital currencies are no longer reported",
+    },
+];
+
+impl Deprecation {
+    /// Parameters of a request for the replacement, from the `params` of a request for the
+    /// deprecated function.
+    pub fn params<'a>(&self, params: &[(&'a str, &'a str)]) -> Vec<(&'a str, &'a str)> {
+        let mut params = params.to_vec();
+        for (name, value) in self.defaults {
+            if !params.iter().any(|p| p.0 == *name) {
+                params.push((name, value));
+            }
+        }
+        params
+    }
+}
+
+impl fmt::Display for Deprecation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} is deprecated: {}", self.function, self.note)
+    }
+}
+
+impl Function {
+    /// Deprecation of the function named `name`, if the API deprecated it.
+    pub fn deprecation(name: &str) -> Option<&'static Deprecation> {
+        DEPRECATIONS.iter().find(|d| d.function == name)
+    }
+
+    /// Name of the function in requests.
+    pub fn as_str(&self) -> &str {
+        use self::Function::*;
+        match self {
+            TimeSeriesIntraday => "TIME_SERIES_INTRADAY",
+            TimeSeriesIntradayExtended => "TIME_SERIES_INTRADAY_EXTENDED",
+            TimeSeriesDaily => "TIME_SERIES_DAILY",
+            TimeSeriesDailyAdjusted => "TIME_SERIES_DAILY_ADJUSTED",
+            TimeSeriesWeekly => "TIME_SERIES_WEEKLY",
+            TimeSeriesWeeklyAdjusted => "TIME_SERIES_WEEKLY_ADJUSTED",
+            TimeSeriesMonthly => "TIME_SERIES_MONTHLY",
+            TimeSeriesMonthlyAdjusted => "TIME_SERIES_MONTHLY_ADJUSTED",
+            CurrencyExchangeRate => "CURRENCY_EXCHANGE_RATE",
+            CryptoIntraday => "CRYPTO_INTRADAY",
+            DigitalCurrencyDaily => "DIGITAL_CURRENCY_DAILY",
+            DigitalCurrencyWeekly => "DIGITAL_CURRENCY_WEEKLY",
+            DigitalCurrencyMonthly => "DIGITAL_CURRENCY_MONTHLY",
+            FxIntraday => "FX_INTRADAY",
+            FxDaily => "FX_DAILY",
+            FxWeekly => "FX_WEEKLY",
+            FxMonthly => "FX_MONTHLY",
+            GlobalQuote => "GLOBAL_QUOTE",
+            RealtimeBulkQuotes => "REALTIME_BULK_QUOTES",
+            SymbolSearch => "SYMBOL_SEARCH",
+            Overview => "OVERVIEW",
+            EtfProfile => "ETF_PROFILE",
+            Dividends => "DIVIDENDS",
+            Splits => "SPLITS",
+            Earnings => "EARNINGS",
+            EarningsEstimates => "EARNINGS_ESTIMATES",
+            EarningsCalendar => "EARNINGS_CALENDAR",
+            IpoCalendar => "IPO_CALENDAR",
+            IncomeStatement => "INCOME_STATEMENT",
+            CashFlow => "CASH_FLOW",
+            Wti => "WTI",
+            Brent => "BRENT",
+            NaturalGas => "NATURAL_GAS",
+            Copper => "COPPER",
+            Aluminum => "ALUMINUM",
+            Wheat => "WHEAT",
+            Corn => "CORN",
+            Cotton => "COTTON",
+            Sugar => "SUGAR",
+            Coffee => "COFFEE",
+            TreasuryYield => "TREASURY_YIELD",
+            FederalFundsRate => "FEDERAL_FUNDS_RATE",
+            RealGdp => "REAL_GDP",
+            Cpi => "CPI",
+            AnalyticsFixedWindow => "ANALYTICS_FIXED_WINDOW",
+            AnalyticsSlidingWindow => "ANALYTICS_SLIDING_WINDOW",
+            TopGainersLosers => "TOP_GAINERS_LOSERS",
+            ListingStatus => "LISTING_STATUS",
+            NewsSentiment => "NEWS_SENTIMENT",
+            DigitalCurrencyList => "DIGITAL_CURRENCY_LIST",
+            PhysicalCurrencyList => "PHYSICAL_CURRENCY_LIST",
+            Custom(name) => name,
+        }
+    }
+
+    /// Whether the function isn't supported by the crate.
+    pub fn is_custom(&self) -> bool {
+        matches!(self, Function::Custom(_))
+    }
+
+    /// Whether the function is restricted to premium tiers.
+    ///
+    /// Custom functions are assumed to be available to every tier.
+    pub fn is_premium(&self) -> bool {
+        use self::Function::*;
+        matches!(
+            self,
+            TimeSeriesDailyAdjusted | CryptoIntraday | FxIntraday | RealtimeBulkQuotes
+        )
+    }
+
+    /// Every function supported by the crate, excluding [`Function::Custom`].
+    pub fn supported() -> &'static [Function] {
+        &FUNCTIONS
+    }
+}
+
+impl FromStr for Function {
+    type Err = Infallible;
+
+    /// Parse a function by name, falling back to [`Function::Custom`] for unsupported ones.
+    fn from_str(name: &str) -> Result<Function, Infallible> {
+        let function = FUNCTIONS
+            .iter()
+            .find(|f| f.as_str() == name)
+            .cloned()
+            .unwrap_or_else(|| Function::Custom(name.to_string()));
+        Ok(function)
+    }
+}
+
+impl From<&str> for Function {
+    fn from(name: &str) -> Function {
+        match name.parse() {
+            Ok(function) => function,
+            Err(never) => match never {},
+        }
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names() {
+        for function in Function::supported() {
+            assert_eq!(Function::from(function.as_str()), *function);
+        }
+        assert_eq!(
+            "GLOBAL_QUOTE".parse::<Function>().unwrap(),
+            Function::GlobalQuote
+        );
+        let custom = Function::from("INSIDER_TRANSACTIONS");
+        assert_eq!(custom, Function::Custom("INSIDER_TRANSACTIONS".to_string()));
+        assert_eq!(custom.to_string(), "INSIDER_TRANSACTIONS");
+    }
+
+    #[test]
+    fn deprecations() {
+        let deprecation = Function::deprecation("DIGITAL_CURRENCY_INTRADAY").unwrap();
+        assert_eq!(deprecation.replacement, Some(Function::CryptoIntraday));
+        assert_eq!(
+            deprecation.params(&[("symbol", "ETH"), ("market", "USD")]),
+            vec![("symbol", "ETH"), ("market", "USD"), ("interval", "5min")]
+        );
+        assert_eq!(
+            deprecation.params(&[("interval", "1min")]),
+            vec![("interval", "1min")]
+        );
+        assert_eq!(Function::deprecation("SECTOR").unwrap().replacement, None);
+        assert_eq!(Function::deprecation("GLOBAL_QUOTE"), None);
+        for deprecation in DEPRECATIONS.iter() {
+            assert!(Function::from(deprecation.function).is_custom());
+        }
+    }
+}
@@ -0,0 +1,1434 @@
+//! Fundamental data related operations
+use crate::error::Error;
+use crate::parsing::ParseWarning;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Represents the company information and key financial metrics for an equity.
+///
+/// Metrics the API reports as `None` or `-` are left out.
+#[derive(Debug, Default, PartialEq, Clone, Serialize)]
+pub struct CompanyOverview {
+    /// Symbol the overview refers to.
+    pub symbol: String,
+    /// Asset type (e.g. common stock).
+    pub asset_type: String,
+    /// Company name.
+    pub name: String,
+    /// Description of the company.
+    pub description: String,
+    /// Central Index Key of the company at the SEC.
+    pub cik: String,
+    /// Exchange the equity is listed on.
+    pub exchange: String,
+    /// Currency the equity is quoted in.
+    pub currency: String,
+    /// Country the company is based in.
+    pub country: String,
+    /// Sector the company operates in.
+    pub sector: String,
+    /// Industry the company operates in.
+    pub industry: String,
+    /// Address of the company's headquarters.
+    pub address: String,
+    /// Month the fiscal year of the company ends in.
+    pub fiscal_year_end: String,
+    /// End of the latest quarter reported.
+    pub latest_quarter: Option<NaiveDate>,
+    /// Market capitalization.
+    pub market_capitalization: Option<u64>,
+    /// Earnings before interest, taxes, depreciation and amortization.
+    pub ebitda: Option<i64>,
+    /// Price to earnings ratio.
+    pub pe_ratio: Option<f64>,
+    /// Price to earnings to growth ratio.
+    pub peg_ratio: Option<f64>,
+    /// Book value per share.
+    pub book_value: Option<f64>,
+    /// Dividend per share.
+    pub dividend_per_share: Option<f64>,
+    /// Dividend yield.
+    pub dividend_yield: Option<f64>,
+    /// Earnings per share.
+    pub eps: Option<f64>,
+    /// Revenue per share over the trailing twelve months.
+    pub revenue_per_share_ttm: Option<f64>,
+    /// Profit margin.
+    pub profit_margin: Option<f64>,
+    /// Operating margin over the trailing twelve months.
+    pub operating_margin_ttm: Option<f64>,
+    /// Return on assets over the trailing twelve months.
+    pub return_on_assets_ttm: Option<f64>,
+    /// Return on equity over the trailing twelve months.
+    pub return_on_equity_ttm: Option<f64>,
+    /// Revenue over the trailing twelve months.
+    pub revenue_ttm: Option<i64>,
+    /// Gross profit over the trailing twelve months.
+    pub gross_profit_ttm: Option<i64>,
+    /// Diluted earnings per share over the trailing twelve months.
+    pub diluted_eps_ttm: Option<f64>,
+    /// Year over year growth of the quarterly earnings.
+    pub quarterly_earnings_growth_yoy: Option<f64>,
+    /// Year over year growth of the quarterly revenue.
+    pub quarterly_revenue_growth_yoy: Option<f64>,
+    /// Price targeted by analysts.
+    pub analyst_target_price: Option<f64>,
+    /// Number of analysts rating the equity a strong buy.
+    pub analyst_rating_strong_buy: Option<u32>,
+    /// Number of analysts rating the equity a buy.
+    pub analyst_rating_buy: Option<u32>,
+    /// Number of analysts rating the equity a hold.
+    pub analyst_rating_hold: Option<u32>,
+    /// Number of analysts rating the equity a sell.
+    pub analyst_rating_sell: Option<u32>,
+    /// Number of analysts rating the equity a strong sell.
+    pub analyst_rating_strong_sell: Option<u32>,
+    /// Trailing price to earnings ratio.
+    pub trailing_pe: Option<f64>,
+    /// Forward price to earnings ratio.
+    pub forward_pe: Option<f64>,
+    /// Price to sales ratio over the trailing twelve months.
+    pub price_to_sales_ratio_ttm: Option<f64>,
+    /// Price to book ratio.
+    pub price_to_book_ratio: Option<f64>,
+    /// Enterprise value to revenue ratio.
+    pub ev_to_revenue: Option<f64>,
+    /// Enterprise value to EBITDA ratio.
+    pub ev_to_ebitda: Option<f64>,
+    /// Beta of the equity.
+    pub beta: Option<f64>,
+    /// Highest price over the last 52 weeks.
+    pub week_52_high: Option<f64>,
+    /// Lowest price over the last 52 weeks.
+    pub week_52_low: Option<f64>,
+    /// Moving average of the price over the last 50 days.
+    pub day_50_moving_average: Option<f64>,
+    /// Moving average of the price over the last 200 days.
+    pub day_200_moving_average: Option<f64>,
+    /// Number of shares outstanding.
+    pub shares_outstanding: Option<u64>,
+    /// Date the latest dividend is paid on.
+    pub dividend_date: Option<NaiveDate>,
+    /// Ex-dividend date of the latest dividend.
+    pub ex_dividend_date: Option<NaiveDate>,
+    /// Issues found while parsing the overview, e.g. fields unknown to the crate. Not compared by
+    /// [`CompanyOverview::diff`].
+    #[serde(skip)]
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Represents the income statement reported for a fiscal year or quarter.
+///
+/// Amounts are in the reported currency, and left out when the API reports them as `None`.
+#[derive(Debug, Default, PartialEq, Clone, Serialize)]
+pub struct IncomeReport {
+    /// Last day of the fiscal period.
+    pub fiscal_date_ending: NaiveDate,
+    /// Currency the amounts are reported in.
+    pub reported_currency: String,
+    /// Gross profit.
+    pub gross_profit: Option<i64>,
+    /// Total revenue.
+    pub total_revenue: Option<i64>,
+    /// Cost of revenue.
+    pub cost_of_revenue: Option<i64>,
+    /// Cost of goods and services sold.
+    pub cost_of_goods_and_services_sold: Option<i64>,
+    /// Operating income.
+    pub operating_income: Option<i64>,
+    /// Selling, general and administrative expenses.
+    pub selling_general_and_administrative: Option<i64>,
+    /// Research and development expenses.
+    pub research_and_development: Option<i64>,
+    /// Operating expenses.
+    pub operating_expenses: Option<i64>,
+    /// Net investment income.
+    pub investment_income_net: Option<i64>,
+    /// Net interest income.
+    pub net_interest_income: Option<i64>,
+    /// Interest income.
+    pub interest_income: Option<i64>,
+    /// Interest expense.
+    pub interest_expense: Option<i64>,
+    /// Non-interest income.
+    pub non_interest_income: Option<i64>,
+    /// Other non-operating income.
+    pub other_non_operating_income: Option<i64>,
+    /// Depreciation.
+    pub depreciation: Option<i64>,
+    /// Depreciation and amortization.
+    pub depreciation_and_amortization: Option<i64>,
+    /// Income before taxes.
+    pub income_before_tax: Option<i64>,
+    /// Income tax expense.
+    pub income_tax_expense: Option<i64>,
+    /// Interest and debt expense.
+    pub interest_and_debt_expense: Option<i64>,
+    /// Net income from continuing operations.
+    pub net_income_from_continuing_operations: Option<i64>,
+    /// Comprehensive income, net of taxes.
+    pub comprehensive_income_net_of_tax: Option<i64>,
+    /// Earnings before interest and taxes.
+    pub ebit: Option<i64>,
+    /// Earnings before interest, taxes, depreciation and amortization.
+    pub ebitda: Option<i64>,
+    /// Net income.
+    pub net_income: Option<i64>,
+}
+
+/// Represents the income statements of a company.
+#[derive(Debug, PartialEq, Clone)]
+pub struct IncomeStatement {
+    /// Symbol the income statements refer to.
+    pub symbol: String,
+    /// Annual reports, sorted by ascending fiscal dates.
+    pub annual: Vec<IncomeReport>,
+    /// Quarterly reports, sorted by ascending fiscal dates.
+    pub quarterly: Vec<IncomeReport>,
+    /// Malformed reports skipped while leniently parsing the income statements.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Represents the cash flow statement reported for a fiscal year or quarter.
+///
+/// Amounts are in the reported currency, and left out when the API reports them as `None`.
+#[derive(Debug, Default, PartialEq, Clone, Serialize)]
+pub struct CashFlowReport {
+    /// Last day of the fiscal period.
+    pub fiscal_date_ending: NaiveDate,
+    /// Currency the amounts are reported in.
+    pub reported_currency: String,
+    /// Cash flow from operating activities.
+    pub operating_cashflow: Option<i64>,
+    /// Payments for operating activities.
+    pub payments_for_operating_activities: Option<i64>,
+    /// Proceeds from operating activities.
+    pub proceeds_from_operating_activities: Option<i64>,
+    /// Change in operating liabilities.
+    pub change_in_operating_liabilities: Option<i64>,
+    /// Change in operating assets.
+    pub change_in_operating_assets: Option<i64>,
+    /// Depreciation, depletion and amortization.
+    pub depreciation_depletion_and_amortization: Option<i64>,
+    /// Capital expenditures.
+    pub capital_expenditures: Option<i64>,
+    /// Change in receivables.
+    pub change_in_receivables: Option<i64>,
+    /// Change in inventory.
+    pub change_in_inventory: Option<i64>,
+    /// Profit or loss.
+    pub profit_loss: Option<i64>,
+    /// Cash flow from investing activities.
+    pub cashflow_from_investment: Option<i64>,
+    /// Cash flow from financing activities.
+    pub cashflow_from_financing: Option<i64>,
+    /// Net proceeds from repayments of short term debt.
+    pub proceeds_from_repayments_of_short_term_debt: Option<i64>,
+    /// Payments for the repurchase of common stock.
+    pub payments_for_repurchase_of_common_stock: Option<i64>,
+    /// Payments for the repurchase of equity.
+    pub payments_for_repurchase_of_equity: Option<i64>,
+    /// Payments for the repurchase of preferred stock.
+    pub payments_for_repurchase_of_preferred_stock: Option<i64>,
+    /// Dividends paid.
+    pub dividend_payout: Option<i64>,
+    /// Dividends paid on common stock.
+    pub dividend_payout_common_stock: Option<i64>,
+    /// Dividends paid on preferred stock.
+    pub dividend_payout_preferred_stock: Option<i64>,
+    /// Proceeds from the issuance of common stock.
+    pub proceeds_from_issuance_of_common_stock: Option<i64>,
+    /// Net proceeds from the issuance of long term debt and capital securities.
+    pub proceeds_from_issuance_of_long_term_debt_and_capital_securities_net: Option<i64>,
+    /// Proceeds from the issuance of preferred stock.
+    pub proceeds_from_issuance_of_preferred_stock: Option<i64>,
+    /// Net proceeds from the repurchase of equity.
+    pub proceeds_from_repurchase_of_equity: Option<i64>,
+    /// Proceeds from the sale of treasury stock.
+    pub proceeds_from_sale_of_treasury_stock: Option<i64>,
+    /// Change in cash and cash equivalents.
+    pub change_in_cash_and_cash_equivalents: Option<i64>,
+    /// Effect of exchange rate changes on cash.
+    pub change_in_exchange_rate: Option<i64>,
+    /// Net income.
+    pub net_income: Option<i64>,
+}
+
+/// Represents the cash flow statements of a company.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CashFlow {
+    /// Symbol the cash flow statements refer to.
+    pub symbol: String,
+    /// Annual reports, sorted by ascending fiscal dates.
+    pub annual: Vec<CashFlowReport>,
+    /// Quarterly reports, sorted by ascending fiscal dates.
+    pub quarterly: Vec<CashFlowReport>,
+    /// Malformed reports skipped while leniently parsing the cash flow statements.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Represents a set of symbols grouped by the sector and industry they operate in.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Classification {
+    /// Symbols in each sector.
+    pub sectors: BTreeMap<String, Vec<String>>,
+    /// Symbols in each industry.
+    pub industries: BTreeMap<String, Vec<String>>,
+}
+
+impl Classification {
+    /// Group the symbols of `overviews` by sector and industry.
+    pub fn new(overviews: &[CompanyOverview]) -> Classification {
+        let mut classification = Classification::default();
+        for overview in overviews {
+            classification
+                .sectors
+                .entry(overview.sector.clone())
+                .or_default()
+                .push(overview.symbol.clone());
+            classification
+                .industries
+                .entry(overview.industry.clone())
+                .or_default()
+                .push(overview.symbol.clone());
+        }
+        classification
+    }
+
+    /// Sector `symbol` belongs to.
+    pub fn sector_of(&self, symbol: &str) -> Option<&str> {
+        find_group(&self.sectors, symbol)
+    }
+
+    /// Industry `symbol` belongs to.
+    pub fn industry_of(&self, symbol: &str) -> Option<&str> {
+        find_group(&self.industries, symbol)
+    }
+}
+
+/// Represents a financial statement which can be retrieved along with a company overview.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Statement {
+    /// Annual and quarterly income statements (`INCOME_STATEMENT`).
+    IncomeStatement,
+    /// Annual and quarterly cash flow statements (`CASH_FLOW`).
+    CashFlow,
+}
+
+/// Represents the fundamental data retrieved for an equity.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Fundamentals {
+    /// Company information and key financial metrics.
+    pub overview: CompanyOverview,
+    /// Income statements, if requested.
+    pub income_statement: Option<IncomeStatement>,
+    /// Cash flow statements, if requested.
+    pub cash_flow: Option<CashFlow>,
+}
+
+/// Represents the fundamental data retrieved for a list of symbols, along with the errors
+/// raised retrieving it for the other symbols.
+#[derive(Debug, Default)]
+pub struct BulkFundamentals {
+    /// Fundamental data retrieved, by symbol.
+    pub fundamentals: BTreeMap<String, Fundamentals>,
+    /// Errors raised retrieving fundamental data, by symbol.
+    pub failures: BTreeMap<String, Error>,
+}
+
+impl BulkFundamentals {
+    /// Record the `result` of retrieving the fundamental data of `symbol`.
+    pub fn insert(&mut self, symbol: &str, result: Result<Fundamentals, Error>) {
+        match result {
+            Ok(fundamentals) => {
+                self.fundamentals.insert(symbol.to_string(), fundamentals);
+            }
+            Err(error) => {
+                self.failures.insert(symbol.to_string(), error);
+            }
+        }
+    }
+
+    /// Whether fundamental data was retrieved for every symbol.
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Represents a field whose value differs between two snapshots of fundamental data.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FieldChange {
+    /// Path of the field, e.g. `shares_outstanding` for an overview or
+    /// `quarterly.2024-03-31.net_income` for a statement.
+    pub field: String,
+    /// Value in the older snapshot, `null` for a field or report it didn't include.
+    pub old: Value,
+    /// Value in the newer snapshot, `null` for a field or report it doesn't include.
+    pub new: Value,
+}
+
+impl FieldChange {
+    /// Difference between the new and old values, if both are numbers.
+    pub fn delta(&self) -> Option<f64> {
+        Some(self.new.as_f64()? - self.old.as_f64()?)
+    }
+
+    /// Difference between the new and old values relative to the old one, if both are numbers
+    /// and the old one isn't zero.
+    pub fn relative_change(&self) -> Option<f64> {
+        let old = self.old.as_f64()?;
+        if old == 0.0 {
+            None
+        } else {
+            Some(self.delta()? / old.abs())
+        }
+    }
+}
+
+impl CompanyOverview {
+    /// Fields which changed from this overview to a `newer` one of the same symbol, e.g. to
+    /// alert on revisions of the shares outstanding.
+    pub fn diff(&self, newer: &CompanyOverview) -> Vec<FieldChange> {
+        let mut changes = vec![];
+        diff_values("", to_value(self), to_value(newer), &mut changes);
+        changes
+    }
+}
+
+impl IncomeStatement {
+    /// Fields which changed from these income statements to `newer` ones of the same symbol.
+    ///
+    /// Reports are matched by fiscal date, a report included in only one of the snapshots being
+    /// reported as a whole.
+    pub fn diff(&self, newer: &IncomeStatement) -> Vec<FieldChange> {
+        let mut changes = vec![];
+        diff_reports("annual", &self.annual, &newer.annual, &mut changes);
+        diff_reports("quarterly", &self.quarterly, &newer.quarterly, &mut changes);
+        changes
+    }
+}
+
+impl CashFlow {
+    /// Fields which changed from these cash flow statements to `newer` ones of the same symbol.
+    ///
+    /// Reports are matched by fiscal date, a report included in only one of the snapshots being
+    /// reported as a whole.
+    pub fn diff(&self, newer: &CashFlow) -> Vec<FieldChange> {
+        let mut changes = vec![];
+        diff_reports("annual", &self.annual, &newer.annual, &mut changes);
+        diff_reports("quarterly", &self.quarterly, &newer.quarterly, &mut changes);
+        changes
+    }
+}
+
+fn to_value(value: &impl Serialize) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// Record the differences between the `old` and `new` values at `path`, field by field for
+/// objects.
+fn diff_values(path: &str, old: Value, new: Value, changes: &mut Vec<FieldChange>) {
+    match (old, new) {
+        (Value::Object(mut old), Value::Object(mut new)) => {
+            let mut fields: Vec<String> = old.keys().chain(new.keys()).cloned().collect();
+            fields.sort();
+            fields.dedup();
+            for field in fields {
+                let path = match path {
+                    "" => field.clone(),
+                    path => format!("{}.{}", path, field),
+                };
+                let old = old.remove(&field).unwrap_or(Value::Null);
+                let new = new.remove(&field).unwrap_or(Value::Null);
+                diff_values(&path, old, new, changes);
+            }
+        }
+        (old, new) if old != new => changes.push(FieldChange {
+            field: path.to_string(),
+            old,
+            new,
+        }),
+        _ => {}
+    }
+}
+
+/// Record the differences between the `old` and `new` reports of a `period`, matched by their
+/// fiscal dates.
+fn diff_reports<R: Serialize>(period: &str, old: &[R], new: &[R], changes: &mut Vec<FieldChange>) {
+    let by_date = |reports: &[R]| -> BTreeMap<String, Value> {
+        reports
+            .iter()
+            .map(|r| {
+                let mut value = to_value(r);
+                let date = match value.as_object_mut() {
+                    Some(fields) => fields.remove("fiscal_date_ending"),
+                    None => None,
+                };
+                let date = date.and_then(|d| d.as_str().map(str::to_string));
+                (date.unwrap_or_default(), value)
+            })
+            .collect()
+    };
+    let (mut old, mut new) = (by_date(old), by_date(new));
+    let mut dates: Vec<String> = old.keys().chain(new.keys()).cloned().collect();
+    dates.sort();
+    dates.dedup();
+    for date in dates {
+        let path = format!("{}.{}", period, date);
+        match (old.remove(&date), new.remove(&date)) {
+            (Some(old), Some(new)) => diff_values(&path, old, new, changes),
+            (old, new) => changes.push(FieldChange {
+                field: path,
+                old: old.unwrap_or(Value::Null),
+                new: new.unwrap_or(Value::Null),
+            }),
+        }
+    }
+}
+
+fn find_group<'a>(groups: &'a BTreeMap<String, Vec<String>>, symbol: &str) -> Option<&'a str> {
+    groups
+        .iter()
+        .find(|(_, symbols)| symbols.iter().any(|s| s == symbol))
+        .map(|(group, _)| group.as_str())
+}
+
+pub mod parser {
+    use super::*;
+    use crate::deserialize::{from_str_or_none, DATE_FORMAT};
+    use crate::error::Error;
+    use crate::parsing::Parser;
+    use std::io::Read;
+
+    #[derive(Debug, Deserialize)]
+    struct CompanyOverviewHelper {
+        #[serde(rename = "Symbol")]
+        symbol: String,
+        #[serde(rename = "AssetType")]
+        asset_type: String,
+        #[serde(rename = "Name")]
+        name: String,
+        #[serde(rename = "Description", default)]
+        description: String,
+        #[serde(rename = "CIK", default)]
+        cik: String,
+        #[serde(rename = "Exchange")]
+        exchange: String,
+        #[serde(rename = "Currency")]
+        currency: String,
+        #[serde(rename = "Country")]
+        country: String,
+        #[serde(rename = "Sector")]
+        sector: String,
+        #[serde(rename = "Industry")]
+        industry: String,
+        #[serde(rename = "Address", default)]
+        address: String,
+        #[serde(rename = "FiscalYearEnd", default)]
+        fiscal_year_end: String,
+        #[serde(
+            rename = "LatestQuarter",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        latest_quarter: Option<NaiveDate>,
+        #[serde(
+            rename = "MarketCapitalization",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        market_capitalization: Option<u64>,
+        #[serde(rename = "EBITDA", default, deserialize_with = "from_str_or_none")]
+        ebitda: Option<i64>,
+        #[serde(rename = "PERatio", default, deserialize_with = "from_str_or_none")]
+        pe_ratio: Option<f64>,
+        #[serde(rename = "PEGRatio", default, deserialize_with = "from_str_or_none")]
+        peg_ratio: Option<f64>,
+        #[serde(rename = "BookValue", default, deserialize_with = "from_str_or_none")]
+        book_value: Option<f64>,
+        #[serde(
+            rename = "DividendPerShare",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        dividend_per_share: Option<f64>,
+        #[serde(
+            rename = "DividendYield",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        dividend_yield: Option<f64>,
+        #[serde(rename = "EPS", default, deserialize_with = "from_str_or_none")]
+        eps: Option<f64>,
+        #[serde(
+            rename = "RevenuePerShareTTM",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        revenue_per_share_ttm: Option<f64>,
+        #[serde(
+            rename = "ProfitMargin",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        profit_margin: Option<f64>,
+        #[serde(
+            rename = "OperatingMarginTTM",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        operating_margin_ttm: Option<f64>,
+        #[serde(
+            rename = "ReturnOnAssetsTTM",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        return_on_assets_ttm: Option<f64>,
+        #[serde(
+            rename = "ReturnOnEquityTTM",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        return_on_equity_ttm: Option<f64>,
+        #[serde(rename = "RevenueTTM", default, deserialize_with = "from_str_or_none")]
+        revenue_ttm: Option<i64>,
+        #[serde(
+            rename = "GrossProfitTTM",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        gross_profit_ttm: Option<i64>,
+        #[serde(
+            rename = "DilutedEPSTTM",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        diluted_eps_ttm: Option<f64>,
+        #[serde(
+            rename = "QuarterlyEarningsGrowthYOY",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        quarterly_earnings_growth_yoy: Option<f64>,
+        #[serde(
+            rename = "QuarterlyRevenueGrowthYOY",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        quarterly_revenue_growth_yoy: Option<f64>,
+        #[serde(
+            rename = "AnalystTargetPrice",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        analyst_target_price: Option<f64>,
+        #[serde(
+            rename = "AnalystRatingStrongBuy",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        analyst_rating_strong_buy: Option<u32>,
+        #[serde(
+            rename = "AnalystRatingBuy",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        analyst_rating_buy: Option<u32>,
+        #[serde(
+            rename = "AnalystRatingHold",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        analyst_rating_hold: Option<u32>,
+        #[serde(
+            rename = "AnalystRatingSell",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        analyst_rating_sell: Option<u32>,
+        #[serde(
+            rename = "AnalystRatingStrongSell",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        analyst_rating_strong_sell: Option<u32>,
+        #[serde(rename = "TrailingPE", default, deserialize_with = "from_str_or_none")]
+        trailing_pe: Option<f64>,
+        #[serde(rename = "ForwardPE", default, deserialize_with = "from_str_or_none")]
+        forward_pe: Option<f64>,
+        #[serde(
+            rename = "PriceToSalesRatioTTM",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        price_to_sales_ratio_ttm: Option<f64>,
+        #[serde(
+            rename = "PriceToBookRatio",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        price_to_book_ratio: Option<f64>,
+        #[serde(rename = "EVToRevenue", default, deserialize_with = "from_str_or_none")]
+        ev_to_revenue: Option<f64>,
+        #[serde(rename = "EVToEBITDA", default, deserialize_with = "from_str_or_none")]
+        ev_to_ebitda: Option<f64>,
+        #[serde(rename = "Beta", default, deserialize_with = "from_str_or_none")]
+        beta: Option<f64>,
+        #[serde(rename = "52WeekHigh", default, deserialize_with = "from_str_or_none")]
+        week_52_high: Option<f64>,
+        #[serde(rename = "52WeekLow", default, deserialize_with = "from_str_or_none")]
+        week_52_low: Option<f64>,
+        #[serde(
+            rename = "50DayMovingAverage",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        day_50_moving_average: Option<f64>,
+        #[serde(
+            rename = "200DayMovingAverage",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        day_200_moving_average: Option<f64>,
+        #[serde(
+            rename = "SharesOutstanding",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        shares_outstanding: Option<u64>,
+        #[serde(
+            rename = "DividendDate",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        dividend_date: Option<NaiveDate>,
+        #[serde(
+            rename = "ExDividendDate",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        ex_dividend_date: Option<NaiveDate>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct IncomeStatementHelper {
+        #[serde(rename = "Error Message")]
+        error: Option<String>,
+        symbol: Option<String>,
+        #[serde(rename = "annualReports", default)]
+        annual: Vec<serde_json::Value>,
+        #[serde(rename = "quarterlyReports", default)]
+        quarterly: Vec<serde_json::Value>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct IncomeReportHelper {
+        #[serde(rename = "fiscalDateEnding")]
+        fiscal_date_ending: String,
+        #[serde(rename = "reportedCurrency")]
+        reported_currency: String,
+        #[serde(rename = "grossProfit", default, deserialize_with = "from_str_or_none")]
+        gross_profit: Option<i64>,
+        #[serde(
+            rename = "totalRevenue",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        total_revenue: Option<i64>,
+        #[serde(
+            rename = "costOfRevenue",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        cost_of_revenue: Option<i64>,
+        #[serde(
+            rename = "costofGoodsAndServicesSold",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        cost_of_goods_and_services_sold: Option<i64>,
+        #[serde(
+            rename = "operatingIncome",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        operating_income: Option<i64>,
+        #[serde(
+            rename = "sellingGeneralAndAdministrative",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        selling_general_and_administrative: Option<i64>,
+        #[serde(
+            rename = "researchAndDevelopment",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        research_and_development: Option<i64>,
+        #[serde(
+            rename = "operatingExpenses",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        operating_expenses: Option<i64>,
+        #[serde(
+            rename = "investmentIncomeNet",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        investment_income_net: Option<i64>,
+        #[serde(
+            rename = "netInterestIncome",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        net_interest_income: Option<i64>,
+        #[serde(
+            rename = "interestIncome",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        interest_income: Option<i64>,
+        #[serde(
+            rename = "interestExpense",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        interest_expense: Option<i64>,
+        #[serde(
+            rename = "nonInterestIncome",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        non_interest_income: Option<i64>,
+        #[serde(
+            rename = "otherNonOperatingIncome",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        other_non_operating_income: Option<i64>,
+        #[serde(
+            rename = "depreciation",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        depreciation: Option<i64>,
+        #[serde(
+            rename = "depreciationAndAmortization",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        depreciation_and_amortization: Option<i64>,
+        #[serde(
+            rename = "incomeBeforeTax",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        income_before_tax: Option<i64>,
+        #[serde(
+            rename = "incomeTaxExpense",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        income_tax_expense: Option<i64>,
+        #[serde(
+            rename = "interestAndDebtExpense",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        interest_and_debt_expense: Option<i64>,
+        #[serde(
+            rename = "netIncomeFromContinuingOperations",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        net_income_from_continuing_operations: Option<i64>,
+        #[serde(
+            rename = "comprehensiveIncomeNetOfTax",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        comprehensive_income_net_of_tax: Option<i64>,
+        #[serde(rename = "ebit", default, deserialize_with = "from_str_or_none")]
+        ebit: Option<i64>,
+        #[serde(rename = "ebitda", default, deserialize_with = "from_str_or_none")]
+        ebitda: Option<i64>,
+        #[serde(rename = "netIncome", default, deserialize_with = "from_str_or_none")]
+        net_income: Option<i64>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct CashFlowHelper {
+        #[serde(rename = "Error Message")]
+        error: Option<String>,
+        symbol: Option<String>,
+        #[serde(rename = "annualReports", default)]
+        annual: Vec<serde_json::Value>,
+        #[serde(rename = "quarterlyReports", default)]
+        quarterly: Vec<serde_json::Value>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct CashFlowReportHelper {
+        #[serde(rename = "fiscalDateEnding")]
+        fiscal_date_ending: String,
+        #[serde(rename = "reportedCurrency")]
+        reported_currency: String,
+        #[serde(
+            rename = "operatingCashflow",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        operating_cashflow: Option<i64>,
+        #[serde(
+            rename = "paymentsForOperatingActivities",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        payments_for_operating_activities: Option<i64>,
+        #[serde(
+            rename = "proceedsFromOperatingActivities",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        proceeds_from_operating_activities: Option<i64>,
+        #[serde(
+            rename = "changeInOperatingLiabilities",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        change_in_operating_liabilities: Option<i64>,
+        #[serde(
+            rename = "changeInOperatingAssets",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        change_in_operating_assets: Option<i64>,
+        #[serde(
+            rename = "depreciationDepletionAndAmortization",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        depreciation_depletion_and_amortization: Option<i64>,
+        #[serde(
+            rename = "capitalExpenditures",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        capital_expenditures: Option<i64>,
+        #[serde(
+            rename = "changeInReceivables",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        change_in_receivables: Option<i64>,
+        #[serde(
+            rename = "changeInInventory",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        change_in_inventory: Option<i64>,
+        #[serde(rename = "profitLoss", default, deserialize_with = "from_str_or_none")]
+        profit_loss: Option<i64>,
+        #[serde(
+            rename = "cashflowFromInvestment",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        cashflow_from_investment: Option<i64>,
+        #[serde(
+            rename = "cashflowFromFinancing",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        cashflow_from_financing: Option<i64>,
+        #[serde(
+            rename = "proceedsFromRepaymentsOfShortTermDebt",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        proceeds_from_repayments_of_short_term_debt: Option<i64>,
+        #[serde(
+            rename = "paymentsForRepurchaseOfCommonStock",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        payments_for_repurchase_of_common_stock: Option<i64>,
+        #[serde(
+            rename = "paymentsForRepurchaseOfEquity",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        payments_for_repurchase_of_equity: Option<i64>,
+        #[serde(
+            rename = "paymentsForRepurchaseOfPreferredStock",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        payments_for_repurchase_of_preferred_stock: Option<i64>,
+        #[serde(
+            rename = "dividendPayout",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        dividend_payout: Option<i64>,
+        #[serde(
+            rename = "dividendPayoutCommonStock",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        dividend_payout_common_stock: Option<i64>,
+        #[serde(
+            rename = "dividendPayoutPreferredStock",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        dividend_payout_preferred_stock: Option<i64>,
+        #[serde(
+            rename = "proceedsFromIssuanceOfCommonStock",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        proceeds_from_issuance_of_common_stock: Option<i64>,
+        #[serde(
+            rename = "proceedsFromIssuanceOfLongTermDebtAndCapitalSecuritiesNet",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        proceeds_from_issuance_of_long_term_debt_and_capital_securities_net: Option<i64>,
+        #[serde(
+            rename = "proceedsFromIssuanceOfPreferredStock",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        proceeds_from_issuance_of_preferred_stock: Option<i64>,
+        #[serde(
+            rename = "proceedsFromRepurchaseOfEquity",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        proceeds_from_repurchase_of_equity: Option<i64>,
+        #[serde(
+            rename = "proceedsFromSaleOfTreasuryStock",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        proceeds_from_sale_of_treasury_stock: Option<i64>,
+        #[serde(
+            rename = "changeInCashAndCashEquivalents",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        change_in_cash_and_cash_equivalents: Option<i64>,
+        #[serde(
+            rename = "changeInExchangeRate",
+            default,
+            deserialize_with = "from_str_or_none"
+        )]
+        change_in_exchange_rate: Option<i64>,
+        #[serde(rename = "netIncome", default, deserialize_with = "from_str_or_none")]
+        net_income: Option<i64>,
+    }
+
+    pub fn parse_company_overview(
+        mut parser: Parser,
+        reader: impl Read,
+    ) -> Result<CompanyOverview, Error> {
+        let value: serde_json::Value = parser.parse_json(reader)?;
+
+        if let Some(error) = value.get("Error Message").and_then(|e| e.as_str()) {
+            return Err(Error::APIError(error.to_string()));
+        }
+
+        if value.as_object().is_none_or(|o| o.is_empty()) {
+            return Err(Error::ParsingError("missing company overview data".into()));
+        }
+
+        let helper: CompanyOverviewHelper = parser.parse_value("", &value)?;
+
+        let overview = CompanyOverview {
+            symbol: helper.symbol,
+            asset_type: helper.asset_type,
+            name: helper.name,
+            description: helper.description,
+            cik: helper.cik,
+            exchange: helper.exchange,
+            currency: helper.currency,
+            country: helper.country,
+            sector: helper.sector,
+            industry: helper.industry,
+            address: helper.address,
+            fiscal_year_end: helper.fiscal_year_end,
+            latest_quarter: helper.latest_quarter,
+            market_capitalization: helper.market_capitalization,
+            ebitda: helper.ebitda,
+            pe_ratio: helper.pe_ratio,
+            peg_ratio: helper.peg_ratio,
+            book_value: helper.book_value,
+            dividend_per_share: helper.dividend_per_share,
+            dividend_yield: helper.dividend_yield,
+            eps: helper.eps,
+            revenue_per_share_ttm: helper.revenue_per_share_ttm,
+            profit_margin: helper.profit_margin,
+            operating_margin_ttm: helper.operating_margin_ttm,
+            return_on_assets_ttm: helper.return_on_assets_ttm,
+            return_on_equity_ttm: helper.return_on_equity_ttm,
+            revenue_ttm: helper.revenue_ttm,
+            gross_profit_ttm: helper.gross_profit_ttm,
+            diluted_eps_ttm: helper.diluted_eps_ttm,
+            quarterly_earnings_growth_yoy: helper.quarterly_earnings_growth_yoy,
+            quarterly_revenue_growth_yoy: helper.quarterly_revenue_growth_yoy,
+            analyst_target_price: helper.analyst_target_price,
+            analyst_rating_strong_buy: helper.analyst_rating_strong_buy,
+            analyst_rating_buy: helper.analyst_rating_buy,
+            analyst_rating_hold: helper.analyst_rating_hold,
+            analyst_rating_sell: helper.analyst_rating_sell,
+            analyst_rating_strong_sell: helper.analyst_rating_strong_sell,
+            trailing_pe: helper.trailing_pe,
+            forward_pe: helper.forward_pe,
+            price_to_sales_ratio_ttm: helper.price_to_sales_ratio_ttm,
+            price_to_book_ratio: helper.price_to_book_ratio,
+            ev_to_revenue: helper.ev_to_revenue,
+            ev_to_ebitda: helper.ev_to_ebitda,
+            beta: helper.beta,
+            week_52_high: helper.week_52_high,
+            week_52_low: helper.week_52_low,
+            day_50_moving_average: helper.day_50_moving_average,
+            day_200_moving_average: helper.day_200_moving_average,
+            shares_outstanding: helper.shares_outstanding,
+            dividend_date: helper.dividend_date,
+            ex_dividend_date: helper.ex_dividend_date,
+            warnings: parser.finish(),
+        };
+        Ok(overview)
+    }
+
+    pub fn parse_income_statement(
+        mut parser: Parser,
+        reader: impl Read,
+    ) -> Result<IncomeStatement, Error> {
+        let helper: IncomeStatementHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let symbol = helper
+            .symbol
+            .ok_or_else(|| Error::ParsingError("missing symbol".into()))?;
+
+        let annual = parse_income_reports(&mut parser, "annualReports", &helper.annual)?;
+        let quarterly = parse_income_reports(&mut parser, "quarterlyReports", &helper.quarterly)?;
+
+        Ok(IncomeStatement {
+            symbol,
+            annual,
+            quarterly,
+            warnings: parser.finish(),
+        })
+    }
+
+    fn parse_income_reports(
+        parser: &mut Parser,
+        key: &str,
+        values: &[serde_json::Value],
+    ) -> Result<Vec<IncomeReport>, Error> {
+        let mut reports = vec![];
+        for (i, value) in values.iter().enumerate() {
+            let path = format!("{}.{}", key, i);
+            let report = parser
+                .parse_value(&path, value)
+                .and_then(|r: IncomeReportHelper| {
+                    Ok(IncomeReport {
+                        fiscal_date_ending: NaiveDate::parse_from_str(
+                            &r.fiscal_date_ending,
+                            DATE_FORMAT,
+                        )?,
+                        reported_currency: r.reported_currency,
+                        gross_profit: r.gross_profit,
+                        total_revenue: r.total_revenue,
+                        cost_of_revenue: r.cost_of_revenue,
+                        cost_of_goods_and_services_sold: r.cost_of_goods_and_services_sold,
+                        operating_income: r.operating_income,
+                        selling_general_and_administrative: r.selling_general_and_administrative,
+                        research_and_development: r.research_and_development,
+                        operating_expenses: r.operating_expenses,
+                        investment_income_net: r.investment_income_net,
+                        net_interest_income: r.net_interest_income,
+                        interest_income: r.interest_income,
+                        interest_expense: r.interest_expense,
+                        non_interest_income: r.non_interest_income,
+                        other_non_operating_income: r.other_non_operating_income,
+                        depreciation: r.depreciation,
+                        depreciation_and_amortization: r.depreciation_and_amortization,
+                        income_before_tax: r.income_before_tax,
+                        income_tax_expense: r.income_tax_expense,
+                        interest_and_debt_expense: r.interest_and_debt_expense,
+                        net_income_from_continuing_operations: r
+                            .net_income_from_continuing_operations,
+                        comprehensive_income_net_of_tax: r.comprehensive_income_net_of_tax,
+                        ebit: r.ebit,
+                        ebitda: r.ebitda,
+                        net_income: r.net_income,
+                    })
+                });
+            if let Some(report) = parser.entry(&path, report)? {
+                reports.push(report);
+            }
+        }
+        reports.sort_by_key(|r| r.fiscal_date_ending);
+        Ok(reports)
+    }
+    pub fn parse_cash_flow(mut parser: Parser, reader: impl Read) -> Result<CashFlow, Error> {
+        let helper: CashFlowHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let symbol = helper
+            .symbol
+            .ok_or_else(|| Error::ParsingError("missing symbol".into()))?;
+
+        let annual = parse_cash_flow_reports(&mut parser, "annualReports", &helper.annual)?;
+        let quarterly =
+            parse_cash_flow_reports(&mut parser, "quarterlyReports", &helper.quarterly)?;
+
+        Ok(CashFlow {
+            symbol,
+            annual,
+            quarterly,
+            warnings: parser.finish(),
+        })
+    }
+
+    fn parse_cash_flow_reports(
+        parser: &mut Parser,
+        key: &str,
+        values: &[serde_json::Value],
+    ) -> Result<Vec<CashFlowReport>, Error> {
+        let mut reports = vec![];
+        for (i, value) in values.iter().enumerate() {
+            let path = format!("{}.{}", key, i);
+            let report = parser
+                .parse_value(&path, value)
+                .and_then(|r: CashFlowReportHelper| {
+                    Ok(CashFlowReport {
+                        fiscal_date_ending: NaiveDate::parse_from_str(
+                            &r.fiscal_date_ending,
+                            DATE_FORMAT,
+                        )?,
+                        reported_currency: r.reported_currency,
+                        operating_cashflow: r.operating_cashflow,
+                        payments_for_operating_activities: r.payments_for_operating_activities,
+                        proceeds_from_operating_activities: r.proceeds_from_operating_activities,
+                        change_in_operating_liabilities: r.change_in_operating_liabilities,
+                        change_in_operating_assets: r.change_in_operating_assets,
+                        depreciation_depletion_and_amortization: r
+                            .depreciation_depletion_and_amortization,
+                        capital_expenditures: r.capital_expenditures,
+                        change_in_receivables: r.change_in_receivables,
+                        change_in_inventory: r.change_in_inventory,
+                        profit_loss: r.profit_loss,
+                        cashflow_from_investment: r.cashflow_from_investment,
+                        cashflow_from_financing: r.cashflow_from_financing,
+                        proceeds_from_repayments_of_short_term_debt: r
+                            .proceeds_from_repayments_of_short_term_debt,
+                        payments_for_repurchase_of_common_stock: r
+                            .payments_for_repurchase_of_common_stock,
+                        payments_for_repurchase_of_equity: r.payments_for_repurchase_of_equity,
+                        payments_for_repurchase_of_preferred_stock: r
+                            .payments_for_repurchase_of_preferred_stock,
+                        dividend_payout: r.dividend_payout,
+                        dividend_payout_common_stock: r.dividend_payout_common_stock,
+                        dividend_payout_preferred_stock: r.dividend_payout_preferred_stock,
+                        proceeds_from_issuance_of_common_stock: r
+                            .proceeds_from_issuance_of_common_stock,
+                        proceeds_from_issuance_of_long_term_debt_and_capital_securities_net: r
+                            .proceeds_from_issuance_of_long_term_debt_and_capital_securities_net,
+                        proceeds_from_issuance_of_preferred_stock: r
+                            .proceeds_from_issuance_of_preferred_stock,
+                        proceeds_from_repurchase_of_equity: r.proceeds_from_repurchase_of_equity,
+                        proceeds_from_sale_of_treasury_stock: r
+                            .proceeds_from_sale_of_treasury_stock,
+                        change_in_cash_and_cash_equivalents: r.change_in_cash_and_cash_equivalents,
+                        change_in_exchange_rate: r.change_in_exchange_rate,
+                        net_income: r.net_income,
+                    })
+                });
+            if let Some(report) = parser.entry(&path, report)? {
+                reports.push(report);
+            }
+        }
+        reports.sort_by_key(|r| r.fiscal_date_ending);
+        Ok(reports)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+    use std::io::BufReader;
+
+    #[test]
+    fn parse_company_overview() {
+        let data: &[u8] = include_bytes!("../../tests/json/company_overview.json");
+        let overview = parser::parse_company_overview(Parser::default(), BufReader::new(data))
+            .expect("failed to parse company overview");
+        assert_eq!(overview.symbol, "IBM");
+        assert_eq!(overview.name, "International Business Machines");
+        assert_eq!(overview.cik, "51143");
+        assert_eq!(overview.sector, "TECHNOLOGY");
+        assert_eq!(overview.industry, "COMPUTER & OFFICE EQUIPMENT");
+        assert_eq!(overview.fiscal_year_end, "December");
+        assert_eq!(
+            overview.latest_quarter,
+            Some(NaiveDate::from_ymd_opt(2024, 3, 31).unwrap())
+        );
+        assert_eq!(overview.market_capitalization, Some(152394793000));
+        assert_eq!(overview.ebitda, Some(14625000000));
+        assert_eq!(overview.pe_ratio, Some(18.72));
+        assert_eq!(overview.eps, Some(8.85));
+        assert_eq!(overview.profit_margin, Some(0.131));
+        assert_eq!(overview.revenue_ttm, Some(62068998000));
+        assert_eq!(overview.quarterly_earnings_growth_yoy, Some(-0.01));
+        assert_eq!(overview.analyst_rating_hold, Some(9));
+        assert_eq!(overview.week_52_high, Some(199.18));
+        assert_eq!(overview.week_52_low, Some(119.73));
+        assert_eq!(overview.day_200_moving_average, Some(163.65));
+        assert_eq!(overview.shares_outstanding, Some(916676000));
+        assert_eq!(
+            overview.ex_dividend_date,
+            Some(NaiveDate::from_ymd_opt(2024, 5, 9).unwrap())
+        );
+    }
+
+    #[test]
+    fn parse_company_overview_none() {
+        let data: &[u8] = include_bytes!("../../tests/json/company_overview_none.json");
+        let overview = parser::parse_company_overview(Parser::default(), BufReader::new(data))
+            .expect("failed to parse company overview");
+        assert_eq!(overview.market_capitalization, Some(1250000000));
+        assert_eq!(overview.pe_ratio, None);
+        assert_eq!(overview.eps, None);
+        assert_eq!(overview.dividend_date, None);
+        assert_eq!(overview.description, "");
+    }
+
+    #[test]
+    fn parse_income_statement() {
+        let data: &[u8] = include_bytes!("../../tests/json/income_statement.json");
+        let statement = parser::parse_income_statement(Parser::default(), BufReader::new(data))
+            .expect("failed to parse income statement");
+        assert_eq!(statement.symbol, "IBM");
+        assert_eq!(statement.annual.len(), 1);
+        let report = &statement.quarterly[1];
+        assert_eq!(
+            report.fiscal_date_ending,
+            NaiveDate::from_ymd_opt(2024, 3, 31).unwrap()
+        );
+        assert_eq!(report.reported_currency, "USD");
+        assert_eq!(report.total_revenue, Some(14462000000));
+        assert_eq!(report.net_interest_income, Some(-420000000));
+        assert_eq!(report.investment_income_net, None);
+        assert_eq!(report.net_income, Some(1605000000));
+        assert_eq!(statement.quarterly[0].depreciation, None);
+        assert!(statement.warnings.is_empty());
+
+        let data: &[u8] = br#"{"Error Message": "Invalid API call."}"#;
+        assert!(parser::parse_income_statement(Parser::default(), data).is_err());
+    }
+
+    #[test]
+    fn parse_cash_flow() {
+        let data: &[u8] = include_bytes!("../../tests/json/cash_flow.json");
+        let cash_flow = parser::parse_cash_flow(Parser::default(), BufReader::new(data))
+            .expect("failed to parse cash flow");
+        assert_eq!(cash_flow.symbol, "IBM");
+        assert_eq!(cash_flow.annual.len(), 1);
+        assert_eq!(cash_flow.annual[0].dividend_payout, Some(6040000000));
+        let report = &cash_flow.quarterly[1];
+        assert_eq!(
+            report.fiscal_date_ending,
+            NaiveDate::from_ymd_opt(2024, 3, 31).unwrap()
+        );
+        assert_eq!(report.operating_cashflow, Some(4186000000));
+        assert_eq!(report.cashflow_from_investment, Some(-6292000000));
+        assert_eq!(report.cashflow_from_financing, Some(1302000000));
+        assert_eq!(report.change_in_receivables, None);
+        assert!(cash_flow.warnings.is_empty());
+
+        let data: &[u8] = br#"{"Error Message": "Invalid API call."}"#;
+        assert!(parser::parse_cash_flow(Parser::default(), data).is_err());
+    }
+
+    #[test]
+    fn diff_overviews() {
+        let data: &[u8] = include_bytes!("../../tests/json/company_overview.json");
+        let old = parser::parse_company_overview(Parser::default(), BufReader::new(data)).unwrap();
+        assert!(old.diff(&old).is_empty());
+
+        let mut new = old.clone();
+        new.shares_outstanding = old.shares_outstanding.map(|shares| shares - 1_000_000);
+        new.pe_ratio = None;
+        let changes = old.diff(&new);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].field, "pe_ratio");
+        assert_eq!(changes[0].new, Value::Null);
+        assert_eq!(changes[0].delta(), None);
+        assert_eq!(changes[1].field, "shares_outstanding");
+        assert_eq!(changes[1].delta(), Some(-1_000_000.0));
+        assert!(changes[1].relative_change().unwrap() < 0.0);
+    }
+
+    #[test]
+    fn diff_income_statements() {
+        let data: &[u8] = include_bytes!("../../tests/json/income_statement.json");
+        let old = parser::parse_income_statement(Parser::default(), BufReader::new(data)).unwrap();
+        let mut new = old.clone();
+        new.quarterly[1].net_income = Some(1_700_000_000);
+        new.annual.clear();
+        let changes = old.diff(&new);
+        assert_eq!(changes.len(), 2);
+        let date = old.annual[0].fiscal_date_ending;
+        assert_eq!(changes[0].field, format!("annual.{}", date));
+        assert!(changes[0].old.is_object());
+        assert_eq!(changes[0].new, Value::Null);
+        assert_eq!(changes[1].field, "quarterly.2024-03-31.net_income");
+        assert_eq!(changes[1].old, Value::from(1_605_000_000));
+        assert_eq!(changes[1].new, Value::from(1_700_000_000));
+    }
+
+    #[test]
+    fn classification() {
+        let overview = |symbol: &str, sector: &str, industry: &str| CompanyOverview {
+            symbol: symbol.to_string(),
+            sector: sector.to_string(),
+            industry: industry.to_string(),
+            ..CompanyOverview::default()
+        };
+        let classification = Classification::new(&[
+            overview("IBM", "TECHNOLOGY", "COMPUTER & OFFICE EQUIPMENT"),
+            overview("MSFT", "TECHNOLOGY", "SERVICES-PREPACKAGED SOFTWARE"),
+            overview("XOM", "ENERGY", "PETROLEUM REFINING"),
+        ]);
+        assert_eq!(classification.sectors["TECHNOLOGY"], vec!["IBM", "MSFT"]);
+        assert_eq!(classification.sectors["ENERGY"], vec!["XOM"]);
+        assert_eq!(classification.industries.len(), 3);
+        assert_eq!(classification.sector_of("XOM"), Some("ENERGY"));
+        assert_eq!(
+            classification.industry_of("MSFT"),
+            Some("SERVICES-PREPACKAGED SOFTWARE")
+        );
+        assert_eq!(classification.sector_of("AAPL"), None);
+    }
+
+    #[test]
+    fn parse_company_overview_empty() {
+        let data: &[u8] = b"{}";
+        assert!(parser::parse_company_overview(Parser::default(), BufReader::new(data)).is_err());
+    }
+}
@@ -0,0 +1,71 @@
+//! # alphavantage-types
+//!
+//! Response types of the [Alpha Vantage](https://www.alphavantage.co) API along with their
+//! parsers, without any HTTP client or async runtime, e.g. to deserialize payloads received
+//! from a queue.
+//!
+//! Responses are parsed with the `parser` module of the operation they belong to:
+//!
+//! ```
+//! use alphavantage_types::quote;
+//! use alphavantage_types::{ParseMode, Parser};
+//!
+//! # fn run(payload: &[u8]) -> Result<(), alphavantage_types::Error> {
+//! let quote = quote::parser::parse(Parser::new(ParseMode::Standard), payload)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Every type is re-exported by the `alphavantage` crate, which issues the requests.
+
+mod deserialize;
+mod error;
+mod function;
+mod matching;
+mod parsing;
+
+pub mod analytics;
+#[cfg(feature = "test-util")]
+pub mod approx;
+pub mod asset;
+pub mod calendar;
+pub mod commodities;
+pub mod corporate_actions;
+pub mod crypto;
+pub mod currencies;
+pub mod earnings;
+pub mod economic;
+pub mod etf;
+pub mod exchange_rate;
+pub mod forex;
+pub mod format;
+pub mod fundamentals;
+pub mod listing;
+pub mod market_movers;
+pub mod news;
+pub mod options;
+pub mod patterns;
+pub mod quote;
+pub mod rate;
+pub mod returns;
+pub mod search;
+pub mod snapshot;
+pub mod symbol;
+#[cfg(feature = "table")]
+mod table;
+pub mod time_series;
+pub use crate::error::Error;
+pub use crate::function::{Deprecation, Function};
+pub use crate::parsing::{ParseMode, ParseWarning, ParseWarningKind, Parser};
+
+/// Helpers shared with the `alphavantage` crate, only available with the `internal` feature.
+///
+/// This module isn't covered by semantic versioning: anything in it may change or be removed in
+/// any release, so it shouldn't be relied upon outside of the `alphavantage` crate.
+#[cfg(feature = "internal")]
+pub mod internal {
+    pub use crate::deserialize::{
+        parse_date, parse_date_with_time_zone, DATETIME_FORMAT, DATE_FORMAT,
+    };
+    pub use crate::matching::{normalize, score};
+}
@@ -0,0 +1,508 @@
+//! Listing status related operations
+use crate::asset::AssetType;
+use crate::calendar::{self, CalendarEvent};
+use crate::parsing::ParseWarning;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+/// Represents whether a symbol is actively traded or has been delisted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListingState {
+    /// Actively traded.
+    Active,
+    /// Delisted.
+    Delisted,
+}
+
+impl ListingState {
+    /// Value of the `state` parameter.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ListingState::Active => "active",
+            ListingState::Delisted => "delisted",
+        }
+    }
+}
+
+/// Represents a symbol listed on a US exchange.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Listing {
+    /// Symbol.
+    pub symbol: String,
+    /// Name of the company or fund.
+    pub name: String,
+    /// Exchange the symbol is listed on.
+    pub exchange: String,
+    /// Type of asset, i.e. [`AssetType::Equity`] for stocks or [`AssetType::Etf`].
+    pub asset_type: AssetType,
+    /// Date the symbol was first listed, if known.
+    pub ipo_date: Option<NaiveDate>,
+    /// Date the symbol was delisted, if it has been.
+    pub delisting_date: Option<NaiveDate>,
+    /// Whether the symbol is actively traded or has been delisted.
+    pub status: ListingState,
+}
+
+/// Represents the symbols which were active or delisted on a given date.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ListingStatus {
+    /// Date the listings refer to, or `None` for the latest trading day.
+    pub date: Option<NaiveDate>,
+    /// Whether the listings are of active or delisted symbols.
+    pub state: ListingState,
+    /// Listings, in the order returned by the API.
+    pub listings: Vec<Listing>,
+    /// Malformed listings skipped while leniently parsing the listing status.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Represents an initial public offering expected in the coming months.
+#[derive(Debug, PartialEq, Clone)]
+pub struct IpoEntry {
+    /// Symbol the company is expected to be listed under.
+    pub symbol: String,
+    /// Name of the company.
+    pub name: String,
+    /// Date the symbol is expected to be first listed on.
+    pub ipo_date: NaiveDate,
+    /// Low end of the expected offering price range, `None` when it isn't announced yet.
+    pub price_range_low: Option<f64>,
+    /// High end of the expected offering price range, `None` when it isn't announced yet.
+    pub price_range_high: Option<f64>,
+    /// Currency of the price range.
+    pub currency: String,
+    /// Exchange the symbol is expected to be listed on.
+    pub exchange: String,
+}
+
+/// Represents the initial public offerings expected in the coming three months.
+#[derive(Debug, PartialEq, Clone)]
+pub struct IpoCalendar {
+    /// Upcoming offerings, sorted by ascending IPO dates.
+    pub entries: Vec<IpoEntry>,
+    /// Malformed entries skipped while leniently parsing the calendar.
+    pub warnings: Vec<ParseWarning>,
+}
+
+impl IpoCalendar {
+    /// Offerings added to, removed from or rescheduled in the `later` calendar.
+    pub fn diff(&self, later: &IpoCalendar) -> Vec<CalendarEvent<IpoEntry>> {
+        calendar::diff(
+            &self.entries,
+            &later.entries,
+            |e| e.symbol.clone(),
+            |e| e.ipo_date,
+        )
+    }
+}
+
+/// Represents the changes between two listing status snapshots.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ListingChanges {
+    /// Listings only present in the later snapshot.
+    pub added: Vec<Listing>,
+    /// Listings only present in the earlier snapshot.
+    pub removed: Vec<Listing>,
+}
+
+impl ListingStatus {
+    /// Listing for `symbol`, if present in the snapshot.
+    pub fn get(&self, symbol: &str) -> Option<&Listing> {
+        self.listings.iter().find(|l| l.symbol == symbol)
+    }
+
+    /// Compare this snapshot with a `later` one, matching listings by symbol.
+    ///
+    /// For snapshots of active symbols, added listings were newly listed and removed listings
+    /// were delisted between both dates.
+    pub fn diff(&self, later: &ListingStatus) -> ListingChanges {
+        let earlier_symbols: HashSet<&str> =
+            self.listings.iter().map(|l| l.symbol.as_str()).collect();
+        let later_symbols: HashSet<&str> =
+            later.listings.iter().map(|l| l.symbol.as_str()).collect();
+        ListingChanges {
+            added: later
+                .listings
+                .iter()
+                .filter(|l| !earlier_symbols.contains(l.symbol.as_str()))
+                .cloned()
+                .collect(),
+            removed: self
+                .listings
+                .iter()
+                .filter(|l| !later_symbols.contains(l.symbol.as_str()))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Represents every symbol known to have been listed, to tell which symbols were listed on a
+/// given date free of survivorship bias.
+///
+/// A universe is built from listing status snapshots, whose delisted listings tell which
+/// symbols were listed in the past but not anymore.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Universe {
+    /// Listings, sorted by symbol then IPO date.
+    pub listings: Vec<Listing>,
+}
+
+impl Universe {
+    /// Build a universe from listing status `snapshots` of active or delisted symbols.
+    ///
+    /// Listings of the same symbol on the same exchange with the same IPO date are merged,
+    /// keeping their delisting date if any. A symbol reused after being delisted is kept as a
+    /// separate listing.
+    pub fn new(snapshots: &[ListingStatus]) -> Universe {
+        let mut listings: HashMap<(&str, &str, Option<NaiveDate>), &Listing> = HashMap::new();
+        for listing in snapshots.iter().flat_map(|s| &s.listings) {
+            let key = (
+                listing.symbol.as_str(),
+                listing.exchange.as_str(),
+                listing.ipo_date,
+            );
+            match listings.get(&key) {
+                Some(known) if known.delisting_date.is_some() => {}
+                _ => {
+                    listings.insert(key, listing);
+                }
+            }
+        }
+        let mut listings: Vec<Listing> = listings.values().map(|l| (*l).clone()).collect();
+        listings.sort_by(|a, b| (&a.symbol, a.ipo_date).cmp(&(&b.symbol, b.ipo_date)));
+        Universe { listings }
+    }
+
+    /// Listings on `exchange` (e.g. `NYSE`) on `date`, i.e. listed on or before `date` and
+    /// delisted after it. Listings with an unknown IPO date are assumed to be listed.
+    pub fn listed_on(&self, exchange: &str, date: NaiveDate) -> Vec<&Listing> {
+        self.listings
+            .iter()
+            .filter(|l| l.exchange == exchange && is_listed(l, date))
+            .collect()
+    }
+
+    /// Whether `symbol` was listed on any exchange on `date`.
+    pub fn is_listed(&self, symbol: &str, date: NaiveDate) -> bool {
+        self.listings
+            .iter()
+            .any(|l| l.symbol == symbol && is_listed(l, date))
+    }
+}
+
+fn is_listed(listing: &Listing, date: NaiveDate) -> bool {
+    listing.ipo_date.is_none_or(|ipo| ipo <= date)
+        && listing
+            .delisting_date
+            .is_none_or(|delisting| date < delisting)
+}
+
+pub mod parser {
+    use super::*;
+    use crate::deserialize::{from_str_or_none, parse_optional_date};
+    use crate::error::Error;
+    use crate::parsing::Parser;
+    use std::io::Read;
+
+    #[derive(Debug, Deserialize)]
+    struct ListingHelper {
+        symbol: String,
+        name: String,
+        exchange: String,
+        #[serde(rename = "assetType")]
+        asset_type: String,
+        #[serde(rename = "ipoDate")]
+        ipo_date: String,
+        #[serde(rename = "delistingDate")]
+        delisting_date: String,
+        status: String,
+    }
+
+    const COLUMNS: [&str; 7] = [
+        "symbol",
+        "name",
+        "exchange",
+        "assetType",
+        "ipoDate",
+        "delistingDate",
+        "status",
+    ];
+
+    fn listing(record: Result<ListingHelper, csv::Error>) -> Result<Listing, Error> {
+        let l = record?;
+        let status = match l.status.as_str() {
+            "Active" => ListingState::Active,
+            "Delisted" => ListingState::Delisted,
+            status => {
+                return Err(Error::ParsingError(format!(
+                    "unknown listing status {}",
+                    status
+                )))
+            }
+        };
+        let asset_type = AssetType::from_name(&l.asset_type)
+            .ok_or_else(|| Error::ParsingError(format!("unknown asset type {}", l.asset_type)))?;
+        Ok(Listing {
+            symbol: l.symbol,
+            name: l.name,
+            exchange: l.exchange,
+            asset_type,
+            ipo_date: parse_optional_date(&l.ipo_date)?,
+            delisting_date: parse_optional_date(&l.delisting_date)?,
+            status,
+        })
+    }
+
+    pub fn parse(
+        mut parser: Parser,
+        date: Option<NaiveDate>,
+        state: ListingState,
+        reader: impl Read,
+    ) -> Result<ListingStatus, Error> {
+        let mut reader = csv::Reader::from_reader(reader);
+        parser.check_columns(reader.headers()?, &COLUMNS)?;
+        let mut listings = vec![];
+        for (i, record) in reader.deserialize().enumerate() {
+            let path = format!("line {}", i + 2);
+            if let Some(listing) = parser.entry(&path, listing(record))? {
+                listings.push(listing);
+            }
+        }
+        Ok(ListingStatus {
+            date,
+            state,
+            listings,
+            warnings: parser.finish(),
+        })
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct IpoHelper {
+        symbol: String,
+        name: String,
+        #[serde(rename = "ipoDate")]
+        ipo_date: String,
+        #[serde(rename = "priceRangeLow", deserialize_with = "from_str_or_none")]
+        price_range_low: Option<f64>,
+        #[serde(rename = "priceRangeHigh", deserialize_with = "from_str_or_none")]
+        price_range_high: Option<f64>,
+        currency: String,
+        exchange: String,
+    }
+
+    const IPO_COLUMNS: [&str; 7] = [
+        "symbol",
+        "name",
+        "ipoDate",
+        "priceRangeLow",
+        "priceRangeHigh",
+        "currency",
+        "exchange",
+    ];
+
+    fn ipo_entry(record: Result<IpoHelper, csv::Error>) -> Result<IpoEntry, Error> {
+        let i = record?;
+        Ok(IpoEntry {
+            symbol: i.symbol,
+            name: i.name,
+            ipo_date: parse_optional_date(&i.ipo_date)?
+                .ok_or_else(|| Error::ParsingError("missing IPO date".into()))?,
+            // Price ranges which aren't announced yet are reported as 0.
+            price_range_low: i.price_range_low.filter(|p| *p != 0.0),
+            price_range_high: i.price_range_high.filter(|p| *p != 0.0),
+            currency: i.currency,
+            exchange: i.exchange,
+        })
+    }
+
+    pub fn parse_ipo_calendar(mut parser: Parser, reader: impl Read) -> Result<IpoCalendar, Error> {
+        let mut reader = csv::Reader::from_reader(reader);
+        parser.check_columns(reader.headers()?, &IPO_COLUMNS)?;
+        let mut entries = vec![];
+        for (i, record) in reader.deserialize().enumerate() {
+            let path = format!("line {}", i + 2);
+            if let Some(entry) = parser.entry(&path, ipo_entry(record))? {
+                entries.push(entry);
+            }
+        }
+        entries.sort_by_key(|e| e.ipo_date);
+        Ok(IpoCalendar {
+            entries,
+            warnings: parser.finish(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::{ParseMode, Parser};
+    use std::io::BufReader;
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn parse_active() {
+        let data: &[u8] = include_bytes!("../../tests/csv/listing_status.csv");
+        let status = parser::parse(
+            Parser::default(),
+            None,
+            ListingState::Active,
+            BufReader::new(data),
+        )
+        .expect("failed to parse listing status");
+        assert_eq!(status.listings.len(), 4);
+        assert_eq!(
+            status.listings[2],
+            Listing {
+                symbol: "AAA".to_string(),
+                name: "Investment Managers Series Trust II - AXS First Priority CLO Bond ETF"
+                    .to_string(),
+                exchange: "NYSE ARCA".to_string(),
+                asset_type: AssetType::Etf,
+                ipo_date: Some(ymd(2020, 9, 9)),
+                delisting_date: None,
+                status: ListingState::Active,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_delisted() {
+        let data: &[u8] = include_bytes!("../../tests/csv/listing_status_delisted.csv");
+        let status = parser::parse(
+            Parser::default(),
+            Some(ymd(2019, 12, 31)),
+            ListingState::Delisted,
+            BufReader::new(data),
+        )
+        .expect("failed to parse listing status");
+        assert_eq!(status.listings.len(), 2);
+        assert_eq!(status.listings[1].delisting_date, Some(ymd(2019, 10, 7)));
+        assert_eq!(status.listings[1].status, ListingState::Delisted);
+    }
+
+    #[test]
+    fn parse_unknown_asset_type() {
+        let data = b"symbol,name,exchange,assetType,ipoDate,delistingDate,status
+AAPL,Apple Inc,NASDAQ,Stock,1980-12-12,null,Active
+XYZW,Some Warrant,NYSE,Warrant,2021-01-04,null,Active
+";
+        let parse = |mode| parser::parse(Parser::new(mode), None, ListingState::Active, &data[..]);
+        assert!(parse(ParseMode::Strict).is_err());
+        let status = parse(ParseMode::Lenient).unwrap();
+        assert_eq!(status.listings.len(), 1);
+        assert_eq!(status.listings[0].asset_type, AssetType::Equity);
+        assert_eq!(status.warnings.len(), 1);
+        assert_eq!(status.warnings[0].path, "line 3");
+    }
+
+    #[test]
+    fn universe() {
+        let active: &[u8] = include_bytes!("../../tests/csv/listing_status.csv");
+        let delisted: &[u8] = include_bytes!("../../tests/csv/listing_status_delisted.csv");
+        let active = parser::parse(
+            Parser::default(),
+            None,
+            ListingState::Active,
+            BufReader::new(active),
+        )
+        .unwrap();
+        let delisted = parser::parse(
+            Parser::default(),
+            None,
+            ListingState::Delisted,
+            BufReader::new(delisted),
+        )
+        .unwrap();
+        let mut earlier = active.clone();
+        earlier.listings.push(Listing {
+            delisting_date: None,
+            status: ListingState::Active,
+            ..delisted.get("AABA").unwrap().clone()
+        });
+
+        let universe = Universe::new(&[earlier, active, delisted]);
+        assert_eq!(universe.listings.len(), 6);
+        let symbols = |exchange: &str, date: NaiveDate| -> Vec<String> {
+            universe
+                .listed_on(exchange, date)
+                .iter()
+                .map(|l| l.symbol.clone())
+                .collect()
+        };
+        assert_eq!(
+            symbols("NASDAQ", ymd(2016, 1, 4)),
+            vec!["AAAP", "AABA", "AAPL"]
+        );
+        assert_eq!(symbols("NASDAQ", ymd(2019, 10, 7)), vec!["AAPL"]);
+        assert_eq!(symbols("NYSE", ymd(2000, 1, 3)), vec!["A"]);
+        assert!(universe.is_listed("AABA", ymd(2019, 10, 4)));
+        assert!(!universe.is_listed("AA", ymd(2016, 10, 17)));
+    }
+
+    #[test]
+    fn diff() {
+        let data: &[u8] = include_bytes!("../../tests/csv/listing_status.csv");
+        let later = parser::parse(
+            Parser::default(),
+            None,
+            ListingState::Active,
+            BufReader::new(data),
+        )
+        .unwrap();
+        let mut earlier = later.clone();
+        earlier.date = Some(ymd(2018, 1, 2));
+        earlier.listings.retain(|l| l.symbol != "AAA");
+        earlier.listings.push(Listing {
+            symbol: "AABA".to_string(),
+            name: "Altaba Inc".to_string(),
+            exchange: "NASDAQ".to_string(),
+            asset_type: AssetType::Equity,
+            ipo_date: Some(ymd(1996, 4, 12)),
+            delisting_date: None,
+            status: ListingState::Active,
+        });
+
+        let changes = earlier.diff(&later);
+        let symbols = |listings: &[Listing]| -> Vec<String> {
+            listings.iter().map(|l| l.symbol.clone()).collect()
+        };
+        assert_eq!(symbols(&changes.added), vec!["AAA"]);
+        assert_eq!(symbols(&changes.removed), vec!["AABA"]);
+        assert!(later.get("AAPL").is_some());
+    }
+
+    #[test]
+    fn ipo_calendar() {
+        let data: &[u8] = include_bytes!("../../tests/csv/ipo_calendar.csv");
+        let calendar = parser::parse_ipo_calendar(Parser::default(), BufReader::new(data))
+            .expect("failed to parse IPO calendar");
+        let symbols: Vec<&str> = calendar.entries.iter().map(|e| e.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["BRSK", "ACQXU", "NWTN"]);
+        assert_eq!(calendar.entries[0].price_range_high, Some(21.5));
+        assert_eq!(calendar.entries[0].exchange, "NYSE");
+        assert_eq!(calendar.entries[1].symbol, "ACQXU");
+        assert_eq!(calendar.entries[1].price_range_low, None);
+        assert_eq!(calendar.entries[1].price_range_high, None);
+
+        let mut later = calendar.clone();
+        later.entries.remove(1);
+        later.entries[0].ipo_date = ymd(2024, 5, 31);
+        later.entries[0].price_range_low = Some(20.0);
+        assert_eq!(
+            calendar.diff(&later),
+            vec![
+                CalendarEvent::Rescheduled {
+                    previous_date: ymd(2024, 5, 24),
+                    entry: later.entries[0].clone(),
+                },
+                CalendarEvent::Removed(calendar.entries[1].clone()),
+            ]
+        );
+    }
+}
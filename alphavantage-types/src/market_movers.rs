@@ -0,0 +1,483 @@
+//! Market movers related operations
+use crate::fundamentals::CompanyOverview;
+use crate::parsing::ParseWarning;
+use chrono::DateTime;
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+#[cfg(feature = "table")]
+use crate::table;
+
+/// Represents a ticker moving the US market.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Mover {
+    /// Ticker symbol.
+    pub ticker: String,
+    /// Latest price.
+    pub price: f64,
+    /// Change in price since the previous close.
+    pub change_amount: f64,
+    /// Change in price since the previous close, as a percentage.
+    pub change_percentage: f64,
+    /// Trading volume.
+    pub volume: u64,
+}
+
+/// Represents the top gainers, losers and most actively traded tickers in the US market.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MarketMovers {
+    /// Date the information was last updated at.
+    pub last_updated: DateTime<Tz>,
+    /// Tickers with the largest price increase.
+    pub top_gainers: Vec<Mover>,
+    /// Tickers with the largest price decrease.
+    pub top_losers: Vec<Mover>,
+    /// Tickers with the largest trading volume.
+    pub most_actively_traded: Vec<Mover>,
+    /// Issues found while parsing the market movers, e.g. fields unknown to the crate.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Represents the list a market mover was reported in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    /// Top gainers.
+    Gainer,
+    /// Top losers.
+    Loser,
+    /// Most actively traded.
+    MostActive,
+}
+
+/// Represents a change in the lists of market movers between two snapshots.
+///
+/// Ranks start at 1 for the first mover of a list.
+#[derive(Debug, PartialEq, Clone)]
+pub enum MoverEvent {
+    /// A ticker entered the list of a category.
+    Entered {
+        /// List the ticker entered.
+        category: Category,
+        /// Rank of the ticker in the list.
+        rank: usize,
+        /// The market mover.
+        mover: Mover,
+    },
+    /// A ticker left the list of a category.
+    Left {
+        /// List the ticker left.
+        category: Category,
+        /// Rank the ticker had in the list.
+        rank: usize,
+        /// The market mover, as last reported in the list.
+        mover: Mover,
+    },
+    /// A ticker changed rank in the list of a category.
+    RankChanged {
+        /// List the ticker is reported in.
+        category: Category,
+        /// Rank the ticker had in the list.
+        previous_rank: usize,
+        /// Rank the ticker has in the list.
+        rank: usize,
+        /// The market mover.
+        mover: Mover,
+    },
+}
+
+/// Represents a market mover matching the filters of a [`Screener`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct ScreenedMover {
+    /// List the mover was reported in.
+    pub category: Category,
+    /// The market mover.
+    pub mover: Mover,
+    /// Company overview for the ticker, if requested and available.
+    pub overview: Option<CompanyOverview>,
+}
+
+/// Filters applied to market movers on the client side.
+#[derive(Debug, Default, Clone)]
+pub struct Screener {
+    min_price: Option<f64>,
+    min_volume: Option<u64>,
+    min_change_percentage: Option<f64>,
+    categories: Option<Vec<Category>>,
+    overview: bool,
+}
+
+impl MarketMovers {
+    /// Iterate over every market mover along with the list it was reported in.
+    pub fn iter(&self) -> impl Iterator<Item = (Category, &Mover)> {
+        let gainers = self.top_gainers.iter().map(|m| (Category::Gainer, m));
+        let losers = self.top_losers.iter().map(|m| (Category::Loser, m));
+        let active = self
+            .most_actively_traded
+            .iter()
+            .map(|m| (Category::MostActive, m));
+        gainers.chain(losers).chain(active)
+    }
+
+    /// Render the first `n` movers of each list as an aligned text table, e.g. to inspect them
+    /// in a REPL.
+    #[cfg(feature = "table")]
+    pub fn to_table(&self, n: usize) -> String {
+        let categories = [Category::Gainer, Category::Loser, Category::MostActive];
+        let rows: Vec<Vec<String>> = categories
+            .iter()
+            .flat_map(|category| {
+                let label = match category {
+                    Category::Gainer => "gainer",
+                    Category::Loser => "loser",
+                    Category::MostActive => "most active",
+                };
+                self.category(*category).iter().take(n).map(move |m| {
+                    vec![
+                        label.to_string(),
+                        m.ticker.clone(),
+                        table::decimal(m.price),
+                        table::decimal(m.change_amount),
+                        format!("{:.2}", m.change_percentage),
+                        m.volume.to_string(),
+                    ]
+                })
+            })
+            .collect();
+        table::render(
+            &["list", "ticker", "price", "change", "change %", "volume"],
+            &rows,
+        )
+    }
+
+    /// Movers reported in the list of `category`, by rank.
+    pub fn category(&self, category: Category) -> &[Mover] {
+        match category {
+            Category::Gainer => &self.top_gainers,
+            Category::Loser => &self.top_losers,
+            Category::MostActive => &self.most_actively_traded,
+        }
+    }
+
+    /// Changes in the lists of market movers from this snapshot to a `later` one.
+    ///
+    /// For each category, tickers entering or changing rank are reported by their rank in the
+    /// later snapshot, followed by the tickers leaving by their previous rank.
+    pub fn diff(&self, later: &MarketMovers) -> Vec<MoverEvent> {
+        let mut events = vec![];
+        for category in [Category::Gainer, Category::Loser, Category::MostActive].iter() {
+            let category = *category;
+            let before = self.category(category);
+            let after = later.category(category);
+            let rank = |movers: &[Mover], ticker: &str| {
+                movers
+                    .iter()
+                    .position(|m| m.ticker == ticker)
+                    .map(|i| i + 1)
+            };
+            for (i, mover) in after.iter().enumerate() {
+                let event = match rank(before, &mover.ticker) {
+                    None => MoverEvent::Entered {
+                        category,
+                        rank: i + 1,
+                        mover: mover.clone(),
+                    },
+                    Some(previous_rank) if previous_rank != i + 1 => MoverEvent::RankChanged {
+                        category,
+                        previous_rank,
+                        rank: i + 1,
+                        mover: mover.clone(),
+                    },
+                    Some(_) => continue,
+                };
+                events.push(event);
+            }
+            for (i, mover) in before.iter().enumerate() {
+                if rank(after, &mover.ticker).is_none() {
+                    events.push(MoverEvent::Left {
+                        category,
+                        rank: i + 1,
+                        mover: mover.clone(),
+                    });
+                }
+            }
+        }
+        events
+    }
+
+    /// Snapshot without any market movers, as of the same date as this one, e.g. to report every
+    /// mover of a first snapshot as having entered with [`MarketMovers::diff`].
+    pub fn empty(&self) -> MarketMovers {
+        MarketMovers {
+            last_updated: self.last_updated,
+            top_gainers: vec![],
+            top_losers: vec![],
+            most_actively_traded: vec![],
+            warnings: vec![],
+        }
+    }
+}
+
+impl Screener {
+    /// Create a screener matching every market mover.
+    pub fn new() -> Screener {
+        Screener::default()
+    }
+
+    /// Only match market movers with a price of at least `price`.
+    pub fn min_price(mut self, price: f64) -> Screener {
+        self.min_price = Some(price);
+        self
+    }
+
+    /// Only match market movers with a trading volume of at least `volume`.
+    pub fn min_volume(mut self, volume: u64) -> Screener {
+        self.min_volume = Some(volume);
+        self
+    }
+
+    /// Only match market movers whose price moved, up or down, by at least `percentage`.
+    pub fn min_change_percentage(mut self, percentage: f64) -> Screener {
+        self.min_change_percentage = Some(percentage);
+        self
+    }
+
+    /// Only match market movers reported in the given `categories`.
+    pub fn categories(mut self, categories: &[Category]) -> Screener {
+        self.categories = Some(categories.to_vec());
+        self
+    }
+
+    /// Retrieve the company overview of each matching market mover.
+    pub fn with_overview(mut self) -> Screener {
+        self.overview = true;
+        self
+    }
+
+    /// Whether the company overview of each matching market mover is retrieved, see
+    /// [`Screener::with_overview`].
+    pub fn wants_overview(&self) -> bool {
+        self.overview
+    }
+
+    /// Whether `mover`, reported in `category`, matches the filters of the screener.
+    pub fn matches(&self, category: Category, mover: &Mover) -> bool {
+        self.categories
+            .as_ref()
+            .is_none_or(|categories| categories.contains(&category))
+            && self.min_price.is_none_or(|price| mover.price >= price)
+            && self.min_volume.is_none_or(|volume| mover.volume >= volume)
+            && self
+                .min_change_percentage
+                .is_none_or(|percentage| mover.change_percentage.abs() >= percentage)
+    }
+
+    /// Apply the filters of the screener to `movers`, without company overviews.
+    pub fn screen(&self, movers: &MarketMovers) -> Vec<ScreenedMover> {
+        movers
+            .iter()
+            .filter(|(category, mover)| self.matches(*category, mover))
+            .map(|(category, mover)| ScreenedMover {
+                category,
+                mover: mover.clone(),
+                overview: None,
+            })
+            .collect()
+    }
+}
+
+pub mod parser {
+    use super::*;
+    use crate::deserialize::{from_percent_str, from_str, parse_date_with_time_zone};
+    use crate::error::Error;
+    use crate::parsing::Parser;
+    use std::io::Read;
+
+    #[derive(Debug, Deserialize)]
+    struct MoverHelper {
+        ticker: String,
+        #[serde(deserialize_with = "from_str")]
+        price: f64,
+        #[serde(deserialize_with = "from_str")]
+        change_amount: f64,
+        #[serde(deserialize_with = "from_percent_str")]
+        change_percentage: f64,
+        #[serde(deserialize_with = "from_str")]
+        volume: u64,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct MarketMoversHelper {
+        #[serde(rename = "Error Message")]
+        error: Option<String>,
+        last_updated: Option<String>,
+        top_gainers: Option<Vec<MoverHelper>>,
+        top_losers: Option<Vec<MoverHelper>>,
+        most_actively_traded: Option<Vec<MoverHelper>>,
+    }
+
+    fn movers(helpers: Option<Vec<MoverHelper>>, name: &str) -> Result<Vec<Mover>, Error> {
+        let helpers = helpers.ok_or_else(|| Error::ParsingError(format!("missing {}", name)))?;
+        let movers = helpers
+            .into_iter()
+            .map(|m| Mover {
+                ticker: m.ticker,
+                price: m.price,
+                change_amount: m.change_amount,
+                change_percentage: m.change_percentage,
+                volume: m.volume,
+            })
+            .collect();
+        Ok(movers)
+    }
+
+    pub fn parse(mut parser: Parser, reader: impl Read) -> Result<MarketMovers, Error> {
+        let helper: MarketMoversHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let last_updated = helper
+            .last_updated
+            .ok_or_else(|| Error::ParsingError("missing last updated".into()))
+            .map(|v| parse_date_with_time_zone(&v))??;
+
+        let market_movers = MarketMovers {
+            last_updated,
+            top_gainers: movers(helper.top_gainers, "top gainers")?,
+            top_losers: movers(helper.top_losers, "top losers")?,
+            most_actively_traded: movers(helper.most_actively_traded, "most actively traded")?,
+            warnings: parser.finish(),
+        };
+        Ok(market_movers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deserialize::parse_date;
+    use crate::parsing::Parser;
+    use chrono_tz::US::Eastern;
+    use std::io::BufReader;
+
+    fn market_movers() -> MarketMovers {
+        let data: &[u8] = include_bytes!("../../tests/json/top_gainers_losers.json");
+        parser::parse(Parser::default(), BufReader::new(data))
+            .expect("failed to parse market movers")
+    }
+
+    #[test]
+    fn parse() {
+        let market_movers = market_movers();
+        assert_eq!(
+            market_movers.last_updated,
+            parse_date("2024-05-03 16:15:59", Eastern).unwrap()
+        );
+        assert_eq!(market_movers.top_gainers.len(), 3);
+        assert_eq!(market_movers.top_losers.len(), 2);
+        assert_eq!(market_movers.most_actively_traded.len(), 2);
+        assert_eq!(
+            market_movers.top_losers[0],
+            Mover {
+                ticker: "ATNF".to_string(),
+                price: 0.711,
+                change_amount: -0.669,
+                change_percentage: -48.4783,
+                volume: 1824364,
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "table")]
+    fn movers_table() {
+        let table = market_movers().to_table(1);
+        assert_eq!(
+            table,
+            concat!(
+                "list         ticker   price  change  change %     volume\n",
+                "-----------  ------  ------  ------  --------  ---------\n",
+                "gainer       ICCT      0.29    0.19    190.00   14616523\n",
+                "loser        ATNF      0.71   -0.67    -48.48    1824364\n",
+                "most active  AAPL    183.38   10.35      5.98  163224109\n",
+            )
+        );
+    }
+
+    #[test]
+    fn diff() {
+        let before = market_movers();
+        let mut after = before.clone();
+        after.top_gainers.swap(0, 1);
+        let left = after.top_losers.remove(0);
+        let mut entered = left.clone();
+        entered.ticker = "MSFT".to_string();
+        after.top_losers.push(entered.clone());
+
+        assert!(before.diff(&before).is_empty());
+        assert_eq!(
+            before.diff(&after),
+            vec![
+                MoverEvent::RankChanged {
+                    category: Category::Gainer,
+                    previous_rank: 2,
+                    rank: 1,
+                    mover: after.top_gainers[0].clone(),
+                },
+                MoverEvent::RankChanged {
+                    category: Category::Gainer,
+                    previous_rank: 1,
+                    rank: 2,
+                    mover: after.top_gainers[1].clone(),
+                },
+                MoverEvent::RankChanged {
+                    category: Category::Loser,
+                    previous_rank: 2,
+                    rank: 1,
+                    mover: after.top_losers[0].clone(),
+                },
+                MoverEvent::Entered {
+                    category: Category::Loser,
+                    rank: 2,
+                    mover: entered,
+                },
+                MoverEvent::Left {
+                    category: Category::Loser,
+                    rank: 1,
+                    mover: left,
+                },
+            ]
+        );
+        assert_eq!(before.empty().diff(&before).len(), 7);
+    }
+
+    #[test]
+    fn screen() {
+        let market_movers = market_movers();
+        let tickers = |screener: Screener| -> Vec<String> {
+            screener
+                .screen(&market_movers)
+                .into_iter()
+                .map(|m| m.mover.ticker)
+                .collect()
+        };
+        assert_eq!(tickers(Screener::new()).len(), 7);
+        assert_eq!(
+            tickers(Screener::new().min_price(1.0).min_volume(1_000_000)),
+            vec!["NVTS", "FNKO", "AAPL", "NVTS"]
+        );
+        assert_eq!(
+            tickers(Screener::new().min_change_percentage(40.0)),
+            vec!["ICCT", "NVTS", "ATNF", "NVTS"]
+        );
+        assert_eq!(
+            tickers(
+                Screener::new()
+                    .min_change_percentage(40.0)
+                    .categories(&[Category::Loser])
+            ),
+            vec!["ATNF"]
+        );
+    }
+}
@@ -0,0 +1,35 @@
+//! Fuzzy matching of search keywords against symbols and names
+/// Normalize search `keywords`, ignoring case and surrounding whitespace.
+pub fn normalize(keywords: &str) -> String {
+    keywords.trim().to_lowercase()
+}
+
+/// Score how well a normalized `query` matches a symbol and its name, between 0 and 1.
+pub fn score(query: &str, symbol: &str, name: &str) -> f64 {
+    if query.is_empty() {
+        return 0.0;
+    }
+    let symbol = symbol.to_lowercase();
+    let name = name.to_lowercase();
+    if symbol == query || name == query {
+        1.0
+    } else if symbol.starts_with(query) {
+        0.9
+    } else if name.starts_with(query) || name.split_whitespace().any(|w| w.starts_with(query)) {
+        0.8
+    } else if symbol.contains(query) || name.contains(query) {
+        0.6
+    } else if is_subsequence(query, &name) {
+        0.4
+    } else {
+        0.0
+    }
+}
+
+fn is_subsequence(query: &str, value: &str) -> bool {
+    let mut chars = value.chars();
+    query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .all(|q| chars.any(|c| c == q))
+}
@@ -0,0 +1,355 @@
+//! News and sentiment related operations
+use crate::parsing::ParseWarning;
+use chrono::{DateTime, Duration};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Minimum change in weighted sentiment between the halves of a window for a trend to be
+/// reported as improving or deteriorating.
+const TREND_THRESHOLD: f64 = 0.05;
+
+/// Represents a news article along with its sentiment.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Article {
+    /// Title.
+    pub title: String,
+    /// URL of the article.
+    pub url: String,
+    /// Date the article was published at.
+    pub time_published: DateTime<Tz>,
+    /// Authors.
+    pub authors: Vec<String>,
+    /// Summary.
+    pub summary: String,
+    /// URL of the banner image, if any.
+    pub banner_image: Option<String>,
+    /// Name of the source (e.g. a newspaper).
+    pub source: String,
+    /// Category of the article within its source.
+    pub category_within_source: String,
+    /// Domain of the source.
+    pub source_domain: String,
+    /// Topics covered by the article.
+    pub topics: Vec<Topic>,
+    /// Overall sentiment, from -1 (bearish) to 1 (bullish).
+    pub overall_sentiment_score: f64,
+    /// Label of the overall sentiment (e.g. `Somewhat-Bullish`).
+    pub overall_sentiment_label: String,
+    /// Sentiment of the article about each ticker it mentions.
+    pub ticker_sentiment: Vec<TickerSentiment>,
+}
+
+/// Represents a topic covered by a news article.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Topic {
+    /// Name of the topic.
+    pub topic: String,
+    /// How relevant the topic is to the article, between 0 and 1.
+    pub relevance_score: f64,
+}
+
+/// Represents the sentiment of a news article about a ticker.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TickerSentiment {
+    /// Ticker symbol.
+    pub ticker: String,
+    /// How relevant the ticker is to the article, between 0 and 1.
+    pub relevance_score: f64,
+    /// Sentiment about the ticker, from -1 (bearish) to 1 (bullish).
+    pub sentiment_score: f64,
+    /// Label of the sentiment about the ticker (e.g. `Somewhat-Bullish`).
+    pub sentiment_label: String,
+}
+
+/// Represents a feed of news articles, sorted by descending publication dates.
+#[derive(Debug, PartialEq, Clone)]
+pub struct NewsFeed {
+    /// Articles in the feed.
+    pub articles: Vec<Article>,
+    /// Malformed articles skipped while leniently parsing the feed.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Represents the direction sentiment about a ticker moved in over a window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    /// Sentiment was more bullish during the second half of the window.
+    Improving,
+    /// Sentiment was more bearish during the second half of the window.
+    Deteriorating,
+    /// Sentiment didn't change noticeably, or there wasn't enough data to tell.
+    Stable,
+}
+
+/// Represents the sentiment about a ticker aggregated over the articles of a window.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SentimentSummary {
+    /// Ticker symbol.
+    pub ticker: String,
+    /// Number of articles mentioning the ticker.
+    pub articles: usize,
+    /// Average sentiment about the ticker.
+    pub average_sentiment: f64,
+    /// Average sentiment about the ticker, weighted by its relevance to each article.
+    pub weighted_sentiment: f64,
+    /// Direction the sentiment moved in between the halves of the window.
+    pub trend: Trend,
+}
+
+impl NewsFeed {
+    /// Aggregate the sentiment about each ticker mentioned by the articles published from
+    /// `start` to `end`, both inclusive, sorted by ticker.
+    pub fn sentiment_by_ticker(
+        &self,
+        start: DateTime<Tz>,
+        end: DateTime<Tz>,
+    ) -> Vec<SentimentSummary> {
+        let middle = start + Duration::seconds((end - start).num_seconds() / 2);
+        let mut by_ticker: BTreeMap<&str, Vec<(&TickerSentiment, bool)>> = BTreeMap::new();
+        for article in &self.articles {
+            if article.time_published < start || article.time_published > end {
+                continue;
+            }
+            let late = article.time_published > middle;
+            for sentiment in &article.ticker_sentiment {
+                by_ticker
+                    .entry(&sentiment.ticker)
+                    .or_default()
+                    .push((sentiment, late));
+            }
+        }
+        by_ticker
+            .into_iter()
+            .map(|(ticker, sentiments)| {
+                let all: Vec<&TickerSentiment> = sentiments.iter().map(|s| s.0).collect();
+                let early: Vec<&TickerSentiment> =
+                    sentiments.iter().filter(|s| !s.1).map(|s| s.0).collect();
+                let late: Vec<&TickerSentiment> =
+                    sentiments.iter().filter(|s| s.1).map(|s| s.0).collect();
+                let trend = match (weighted_average(&early), weighted_average(&late)) {
+                    (Some(early), Some(late)) if late - early >= TREND_THRESHOLD => {
+                        Trend::Improving
+                    }
+                    (Some(early), Some(late)) if early - late >= TREND_THRESHOLD => {
+                        Trend::Deteriorating
+                    }
+                    _ => Trend::Stable,
+                };
+                SentimentSummary {
+                    ticker: ticker.to_string(),
+                    articles: all.len(),
+                    average_sentiment: all.iter().map(|s| s.sentiment_score).sum::<f64>()
+                        / all.len() as f64,
+                    weighted_sentiment: weighted_average(&all).unwrap_or(0.0),
+                    trend,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Average of `sentiments` weighted by relevance, if any of them is relevant.
+fn weighted_average(sentiments: &[&TickerSentiment]) -> Option<f64> {
+    let weights: f64 = sentiments.iter().map(|s| s.relevance_score).sum();
+    if weights <= 0.0 {
+        return None;
+    }
+    let total: f64 = sentiments
+        .iter()
+        .map(|s| s.sentiment_score * s.relevance_score)
+        .sum();
+    Some(total / weights)
+}
+
+pub mod parser {
+    use super::*;
+    use crate::deserialize::from_str;
+    use crate::error::Error;
+    use crate::parsing::Parser;
+    use chrono::{NaiveDateTime, TimeZone};
+    use chrono_tz::US::Eastern;
+    use serde::de::IgnoredAny;
+    use std::io::Read;
+
+    /// Format of publication dates, which are reported in US/Eastern time.
+    const TIME_PUBLISHED_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+    #[derive(Debug, Deserialize)]
+    struct TopicHelper {
+        topic: String,
+        #[serde(deserialize_with = "from_str")]
+        relevance_score: f64,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct TickerSentimentHelper {
+        ticker: String,
+        #[serde(deserialize_with = "from_str")]
+        relevance_score: f64,
+        #[serde(deserialize_with = "from_str")]
+        ticker_sentiment_score: f64,
+        ticker_sentiment_label: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct ArticleHelper {
+        title: String,
+        url: String,
+        time_published: String,
+        authors: Vec<String>,
+        summary: String,
+        banner_image: Option<String>,
+        source: String,
+        category_within_source: String,
+        source_domain: String,
+        topics: Vec<TopicHelper>,
+        overall_sentiment_score: f64,
+        overall_sentiment_label: String,
+        ticker_sentiment: Vec<TickerSentimentHelper>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct NewsFeedHelper {
+        #[serde(rename = "Error Message")]
+        error: Option<String>,
+        #[serde(rename = "items", default)]
+        _items: IgnoredAny,
+        #[serde(rename = "sentiment_score_definition", default)]
+        _sentiment_score_definition: IgnoredAny,
+        #[serde(rename = "relevance_score_definition", default)]
+        _relevance_score_definition: IgnoredAny,
+        feed: Option<Vec<serde_json::Value>>,
+    }
+
+    fn article(
+        parser: &mut Parser,
+        path: &str,
+        value: &serde_json::Value,
+    ) -> Result<Article, Error> {
+        let a: ArticleHelper = parser.parse_value(path, value)?;
+        let time_published =
+            NaiveDateTime::parse_from_str(&a.time_published, TIME_PUBLISHED_FORMAT)?;
+        let time_published = Eastern
+            .from_local_datetime(&time_published)
+            .single()
+            .ok_or_else(|| Error::ParsingError("unable to parse time published".into()))?;
+        Ok(Article {
+            title: a.title,
+            url: a.url,
+            time_published,
+            authors: a.authors,
+            summary: a.summary,
+            banner_image: a.banner_image.filter(|image| !image.is_empty()),
+            source: a.source,
+            category_within_source: a.category_within_source,
+            source_domain: a.source_domain,
+            topics: a
+                .topics
+                .into_iter()
+                .map(|t| Topic {
+                    topic: t.topic,
+                    relevance_score: t.relevance_score,
+                })
+                .collect(),
+            overall_sentiment_score: a.overall_sentiment_score,
+            overall_sentiment_label: a.overall_sentiment_label,
+            ticker_sentiment: a
+                .ticker_sentiment
+                .into_iter()
+                .map(|s| TickerSentiment {
+                    ticker: s.ticker,
+                    relevance_score: s.relevance_score,
+                    sentiment_score: s.ticker_sentiment_score,
+                    sentiment_label: s.ticker_sentiment_label,
+                })
+                .collect(),
+        })
+    }
+
+    pub fn parse(mut parser: Parser, reader: impl Read) -> Result<NewsFeed, Error> {
+        let helper: NewsFeedHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let feed = helper
+            .feed
+            .ok_or_else(|| Error::ParsingError("missing feed".into()))?;
+
+        let mut articles = vec![];
+        for (i, value) in feed.iter().enumerate() {
+            let path = format!("feed.{}", i);
+            let result = article(&mut parser, &path, value);
+            if let Some(article) = parser.entry(&path, result)? {
+                articles.push(article);
+            }
+        }
+        articles.sort_by_key(|a| std::cmp::Reverse(a.time_published));
+        Ok(NewsFeed {
+            articles,
+            warnings: parser.finish(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deserialize::parse_date;
+    use crate::parsing::Parser;
+    use chrono_tz::US::Eastern;
+    use std::io::BufReader;
+
+    fn news_feed() -> NewsFeed {
+        let data: &[u8] = include_bytes!("../../tests/json/news_sentiment.json");
+        parser::parse(Parser::default(), BufReader::new(data)).expect("failed to parse news feed")
+    }
+
+    #[test]
+    fn parse() {
+        let feed = news_feed();
+        assert_eq!(feed.articles.len(), 3);
+        let article = &feed.articles[0];
+        assert_eq!(
+            article.time_published,
+            parse_date("2024-05-03 15:30:00", Eastern).unwrap()
+        );
+        assert_eq!(article.authors, vec!["Jane Doe"]);
+        assert_eq!(article.topics[0].topic, "Earnings");
+        assert_eq!(
+            article.ticker_sentiment[0],
+            TickerSentiment {
+                ticker: "MSFT".to_string(),
+                relevance_score: 0.8,
+                sentiment_score: 0.45,
+                sentiment_label: "Bullish".to_string(),
+            }
+        );
+        assert_eq!(feed.articles[1].banner_image, None);
+        assert_eq!(feed.articles[2].banner_image, None);
+    }
+
+    #[test]
+    fn sentiment_by_ticker() {
+        let feed = news_feed();
+        let start = parse_date("2024-05-01", Eastern).unwrap();
+        let end = parse_date("2024-05-03 23:59:59", Eastern).unwrap();
+        let summaries = feed.sentiment_by_ticker(start, end);
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].ticker, "AAPL");
+        assert_eq!(summaries[0].articles, 1);
+        assert_eq!(summaries[0].trend, Trend::Stable);
+
+        let msft = &summaries[1];
+        assert_eq!(msft.articles, 3);
+        assert!((msft.average_sentiment - 0.4 / 3.0).abs() < 1e-9);
+        assert!((msft.weighted_sentiment - 0.185 / 2.3).abs() < 1e-9);
+        assert_eq!(msft.trend, Trend::Improving);
+
+        let start = parse_date("2024-05-02", Eastern).unwrap();
+        let summaries = feed.sentiment_by_ticker(start, end);
+        assert_eq!(summaries[1].articles, 2);
+    }
+}
@@ -0,0 +1,190 @@
+//! Expiration calendar of US equity options
+//!
+//! Expirations follow the standard listing schedule: weekly options expire on Fridays and
+//! monthly options on the third Friday of the month, or on the previous trading day when the
+//! market is closed that Friday. Monthly expirations more than a year away are LEAPS, which are
+//! only listed in January.
+//!
+//! ```
+//! use alphavantage_types::options::{self, ExpirationKind};
+//! use chrono::NaiveDate;
+//!
+//! let today = NaiveDate::from_ymd_opt(2025, 4, 1).unwrap();
+//! let until = NaiveDate::from_ymd_opt(2025, 4, 30).unwrap();
+//! let expirations = options::expirations(today, until, false);
+//! // Good Friday moves the April expiration to Thursday.
+//! assert_eq!(expirations[0].date, NaiveDate::from_ymd_opt(2025, 4, 17).unwrap());
+//! assert_eq!(expirations[0].kind, ExpirationKind::Monthly);
+//! assert_eq!(expirations[0].days_to_expiry(today), 12);
+//! ```
+use crate::calendar::{is_trading_day, previous_trading_day, trading_days};
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
+
+/// Represents the kind of an option expiration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExpirationKind {
+    /// Weekly expiration, on any other Friday than the monthly one.
+    Weekly,
+    /// Standard monthly expiration, on the third Friday of the month.
+    Monthly,
+    /// Long-term monthly expiration, more than a year away.
+    Leaps,
+}
+
+/// Represents the expiration date of options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Expiration {
+    /// Last trading day of the options.
+    pub date: NaiveDate,
+    /// Kind of the expiration, as of the day it was classified on.
+    pub kind: ExpirationKind,
+}
+
+impl Expiration {
+    /// Classify the expiration on `date` as of `today`.
+    pub fn new(date: NaiveDate, today: NaiveDate) -> Expiration {
+        let kind = if Some(date) != monthly_expiration(date.year(), date.month()) {
+            ExpirationKind::Weekly
+        } else if date > today + Months::new(12) {
+            ExpirationKind::Leaps
+        } else {
+            ExpirationKind::Monthly
+        };
+        Expiration { date, kind }
+    }
+
+    /// Number of trading days after `today` up to the expiration included, i.e. `0` on the
+    /// expiration date or once it's past.
+    pub fn days_to_expiry(&self, today: NaiveDate) -> u32 {
+        if today < self.date {
+            trading_days(today + Duration::days(1), self.date).len() as u32
+        } else {
+            0
+        }
+    }
+
+    /// Number of calendar days between `today` and the expiration, negative once it's past.
+    pub fn calendar_days(&self, today: NaiveDate) -> i64 {
+        (self.date - today).num_days()
+    }
+}
+
+/// Monthly expiration of the given `month` of `year`, `None` if `month` isn't between 1 and 12.
+pub fn monthly_expiration(year: i32, month: u32) -> Option<NaiveDate> {
+    NaiveDate::from_weekday_of_month_opt(year, month, Weekday::Fri, 3).map(expiration_on)
+}
+
+/// Standard expirations from `today` to `until`, both inclusive, sorted by date.
+///
+/// Weekly expirations are only included with `weeklies`, as options on most symbols are only
+/// listed monthly, and LEAPS are only included in January.
+pub fn expirations(today: NaiveDate, until: NaiveDate, weeklies: bool) -> Vec<Expiration> {
+    let mut expirations = vec![];
+    let mut friday = today;
+    while friday.weekday() != Weekday::Fri {
+        friday += Duration::days(1);
+    }
+    loop {
+        let date = expiration_on(friday);
+        friday += Duration::days(7);
+        if date < today {
+            continue;
+        }
+        if date > until {
+            break;
+        }
+        let expiration = Expiration::new(date, today);
+        let listed = match expiration.kind {
+            ExpirationKind::Weekly => weeklies,
+            ExpirationKind::Monthly => true,
+            ExpirationKind::Leaps => date.month() == 1,
+        };
+        if listed {
+            expirations.push(expiration);
+        }
+    }
+    expirations
+}
+
+/// Options expiring on `friday` expire on the previous trading day when the market is closed.
+fn expiration_on(friday: NaiveDate) -> NaiveDate {
+    if is_trading_day(friday) {
+        friday
+    } else {
+        previous_trading_day(friday)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn monthly_expirations() {
+        assert_eq!(monthly_expiration(2024, 5), Some(ymd(2024, 5, 17)));
+        // Juneteenth falls on the third Friday of June 2026.
+        assert_eq!(monthly_expiration(2026, 6), Some(ymd(2026, 6, 18)));
+        assert_eq!(monthly_expiration(2024, 13), None);
+
+        let today = ymd(2024, 5, 3);
+        assert_eq!(
+            Expiration::new(ymd(2024, 5, 17), today).kind,
+            ExpirationKind::Monthly
+        );
+        assert_eq!(
+            Expiration::new(ymd(2024, 5, 10), today).kind,
+            ExpirationKind::Weekly
+        );
+        assert_eq!(
+            Expiration::new(ymd(2025, 6, 20), today).kind,
+            ExpirationKind::Leaps
+        );
+    }
+
+    #[test]
+    fn enumerate_expirations() {
+        let today = ymd(2024, 5, 10);
+        let dates = |weeklies| -> Vec<NaiveDate> {
+            expirations(today, ymd(2024, 6, 30), weeklies)
+                .iter()
+                .map(|e| e.date)
+                .collect()
+        };
+        assert_eq!(dates(false), vec![ymd(2024, 5, 17), ymd(2024, 6, 21)]);
+        assert_eq!(
+            dates(true),
+            vec![
+                ymd(2024, 5, 10),
+                ymd(2024, 5, 17),
+                ymd(2024, 5, 24),
+                ymd(2024, 5, 31),
+                ymd(2024, 6, 7),
+                ymd(2024, 6, 14),
+                ymd(2024, 6, 21),
+                ymd(2024, 6, 28),
+            ]
+        );
+
+        let leaps: Vec<Expiration> = expirations(today, ymd(2027, 1, 31), false)
+            .into_iter()
+            .filter(|e| e.kind == ExpirationKind::Leaps)
+            .collect();
+        let dates: Vec<NaiveDate> = leaps.iter().map(|e| e.date).collect();
+        assert_eq!(dates, vec![ymd(2026, 1, 16), ymd(2027, 1, 15)]);
+    }
+
+    #[test]
+    fn days_to_expiry() {
+        // Memorial Day isn't a trading day.
+        let expiration = Expiration::new(ymd(2024, 5, 31), ymd(2024, 5, 24));
+        assert_eq!(expiration.days_to_expiry(ymd(2024, 5, 24)), 4);
+        assert_eq!(expiration.calendar_days(ymd(2024, 5, 24)), 7);
+        assert_eq!(expiration.days_to_expiry(ymd(2024, 5, 31)), 0);
+        assert_eq!(expiration.days_to_expiry(ymd(2024, 6, 3)), 0);
+        assert_eq!(expiration.calendar_days(ymd(2024, 6, 3)), -3);
+    }
+}
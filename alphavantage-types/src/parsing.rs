@@ -0,0 +1,356 @@
+//! Parsing of API responses
+use crate::deserialize;
+use crate::error::Error;
+use chrono::{DateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::io::Read;
+use std::time::Duration;
+
+/// How strictly responses are parsed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParseMode {
+    /// Fail on malformed or missing data, ignoring fields which aren't captured by the parsed
+    /// types.
+    #[default]
+    Standard,
+    /// Fail on malformed or missing data, as well as on any field which isn't captured by the
+    /// parsed types.
+    Strict,
+    /// Skip malformed individual entries (e.g. a single day of a time series), reporting them as
+    /// warnings on the parsed result instead of failing. Fields which aren't captured by the
+    /// parsed types are reported as warnings too.
+    Lenient,
+}
+
+/// Represents the kind of issue reported by a [`ParseWarning`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseWarningKind {
+    /// A malformed entry was skipped.
+    SkippedEntry,
+    /// A field isn't captured by the parsed types, e.g. because it was recently added to the API.
+    UnknownField,
+    /// The time zone of the response isn't known, so its dates were read in a default time zone
+    /// instead.
+    UnknownTimeZone,
+    /// The data was last refreshed longer ago than the maximum age configured with
+    /// [`Parser::stale_after`]. Raised in every parse mode.
+    StaleData,
+}
+
+/// Represents an issue encountered while leniently parsing a response.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseWarning {
+    /// Kind of issue.
+    pub kind: ParseWarningKind,
+    /// Path of the offending entry or field in the response (e.g.
+    /// `Time Series (Daily).2024-05-03`).
+    pub path: String,
+    /// Description of the issue.
+    pub message: String,
+}
+
+/// Parses the pieces of a single response according to a [`ParseMode`], collecting the
+/// warnings raised along the way.
+#[derive(Default)]
+pub struct Parser {
+    mode: ParseMode,
+    warnings: Vec<ParseWarning>,
+    notify: Option<Notify>,
+    max_age: Option<Duration>,
+}
+
+/// Callback notified of the warnings raised while parsing a response.
+type Notify = Box<dyn Fn(&[ParseWarning]) + Send + Sync>;
+
+impl Parser {
+    /// Create a parser parsing a response in the given `mode`.
+    pub fn new(mode: ParseMode) -> Parser {
+        Parser {
+            mode,
+            warnings: vec![],
+            notify: None,
+            max_age: None,
+        }
+    }
+
+    /// Warn about data last refreshed longer than `max_age` ago, e.g. a daily time series the
+    /// API stopped updating.
+    pub fn stale_after(mut self, max_age: Duration) -> Parser {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Call `notify` with the warnings raised while parsing the response, if any, e.g. to pass
+    /// them on to an observer.
+    pub fn on_warnings(
+        mut self,
+        notify: impl Fn(&[ParseWarning]) + Send + Sync + 'static,
+    ) -> Parser {
+        self.notify = Some(Box::new(notify));
+        self
+    }
+
+    /// Deserialize a JSON response.
+    pub(crate) fn parse_json<T: DeserializeOwned>(
+        &mut self,
+        reader: impl Read,
+    ) -> Result<T, Error> {
+        let mut unknown = vec![];
+        let value = deserialize::from_reader(reader, &mut unknown)?;
+        self.check_unknown(unknown)?;
+        Ok(value)
+    }
+
+    /// Deserialize a JSON `value` found at `path` in a response.
+    pub(crate) fn parse_value<T: DeserializeOwned>(
+        &mut self,
+        path: &str,
+        value: &serde_json::Value,
+    ) -> Result<T, Error> {
+        let mut unknown = vec![];
+        let value = deserialize::from_value(path, value, &mut unknown)?;
+        self.check_unknown(unknown)?;
+        Ok(value)
+    }
+
+    /// Handle the `result` of parsing the individual entry found at `path` in a response.
+    ///
+    /// In lenient mode, a malformed entry is skipped and reported as a warning.
+    pub(crate) fn entry<T>(
+        &mut self,
+        path: &str,
+        result: Result<T, Error>,
+    ) -> Result<Option<T>, Error> {
+        match result {
+            Ok(entry) => Ok(Some(entry)),
+            Err(Error::ParsingError(message)) if self.mode == ParseMode::Lenient => {
+                self.warnings.push(ParseWarning {
+                    kind: ParseWarningKind::SkippedEntry,
+                    path: path.to_string(),
+                    message,
+                });
+                Ok(None)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Parse the time zone `name` found at `path` in a response.
+    ///
+    /// In lenient mode, an unknown time zone is reported as a warning and `default` is used
+    /// instead.
+    pub(crate) fn time_zone(&mut self, path: &str, name: &str, default: Tz) -> Result<Tz, Error> {
+        match name.parse() {
+            Ok(time_zone) => Ok(time_zone),
+            Err(_) if self.mode == ParseMode::Lenient => {
+                self.warnings.push(ParseWarning {
+                    kind: ParseWarningKind::UnknownTimeZone,
+                    path: path.to_string(),
+                    message: format!("unknown time zone {}, using {}", name, default.name()),
+                });
+                Ok(default)
+            }
+            Err(_) => Err(Error::ParsingError("error parsing time zone".into())),
+        }
+    }
+
+    /// Warn if the data `last_refreshed`, found at `path` in a response, is older than the
+    /// maximum age configured with [`Parser::stale_after`].
+    pub(crate) fn check_freshness<Z: TimeZone>(
+        &mut self,
+        path: &str,
+        last_refreshed: &DateTime<Z>,
+    ) {
+        let max_age = match self
+            .max_age
+            .and_then(|a| chrono::Duration::from_std(a).ok())
+        {
+            Some(max_age) => max_age,
+            None => return,
+        };
+        let age = Utc::now().signed_duration_since(last_refreshed.clone());
+        if age > max_age {
+            self.warnings.push(ParseWarning {
+                kind: ParseWarningKind::StaleData,
+                path: path.to_string(),
+                message: format!("last refreshed {} hours ago", age.num_hours()),
+            });
+        }
+    }
+
+    /// Fail in strict mode if the columns of a CSV response aren't all `known`.
+    pub(crate) fn check_columns(
+        &mut self,
+        columns: &csv::StringRecord,
+        known: &[&str],
+    ) -> Result<(), Error> {
+        let unknown = columns
+            .iter()
+            .filter(|c| !known.contains(c))
+            .map(String::from)
+            .collect();
+        self.check_unknown(unknown)
+    }
+
+    /// Warnings raised while parsing the response, after notifying them.
+    pub(crate) fn finish(self) -> Vec<ParseWarning> {
+        if let Some(notify) = &self.notify {
+            if !self.warnings.is_empty() {
+                notify(&self.warnings);
+            }
+        }
+        self.warnings
+    }
+
+    fn check_unknown(&mut self, unknown: Vec<String>) -> Result<(), Error> {
+        match self.mode {
+            ParseMode::Strict => match unknown.first() {
+                Some(path) => Err(Error::ParsingError(format!("unknown field at `{}`", path))),
+                None => Ok(()),
+            },
+            ParseMode::Lenient => {
+                for path in unknown {
+                    self.warnings.push(ParseWarning {
+                        kind: ParseWarningKind::UnknownField,
+                        path,
+                        message: "unknown field".to_string(),
+                    });
+                }
+                Ok(())
+            }
+            ParseMode::Standard => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::sync::{Arc, Mutex};
+
+    /// Parser recording the warnings it's notified of in `recorded`.
+    fn recording(mode: ParseMode, recorded: &Arc<Mutex<Vec<ParseWarning>>>) -> Parser {
+        let recorded = recorded.clone();
+        Parser::new(mode).on_warnings(move |warnings| {
+            recorded.lock().unwrap().extend_from_slice(warnings);
+        })
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Helper {
+        #[serde(rename = "1. open")]
+        _open: String,
+    }
+
+    #[test]
+    fn unknown_fields() {
+        let data: &[u8] = br#"{"1. open": "402.28", "2. high": "407.15"}"#;
+        let result: Result<Helper, Error> = Parser::new(ParseMode::Standard).parse_json(data);
+        assert!(result.is_ok());
+        let result: Result<Helper, Error> = Parser::new(ParseMode::Strict).parse_json(data);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "parsing error: unknown field at `2. high`"
+        );
+    }
+
+    #[test]
+    fn unknown_fields_drift() {
+        let data: &[u8] = br#"{"1. open": "402.28", "2. high": "407.15"}"#;
+        let recorded = Arc::new(Mutex::new(vec![]));
+        let mut parser = recording(ParseMode::Lenient, &recorded);
+        let result: Result<Helper, Error> = parser.parse_json(data);
+        assert!(result.is_ok());
+        let warning = ParseWarning {
+            kind: ParseWarningKind::UnknownField,
+            path: "2. high".to_string(),
+            message: "unknown field".to_string(),
+        };
+        assert_eq!(parser.finish(), vec![warning.clone()]);
+        assert_eq!(*recorded.lock().unwrap(), vec![warning]);
+
+        let recorded = Arc::new(Mutex::new(vec![]));
+        let mut parser = recording(ParseMode::Standard, &recorded);
+        let result: Result<Helper, Error> = parser.parse_json(data);
+        assert!(result.is_ok());
+        assert!(parser.finish().is_empty());
+        assert!(recorded.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn time_zones() {
+        let mut parser = Parser::new(ParseMode::Standard);
+        assert_eq!(
+            parser
+                .time_zone("5. Time Zone", "US/Eastern", Tz::UTC)
+                .unwrap(),
+            Tz::US__Eastern
+        );
+        assert!(parser
+            .time_zone("5. Time Zone", "Mars/Olympus", Tz::UTC)
+            .is_err());
+
+        let mut parser = Parser::new(ParseMode::Lenient);
+        assert_eq!(
+            parser
+                .time_zone("5. Time Zone", "Mars/Olympus", Tz::UTC)
+                .unwrap(),
+            Tz::UTC
+        );
+        let warnings = parser.finish();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, ParseWarningKind::UnknownTimeZone);
+        assert_eq!(warnings[0].path, "5. Time Zone");
+    }
+
+    #[test]
+    fn stale_data() {
+        let last_refreshed = Utc::now() - chrono::Duration::days(3);
+        let mut parser = Parser::new(ParseMode::Standard);
+        parser.check_freshness("3. Last Refreshed", &last_refreshed);
+        assert!(parser.finish().is_empty());
+
+        let day = Duration::from_secs(24 * 60 * 60);
+        let mut parser = Parser::new(ParseMode::Standard).stale_after(4 * day);
+        parser.check_freshness("3. Last Refreshed", &last_refreshed);
+        assert!(parser.finish().is_empty());
+
+        let mut parser = Parser::new(ParseMode::Standard).stale_after(day);
+        parser.check_freshness("3. Last Refreshed", &last_refreshed);
+        assert_eq!(
+            parser.finish(),
+            vec![ParseWarning {
+                kind: ParseWarningKind::StaleData,
+                path: "3. Last Refreshed".to_string(),
+                message: "last refreshed 72 hours ago".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn malformed_entries() {
+        let malformed = || Err::<(), _>(Error::ParsingError("invalid digit".into()));
+        assert!(Parser::new(ParseMode::Strict)
+            .entry("2024-05-03", malformed())
+            .is_err());
+
+        let mut parser = Parser::new(ParseMode::Lenient);
+        assert_eq!(parser.entry("2024-05-03", malformed()).unwrap(), None);
+        assert_eq!(parser.entry("2024-05-02", Ok(())).unwrap(), Some(()));
+        assert!(parser
+            .entry("2024-05-01", Err::<(), _>(Error::ServerError(500)))
+            .is_err());
+        assert_eq!(
+            parser.finish(),
+            vec![ParseWarning {
+                kind: ParseWarningKind::SkippedEntry,
+                path: "2024-05-03".to_string(),
+                message: "invalid digit".to_string(),
+            }]
+        );
+    }
+}
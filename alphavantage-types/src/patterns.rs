@@ -0,0 +1,208 @@
+//! Candlestick patterns detected locally from time series
+use crate::time_series::{Entry, TimeSeries};
+use chrono::DateTime;
+use chrono_tz::Tz;
+
+/// Largest body of a doji, as a share of its range.
+const DOJI_BODY: f64 = 0.1;
+
+/// Smallest body of the first candle of a morning star, as a share of its range.
+const MORNING_STAR_BODY: f64 = 0.5;
+
+/// Largest body of the star of a morning star, as a share of the body of the first candle.
+const STAR_BODY: f64 = 0.3;
+
+/// Represents a candlestick pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Pattern {
+    /// A single candle opening and closing at about the same value.
+    Doji,
+    /// A candle with a small body at the top of its range and a long lower shadow.
+    Hammer,
+    /// A bullish candle whose body engulfs the body of the preceding bearish candle.
+    BullishEngulfing,
+    /// A bearish candle whose body engulfs the body of the preceding bullish candle.
+    BearishEngulfing,
+    /// A long bearish candle, followed by a small body gapping below it and a bullish candle
+    /// closing above the middle of the first one.
+    MorningStar,
+}
+
+impl Pattern {
+    /// Number of candles the pattern is made of.
+    pub fn candles(self) -> usize {
+        match self {
+            Pattern::Doji | Pattern::Hammer => 1,
+            Pattern::BullishEngulfing | Pattern::BearishEngulfing => 2,
+            Pattern::MorningStar => 3,
+        }
+    }
+}
+
+/// Represents a pattern completed by an entry of a time series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternEvent {
+    /// Date of the last entry of the pattern.
+    pub date: DateTime<Tz>,
+    /// The pattern.
+    pub pattern: Pattern,
+}
+
+/// Detect the candlestick patterns completed by each entry of `time_series`, sorted by
+/// ascending dates.
+///
+/// An entry can complete several patterns, e.g. a doji which is also a hammer.
+pub fn detect(time_series: &TimeSeries) -> Vec<PatternEvent> {
+    let entries = &time_series.entries;
+    let mut events = vec![];
+    for (i, entry) in entries.iter().enumerate() {
+        let previous = i.checked_sub(1).map(|i| &entries[i]);
+        let first = i.checked_sub(2).map(|i| &entries[i]);
+        let mut push = |pattern| {
+            events.push(PatternEvent {
+                date: entry.date,
+                pattern,
+            })
+        };
+        if is_doji(entry) {
+            push(Pattern::Doji);
+        }
+        if is_hammer(entry) {
+            push(Pattern::Hammer);
+        }
+        if let Some(previous) = previous {
+            if is_bullish_engulfing(previous, entry) {
+                push(Pattern::BullishEngulfing);
+            }
+            if is_bearish_engulfing(previous, entry) {
+                push(Pattern::BearishEngulfing);
+            }
+        }
+        if let (Some(first), Some(star)) = (first, previous) {
+            if is_morning_star(first, star, entry) {
+                push(Pattern::MorningStar);
+            }
+        }
+    }
+    events
+}
+
+fn upper_shadow(entry: &Entry) -> f64 {
+    entry.high - entry.open.max(entry.close)
+}
+
+fn lower_shadow(entry: &Entry) -> f64 {
+    entry.open.min(entry.close) - entry.low
+}
+
+fn is_doji(entry: &Entry) -> bool {
+    entry.range() > 0.0 && entry.body() <= DOJI_BODY * entry.range()
+}
+
+fn is_hammer(entry: &Entry) -> bool {
+    entry.body() > 0.0
+        && lower_shadow(entry) >= 2.0 * entry.body()
+        && upper_shadow(entry) <= entry.body()
+}
+
+fn is_bullish_engulfing(previous: &Entry, entry: &Entry) -> bool {
+    previous.is_bearish()
+        && entry.is_bullish()
+        && entry.open <= previous.close
+        && entry.close >= previous.open
+        && entry.body() > previous.body()
+}
+
+fn is_bearish_engulfing(previous: &Entry, entry: &Entry) -> bool {
+    previous.is_bullish()
+        && entry.is_bearish()
+        && entry.open >= previous.close
+        && entry.close <= previous.open
+        && entry.body() > previous.body()
+}
+
+fn is_morning_star(first: &Entry, star: &Entry, entry: &Entry) -> bool {
+    first.is_bearish()
+        && first.body() >= MORNING_STAR_BODY * first.range()
+        && star.body() <= STAR_BODY * first.body()
+        && star.open.max(star.close) < first.close
+        && entry.is_bullish()
+        && entry.close > (first.open + first.close) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deserialize::parse_date;
+    use chrono_tz::US::Eastern;
+
+    fn entry(date: &str, open: f64, high: f64, low: f64, close: f64) -> Entry {
+        Entry::builder(parse_date(date, Eastern).unwrap())
+            .open(open)
+            .high(high)
+            .low(low)
+            .close(close)
+            .build()
+    }
+
+    fn time_series(entries: Vec<Entry>) -> TimeSeries {
+        TimeSeries {
+            symbol: "MSFT".to_string(),
+            last_refreshed: entries.last().unwrap().date,
+            entries,
+            warnings: vec![],
+        }
+    }
+
+    fn patterns(time_series: &TimeSeries) -> Vec<(String, Pattern)> {
+        detect(time_series)
+            .into_iter()
+            .map(|e| (e.date.format("%Y-%m-%d").to_string(), e.pattern))
+            .collect()
+    }
+
+    #[test]
+    fn single_candles() {
+        let time_series = time_series(vec![
+            entry("2024-05-01", 100.0, 102.0, 98.0, 100.2),
+            entry("2024-05-02", 100.0, 101.0, 94.0, 101.0),
+            entry("2024-05-03", 100.0, 100.0, 100.0, 100.0),
+        ]);
+        assert_eq!(
+            patterns(&time_series),
+            vec![
+                ("2024-05-01".to_string(), Pattern::Doji),
+                ("2024-05-02".to_string(), Pattern::Hammer),
+            ]
+        );
+    }
+
+    #[test]
+    fn engulfing() {
+        let time_series = time_series(vec![
+            entry("2024-05-01", 102.0, 103.0, 100.0, 101.0),
+            entry("2024-05-02", 100.5, 105.0, 100.0, 104.0),
+            entry("2024-05-03", 104.5, 105.0, 99.0, 100.0),
+        ]);
+        assert_eq!(
+            patterns(&time_series),
+            vec![
+                ("2024-05-02".to_string(), Pattern::BullishEngulfing),
+                ("2024-05-03".to_string(), Pattern::BearishEngulfing),
+            ]
+        );
+    }
+
+    #[test]
+    fn morning_star() {
+        let time_series = time_series(vec![
+            entry("2024-05-01", 110.0, 111.0, 99.0, 100.0),
+            entry("2024-05-02", 98.0, 99.0, 96.0, 98.5),
+            entry("2024-05-03", 99.0, 107.0, 98.5, 106.0),
+        ]);
+        let events = detect(&time_series);
+        assert_eq!(events.last().unwrap().pattern, Pattern::MorningStar);
+        assert_eq!(events.last().unwrap().date, time_series.entries[2].date);
+        assert_eq!(Pattern::MorningStar.candles(), 3);
+    }
+}
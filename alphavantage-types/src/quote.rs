@@ -0,0 +1,430 @@
+//! Quote related operations
+use crate::calendar::{self, Session};
+use crate::parsing::ParseWarning;
+use crate::time_series::TimeSeries;
+use chrono::{DateTime, NaiveDate};
+use chrono_tz::Tz;
+use chrono_tz::US::Eastern;
+use serde::Deserialize;
+
+#[cfg(feature = "table")]
+use crate::table;
+
+/// Represents the latest price and volume information for a symbol.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Quote {
+    /// Symbol the quote refers to.
+    pub symbol: String,
+    /// Open value.
+    pub open: f64,
+    /// High value.
+    pub high: f64,
+    /// Low value.
+    pub low: f64,
+    /// Latest price.
+    pub price: f64,
+    /// Trading volume.
+    pub volume: u64,
+    /// Latest trading day the quote corresponds to.
+    pub latest_trading_day: NaiveDate,
+    /// Close value of the previous trading day.
+    pub previous_close: f64,
+    /// Change in price since the previous close.
+    pub change: f64,
+    /// Change in price since the previous close, as a percentage.
+    pub change_percentage: f64,
+    /// Issues found while parsing the quote, e.g. fields unknown to the crate.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Represents the quotes of several symbols retrieved in bulk.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BulkQuotes {
+    /// Quotes, in the order they were returned.
+    pub quotes: Vec<Quote>,
+    /// Malformed quotes skipped while leniently parsing the response.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Maximum number of symbols quoted by a single `REALTIME_BULK_QUOTES` request.
+pub const MAX_BULK_SYMBOLS: usize = 100;
+
+/// Represents the direction the price moved in since the previous close.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// The price rose.
+    Up,
+    /// The price fell.
+    Down,
+    /// The price didn't change.
+    Flat,
+}
+
+/// Render `quotes` as an aligned text table, e.g. to inspect them in a REPL.
+#[cfg(feature = "table")]
+pub fn to_table(quotes: &[Quote]) -> String {
+    let rows: Vec<Vec<String>> = quotes
+        .iter()
+        .map(|q| {
+            vec![
+                q.symbol.clone(),
+                table::decimal(q.price),
+                table::decimal(q.change),
+                format!("{:.2}", q.change_percentage),
+                q.volume.to_string(),
+                q.latest_trading_day.to_string(),
+            ]
+        })
+        .collect();
+    table::render(
+        &[
+            "symbol",
+            "price",
+            "change",
+            "change %",
+            "volume",
+            "trading day",
+        ],
+        &rows,
+    )
+}
+
+impl Quote {
+    /// Render the quote as an aligned text table, see [`to_table`].
+    #[cfg(feature = "table")]
+    pub fn to_table(&self) -> String {
+        to_table(std::slice::from_ref(self))
+    }
+
+    /// Direction the price moved in since the previous close.
+    pub fn direction(&self) -> Direction {
+        if self.change > 0.0 {
+            Direction::Up
+        } else if self.change < 0.0 {
+            Direction::Down
+        } else {
+            Direction::Flat
+        }
+    }
+}
+
+/// Represents the latest price of a symbol, including pre-market and after-hours trading.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ExtendedHoursQuote {
+    /// Symbol the quote refers to.
+    pub symbol: String,
+    /// Latest price.
+    pub price: f64,
+    /// Session the latest price was traded in.
+    pub session: Session,
+    /// Time of the intraday entry the latest price was taken from, if traded outside of regular
+    /// trading hours.
+    pub time: Option<DateTime<Tz>>,
+    /// Latest price of the regular session.
+    pub regular_price: f64,
+    /// Change in price since the latest price of the regular session.
+    pub change: f64,
+    /// Change in price since the latest price of the regular session, as a percentage.
+    pub change_percentage: f64,
+}
+
+impl ExtendedHoursQuote {
+    /// Combine a regular `quote` with the latest entry of an intraday `time_series` including
+    /// extended hours.
+    ///
+    /// The entry is used when it was traded in the pre-market or after-hours session, on or
+    /// after the latest trading day of the quote. Otherwise the quote's price is used, as traded
+    /// in the regular session.
+    pub fn new(quote: &Quote, time_series: &TimeSeries) -> ExtendedHoursQuote {
+        let extended = time_series.entries.last().filter(|entry| {
+            calendar::session(&entry.date).is_extended()
+                && entry.date.with_timezone(&Eastern).date_naive() >= quote.latest_trading_day
+        });
+        let (price, session, time) = match extended {
+            Some(entry) => (
+                entry.close,
+                calendar::session(&entry.date),
+                Some(entry.date),
+            ),
+            None => (quote.price, Session::Regular, None),
+        };
+        let change = price - quote.price;
+        ExtendedHoursQuote {
+            symbol: quote.symbol.clone(),
+            price,
+            session,
+            time,
+            regular_price: quote.price,
+            change,
+            change_percentage: change / quote.price * 100.0,
+        }
+    }
+}
+
+pub mod parser {
+    use super::*;
+    use crate::deserialize::{empty_as_none, from_percent_str, from_str, DATE_FORMAT};
+    use crate::error::Error;
+    use crate::parsing::Parser;
+    use std::io::Read;
+
+    #[derive(Debug, Deserialize)]
+    struct QuoteHelper {
+        #[serde(rename = "Error Message")]
+        error: Option<String>,
+        #[serde(rename = "Global Quote", default, deserialize_with = "empty_as_none")]
+        data: Option<GlobalQuote>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct GlobalQuote {
+        #[serde(rename = "01. symbol")]
+        symbol: String,
+        #[serde(rename = "02. open", deserialize_with = "from_str")]
+        open: f64,
+        #[serde(rename = "03. high", deserialize_with = "from_str")]
+        high: f64,
+        #[serde(rename = "04. low", deserialize_with = "from_str")]
+        low: f64,
+        #[serde(rename = "05. price", deserialize_with = "from_str")]
+        price: f64,
+        #[serde(rename = "06. volume", deserialize_with = "from_str")]
+        volume: u64,
+        #[serde(rename = "07. latest trading day")]
+        latest_trading_day: String,
+        #[serde(rename = "08. previous close", deserialize_with = "from_str")]
+        previous_close: f64,
+        #[serde(rename = "09. change", deserialize_with = "from_str")]
+        change: f64,
+        #[serde(rename = "10. change percent", deserialize_with = "from_percent_str")]
+        change_percentage: f64,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct BulkQuotesHelper {
+        #[serde(rename = "Error Message")]
+        error: Option<String>,
+        #[serde(rename = "endpoint")]
+        _endpoint: Option<String>,
+        #[serde(rename = "message")]
+        _message: Option<String>,
+        data: Option<Vec<serde_json::Value>>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct BulkQuote {
+        symbol: String,
+        timestamp: String,
+        #[serde(deserialize_with = "from_str")]
+        open: f64,
+        #[serde(deserialize_with = "from_str")]
+        high: f64,
+        #[serde(deserialize_with = "from_str")]
+        low: f64,
+        #[serde(deserialize_with = "from_str")]
+        close: f64,
+        #[serde(deserialize_with = "from_str")]
+        volume: u64,
+        #[serde(deserialize_with = "from_str")]
+        previous_close: f64,
+        #[serde(deserialize_with = "from_str")]
+        change: f64,
+        #[serde(deserialize_with = "from_percent_str")]
+        change_percent: f64,
+        #[serde(rename = "extended_hours_quote")]
+        _extended_hours_quote: Option<String>,
+        #[serde(rename = "extended_hours_change")]
+        _extended_hours_change: Option<String>,
+        #[serde(rename = "extended_hours_change_percent")]
+        _extended_hours_change_percent: Option<String>,
+    }
+
+    fn bulk_quote(q: BulkQuote) -> Result<Quote, Error> {
+        let day = q.timestamp.get(..10).unwrap_or(&q.timestamp);
+        Ok(Quote {
+            symbol: q.symbol,
+            open: q.open,
+            high: q.high,
+            low: q.low,
+            price: q.close,
+            volume: q.volume,
+            latest_trading_day: NaiveDate::parse_from_str(day, DATE_FORMAT)?,
+            previous_close: q.previous_close,
+            change: q.change,
+            change_percentage: q.change_percent,
+            warnings: vec![],
+        })
+    }
+
+    /// Parse a response to `REALTIME_BULK_QUOTES`, in the order of the quotes returned.
+    pub fn parse_bulk(mut parser: Parser, reader: impl Read) -> Result<BulkQuotes, Error> {
+        let helper: BulkQuotesHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let data = helper
+            .data
+            .ok_or_else(|| Error::ParsingError("missing quote data".into()))?;
+
+        let mut quotes = vec![];
+        for (i, value) in data.iter().enumerate() {
+            let path = format!("data.{}", i);
+            let quote = parser.parse_value(&path, value).and_then(bulk_quote);
+            if let Some(quote) = parser.entry(&path, quote)? {
+                quotes.push(quote);
+            }
+        }
+        Ok(BulkQuotes {
+            quotes,
+            warnings: parser.finish(),
+        })
+    }
+
+    pub fn parse(mut parser: Parser, reader: impl Read) -> Result<Quote, Error> {
+        let helper: QuoteHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let data = helper
+            .data
+            .ok_or_else(|| Error::ParsingError("missing quote data".into()))?;
+
+        let latest_trading_day = NaiveDate::parse_from_str(&data.latest_trading_day, DATE_FORMAT)?;
+
+        let quote = Quote {
+            symbol: data.symbol,
+            open: data.open,
+            high: data.high,
+            low: data.low,
+            price: data.price,
+            volume: data.volume,
+            latest_trading_day,
+            previous_close: data.previous_close,
+            change: data.change,
+            change_percentage: data.change_percentage,
+            warnings: parser.finish(),
+        };
+        Ok(quote)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deserialize::parse_date;
+    use crate::parsing::Parser;
+    use crate::time_series::Entry;
+    use std::io::BufReader;
+
+    #[test]
+    fn parse() {
+        let data: &[u8] = include_bytes!("../../tests/json/global_quote.json");
+        let quote =
+            parser::parse(Parser::default(), BufReader::new(data)).expect("failed to parse quote");
+        assert_eq!(
+            quote,
+            Quote {
+                symbol: "MSFT".to_string(),
+                open: 101.0924,
+                high: 101.9500,
+                low: 100.5400,
+                price: 101.6300,
+                volume: 22165128,
+                latest_trading_day: NaiveDate::from_ymd_opt(2018, 6, 8).unwrap(),
+                previous_close: 100.8800,
+                change: 0.7500,
+                change_percentage: 0.7435,
+                warnings: vec![],
+            }
+        );
+        assert_eq!(quote.direction(), Direction::Up);
+    }
+
+    #[test]
+    #[cfg(feature = "table")]
+    fn quote_table() {
+        let data: &[u8] = include_bytes!("../../tests/json/global_quote.json");
+        let quote = parser::parse(Parser::default(), BufReader::new(data)).unwrap();
+        assert_eq!(
+            quote.to_table(),
+            concat!(
+                "symbol   price  change  change %    volume  trading day\n",
+                "------  ------  ------  --------  --------  -----------\n",
+                "MSFT    101.63    0.75      0.74  22165128  2018-06-08\n",
+            )
+        );
+    }
+
+    #[test]
+    fn extended_hours() {
+        let data: &[u8] = include_bytes!("../../tests/json/global_quote.json");
+        let quote = parser::parse(Parser::default(), BufReader::new(data)).unwrap();
+        let time_series = |date: &str| {
+            let date = parse_date(date, Eastern).unwrap();
+            TimeSeries {
+                symbol: "MSFT".to_string(),
+                last_refreshed: date,
+                entries: vec![Entry::builder(date).price(102.0).build()],
+                warnings: vec![],
+            }
+        };
+        let after_hours = ExtendedHoursQuote::new(&quote, &time_series("2018-06-08 19:55:00"));
+        assert_eq!(after_hours.session, Session::AfterHours);
+        assert_eq!(after_hours.price, 102.0);
+        assert_eq!(after_hours.regular_price, 101.63);
+        assert!((after_hours.change - 0.37).abs() < 1e-9);
+        assert!(after_hours.time.is_some());
+
+        for date in &["2018-06-08 15:55:00", "2018-06-07 19:55:00"] {
+            let regular = ExtendedHoursQuote::new(&quote, &time_series(date));
+            assert_eq!(regular.session, Session::Regular);
+            assert_eq!(regular.price, 101.63);
+            assert_eq!(regular.change, 0.0);
+            assert_eq!(regular.time, None);
+        }
+    }
+
+    #[test]
+    fn parse_bulk() {
+        let data: &[u8] = include_bytes!("../../tests/json/realtime_bulk_quotes.json");
+        let quotes = parser::parse_bulk(Parser::default(), BufReader::new(data))
+            .expect("failed to parse quotes");
+        assert_eq!(quotes.quotes.len(), 2);
+        assert!(quotes.warnings.is_empty());
+        assert_eq!(
+            quotes.quotes[1],
+            Quote {
+                symbol: "AAPL".to_string(),
+                open: 186.65,
+                high: 187.0,
+                low: 182.66,
+                price: 183.38,
+                volume: 163224109,
+                latest_trading_day: NaiveDate::from_ymd_opt(2024, 5, 3).unwrap(),
+                previous_close: 173.03,
+                change: 10.35,
+                change_percentage: 5.98162,
+                warnings: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn parse_empty() {
+        let data: &[u8] = include_bytes!("../../tests/json/global_quote_empty.json");
+        assert!(parser::parse(Parser::default(), BufReader::new(data)).is_err());
+    }
+
+    #[test]
+    fn parse_error_path() {
+        let data: &[u8] = br#"{"Global Quote": {"01. symbol": "MSFT", "02. open": "n/a"}}"#;
+        let error = parser::parse(Parser::default(), BufReader::new(data)).unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("at `Global Quote`"));
+        assert!(message.contains(r#"near `"01. symbol": "MSFT", "02. open": "n/a"}}`"#));
+    }
+}
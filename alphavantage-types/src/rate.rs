@@ -0,0 +1,246 @@
+//! Rates of currency pairs, regardless of the function they're retrieved from
+//!
+//! Realtime exchange rates of physical and digital currencies, foreign exchange time series and
+//! digital currency time series all implement [`RateSeries`], so conversion and charting code can
+//! handle them the same way:
+//!
+//! ```
+//! use alphavantage_types::rate::{RateSeries, RateSource};
+//!
+//! fn describe(series: &dyn RateSeries) -> String {
+//!     match series.latest() {
+//!         Some(rate) => format!("{} {:.4} ({:?})", rate.pair, rate.value, rate.source),
+//!         None => format!("{} has no rate", series.pair()),
+//!     }
+//! }
+//! ```
+use crate::crypto::{CryptoSeries, DigitalCurrencySeries};
+use crate::exchange_rate::{CurrencyPair, ExchangeRate};
+use crate::forex::ForexSeries;
+use chrono::DateTime;
+use chrono_tz::Tz;
+
+/// Represents the kind of response a rate was retrieved from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateSource {
+    /// Realtime rate of physical or digital currencies, from `CURRENCY_EXCHANGE_RATE`.
+    ExchangeRate,
+    /// Foreign exchange time series, e.g. from `FX_DAILY`.
+    Forex,
+    /// Digital currency time series quoted in its market, e.g. from `DIGITAL_CURRENCY_DAILY`.
+    Crypto,
+}
+
+/// Represents the open, high, low and close rates of a pair over a period, e.g. for charting.
+///
+/// Realtime exchange rates are reported as a single point whose values are all equal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PricePoint {
+    /// Date.
+    pub date: DateTime<Tz>,
+    /// Open rate.
+    pub open: f64,
+    /// High rate.
+    pub high: f64,
+    /// Low rate.
+    pub low: f64,
+    /// Close rate.
+    pub close: f64,
+    /// Traded volume, in units of the currency the rates are quoted for, `None` when the source
+    /// doesn't report any.
+    pub volume: Option<f64>,
+}
+
+/// Represents the rate of a pair at a date, along with the kind of response it comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rate {
+    /// Pair the rate is quoted for.
+    pub pair: CurrencyPair,
+    /// Value of one unit of the pair's first currency in its second currency.
+    pub value: f64,
+    /// Date the rate corresponds to.
+    pub date: DateTime<Tz>,
+    /// Kind of response the rate was retrieved from.
+    pub source: RateSource,
+}
+
+impl Rate {
+    /// Convert an `amount` of the pair's first currency into its second currency.
+    pub fn convert(&self, amount: f64) -> f64 {
+        amount * self.value
+    }
+
+    /// Rate of the reversed pair.
+    pub fn inverse(&self) -> Rate {
+        Rate {
+            pair: CurrencyPair::new(&self.pair.to, &self.pair.from),
+            value: 1.0 / self.value,
+            date: self.date,
+            source: self.source,
+        }
+    }
+}
+
+/// Represents any response holding rates of a currency pair.
+pub trait RateSeries {
+    /// Pair the rates are quoted for.
+    fn pair(&self) -> CurrencyPair;
+
+    /// Kind of response the rates come from.
+    fn source(&self) -> RateSource;
+
+    /// Rates over each period, sorted by ascending dates.
+    fn price_points(&self) -> Vec<PricePoint>;
+
+    /// Close rates of each period, sorted by ascending dates.
+    fn rates(&self) -> Vec<Rate> {
+        let pair = self.pair();
+        let source = self.source();
+        self.price_points()
+            .into_iter()
+            .map(|p| Rate {
+                pair: pair.clone(),
+                value: p.close,
+                date: p.date,
+                source,
+            })
+            .collect()
+    }
+
+    /// Most recent rate.
+    fn latest(&self) -> Option<Rate> {
+        self.rates().pop()
+    }
+}
+
+impl RateSeries for ExchangeRate {
+    fn pair(&self) -> CurrencyPair {
+        CurrencyPair::new(&self.from.code, &self.to.code)
+    }
+
+    fn source(&self) -> RateSource {
+        RateSource::ExchangeRate
+    }
+
+    fn price_points(&self) -> Vec<PricePoint> {
+        vec![PricePoint {
+            date: self.date,
+            open: self.rate,
+            high: self.rate,
+            low: self.rate,
+            close: self.rate,
+            volume: None,
+        }]
+    }
+}
+
+impl RateSeries for ForexSeries {
+    fn pair(&self) -> CurrencyPair {
+        CurrencyPair::new(&self.from_symbol, &self.to_symbol)
+    }
+
+    fn source(&self) -> RateSource {
+        RateSource::Forex
+    }
+
+    fn price_points(&self) -> Vec<PricePoint> {
+        self.entries
+            .iter()
+            .map(|e| PricePoint {
+                date: e.date,
+                open: e.open,
+                high: e.high,
+                low: e.low,
+                close: e.close,
+                volume: None,
+            })
+            .collect()
+    }
+}
+
+impl RateSeries for CryptoSeries {
+    fn pair(&self) -> CurrencyPair {
+        CurrencyPair::new(&self.symbol, self.market.as_str())
+    }
+
+    fn source(&self) -> RateSource {
+        RateSource::Crypto
+    }
+
+    fn price_points(&self) -> Vec<PricePoint> {
+        self.entries
+            .iter()
+            .map(|e| PricePoint {
+                date: e.date,
+                open: e.open,
+                high: e.high,
+                low: e.low,
+                close: e.close,
+                volume: Some(e.volume),
+            })
+            .collect()
+    }
+}
+
+impl RateSeries for DigitalCurrencySeries {
+    fn pair(&self) -> CurrencyPair {
+        CurrencyPair::new(&self.symbol, self.market.as_str())
+    }
+
+    fn source(&self) -> RateSource {
+        RateSource::Crypto
+    }
+
+    fn price_points(&self) -> Vec<PricePoint> {
+        self.entries
+            .iter()
+            .map(|e| PricePoint {
+                date: e.market.date,
+                open: e.market.open,
+                high: e.market.high,
+                low: e.market.low,
+                close: e.market.close,
+                volume: Some(e.market.volume),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+    use crate::{crypto, exchange_rate, forex};
+
+    #[test]
+    fn rate_series() {
+        let data: &[u8] = include_bytes!("../../tests/json/currency_exchange_rate.json");
+        let exchange_rate = exchange_rate::parser::parse(Parser::default(), data).unwrap();
+        let data: &[u8] = include_bytes!("../../tests/json/fx_daily.json");
+        let forex_series = forex::parser::parse(Parser::default(), data).unwrap();
+        let data: &[u8] = include_bytes!("../../tests/json/digital_currency_daily.json");
+        let crypto_series = crypto::parser::parse_daily(Parser::default(), data).unwrap();
+
+        let series: [&dyn RateSeries; 3] = [&exchange_rate, &forex_series, &crypto_series];
+        let latest: Vec<Rate> = series.iter().filter_map(|s| s.latest()).collect();
+        assert_eq!(latest[0].pair, CurrencyPair::new("EUR", "USD"));
+        assert_eq!(latest[0].value, 1.16665014);
+        assert_eq!(latest[0].source, RateSource::ExchangeRate);
+        assert_eq!(latest[1].pair, CurrencyPair::new("USD", "EUR"));
+        assert_eq!(latest[1].value, 0.8488);
+        assert_eq!(latest[1].date, forex_series.entries.last().unwrap().date);
+        assert_eq!(latest[2].pair, CurrencyPair::new("BTC", "EUR"));
+        assert_eq!(latest[2].source, RateSource::Crypto);
+        assert_eq!(latest[2].convert(2.0), 111540.8);
+
+        let inverse = latest[1].inverse();
+        assert_eq!(inverse.pair, CurrencyPair::new("EUR", "USD"));
+        assert_eq!(inverse.convert(0.8488), 1.0);
+
+        let points = crypto_series.price_points();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[1].volume, Some(1502.36));
+        assert_eq!(exchange_rate.price_points()[0].high, 1.16665014);
+        assert_eq!(forex_series.rates().len(), forex_series.entries.len());
+    }
+}
@@ -0,0 +1,219 @@
+//! Return series derived from time series
+use crate::corporate_actions::Dividends;
+use crate::forex::ForexSeries;
+use crate::time_series::{Entry, TimeSeries};
+use chrono::DateTime;
+use chrono_tz::Tz;
+
+/// Represents the return of an investment for a given period.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ReturnEntry {
+    /// Date.
+    pub date: DateTime<Tz>,
+    /// Value of one unit invested at the start of the series.
+    pub index: f64,
+    /// Return since the previous period, as a fraction.
+    pub period_return: f64,
+}
+
+/// Represents the cumulative return of an investment in a given symbol.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ReturnSeries {
+    /// Symbol the returns refer to.
+    pub symbol: String,
+    /// Entries in the series, sorted by ascending dates.
+    pub entries: Vec<ReturnEntry>,
+}
+
+impl ReturnSeries {
+    /// Build a return series from the close values of `time_series`, ignoring dividends.
+    pub fn price_return(time_series: &TimeSeries) -> ReturnSeries {
+        ReturnSeries::from_entries(time_series, |previous, current| {
+            current.close / previous.close
+        })
+    }
+
+    /// Build a total return series, with dividends reinvested, from `time_series`.
+    ///
+    /// Adjusted close values are used when available. Otherwise the dividend paid in each period
+    /// is reinvested at the close, which doesn't account for splits.
+    pub fn total_return(time_series: &TimeSeries) -> ReturnSeries {
+        ReturnSeries::from_entries(time_series, |previous, current| {
+            match (previous.adjusted_close, current.adjusted_close) {
+                (Some(previous), Some(current)) => current / previous,
+                _ => (current.close + current.dividend_amount.unwrap_or(0.0)) / previous.close,
+            }
+        })
+    }
+
+    /// Build a total return series, with dividends reinvested, from the close values of
+    /// `time_series` and the `dividends` paid over the same period.
+    ///
+    /// Dividends are reinvested at the close of the period their ex-dividend date falls in.
+    /// Splits aren't accounted for.
+    pub fn total_return_with_dividends(
+        time_series: &TimeSeries,
+        dividends: &Dividends,
+    ) -> ReturnSeries {
+        ReturnSeries::from_entries(time_series, |previous, current| {
+            let since = previous.date.date_naive();
+            let until = current.date.date_naive();
+            let dividend: f64 = dividends
+                .dividends
+                .iter()
+                .filter(|d| d.ex_dividend_date > since && d.ex_dividend_date <= until)
+                .map(|d| d.amount)
+                .sum();
+            (current.close + dividend) / previous.close
+        })
+    }
+
+    /// Build a total return series, with dividends reinvested, for an investor in the currency
+    /// `forex_series` is quoted in, from `time_series` quoted in the currency `forex_series` is
+    /// quoted for.
+    ///
+    /// See [`ForexSeries::convert`](crate::forex::ForexSeries::convert) for how dates are aligned.
+    pub fn total_return_in_currency(
+        time_series: &TimeSeries,
+        forex_series: &ForexSeries,
+    ) -> ReturnSeries {
+        ReturnSeries::total_return(&forex_series.convert(time_series))
+    }
+
+    /// Return over the whole series, as a fraction.
+    pub fn cumulative_return(&self) -> Option<f64> {
+        self.entries.last().map(|e| e.index - 1.0)
+    }
+
+    fn from_entries<F>(time_series: &TimeSeries, growth: F) -> ReturnSeries
+    where
+        F: Fn(&Entry, &Entry) -> f64,
+    {
+        let mut entries: Vec<ReturnEntry> = Vec::with_capacity(time_series.entries.len());
+        let mut previous: Option<&Entry> = None;
+        for entry in &time_series.entries {
+            let (index, period_return) = match (previous, entries.last()) {
+                (Some(previous), Some(last)) => {
+                    let growth = growth(previous, entry);
+                    (last.index * growth, growth - 1.0)
+                }
+                _ => (1.0, 0.0),
+            };
+            entries.push(ReturnEntry {
+                date: entry.date,
+                index,
+                period_return,
+            });
+            previous = Some(entry);
+        }
+        ReturnSeries {
+            symbol: time_series.symbol.clone(),
+            entries,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::corporate_actions::Dividend;
+    use crate::deserialize::parse_date;
+    use chrono::NaiveDate;
+    use chrono_tz::US::Eastern;
+
+    fn entry(date: &str, close: f64, adjusted_close: Option<f64>) -> Entry {
+        let entry = Entry::builder(parse_date(date, Eastern).unwrap()).price(close);
+        match adjusted_close {
+            Some(adjusted_close) => entry.adjusted_close(adjusted_close).build(),
+            None => entry.build(),
+        }
+    }
+
+    fn time_series(entries: Vec<Entry>) -> TimeSeries {
+        TimeSeries {
+            symbol: "MSFT".to_string(),
+            last_refreshed: entries.last().unwrap().date,
+            entries,
+            warnings: vec![],
+        }
+    }
+
+    fn assert_indexes(series: &ReturnSeries, expected: &[f64]) {
+        let indexes: Vec<f64> = series.entries.iter().map(|e| e.index).collect();
+        assert_eq!(indexes.len(), expected.len());
+        for (index, expected) in indexes.iter().zip(expected) {
+            assert!((index - expected).abs() < 1e-9, "{} != {}", index, expected);
+        }
+    }
+
+    #[test]
+    fn price_return() {
+        let series = ReturnSeries::price_return(&time_series(vec![
+            entry("2018-06-01", 100.0, None),
+            entry("2018-06-04", 110.0, None),
+            entry("2018-06-05", 99.0, None),
+        ]));
+        assert_indexes(&series, &[1.0, 1.1, 0.99]);
+        assert!((series.entries[2].period_return + 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn total_return_adjusted() {
+        let series = ReturnSeries::total_return(&time_series(vec![
+            entry("2018-06-01", 100.0, Some(98.0)),
+            entry("2018-06-04", 100.0, Some(100.0)),
+        ]));
+        assert_indexes(&series, &[1.0, 100.0 / 98.0]);
+    }
+
+    #[test]
+    fn total_return_in_currency() {
+        let rate = |date: &str, close: f64| crate::forex::ForexEntry {
+            date: parse_date(date, chrono_tz::UTC).unwrap(),
+            open: close,
+            high: close,
+            low: close,
+            close,
+        };
+        let forex_series = ForexSeries {
+            from_symbol: "USD".to_string(),
+            to_symbol: "EUR".to_string(),
+            last_refreshed: parse_date("2018-06-04", chrono_tz::UTC).unwrap(),
+            entries: vec![rate("2018-06-01", 0.8), rate("2018-06-04", 0.9)],
+            warnings: vec![],
+        };
+        let series = ReturnSeries::total_return_in_currency(
+            &time_series(vec![
+                entry("2018-06-01", 100.0, Some(100.0)),
+                entry("2018-06-04", 110.0, Some(110.0)),
+            ]),
+            &forex_series,
+        );
+        assert_indexes(&series, &[1.0, 110.0 * 0.9 / 80.0]);
+    }
+
+    #[test]
+    fn total_return_with_dividends() {
+        let dividends = Dividends {
+            symbol: "MSFT".to_string(),
+            dividends: vec![Dividend {
+                ex_dividend_date: NaiveDate::from_ymd_opt(2018, 6, 4).unwrap(),
+                declaration_date: None,
+                record_date: None,
+                payment_date: None,
+                amount: 2.0,
+            }],
+            warnings: vec![],
+        };
+        let series = ReturnSeries::total_return_with_dividends(
+            &time_series(vec![
+                entry("2018-06-01", 100.0, None),
+                entry("2018-06-04", 98.0, None),
+                entry("2018-06-05", 99.0, None),
+            ]),
+            &dividends,
+        );
+        assert_indexes(&series, &[1.0, 1.0, 99.0 / 98.0]);
+        assert!((series.cumulative_return().unwrap() - (99.0 / 98.0 - 1.0)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,149 @@
+//! Symbol search related operations
+use crate::error::Error;
+use crate::parsing::ParseWarning;
+use crate::symbol::Symbol;
+use serde::Deserialize;
+
+/// Represents a symbol matching a search.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SymbolMatch {
+    /// Symbol.
+    pub symbol: String,
+    /// Name of the company or fund.
+    pub name: String,
+    /// Type of asset (e.g. equity or ETF).
+    pub asset_type: String,
+    /// Region the symbol is traded in.
+    pub region: String,
+    /// Local time the market opens at.
+    pub market_open: String,
+    /// Local time the market closes at.
+    pub market_close: String,
+    /// Time zone of the market, as an offset from UTC.
+    pub timezone: String,
+    /// Currency the symbol is traded in.
+    pub currency: String,
+    /// How well the symbol matches the search, between 0 and 1.
+    pub match_score: f64,
+}
+
+/// Represents the symbols matching a search.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SymbolMatches {
+    /// Matching symbols, in the order they were returned.
+    pub matches: Vec<SymbolMatch>,
+    /// Issues found while parsing the matches, e.g. fields unknown to the crate.
+    pub warnings: Vec<ParseWarning>,
+}
+
+impl SymbolMatch {
+    /// Symbol along with the exchange it's listed on, as identified by its suffix.
+    pub fn to_symbol(&self) -> Symbol {
+        Symbol::from(self.symbol.as_str())
+    }
+}
+
+pub mod parser {
+    use super::*;
+    use crate::deserialize::from_str;
+    use crate::parsing::Parser;
+    use std::io::Read;
+
+    #[derive(Debug, Deserialize)]
+    struct SymbolSearchHelper {
+        #[serde(rename = "Error Message")]
+        error: Option<String>,
+        #[serde(rename = "bestMatches")]
+        best_matches: Option<Vec<SymbolMatchHelper>>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct SymbolMatchHelper {
+        #[serde(rename = "1. symbol")]
+        symbol: String,
+        #[serde(rename = "2. name")]
+        name: String,
+        #[serde(rename = "3. type")]
+        asset_type: String,
+        #[serde(rename = "4. region")]
+        region: String,
+        #[serde(rename = "5. marketOpen")]
+        market_open: String,
+        #[serde(rename = "6. marketClose")]
+        market_close: String,
+        #[serde(rename = "7. timezone")]
+        timezone: String,
+        #[serde(rename = "8. currency")]
+        currency: String,
+        #[serde(rename = "9. matchScore", deserialize_with = "from_str")]
+        match_score: f64,
+    }
+
+    pub fn parse(mut parser: Parser, reader: impl Read) -> Result<SymbolMatches, Error> {
+        let helper: SymbolSearchHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let best_matches = helper
+            .best_matches
+            .ok_or_else(|| Error::ParsingError("missing best matches".into()))?;
+
+        let matches = best_matches
+            .into_iter()
+            .map(|m| SymbolMatch {
+                symbol: m.symbol,
+                name: m.name,
+                asset_type: m.asset_type,
+                region: m.region,
+                market_open: m.market_open,
+                market_close: m.market_close,
+                timezone: m.timezone,
+                currency: m.currency,
+                match_score: m.match_score,
+            })
+            .collect();
+        Ok(SymbolMatches {
+            matches,
+            warnings: parser.finish(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::Parser;
+    use std::io::BufReader;
+
+    fn matches() -> Vec<SymbolMatch> {
+        let data: &[u8] = include_bytes!("../../tests/json/symbol_search.json");
+        parser::parse(Parser::default(), BufReader::new(data))
+            .expect("failed to parse symbol search")
+            .matches
+    }
+
+    #[test]
+    fn parse() {
+        let matches = matches();
+        assert_eq!(matches.len(), 3);
+        assert_eq!(
+            matches[0],
+            SymbolMatch {
+                symbol: "TSCO.LON".to_string(),
+                name: "Tesco PLC".to_string(),
+                asset_type: "Equity".to_string(),
+                region: "United Kingdom".to_string(),
+                market_open: "08:00".to_string(),
+                market_close: "16:30".to_string(),
+                timezone: "UTC+01".to_string(),
+                currency: "GBX".to_string(),
+                match_score: 0.7273,
+            }
+        );
+        let symbol = matches[0].to_symbol();
+        assert_eq!(symbol.exchange(), crate::symbol::Exchange::London);
+        assert_eq!(symbol.currency(), matches[0].currency);
+    }
+}
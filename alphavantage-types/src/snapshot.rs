@@ -0,0 +1,44 @@
+//! Combined quote and company overview
+use crate::fundamentals::CompanyOverview;
+use crate::quote::Quote;
+use chrono::NaiveDate;
+
+/// Represents the latest price information for an equity together with key company metrics.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Snapshot {
+    /// Symbol the snapshot refers to.
+    pub symbol: String,
+    /// Company name.
+    pub name: String,
+    /// Latest price.
+    pub price: f64,
+    /// Change in price since the previous close.
+    pub change: f64,
+    /// Latest trading day the price corresponds to.
+    pub latest_trading_day: NaiveDate,
+    /// Market capitalization.
+    pub market_capitalization: Option<u64>,
+    /// Price to earnings ratio.
+    pub pe_ratio: Option<f64>,
+    /// Sector the company operates in.
+    pub sector: String,
+    /// Industry the company operates in.
+    pub industry: String,
+}
+
+impl Snapshot {
+    /// Create a snapshot from the `quote` and `overview` of the same equity.
+    pub fn new(quote: Quote, overview: CompanyOverview) -> Snapshot {
+        Snapshot {
+            symbol: quote.symbol,
+            name: overview.name,
+            price: quote.price,
+            change: quote.change,
+            latest_trading_day: quote.latest_trading_day,
+            market_capitalization: overview.market_capitalization,
+            pe_ratio: overview.pe_ratio,
+            sector: overview.sector,
+            industry: overview.industry,
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Symbols listed on US and international exchanges
+//!
+//! Symbols listed outside of the US are identified by the API with a suffix naming their
+//! exchange (e.g. `TSCO.LON`, `RELIANCE.BSE` or `SHOP.TRT`).
+use chrono_tz::Tz;
+use std::fmt;
+
+/// Represents an exchange a symbol can be listed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Exchange {
+    /// US exchanges (e.g. NYSE or NASDAQ), identified without suffix.
+    US,
+    /// London Stock Exchange (`LON`).
+    London,
+    /// Toronto Stock Exchange (`TRT`).
+    Toronto,
+    /// TSX Venture Exchange (`TRV`).
+    TorontoVenture,
+    /// XETRA (`DEX`).
+    Xetra,
+    /// Bombay Stock Exchange (`BSE`).
+    Bombay,
+    /// Shanghai Stock Exchange (`SHH`).
+    Shanghai,
+    /// Shenzhen Stock Exchange (`SHZ`).
+    Shenzhen,
+}
+
+const EXCHANGES: [Exchange; 8] = [
+    Exchange::US,
+    Exchange::London,
+    Exchange::Toronto,
+    Exchange::TorontoVenture,
+    Exchange::Xetra,
+    Exchange::Bombay,
+    Exchange::Shanghai,
+    Exchange::Shenzhen,
+];
+
+impl Exchange {
+    /// Suffix identifying the exchange in symbols, if any.
+    pub fn suffix(self) -> Option<&'static str> {
+        use self::Exchange::*;
+        match self {
+            US => None,
+            London => Some("LON"),
+            Toronto => Some("TRT"),
+            TorontoVenture => Some("TRV"),
+            Xetra => Some("DEX"),
+            Bombay => Some("BSE"),
+            Shanghai => Some("SHH"),
+            Shenzhen => Some("SHZ"),
+        }
+    }
+
+    /// Exchange identified by the given symbol `suffix`, if known.
+    pub fn from_suffix(suffix: &str) -> Option<Exchange> {
+        EXCHANGES
+            .iter()
+            .find(|e| e.suffix() == Some(suffix))
+            .copied()
+    }
+
+    /// Currency prices are quoted in on the exchange.
+    ///
+    /// Prices on the London Stock Exchange are quoted in pence sterling (`GBX`).
+    pub fn currency(self) -> &'static str {
+        use self::Exchange::*;
+        match self {
+            US => "USD",
+            London => "GBX",
+            Toronto | TorontoVenture => "CAD",
+            Xetra => "EUR",
+            Bombay => "INR",
+            Shanghai | Shenzhen => "CNY",
+        }
+    }
+
+    /// Time zone of the exchange, which time series dates are reported in.
+    pub fn time_zone(self) -> Tz {
+        use self::Exchange::*;
+        match self {
+            US => Tz::US__Eastern,
+            London => Tz::Europe__London,
+            Toronto | TorontoVenture => Tz::America__Toronto,
+            Xetra => Tz::Europe__Berlin,
+            Bombay => Tz::Asia__Kolkata,
+            Shanghai | Shenzhen => Tz::Asia__Shanghai,
+        }
+    }
+}
+
+/// Represents a symbol along with the exchange it's listed on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Symbol {
+    code: String,
+    ticker_len: usize,
+    exchange: Exchange,
+}
+
+impl Symbol {
+    /// Create a symbol for the `ticker` listed on `exchange`.
+    pub fn with_exchange(ticker: &str, exchange: Exchange) -> Symbol {
+        let code = match exchange.suffix() {
+            Some(suffix) => format!("{}.{}", ticker, suffix),
+            None => ticker.to_string(),
+        };
+        Symbol {
+            code,
+            ticker_len: ticker.len(),
+            exchange,
+        }
+    }
+
+    /// Symbol as identified by the API, including the exchange suffix if any.
+    pub fn as_str(&self) -> &str {
+        &self.code
+    }
+
+    /// Ticker of the symbol on its exchange, without suffix.
+    pub fn ticker(&self) -> &str {
+        &self.code[..self.ticker_len]
+    }
+
+    /// Exchange the symbol is listed on.
+    pub fn exchange(&self) -> Exchange {
+        self.exchange
+    }
+
+    /// Currency the symbol is expected to be quoted in.
+    pub fn currency(&self) -> &'static str {
+        self.exchange.currency()
+    }
+
+    /// Time zone the symbol's time series dates are expected to be reported in.
+    pub fn time_zone(&self) -> Tz {
+        self.exchange.time_zone()
+    }
+}
+
+impl From<&str> for Symbol {
+    /// Parse a symbol as identified by the API. Symbols without a known exchange suffix (e.g.
+    /// `BRK.B`) are assumed to be listed on US exchanges.
+    fn from(code: &str) -> Symbol {
+        if let Some((ticker, suffix)) = code.rsplit_once('.') {
+            if let Some(exchange) = Exchange::from_suffix(suffix) {
+                return Symbol::with_exchange(ticker, exchange);
+            }
+        }
+        Symbol::with_exchange(code, Exchange::US)
+    }
+}
+
+impl AsRef<str> for Symbol {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbols() {
+        let symbol = Symbol::with_exchange("TSCO", Exchange::London);
+        assert_eq!(symbol.as_str(), "TSCO.LON");
+        assert_eq!(symbol.ticker(), "TSCO");
+        assert_eq!(symbol.currency(), "GBX");
+        assert_eq!(symbol.time_zone(), Tz::Europe__London);
+        assert_eq!(Symbol::from("TSCO.LON"), symbol);
+
+        let symbol = Symbol::from("RELIANCE.BSE");
+        assert_eq!(symbol.exchange(), Exchange::Bombay);
+        assert_eq!(symbol.currency(), "INR");
+
+        let symbol = Symbol::from("BRK.B");
+        assert_eq!(symbol.exchange(), Exchange::US);
+        assert_eq!(symbol.ticker(), "BRK.B");
+        assert_eq!(symbol.to_string(), "BRK.B");
+        assert_eq!(symbol.time_zone(), Tz::US__Eastern);
+    }
+}
@@ -0,0 +1,97 @@
+//! Rendering of results as aligned text tables
+use chrono::{DateTime, TimeZone, Timelike};
+
+/// Separator between the columns of a table.
+const SEPARATOR: &str = "  ";
+
+/// Render `rows` of cells under `columns` as an aligned text table, with a header line
+/// underlined by dashes.
+///
+/// Columns of numbers are aligned right, other columns left.
+pub(crate) fn render(columns: &[&str], rows: &[Vec<String>]) -> String {
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(column.chars().count()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let numeric: Vec<bool> = (0..columns.len())
+        .map(|i| !rows.is_empty() && rows.iter().all(|row| row[i].parse::<f64>().is_ok()))
+        .collect();
+    let line = |cells: &mut dyn Iterator<Item = &str>| -> String {
+        let cells: Vec<String> = cells
+            .zip(widths.iter().zip(&numeric))
+            .map(|(cell, (width, numeric))| {
+                if *numeric {
+                    format!("{:>width$}", cell, width = width)
+                } else {
+                    format!("{:width$}", cell, width = width)
+                }
+            })
+            .collect();
+        cells.join(SEPARATOR).trim_end().to_string()
+    };
+    let mut table = line(&mut columns.iter().copied());
+    table.push('\n');
+    let dashes: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    table.push_str(&dashes.join(SEPARATOR));
+    table.push('\n');
+    for row in rows {
+        table.push_str(&line(&mut row.iter().map(String::as_str)));
+        table.push('\n');
+    }
+    table
+}
+
+/// Format `date`, leaving out its time at midnight, e.g. for daily entries.
+pub(crate) fn date<Tz: TimeZone>(date: &DateTime<Tz>) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    match date.num_seconds_from_midnight() {
+        0 => date.format("%Y-%m-%d").to_string(),
+        _ => date.format("%Y-%m-%d %H:%M").to_string(),
+    }
+}
+
+/// Format a price or rate with two decimals.
+pub(crate) fn decimal(value: f64) -> String {
+    format!("{:.2}", value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deserialize::parse_date;
+    use chrono_tz::US::Eastern;
+
+    #[test]
+    fn render_table() {
+        let rows = vec![
+            vec!["MSFT".to_string(), "402.28".to_string()],
+            vec!["GOOGL".to_string(), "9.10".to_string()],
+        ];
+        assert_eq!(
+            render(&["symbol", "price"], &rows),
+            "symbol   price\n------  ------\nMSFT    402.28\nGOOGL     9.10\n"
+        );
+        assert_eq!(render(&["symbol"], &[]), "symbol\n------\n");
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(
+            date(&parse_date("2024-05-03", Eastern).unwrap()),
+            "2024-05-03"
+        );
+        assert_eq!(
+            date(&parse_date("2024-05-03 16:00:00", Eastern).unwrap()),
+            "2024-05-03 16:00"
+        );
+    }
+}
@@ -0,0 +1,1354 @@
+//! Time series related operations
+use crate::analytics;
+use crate::calendar;
+use crate::parsing::ParseWarning;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use std::convert::From;
+use std::fmt;
+
+#[cfg(feature = "table")]
+use crate::table;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Represents the interval for an intraday time series.
+pub enum IntradayInterval {
+    /// 1 minute.
+    OneMinute,
+    /// 5 minutes.
+    FiveMinutes,
+    /// 15 minutes.
+    FifteenMinutes,
+    /// 30 minutes.
+    ThirtyMinutes,
+    /// 60 minutes.
+    SixtyMinutes,
+}
+
+impl IntradayInterval {
+    /// Value of the `interval` parameter.
+    pub fn to_string(self) -> &'static str {
+        use self::IntradayInterval::*;
+        match self {
+            OneMinute => "1min",
+            FiveMinutes => "5min",
+            FifteenMinutes => "15min",
+            ThirtyMinutes => "30min",
+            SixtyMinutes => "60min",
+        }
+    }
+
+    /// Length of the interval, in minutes.
+    pub fn minutes(self) -> u32 {
+        use self::IntradayInterval::*;
+        match self {
+            OneMinute => 1,
+            FiveMinutes => 5,
+            FifteenMinutes => 15,
+            ThirtyMinutes => 30,
+            SixtyMinutes => 60,
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> Option<IntradayInterval> {
+        use self::IntradayInterval::*;
+        [
+            OneMinute,
+            FiveMinutes,
+            FifteenMinutes,
+            ThirtyMinutes,
+            SixtyMinutes,
+        ]
+        .iter()
+        .find(|i| i.to_string() == name)
+        .copied()
+    }
+}
+
+/// Controls how much data is returned by the Alpha Vantage API.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputSize {
+    /// Default. Returns the latest 100 datapoints.
+    Compact,
+
+    /// Returns the full-length time series.
+    Full,
+}
+
+impl fmt::Display for OutputSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OutputSize::Compact => write!(f, "compact"),
+            OutputSize::Full => write!(f, "full"),
+        }
+    }
+}
+
+/// Represents a window of the trailing two years of intraday data, retrieved from the legacy
+/// `TIME_SERIES_INTRADAY_EXTENDED` function.
+///
+/// Slices span about 30 days each, from the most recent one (year 1, month 1) to the oldest one
+/// (year 2, month 12).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Slice {
+    year: u8,
+    month: u8,
+}
+
+impl Slice {
+    /// Most recent slice.
+    pub const LATEST: Slice = Slice { year: 1, month: 1 };
+
+    /// Slice of the given `month`, from 1 to 12, of the given trailing `year`, 1 or 2.
+    pub fn new(year: u8, month: u8) -> Option<Slice> {
+        if (1..=2).contains(&year) && (1..=12).contains(&month) {
+            Some(Slice { year, month })
+        } else {
+            None
+        }
+    }
+
+    /// Every slice, from the most recent one to the oldest one.
+    pub fn all() -> impl Iterator<Item = Slice> {
+        (1..=2).flat_map(|year| (1..=12).map(move |month| Slice { year, month }))
+    }
+}
+
+impl fmt::Display for Slice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "year{}month{}", self.year, self.month)
+    }
+}
+
+/// Represents a time series for a given symbol.
+#[derive(Debug, Clone)]
+pub struct TimeSeries {
+    /// Symbol the time series refers to.
+    pub symbol: String,
+    /// Date the information was last refreshed at.
+    pub last_refreshed: DateTime<Tz>,
+    /// Entries in the time series, sorted by ascending dates.
+    pub entries: Vec<Entry>,
+    /// Malformed entries skipped while leniently parsing the time series.
+    pub warnings: Vec<ParseWarning>,
+}
+
+impl TimeSeries {
+    /// Summarize the regular session of each trading day covered by an intraday time series.
+    ///
+    /// Entries are grouped by their US/Eastern trading day. The open, high, low, close, volume
+    /// and VWAP of a session only cover its regular hours, as with
+    /// [`analytics::session_vwap`] over [`SessionHours::Regular`](analytics::SessionHours),
+    /// volume traded in the pre-market and after hours is reported separately. Days without any
+    /// regular hours entry are left out.
+    pub fn daily_summary(&self) -> Vec<SessionSummary> {
+        let mut summaries: Vec<SessionSummary> = vec![];
+        for entry in &self.entries {
+            let day = entry
+                .date
+                .with_timezone(&chrono_tz::US::Eastern)
+                .date_naive();
+            let session = calendar::session(&entry.date);
+            if session == calendar::Session::Closed {
+                continue;
+            }
+            if summaries.last().map(|s| s.date) != Some(day) {
+                summaries.push(SessionSummary {
+                    date: day,
+                    open: f64::NAN,
+                    high: f64::MIN,
+                    low: f64::MAX,
+                    close: f64::NAN,
+                    volume: 0,
+                    extended_volume: 0,
+                    vwap: f64::NAN,
+                    gap: None,
+                });
+            }
+            let summary = summaries.last_mut().unwrap();
+            if session.is_extended() {
+                summary.extended_volume += entry.volume;
+                continue;
+            }
+            if summary.open.is_nan() {
+                summary.open = entry.open;
+            }
+            summary.high = summary.high.max(entry.high);
+            summary.low = summary.low.min(entry.low);
+            summary.close = entry.close;
+            summary.volume += entry.volume;
+        }
+        summaries.retain(|s| !s.open.is_nan());
+        for entry in analytics::session_vwap(self, analytics::SessionHours::Regular) {
+            let day = entry
+                .date
+                .with_timezone(&chrono_tz::US::Eastern)
+                .date_naive();
+            if let Ok(i) = summaries.binary_search_by_key(&day, |s| s.date) {
+                summaries[i].vwap = entry.vwap;
+            }
+        }
+        for i in 1..summaries.len() {
+            summaries[i].gap = Some(summaries[i].open - summaries[i - 1].close);
+        }
+        summaries
+    }
+}
+
+#[cfg(feature = "table")]
+impl TimeSeries {
+    /// Render the latest `n` entries as an aligned text table, e.g. to inspect them in a REPL.
+    pub fn to_table(&self, n: usize) -> String {
+        let skipped = self.entries.len().saturating_sub(n);
+        let rows: Vec<Vec<String>> = self.entries[skipped..]
+            .iter()
+            .map(|e| {
+                vec![
+                    table::date(&e.date),
+                    table::decimal(e.open),
+                    table::decimal(e.high),
+                    table::decimal(e.low),
+                    table::decimal(e.close),
+                    e.volume.to_string(),
+                ]
+            })
+            .collect();
+        table::render(&["date", "open", "high", "low", "close", "volume"], &rows)
+    }
+}
+
+/// Represents the regular session of a trading day, summarized from intraday entries by
+/// [`TimeSeries::daily_summary`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct SessionSummary {
+    /// Trading day of the session, in US/Eastern time.
+    pub date: NaiveDate,
+    /// Open value of the first regular hours entry.
+    pub open: f64,
+    /// Highest value over the regular hours.
+    pub high: f64,
+    /// Lowest value over the regular hours.
+    pub low: f64,
+    /// Close value of the last regular hours entry.
+    pub close: f64,
+    /// Volume traded during the regular hours.
+    pub volume: u64,
+    /// Volume traded in the pre-market and after hours.
+    pub extended_volume: u64,
+    /// Volume weighted average of the typical prices of the regular hours entries, `NaN` without
+    /// any volume.
+    pub vwap: f64,
+    /// Difference between the open value and the close value of the previous session, `None`
+    /// for the first session of the time series.
+    pub gap: Option<f64>,
+}
+
+impl SessionSummary {
+    /// Range between the high and low values of the session.
+    pub fn range(&self) -> f64 {
+        self.high - self.low
+    }
+
+    /// Gap relative to the close value of the previous session, e.g. `0.02` for a 2% gap up.
+    pub fn gap_ratio(&self) -> Option<f64> {
+        self.gap.map(|gap| gap / (self.open - gap))
+    }
+}
+
+/// Represents a set of values for an equity for a given period in the time series.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Entry {
+    /// Date.
+    pub date: DateTime<Tz>,
+    /// Open value.
+    pub open: f64,
+    /// High value.
+    pub high: f64,
+    /// Low value.
+    pub low: f64,
+    /// Close value.
+    pub close: f64,
+    /// Trading volume.
+    pub volume: u64,
+    /// Close value adjusted for splits and dividends, only present in adjusted time series.
+    pub adjusted_close: Option<f64>,
+    /// Dividend paid in the period, only present in adjusted time series.
+    pub dividend_amount: Option<f64>,
+    /// Split coefficient for the period, only present in adjusted time series.
+    pub split_coefficient: Option<f64>,
+}
+
+impl Entry {
+    /// Create a builder for an entry dated `date`, with every value set to zero.
+    pub fn builder(date: DateTime<Tz>) -> EntryBuilder {
+        EntryBuilder {
+            entry: Entry {
+                date,
+                open: 0.0,
+                high: 0.0,
+                low: 0.0,
+                close: 0.0,
+                volume: 0,
+                adjusted_close: None,
+                dividend_amount: None,
+                split_coefficient: None,
+            },
+        }
+    }
+
+    /// Average of the high, low and close values.
+    pub fn typical_price(&self) -> f64 {
+        (self.high + self.low + self.close) / 3.0
+    }
+
+    /// Range between the high and low values, extended to the close value of the `previous`
+    /// entry when it falls outside of it.
+    pub fn true_range(&self, previous: Option<&Entry>) -> f64 {
+        match previous {
+            Some(previous) => self.high.max(previous.close) - self.low.min(previous.close),
+            None => self.range(),
+        }
+    }
+
+    /// Size of the body of the candle, between the open and close values.
+    pub fn body(&self) -> f64 {
+        (self.close - self.open).abs()
+    }
+
+    /// Range between the high and low values.
+    pub fn range(&self) -> f64 {
+        self.high - self.low
+    }
+
+    /// Whether the close value is above the open value.
+    pub fn is_bullish(&self) -> bool {
+        self.close > self.open
+    }
+
+    /// Whether the close value is below the open value.
+    pub fn is_bearish(&self) -> bool {
+        self.close < self.open
+    }
+
+    /// Week covered by an entry of a weekly time series, from Monday to Sunday.
+    ///
+    /// Weekly entries are dated with the last trading day of their week, or the day the time
+    /// series was last refreshed for the week in progress.
+    pub fn week(&self) -> Period {
+        let date = self.date.date_naive();
+        let start = date - Duration::days(i64::from(date.weekday().num_days_from_monday()));
+        Period {
+            start,
+            end: start + Duration::days(6),
+        }
+    }
+
+    /// Calendar month covered by an entry of a monthly time series.
+    ///
+    /// Monthly entries are dated with the last trading day of their month, or the day the time
+    /// series was last refreshed for the month in progress.
+    pub fn month(&self) -> Period {
+        let date = self.date.date_naive();
+        let start = date.with_day(1).unwrap();
+        let next = match date.month() {
+            12 => NaiveDate::from_ymd_opt(date.year() + 1, 1, 1),
+            month => NaiveDate::from_ymd_opt(date.year(), month + 1, 1),
+        };
+        Period {
+            start,
+            end: next.unwrap() - Duration::days(1),
+        }
+    }
+}
+
+/// Builder for an [`Entry`].
+#[derive(Debug, Clone)]
+pub struct EntryBuilder {
+    entry: Entry,
+}
+
+impl EntryBuilder {
+    /// Set the open, high, low and close values to the same `price`.
+    pub fn price(self, price: f64) -> EntryBuilder {
+        self.open(price).high(price).low(price).close(price)
+    }
+
+    /// Open value.
+    pub fn open(mut self, open: f64) -> EntryBuilder {
+        self.entry.open = open;
+        self
+    }
+
+    /// High value.
+    pub fn high(mut self, high: f64) -> EntryBuilder {
+        self.entry.high = high;
+        self
+    }
+
+    /// Low value.
+    pub fn low(mut self, low: f64) -> EntryBuilder {
+        self.entry.low = low;
+        self
+    }
+
+    /// Close value.
+    pub fn close(mut self, close: f64) -> EntryBuilder {
+        self.entry.close = close;
+        self
+    }
+
+    /// Trading volume.
+    pub fn volume(mut self, volume: u64) -> EntryBuilder {
+        self.entry.volume = volume;
+        self
+    }
+
+    /// Close value adjusted for splits and dividends.
+    pub fn adjusted_close(mut self, adjusted_close: f64) -> EntryBuilder {
+        self.entry.adjusted_close = Some(adjusted_close);
+        self
+    }
+
+    /// Dividend paid in the period.
+    pub fn dividend_amount(mut self, dividend_amount: f64) -> EntryBuilder {
+        self.entry.dividend_amount = Some(dividend_amount);
+        self
+    }
+
+    /// Split coefficient for the period.
+    pub fn split_coefficient(mut self, split_coefficient: f64) -> EntryBuilder {
+        self.entry.split_coefficient = Some(split_coefficient);
+        self
+    }
+
+    /// Build the entry.
+    pub fn build(self) -> Entry {
+        self.entry
+    }
+}
+
+/// Represents the calendar period covered by an entry of a weekly or monthly time series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Period {
+    /// First day of the period.
+    pub start: NaiveDate,
+    /// Last day of the period, included.
+    pub end: NaiveDate,
+}
+
+impl Period {
+    /// Whether `date` falls within the period.
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+    /// Trading days of the period, see [`calendar::trading_days`].
+    pub fn trading_days(&self) -> Vec<NaiveDate> {
+        calendar::trading_days(self.start, self.end)
+    }
+}
+
+/// Represents the interval between the entries of a time series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interval {
+    /// Intraday interval.
+    Intraday(IntradayInterval),
+    /// 1 day.
+    Daily,
+    /// 1 week.
+    Weekly,
+    /// 1 month.
+    Monthly,
+}
+
+impl Interval {
+    /// Whether entries at this interval can be derived from the entries at the `finer` interval
+    /// covering the same period, with [`resample`].
+    ///
+    /// Daily entries aren't derived from intraday entries, which include extended hours.
+    pub fn derives_from(self, finer: Interval) -> bool {
+        match (self, finer) {
+            (Interval::Intraday(coarser), Interval::Intraday(finer)) => {
+                coarser.minutes() > finer.minutes() && coarser.minutes() % finer.minutes() == 0
+            }
+            (Interval::Weekly, Interval::Daily) | (Interval::Monthly, Interval::Daily) => true,
+            _ => false,
+        }
+    }
+
+    /// Time series function retrieving entries at this interval.
+    pub fn function(self) -> Function {
+        match self {
+            Interval::Intraday(interval) => Function::IntraDay(interval),
+            Interval::Daily => Function::Daily,
+            Interval::Weekly => Function::Weekly,
+            Interval::Monthly => Function::Monthly,
+        }
+    }
+}
+
+/// Aggregate the entries of `time_series` into entries at the coarser `interval`.
+///
+/// Intraday entries are dated with the start of their interval, as returned by the API, and
+/// daily, weekly and monthly entries with the date of their last entry. Open values are taken from
+/// the first entry of each interval, close and adjusted close values from the last one, dividends
+/// are summed and split coefficients multiplied.
+pub fn resample(time_series: &TimeSeries, interval: Interval) -> TimeSeries {
+    let mut entries: Vec<Entry> = vec![];
+    let mut bucket = None;
+    for entry in &time_series.entries {
+        let (key, date) = match interval {
+            Interval::Intraday(interval) => {
+                let local = entry.date.naive_local();
+                let minutes = local.hour() * 60 + local.minute();
+                let minutes = minutes - minutes % interval.minutes();
+                let start = local
+                    .date()
+                    .and_hms_opt(minutes / 60, minutes % 60, 0)
+                    .unwrap();
+                let date = entry
+                    .date
+                    .timezone()
+                    .from_local_datetime(&start)
+                    .earliest()
+                    .unwrap_or(entry.date);
+                (start, date)
+            }
+            Interval::Daily => (entry.date.date_naive().into(), entry.date),
+            Interval::Weekly => (entry.week().start.into(), entry.date),
+            Interval::Monthly => (entry.month().start.into(), entry.date),
+        };
+        match entries.last_mut() {
+            Some(last) if bucket == Some(key) => {
+                last.high = last.high.max(entry.high);
+                last.low = last.low.min(entry.low);
+                last.close = entry.close;
+                last.volume += entry.volume;
+                last.adjusted_close = entry.adjusted_close.or(last.adjusted_close);
+                last.dividend_amount = match (last.dividend_amount, entry.dividend_amount) {
+                    (Some(a), Some(b)) => Some(a + b),
+                    (a, b) => a.or(b),
+                };
+                last.split_coefficient = match (last.split_coefficient, entry.split_coefficient) {
+                    (Some(a), Some(b)) => Some(a * b),
+                    (a, b) => a.or(b),
+                };
+                last.date = date;
+            }
+            _ => {
+                bucket = Some(key);
+                entries.push(Entry {
+                    date,
+                    ..entry.clone()
+                });
+            }
+        }
+    }
+    TimeSeries {
+        symbol: time_series.symbol.clone(),
+        last_refreshed: time_series.last_refreshed,
+        entries,
+        warnings: vec![],
+    }
+}
+
+/// Represents a time series of a symbol at a given interval, within [`Timeframes`].
+#[derive(Debug, Clone)]
+pub struct Timeframe {
+    /// Interval between the entries.
+    pub interval: Interval,
+    /// The time series.
+    pub time_series: TimeSeries,
+    /// Whether the time series was derived locally from a finer one, see [`resample`].
+    pub derived: bool,
+}
+
+/// Represents time series of a symbol at several intervals.
+#[derive(Debug, Clone)]
+pub struct Timeframes {
+    /// Symbol the time series refer to.
+    pub symbol: String,
+    /// Time series, in the order their intervals were requested in.
+    pub timeframes: Vec<Timeframe>,
+}
+
+impl Timeframes {
+    /// Time series at the given `interval`, if requested.
+    pub fn get(&self, interval: Interval) -> Option<&TimeSeries> {
+        self.timeframes
+            .iter()
+            .find(|t| t.interval == interval)
+            .map(|t| &t.time_series)
+    }
+}
+
+/// Represents the function a time series is retrieved with, which determines how its response
+/// is parsed by [`parser::parse`].
+#[derive(Debug, Clone)]
+pub enum Function {
+    /// `TIME_SERIES_INTRADAY` at the given interval.
+    IntraDay(IntradayInterval),
+    /// `TIME_SERIES_DAILY`.
+    Daily,
+    /// `TIME_SERIES_DAILY_ADJUSTED`.
+    DailyAdjusted,
+    /// `TIME_SERIES_WEEKLY`.
+    Weekly,
+    /// `TIME_SERIES_WEEKLY_ADJUSTED`.
+    WeeklyAdjusted,
+    /// `TIME_SERIES_MONTHLY`.
+    Monthly,
+    /// `TIME_SERIES_MONTHLY_ADJUSTED`.
+    MonthlyAdjusted,
+}
+
+impl From<&'_ Function> for &'static str {
+    fn from(function: &'_ Function) -> Self {
+        use Function::*;
+        match function {
+            IntraDay(_) => "TIME_SERIES_INTRADAY",
+            Daily => "TIME_SERIES_DAILY",
+            DailyAdjusted => "TIME_SERIES_DAILY_ADJUSTED",
+            Weekly => "TIME_SERIES_WEEKLY",
+            WeeklyAdjusted => "TIME_SERIES_WEEKLY_ADJUSTED",
+            Monthly => "TIME_SERIES_MONTHLY",
+            MonthlyAdjusted => "TIME_SERIES_MONTHLY_ADJUSTED",
+        }
+    }
+}
+
+impl From<&'_ Function> for crate::Function {
+    fn from(function: &'_ Function) -> Self {
+        use Function::*;
+        match function {
+            IntraDay(_) => crate::Function::TimeSeriesIntraday,
+            Daily => crate::Function::TimeSeriesDaily,
+            DailyAdjusted => crate::Function::TimeSeriesDailyAdjusted,
+            Weekly => crate::Function::TimeSeriesWeekly,
+            WeeklyAdjusted => crate::Function::TimeSeriesWeeklyAdjusted,
+            Monthly => crate::Function::TimeSeriesMonthly,
+            MonthlyAdjusted => crate::Function::TimeSeriesMonthlyAdjusted,
+        }
+    }
+}
+
+pub mod parser {
+    use super::*;
+    use crate::deserialize::{from_str, from_str_opt, join_path, parse_date};
+    use crate::error::Error;
+    use crate::parsing::Parser;
+    use chrono_tz::Tz;
+    use std::collections::HashMap;
+    use std::io::Read;
+
+    #[derive(Debug, Deserialize)]
+    struct EntryHelper {
+        #[serde(rename = "1. open", deserialize_with = "from_str")]
+        pub open: f64,
+        #[serde(rename = "2. high", deserialize_with = "from_str")]
+        pub high: f64,
+        #[serde(rename = "3. low", deserialize_with = "from_str")]
+        pub low: f64,
+        #[serde(rename = "4. close", deserialize_with = "from_str")]
+        pub close: f64,
+        #[serde(
+            rename = "5. volume",
+            alias = "6. volume",
+            deserialize_with = "from_str"
+        )]
+        pub volume: u64,
+        #[serde(
+            rename = "5. adjusted close",
+            default,
+            deserialize_with = "from_str_opt"
+        )]
+        pub adjusted_close: Option<f64>,
+        #[serde(
+            rename = "7. dividend amount",
+            default,
+            deserialize_with = "from_str_opt"
+        )]
+        pub dividend_amount: Option<f64>,
+        #[serde(
+            rename = "8. split coefficient",
+            default,
+            deserialize_with = "from_str_opt"
+        )]
+        pub split_coefficient: Option<f64>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct TimeSeriesHelper {
+        #[serde(rename = "Error Message")]
+        error: Option<String>,
+        #[serde(rename = "Meta Data")]
+        metadata: Option<HashMap<String, String>>,
+        #[serde(flatten)]
+        time_series: HashMap<String, serde_json::Value>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct ExtendedEntryHelper {
+        time: String,
+        open: f64,
+        high: f64,
+        low: f64,
+        close: f64,
+        volume: u64,
+    }
+
+    const EXTENDED_COLUMNS: [&str; 6] = ["time", "open", "high", "low", "close", "volume"];
+
+    /// Parse a CSV response to `TIME_SERIES_INTRADAY_EXTENDED` for `symbol`, whose dates are
+    /// reported in US/Eastern time.
+    pub fn parse_extended(
+        mut parser: Parser,
+        symbol: &str,
+        reader: impl Read,
+    ) -> Result<TimeSeries, Error> {
+        let mut reader = csv::Reader::from_reader(reader);
+        parser.check_columns(reader.headers()?, &EXTENDED_COLUMNS)?;
+        let mut entries = vec![];
+        for (i, record) in reader.deserialize().enumerate() {
+            let path = format!("line {}", i + 2);
+            let entry = record
+                .map_err(Error::from)
+                .and_then(|e: ExtendedEntryHelper| {
+                    Ok(Entry {
+                        date: parse_date(&e.time, Tz::US__Eastern)?,
+                        open: e.open,
+                        high: e.high,
+                        low: e.low,
+                        close: e.close,
+                        volume: e.volume,
+                        adjusted_close: None,
+                        dividend_amount: None,
+                        split_coefficient: None,
+                    })
+                });
+            if let Some(entry) = parser.entry(&path, entry)? {
+                entries.push(entry);
+            }
+        }
+
+        entries.sort_by_key(|e| e.date);
+
+        let last_refreshed = entries
+            .last()
+            .map(|e| e.date)
+            .ok_or_else(|| Error::ParsingError("empty time series".into()))?;
+
+        Ok(TimeSeries {
+            symbol: symbol.to_string(),
+            last_refreshed,
+            entries,
+            warnings: parser.finish(),
+        })
+    }
+
+    pub fn parse(
+        mut parser: Parser,
+        function: &Function,
+        reader: impl Read,
+    ) -> Result<TimeSeries, Error> {
+        let helper: TimeSeriesHelper = parser.parse_json(reader)?;
+
+        if let Some(error) = helper.error {
+            return Err(Error::APIError(error));
+        }
+
+        let metadata = helper
+            .metadata
+            .ok_or_else(|| Error::ParsingError("missing metadata".into()))?;
+
+        let symbol = metadata
+            .get("2. Symbol")
+            .ok_or_else(|| Error::ParsingError("missing symbol".into()))?
+            .to_string();
+
+        let time_zone_key = match function {
+            Function::IntraDay(_) => "6. Time Zone",
+            Function::Daily | Function::DailyAdjusted => "5. Time Zone",
+            Function::Weekly
+            | Function::WeeklyAdjusted
+            | Function::Monthly
+            | Function::MonthlyAdjusted => "4. Time Zone",
+        };
+
+        let time_zone = metadata
+            .get(time_zone_key)
+            .ok_or_else(|| Error::ParsingError("missing time zone".into()))?;
+        let time_zone = parser.time_zone(time_zone_key, time_zone, Tz::US__Eastern)?;
+
+        let last_refreshed = metadata
+            .get("3. Last Refreshed")
+            .ok_or_else(|| Error::ParsingError("missing last refreshed".into()))
+            .map(|v| parse_date(v, time_zone))??;
+        parser.check_freshness("3. Last Refreshed", &last_refreshed);
+
+        let time_series_key = match function {
+            Function::IntraDay(interval) => format!("Time Series ({})", interval.to_string()),
+            Function::Daily | Function::DailyAdjusted => "Time Series (Daily)".to_string(),
+            Function::Weekly => "Weekly Time Series".to_string(),
+            Function::WeeklyAdjusted => "Weekly Adjusted Time Series".to_string(),
+            Function::Monthly => "Monthly Time Series".to_string(),
+            Function::MonthlyAdjusted => "Monthly Adjusted Time Series".to_string(),
+        };
+
+        let time_series = helper
+            .time_series
+            .get(&time_series_key)
+            .ok_or_else(|| Error::ParsingError("missing requested time series".into()))?;
+        let time_series: HashMap<String, serde_json::Value> =
+            parser.parse_value(&time_series_key, time_series)?;
+
+        let mut entries: Vec<Entry> = vec![];
+
+        for (d, v) in time_series.iter() {
+            let path = join_path(&time_series_key, d);
+            let entry = parse_date(d, time_zone).and_then(|date| {
+                let v: EntryHelper = parser.parse_value(&path, v)?;
+                Ok(Entry {
+                    date,
+                    open: v.open,
+                    high: v.high,
+                    low: v.low,
+                    close: v.close,
+                    volume: v.volume,
+                    adjusted_close: v.adjusted_close,
+                    dividend_amount: v.dividend_amount,
+                    split_coefficient: v.split_coefficient,
+                })
+            });
+            if let Some(entry) = parser.entry(&path, entry)? {
+                entries.push(entry);
+            }
+        }
+
+        entries.sort_by_key(|e| e.date);
+
+        let time_series = TimeSeries {
+            symbol,
+            last_refreshed,
+            entries,
+            warnings: parser.finish(),
+        };
+        Ok(time_series)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deserialize::parse_date;
+    use crate::parsing::{ParseMode, Parser};
+    use chrono_tz::US::Eastern;
+    use std::io::BufReader;
+
+    #[test]
+    #[cfg(feature = "table")]
+    fn time_series_table() {
+        let data: &[u8] = include_bytes!("../../tests/json/time_series_daily.json");
+        let time_series =
+            parser::parse(Parser::default(), &Function::Daily, BufReader::new(data)).unwrap();
+        assert_eq!(
+            time_series.to_table(2),
+            concat!(
+                "date          open    high     low   close    volume\n",
+                "----------  ------  ------  ------  ------  --------\n",
+                "2018-06-07  102.65  102.69  100.38  100.88  28232197\n",
+                "2018-06-08  101.09  101.95  100.54  101.63  22165128\n",
+            )
+        );
+    }
+
+    #[test]
+    fn slices() {
+        assert_eq!(Slice::LATEST.to_string(), "year1month1");
+        assert_eq!(Slice::new(2, 12).unwrap().to_string(), "year2month12");
+        assert_eq!(Slice::new(3, 1), None);
+        assert_eq!(Slice::new(1, 0), None);
+        assert_eq!(Slice::all().count(), 24);
+        assert_eq!(Slice::all().nth(12), Slice::new(2, 1));
+    }
+
+    #[test]
+    fn resample_entries() {
+        let entry = |date: &str, open: f64, close: f64, volume: u64| {
+            Entry::builder(parse_date(date, Eastern).unwrap())
+                .open(open)
+                .high(open.max(close))
+                .low(open.min(close))
+                .close(close)
+                .volume(volume)
+                .build()
+        };
+        let time_series = TimeSeries {
+            symbol: "MSFT".to_string(),
+            last_refreshed: parse_date("2024-05-03 10:10:00", Eastern).unwrap(),
+            entries: vec![
+                entry("2024-05-03 09:55:00", 1.0, 2.0, 10),
+                entry("2024-05-03 10:00:00", 2.0, 4.0, 20),
+                entry("2024-05-03 10:05:00", 4.0, 3.0, 30),
+                entry("2024-05-03 10:10:00", 3.0, 0.5, 40),
+            ],
+            warnings: vec![],
+        };
+        let resampled = resample(
+            &time_series,
+            Interval::Intraday(IntradayInterval::FifteenMinutes),
+        );
+        assert_eq!(
+            resampled.entries,
+            vec![
+                entry("2024-05-03 09:45:00", 1.0, 2.0, 10),
+                Entry {
+                    low: 0.5,
+                    high: 4.0,
+                    ..entry("2024-05-03 10:00:00", 2.0, 0.5, 90)
+                },
+            ]
+        );
+
+        let daily = TimeSeries {
+            entries: vec![
+                entry("2024-04-30", 1.0, 2.0, 10),
+                entry("2024-05-01", 2.0, 3.0, 10),
+                entry("2024-05-03", 3.0, 4.0, 10),
+                entry("2024-05-06", 4.0, 5.0, 10),
+            ],
+            ..time_series
+        };
+        let weekly: Vec<_> = resample(&daily, Interval::Weekly)
+            .entries
+            .iter()
+            .map(|e| (e.date.format("%Y-%m-%d").to_string(), e.open, e.close))
+            .collect();
+        assert_eq!(
+            weekly,
+            vec![
+                ("2024-05-03".to_string(), 1.0, 4.0),
+                ("2024-05-06".to_string(), 4.0, 5.0),
+            ]
+        );
+        assert_eq!(resample(&daily, Interval::Monthly).entries.len(), 2);
+    }
+
+    #[test]
+    fn daily_summary() {
+        let entry = |date: &str, open: f64, close: f64, volume: u64| {
+            Entry::builder(parse_date(date, Eastern).unwrap())
+                .open(open)
+                .high(open.max(close) + 1.0)
+                .low(open.min(close) - 1.0)
+                .close(close)
+                .volume(volume)
+                .build()
+        };
+        let time_series = TimeSeries {
+            symbol: "MSFT".to_string(),
+            last_refreshed: parse_date("2024-05-06 16:00:00", Eastern).unwrap(),
+            entries: vec![
+                entry("2024-05-03 08:00:00", 98.0, 99.0, 5),
+                entry("2024-05-03 09:30:00", 100.0, 102.0, 10),
+                entry("2024-05-03 15:55:00", 102.0, 101.0, 30),
+                entry("2024-05-03 16:00:00", 101.0, 103.0, 7),
+                entry("2024-05-04 10:00:00", 103.0, 104.0, 10),
+                entry("2024-05-06 09:30:00", 105.05, 106.0, 20),
+                entry("2024-05-06 18:00:00", 105.0, 106.0, 3),
+            ],
+            warnings: vec![],
+        };
+        let summaries = time_series.daily_summary();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(
+            summaries[0],
+            SessionSummary {
+                date: NaiveDate::from_ymd_opt(2024, 5, 3).unwrap(),
+                open: 100.0,
+                high: 103.0,
+                low: 99.0,
+                close: 101.0,
+                volume: 40,
+                extended_volume: 12,
+                vwap: 304.0 / 3.0,
+                gap: None,
+            }
+        );
+        assert_eq!(summaries[0].range(), 4.0);
+        assert_eq!(summaries[0].gap_ratio(), None);
+        // The Saturday entry is left out, and the gap spans the weekend.
+        assert_eq!(
+            summaries[1].date,
+            NaiveDate::from_ymd_opt(2024, 5, 6).unwrap()
+        );
+        assert_eq!(summaries[1].extended_volume, 3);
+        assert!((summaries[1].gap.unwrap() - 4.05).abs() < 1e-9);
+        assert!((summaries[1].gap_ratio().unwrap() - 0.0401).abs() < 1e-4);
+    }
+
+    #[test]
+    fn entry_helpers() {
+        let date = parse_date("2024-05-03", Eastern).unwrap();
+        let previous = Entry::builder(date).price(95.0).build();
+        let entry = Entry::builder(date)
+            .open(100.0)
+            .high(110.0)
+            .low(98.0)
+            .close(104.0)
+            .volume(1000)
+            .build();
+        assert_eq!(entry.volume, 1000);
+        assert_eq!(entry.adjusted_close, None);
+        assert_eq!(entry.typical_price(), 104.0);
+        assert_eq!(entry.body(), 4.0);
+        assert_eq!(entry.range(), 12.0);
+        assert_eq!(entry.true_range(None), 12.0);
+        assert_eq!(entry.true_range(Some(&previous)), 15.0);
+        assert!(entry.is_bullish());
+        assert!(!entry.is_bearish());
+        assert!(!previous.is_bullish() && !previous.is_bearish());
+    }
+
+    #[test]
+    fn parse_intraday_extended() {
+        let data: &[u8] = include_bytes!("../../tests/csv/time_series_intraday_extended.csv");
+        let time_series = parser::parse_extended(Parser::default(), "MSFT", BufReader::new(data))
+            .expect("failed to parse entries");
+        assert_eq!(time_series.symbol, "MSFT");
+        assert_eq!(time_series.entries.len(), 4);
+        assert_eq!(
+            time_series.entries[0],
+            Entry {
+                date: parse_date("2024-04-01 04:15:00", Eastern).unwrap(),
+                open: 422.1,
+                high: 422.3,
+                low: 422.0,
+                close: 422.25,
+                volume: 310,
+                adjusted_close: None,
+                dividend_amount: None,
+                split_coefficient: None,
+            }
+        );
+        assert_eq!(
+            time_series.last_refreshed,
+            parse_date("2024-04-30 20:00:00", Eastern).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_intraday() {
+        let data: &[u8] = include_bytes!("../../tests/json/time_series_intraday_1min.json");
+        let time_series = parser::parse(
+            Parser::default(),
+            &Function::IntraDay(IntradayInterval::OneMinute),
+            BufReader::new(data),
+        )
+        .expect("failed to parse entries");
+        assert_eq!(time_series.entries.len(), 100);
+        assert_eq!(
+            time_series.entries[0],
+            Entry {
+                date: parse_date("2018-06-01 14:21:00", Eastern).unwrap(),
+                open: 100.3975,
+                high: 100.4558,
+                low: 100.3850,
+                close: 100.4550,
+                volume: 67726,
+                adjusted_close: None,
+                dividend_amount: None,
+                split_coefficient: None,
+            }
+        );
+        assert_eq!(
+            time_series.entries[99],
+            Entry {
+                date: parse_date("2018-06-01 16:00:00", Eastern).unwrap(),
+                open: 100.6150,
+                high: 100.8100,
+                low: 100.5900,
+                close: 100.7900,
+                volume: 4129781,
+                adjusted_close: None,
+                dividend_amount: None,
+                split_coefficient: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_daily() {
+        let data: &[u8] = include_bytes!("../../tests/json/time_series_daily.json");
+        let time_series = parser::parse(Parser::default(), &Function::Daily, BufReader::new(data))
+            .expect("failed to parse entries");
+        assert_eq!(time_series.entries.len(), 100);
+        assert_eq!(
+            time_series.entries[0],
+            Entry {
+                date: parse_date("2018-01-17", Eastern).unwrap(),
+                open: 89.0800,
+                high: 90.2800,
+                low: 88.7500,
+                close: 90.1400,
+                volume: 24659472,
+                adjusted_close: None,
+                dividend_amount: None,
+                split_coefficient: None,
+            }
+        );
+        assert_eq!(
+            time_series.entries[99],
+            Entry {
+                date: parse_date("2018-06-08", Eastern).unwrap(),
+                open: 101.0924,
+                high: 101.9500,
+                low: 100.5400,
+                close: 101.6300,
+                volume: 22165128,
+                adjusted_close: None,
+                dividend_amount: None,
+                split_coefficient: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_daily_adjusted() {
+        let data: &[u8] = include_bytes!("../../tests/json/time_series_daily_adjusted.json");
+        let time_series = parser::parse(
+            Parser::default(),
+            &Function::DailyAdjusted,
+            BufReader::new(data),
+        )
+        .expect("failed to parse entries");
+        assert_eq!(time_series.entries.len(), 20);
+        assert_eq!(
+            time_series.entries[0],
+            Entry {
+                date: parse_date("2018-05-11", Eastern).unwrap(),
+                open: 97.8000,
+                high: 97.8700,
+                low: 97.0400,
+                close: 97.7000,
+                volume: 16778316,
+                adjusted_close: Some(97.2897),
+                dividend_amount: Some(0.0),
+                split_coefficient: Some(1.0),
+            }
+        );
+        let dividend = time_series
+            .entries
+            .iter()
+            .find(|e| e.date == parse_date("2018-05-16", Eastern).unwrap())
+            .unwrap();
+        assert_eq!(dividend.dividend_amount, Some(0.42));
+    }
+
+    #[test]
+    fn parse_weekly() {
+        let data: &[u8] = include_bytes!("../../tests/json/time_series_weekly.json");
+        let time_series = parser::parse(Parser::default(), &Function::Weekly, BufReader::new(data))
+            .expect("failed to parse entries");
+        assert_eq!(time_series.entries.len(), 961);
+        assert_eq!(
+            time_series.entries[0],
+            Entry {
+                date: parse_date("2000-01-14", Eastern).unwrap(),
+                open: 113.4400,
+                high: 114.2500,
+                low: 101.5000,
+                close: 112.2500,
+                volume: 157400000,
+                adjusted_close: None,
+                dividend_amount: None,
+                split_coefficient: None,
+            }
+        );
+        let week = time_series.entries[0].week();
+        assert_eq!(week.start, NaiveDate::from_ymd_opt(2000, 1, 10).unwrap());
+        assert_eq!(week.end, NaiveDate::from_ymd_opt(2000, 1, 16).unwrap());
+        assert!(week.contains(NaiveDate::from_ymd_opt(2000, 1, 10).unwrap()));
+        assert!(!week.contains(NaiveDate::from_ymd_opt(2000, 1, 17).unwrap()));
+        assert_eq!(week.trading_days().len(), 5);
+        assert_eq!(
+            time_series.entries[960],
+            Entry {
+                date: parse_date("2018-06-08", Eastern).unwrap(),
+                open: 101.2600,
+                high: 102.6900,
+                low: 100.3800,
+                close: 101.6300,
+                volume: 122316267,
+                adjusted_close: None,
+                dividend_amount: None,
+                split_coefficient: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_weekly_adjusted() {
+        let data: &[u8] = include_bytes!("../../tests/json/time_series_weekly_adjusted.json");
+        let time_series = parser::parse(
+            Parser::default(),
+            &Function::WeeklyAdjusted,
+            BufReader::new(data),
+        )
+        .expect("failed to parse entries");
+        assert_eq!(time_series.entries.len(), 2);
+        assert_eq!(
+            time_series.entries[0],
+            Entry {
+                date: parse_date("2018-05-18", Eastern).unwrap(),
+                open: 97.0000,
+                high: 97.9500,
+                low: 96.2100,
+                close: 96.3600,
+                volume: 107864240,
+                adjusted_close: Some(92.1797),
+                dividend_amount: Some(0.42),
+                split_coefficient: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_monthly() {
+        let data: &[u8] = include_bytes!("../../tests/json/time_series_monthly.json");
+        let time_series =
+            parser::parse(Parser::default(), &Function::Monthly, BufReader::new(data))
+                .expect("failed to parse entries");
+        assert_eq!(time_series.entries.len(), 221);
+        assert_eq!(
+            time_series.entries[0],
+            Entry {
+                date: parse_date("2000-02-29", Eastern).unwrap(),
+                open: 98.5000,
+                high: 110.0000,
+                low: 88.1200,
+                close: 89.3700,
+                volume: 667243800,
+                adjusted_close: None,
+                dividend_amount: None,
+                split_coefficient: None,
+            }
+        );
+        let month = time_series.entries[0].month();
+        assert_eq!(month.start, NaiveDate::from_ymd_opt(2000, 2, 1).unwrap());
+        assert_eq!(month.end, NaiveDate::from_ymd_opt(2000, 2, 29).unwrap());
+        // The latest entry covers the month in progress.
+        let month = time_series.entries[220].month();
+        assert_eq!(month.end, NaiveDate::from_ymd_opt(2018, 6, 30).unwrap());
+        assert_eq!(
+            time_series.entries[220],
+            Entry {
+                date: parse_date("2018-06-08", Eastern).unwrap(),
+                open: 99.2798,
+                high: 102.6900,
+                low: 99.1700,
+                close: 101.6300,
+                volume: 150971891,
+                adjusted_close: None,
+                dividend_amount: None,
+                split_coefficient: None,
+            }
+        );
+    }
+
+    const MALFORMED_DAILY: &[u8] = br#"{
+        "Meta Data": {
+            "2. Symbol": "MSFT",
+            "3. Last Refreshed": "2024-05-03",
+            "5. Time Zone": "US/Eastern"
+        },
+        "Time Series (Daily)": {
+            "2024-05-03": {
+                "1. open": "402.28",
+                "2. high": "407.15",
+                "3. low": "401.86",
+                "4. close": "406.66",
+                "5. volume": "n/a"
+            },
+            "2024-05-02": {
+                "1. open": "397.66",
+                "2. high": "399.93",
+                "3. low": "394.65",
+                "4. close": "397.84",
+                "5. volume": "17709360"
+            }
+        }
+    }"#;
+
+    #[test]
+    fn parse_monthly_adjusted() {
+        let data: &[u8] = include_bytes!("../../tests/json/time_series_monthly_adjusted.json");
+        let time_series = parser::parse(
+            Parser::default(),
+            &Function::MonthlyAdjusted,
+            BufReader::new(data),
+        )
+        .expect("failed to parse entries");
+        assert_eq!(time_series.entries.len(), 2);
+        let latest = &time_series.entries[1];
+        assert_eq!(latest.date, parse_date("2018-06-08", Eastern).unwrap());
+        assert_eq!(latest.volume, 150971891);
+        assert_eq!(latest.adjusted_close, Some(97.2212));
+        assert_eq!(latest.dividend_amount, Some(0.0));
+    }
+
+    #[test]
+    fn parse_error_path() {
+        let error =
+            parser::parse(Parser::default(), &Function::Daily, MALFORMED_DAILY).unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("at `Time Series (Daily).2024-05-03.5. volume`"));
+        assert!(message.contains("near `"));
+    }
+
+    #[test]
+    fn parse_lenient() {
+        let parser = Parser::new(ParseMode::Lenient);
+        let time_series = parser::parse(parser, &Function::Daily, MALFORMED_DAILY).unwrap();
+        assert_eq!(time_series.entries.len(), 1);
+        assert_eq!(time_series.warnings.len(), 1);
+        assert_eq!(
+            time_series.warnings[0].path,
+            "Time Series (Daily).2024-05-03"
+        );
+        assert!(time_series.warnings[0].message.contains("5. volume"));
+    }
+
+    #[test]
+    fn parse_strict() {
+        let data: &[u8] = include_bytes!("../../tests/json/time_series_daily.json");
+        let parser = Parser::new(ParseMode::Strict);
+        assert!(parser::parse(parser, &Function::Daily, BufReader::new(data)).is_ok());
+
+        let data: &[u8] = br#"{
+            "Meta Data": {
+                "2. Symbol": "MSFT",
+                "3. Last Refreshed": "2024-05-03",
+                "5. Time Zone": "US/Eastern"
+            },
+            "Time Series (Daily)": {
+                "2024-05-03": {
+                    "1. open": "402.28",
+                    "2. high": "407.15",
+                    "3. low": "401.86",
+                    "4. close": "406.66",
+                    "5. volume": "17446720",
+                    "6. vwap": "405.12"
+                }
+            }
+        }"#;
+        let parser = Parser::new(ParseMode::Strict);
+        let error = parser::parse(parser, &Function::Daily, data).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "parsing error: unknown field at `Time Series (Daily).2024-05-03.6. vwap`"
+        );
+    }
+}
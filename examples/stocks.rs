@@ -24,7 +24,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let token = args
         .token
         .or_else(|| env::var(TOKEN_ENV_KEY).ok())
-        .ok_or_else(|| "missing token")?;
+        .ok_or("missing token")?;
 
     let symbol = &args.symbol;
     let client = Client::new(&token);
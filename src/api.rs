@@ -1,18 +1,143 @@
+use crate::analytics;
+use crate::cache::{DefaultKeyStrategy, KeyStrategy};
+use crate::commodities::{self, Commodity};
+use crate::corporate_actions;
+use crate::crypto;
+use crate::currencies::{self, CurrencyList};
+use crate::earnings::{self, Horizon};
+use crate::economic;
+use crate::etf;
+use crate::exchange_rate;
+use crate::forex;
+use crate::fundamentals;
+use crate::listing::{self, ListingState};
+use crate::market_movers;
+use crate::news;
+use crate::quote;
+use crate::search;
+use crate::time_series::{self, Interval, OutputSize, Slice, TimeSeries, Timeframe, Timeframes};
+use crate::Error;
+use crate::Function;
+use crate::Parser;
+use alphavantage_types::internal::DATE_FORMAT;
+use chrono::NaiveDate;
 #[cfg(feature = "blocking")]
 use reqwest::blocking::Request as BlockingRequest;
 use reqwest::{Method, Request, Url};
-use std::convert::From;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 const URL_ENDPOINT: &str = "https://www.alphavantage.co/query";
 
+/// Size above which responses are assumed not to be error messages.
+const MAX_ERROR_RESPONSE_SIZE: usize = 4096;
+
+/// Keys used by the API to report errors and usage limits in otherwise successful responses.
+const ERROR_KEYS: [&str; 3] = ["Error Message", "Note", "Information"];
+
+/// Phrases of the messages reporting that a request was throttled.
+const THROTTLE_PHRASES: [&str; 2] = ["call frequency", "rate limit"];
+
+/// Largest jitter added to the wait before retrying a throttled request, so clients sharing a
+/// key don't all retry at once.
+const MAX_THROTTLE_JITTER: Duration = Duration::from_secs(2);
+
+/// Files served by the API next to the query endpoint, by the pseudo function requesting them.
+const FILES: [(&str, &str); 2] = [
+    ("DIGITAL_CURRENCY_LIST", "digital_currency_list/"),
+    ("PHYSICAL_CURRENCY_LIST", "physical_currency_list/"),
+];
+
+/// Parameters required by functions, which are rejected before being issued without them.
+const REQUIRED_PARAMS: [(&str, &[&str]); 30] = [
+    ("TIME_SERIES_INTRADAY", &["symbol", "interval"]),
+    (
+        "TIME_SERIES_INTRADAY_EXTENDED",
+        &["symbol", "interval", "slice"],
+    ),
+    ("TIME_SERIES_DAILY", &["symbol"]),
+    ("TIME_SERIES_DAILY_ADJUSTED", &["symbol"]),
+    ("TIME_SERIES_WEEKLY", &["symbol"]),
+    ("TIME_SERIES_WEEKLY_ADJUSTED", &["symbol"]),
+    ("TIME_SERIES_MONTHLY", &["symbol"]),
+    ("TIME_SERIES_MONTHLY_ADJUSTED", &["symbol"]),
+    ("CURRENCY_EXCHANGE_RATE", &["from_currency", "to_currency"]),
+    ("CRYPTO_INTRADAY", &["symbol", "market", "interval"]),
+    ("DIGITAL_CURRENCY_DAILY", &["symbol", "market"]),
+    ("DIGITAL_CURRENCY_WEEKLY", &["symbol", "market"]),
+    ("DIGITAL_CURRENCY_MONTHLY", &["symbol", "market"]),
+    ("FX_INTRADAY", &["from_symbol", "to_symbol", "interval"]),
+    ("FX_DAILY", &["from_symbol", "to_symbol"]),
+    ("FX_WEEKLY", &["from_symbol", "to_symbol"]),
+    ("FX_MONTHLY", &["from_symbol", "to_symbol"]),
+    ("GLOBAL_QUOTE", &["symbol"]),
+    ("REALTIME_BULK_QUOTES", &["symbol"]),
+    ("SYMBOL_SEARCH", &["keywords"]),
+    ("OVERVIEW", &["symbol"]),
+    ("ETF_PROFILE", &["symbol"]),
+    ("DIVIDENDS", &["symbol"]),
+    ("SPLITS", &["symbol"]),
+    ("EARNINGS", &["symbol"]),
+    ("EARNINGS_ESTIMATES", &["symbol"]),
+    ("INCOME_STATEMENT", &["symbol"]),
+    ("CASH_FLOW", &["symbol"]),
+    (
+        "ANALYTICS_FIXED_WINDOW",
+        &["SYMBOLS", "RANGE", "INTERVAL", "CALCULATIONS"],
+    ),
+    (
+        "ANALYTICS_SLIDING_WINDOW",
+        &[
+            "SYMBOLS",
+            "RANGE",
+            "INTERVAL",
+            "WINDOW_SIZE",
+            "CALCULATIONS",
+        ],
+    ),
+];
+
+/// Intervals accepted by intraday functions.
+const INTRADAY_INTERVALS: &[&str] = &["1min", "5min", "15min", "30min", "60min"];
+
+/// Intervals accepted by the functions taking an `interval` parameter.
+///
+/// Intervals accepted by commodity functions are given by [`Commodity::intervals`].
+const INTERVALS: [(&str, &[&str]); 8] = [
+    ("TIME_SERIES_INTRADAY", INTRADAY_INTERVALS),
+    ("TIME_SERIES_INTRADAY_EXTENDED", INTRADAY_INTERVALS),
+    ("CRYPTO_INTRADAY", INTRADAY_INTERVALS),
+    ("FX_INTRADAY", INTRADAY_INTERVALS),
+    ("TREASURY_YIELD", &["daily", "weekly", "monthly"]),
+    ("FEDERAL_FUNDS_RATE", &["daily", "weekly", "monthly"]),
+    ("REAL_GDP", &["quarterly", "annual"]),
+    ("CPI", &["monthly", "semiannual"]),
+];
+
+/// Earliest month intraday time series can be requested for.
+const FIRST_MONTH: &str = "2000-01";
+
+/// First day of the earliest month intraday time series can be requested for.
+pub(crate) fn first_intraday_date() -> NaiveDate {
+    NaiveDate::parse_from_str(&format!("{}-01", FIRST_MONTH), DATE_FORMAT).unwrap()
+}
+
+/// URL of the API endpoint used by default.
+pub(crate) fn default_url() -> Url {
+    Url::parse(URL_ENDPOINT).unwrap()
+}
+
+#[derive(Clone)]
 pub(crate) struct APIRequestBuilder {
     key: String,
+    urls: Vec<Url>,
 }
 
 impl APIRequestBuilder {
-    pub(crate) fn new(key: &str) -> APIRequestBuilder {
+    pub(crate) fn new(key: &str, urls: Vec<Url>) -> APIRequestBuilder {
         APIRequestBuilder {
             key: String::from(key),
+            urls,
         }
     }
 
@@ -21,31 +146,174 @@ impl APIRequestBuilder {
         function: &'a str,
         params: &'a [(&'a str, &'a str)],
     ) -> APIRequest<'a> {
-        APIRequest::new(&self.key, function, params)
+        APIRequest::new(&self.key, &self.urls, function, params)
     }
 }
 
 pub(crate) struct APIRequest<'a> {
     key: &'a str,
+    urls: &'a [Url],
     function: &'a str,
     params: &'a [(&'a str, &'a str)],
 }
 
 impl<'a> APIRequest<'a> {
-    fn new(key: &'a str, function: &'a str, params: &'a [(&'a str, &'a str)]) -> APIRequest<'a> {
+    fn new(
+        key: &'a str,
+        urls: &'a [Url],
+        function: &'a str,
+        params: &'a [(&'a str, &'a str)],
+    ) -> APIRequest<'a> {
         APIRequest {
             key,
+            urls,
             function,
             params,
         }
     }
 
-    fn url(&self) -> Url {
-        let mut url = Url::parse(&URL_ENDPOINT).unwrap();
+    /// Endpoint URLs the request can be issued to, in order of preference.
+    pub(crate) fn urls(&self) -> &'a [Url] {
+        self.urls
+    }
+
+    /// Function called by the request.
+    pub(crate) fn function(&self) -> &'a str {
+        self.function
+    }
+
+    /// Parameters of the request, excluding the function and API key.
+    pub(crate) fn params(&self) -> &'a [(&'a str, &'a str)] {
+        self.params
+    }
+
+    /// Key identifying the request in a cache according to the [`DefaultKeyStrategy`], which
+    /// doesn't include the API key.
+    pub(crate) fn cache_key(&self) -> String {
+        DefaultKeyStrategy.key(self.function, self.params)
+    }
+
+    /// Check that the API key is well-formed before issuing the request, failing with
+    /// [`Error::InvalidApiKey`] otherwise.
+    pub(crate) fn check_key(&self) -> Result<(), Error> {
+        if self.key.is_empty() {
+            return Err(Error::InvalidApiKey("key is empty".into()));
+        }
+        let valid = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+        if !self.key.chars().all(valid) {
+            return Err(Error::InvalidApiKey(
+                "key contains characters other than letters, digits, - and _".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check the parameters of the request before issuing it, so invalid requests fail with
+    /// [`Error::InvalidParams`] without spending quota.
+    ///
+    /// Parameters of functions which aren't supported by the crate are only checked for
+    /// consistency (e.g. `time_period` being positive).
+    pub(crate) fn check_params(&self) -> Result<(), Error> {
+        let invalid = |message: String| Err(Error::InvalidParams(message));
+        let param = |name: &str| self.params.iter().find(|p| p.0 == name).map(|p| p.1);
+        let function = Function::from(self.function);
+
+        for (i, (name, _)) in self.params.iter().enumerate() {
+            if self.params[..i].iter().any(|p| p.0 == *name) {
+                return invalid(format!("{} is given more than once", name));
+            }
+        }
+        if let Some((_, required)) = REQUIRED_PARAMS.iter().find(|r| r.0 == self.function) {
+            for name in required.iter() {
+                if param(name).unwrap_or("").is_empty() {
+                    return invalid(format!("{} is required by {}", name, self.function));
+                }
+            }
+        }
+        if let Some(interval) = param("interval") {
+            let intervals: Option<Vec<&str>> = match Commodity::from_function(self.function) {
+                Some(commodity) => Some(commodity.intervals().iter().map(|i| i.as_str()).collect()),
+                None => INTERVALS
+                    .iter()
+                    .find(|i| i.0 == self.function)
+                    .map(|i| i.1.to_vec()),
+            };
+            match intervals {
+                Some(intervals) if !intervals.contains(&interval) => {
+                    return invalid(format!(
+                        "interval {} is not supported by {}, expected one of {}",
+                        interval,
+                        self.function,
+                        intervals.join(", ")
+                    ));
+                }
+                Some(_) => {}
+                None if function.is_custom() => {}
+                None => return invalid(format!("interval is not supported by {}", self.function)),
+            }
+        }
+        if let Some(slice) = param("slice") {
+            if !Slice::all().any(|s| s.to_string() == slice) {
+                return invalid(format!(
+                    "slice must range from year1month1 to year2month12, got {}",
+                    slice
+                ));
+            }
+        }
+        if let Some(period) = param("time_period") {
+            if !matches!(period.parse::<u32>(), Ok(p) if p >= 1) {
+                return invalid(format!(
+                    "time_period must be a positive integer, got {}",
+                    period
+                ));
+            }
+        }
+        if let Some(size) = param("WINDOW_SIZE") {
+            if !matches!(size.parse::<u32>(), Ok(s) if s >= 10) {
+                return invalid(format!(
+                    "WINDOW_SIZE must be an integer of at least 10, got {}",
+                    size
+                ));
+            }
+        }
+        if let Some(month) = param("month") {
+            let valid = NaiveDate::parse_from_str(&format!("{}-01", month), DATE_FORMAT).is_ok();
+            if !valid || month.len() != FIRST_MONTH.len() || month < FIRST_MONTH {
+                return invalid(format!(
+                    "month must be formatted as YYYY-MM, from {} on, got {}",
+                    FIRST_MONTH, month
+                ));
+            }
+        }
+        if let (Some(from), Some(to)) = (param("time_from"), param("time_to")) {
+            if from > to {
+                return invalid(format!("time_from {} is after time_to {}", from, to));
+            }
+        }
+        Ok(())
+    }
+
+    fn url(&self, endpoint: &Url) -> Url {
+        self.url_with_key(endpoint, self.key)
+    }
+
+    /// URL of the request to the given `endpoint`, with the API key redacted so it can be
+    /// recorded.
+    pub(crate) fn redacted_url(&self, endpoint: &Url) -> Url {
+        self.url_with_key(endpoint, "REDACTED")
+    }
+
+    fn url_with_key(&self, endpoint: &Url, key: &str) -> Url {
+        if let Some((_, path)) = FILES.iter().find(|f| f.0 == self.function) {
+            if let Ok(url) = endpoint.join(path) {
+                return url;
+            }
+        }
+        let mut url = endpoint.clone();
         {
             let mut query = url.query_pairs_mut();
             query.append_pair("function", self.function);
-            query.append_pair("apikey", self.key);
+            query.append_pair("apikey", key);
             for param in self.params {
                 query.append_pair(param.0, param.1);
             }
@@ -54,15 +322,560 @@ impl<'a> APIRequest<'a> {
     }
 }
 
-impl<'a> From<APIRequest<'a>> for Request {
-    fn from(request: APIRequest) -> Self {
-        reqwest::Request::new(Method::GET, request.url())
+/// Check whether a successful response `body` is reporting an error instead of data.
+pub(crate) fn check_response(body: &[u8]) -> Result<(), Error> {
+    if body.len() > MAX_ERROR_RESPONSE_SIZE {
+        return Ok(());
     }
+    let fields: HashMap<String, serde_json::Value> = match serde_json::from_slice(body) {
+        Ok(fields) => fields,
+        Err(_) => return Ok(()),
+    };
+    for key in ERROR_KEYS.iter() {
+        if let Some(serde_json::Value::String(message)) = fields.get(*key) {
+            return Err(classify_message(message, SystemTime::now()));
+        }
+    }
+    Ok(())
 }
 
-#[cfg(feature = "blocking")]
-impl<'a> From<APIRequest<'a>> for BlockingRequest {
-    fn from(request: APIRequest) -> Self {
-        reqwest::blocking::Request::new(Method::GET, request.url())
+/// Classify an error `message` returned by the API at `now`, as [`Error::Throttled`] if it
+/// reports the request frequency or quota of the key to be exceeded.
+///
+/// The API counts requests per calendar minute, or per day once the daily quota is reached, so
+/// throttled requests are retried after the start of the next one rather than backing off.
+fn classify_message(message: &str, now: SystemTime) -> Error {
+    let lowercase = message.to_lowercase();
+    if !THROTTLE_PHRASES.iter().any(|p| lowercase.contains(p)) {
+        return Error::APIError(message.to_string());
+    }
+    let period = if lowercase.contains("per day") && !lowercase.contains("per minute") {
+        24 * 60 * 60
+    } else {
+        60
+    };
+    let since_epoch = now.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let elapsed = Duration::new(since_epoch.as_secs() % period, since_epoch.subsec_nanos());
+    // Derive the jitter from the sub-second part of the clock, which varies between clients.
+    let jitter = MAX_THROTTLE_JITTER.mul_f64(f64::from(since_epoch.subsec_nanos()) / 1e9);
+    Error::Throttled {
+        message: message.to_string(),
+        retry_after: Duration::from_secs(period) - elapsed + jitter,
+    }
+}
+
+/// Error raised by the HTTP client while issuing a request.
+pub(crate) fn connection_error(error: reqwest::Error) -> Error {
+    Error::ConnectionError(error.to_string())
+}
+
+/// Classify `error`, returned by a request, as [`Error::InvalidApiKey`] if the API reported
+/// the key of the request to be invalid.
+pub(crate) fn classify_key_error(error: Error) -> Error {
+    match error {
+        Error::APIError(message) if message.contains("apikey") => Error::InvalidApiKey(message),
+        error => error,
+    }
+}
+
+/// Check that `body` is a valid response to a request to `function` with `params`, by parsing
+/// it into the corresponding type.
+pub(crate) fn validate(
+    parser: Parser,
+    function: &str,
+    params: &[(&str, &str)],
+    body: &[u8],
+) -> Result<(), Error> {
+    check_response(body)?;
+    let param = |name: &str| params.iter().find(|p| p.0 == name).map(|p| p.1);
+    if Commodity::from_function(function).is_some() {
+        return commodities::parser::parse(parser, body).map(drop);
+    }
+    match Function::from(function) {
+        Function::CurrencyExchangeRate => exchange_rate::parser::parse(parser, body).map(drop),
+        Function::CryptoIntraday => crypto::parser::parse_intraday(parser, body).map(drop),
+        Function::DigitalCurrencyDaily => crypto::parser::parse_daily(parser, body).map(drop),
+        Function::DigitalCurrencyWeekly => crypto::parser::parse_weekly(parser, body).map(drop),
+        Function::DigitalCurrencyMonthly => crypto::parser::parse_monthly(parser, body).map(drop),
+        Function::FxIntraday => forex::parser::parse_intraday(parser, body).map(drop),
+        Function::FxDaily => forex::parser::parse(parser, body).map(drop),
+        Function::FxWeekly => forex::parser::parse_weekly(parser, body).map(drop),
+        Function::FxMonthly => forex::parser::parse_monthly(parser, body).map(drop),
+        Function::GlobalQuote => quote::parser::parse(parser, body).map(drop),
+        Function::RealtimeBulkQuotes => quote::parser::parse_bulk(parser, body).map(drop),
+        Function::SymbolSearch => search::parser::parse(parser, body).map(drop),
+        Function::Overview => fundamentals::parser::parse_company_overview(parser, body).map(drop),
+        Function::EtfProfile => {
+            let symbol = param("symbol").unwrap_or_default();
+            etf::parser::parse(parser, symbol, body).map(drop)
+        }
+        Function::TreasuryYield
+        | Function::FederalFundsRate
+        | Function::RealGdp
+        | Function::Cpi => economic::parser::parse(parser, body).map(drop),
+        Function::Dividends => corporate_actions::parser::parse_dividends(parser, body).map(drop),
+        Function::Splits => corporate_actions::parser::parse_splits(parser, body).map(drop),
+        Function::Earnings => earnings::parser::parse(parser, body).map(drop),
+        Function::EarningsEstimates => earnings::parser::parse_estimates(parser, body).map(drop),
+        Function::EarningsCalendar => {
+            let horizon = match param("horizon") {
+                Some("6month") => Horizon::SixMonths,
+                Some("12month") => Horizon::TwelveMonths,
+                _ => Horizon::ThreeMonths,
+            };
+            earnings::parser::parse_calendar(parser, horizon, body).map(drop)
+        }
+        Function::IpoCalendar => listing::parser::parse_ipo_calendar(parser, body).map(drop),
+        Function::IncomeStatement => {
+            fundamentals::parser::parse_income_statement(parser, body).map(drop)
+        }
+        Function::CashFlow => fundamentals::parser::parse_cash_flow(parser, body).map(drop),
+        Function::DigitalCurrencyList | Function::PhysicalCurrencyList => {
+            currencies::parser::parse(parser, body).map(drop)
+        }
+        Function::NewsSentiment => news::parser::parse(parser, body).map(drop),
+        Function::TimeSeriesIntradayExtended => {
+            let symbol = param("symbol").unwrap_or_default();
+            time_series::parser::parse_extended(parser, symbol, body).map(drop)
+        }
+        Function::AnalyticsFixedWindow => {
+            analytics::parser::parse_fixed_window(parser, body).map(drop)
+        }
+        Function::AnalyticsSlidingWindow => {
+            analytics::parser::parse_sliding_window(parser, body).map(drop)
+        }
+        Function::TopGainersLosers => market_movers::parser::parse(parser, body).map(drop),
+        Function::ListingStatus => {
+            let date = match param("date") {
+                Some(date) => Some(NaiveDate::parse_from_str(date, DATE_FORMAT)?),
+                None => None,
+            };
+            let state = match param("state") {
+                Some("delisted") => ListingState::Delisted,
+                _ => ListingState::Active,
+            };
+            listing::parser::parse(parser, date, state, body).map(drop)
+        }
+        _ => match time_series_function(function, params) {
+            Some(series) => time_series::parser::parse(parser, &series, body).map(drop),
+            None => Err(Error::ParsingError(format!(
+                "unsupported function {}",
+                function
+            ))),
+        },
+    }
+}
+
+/// Time series function called by a request to `function` with `params`, if any.
+fn time_series_function(function: &str, params: &[(&str, &str)]) -> Option<time_series::Function> {
+    use time_series::Function::*;
+    use time_series::IntradayInterval::*;
+    match Function::from(function) {
+        Function::TimeSeriesIntraday => {
+            let interval = params.iter().find(|p| p.0 == "interval")?.1;
+            [
+                OneMinute,
+                FiveMinutes,
+                FifteenMinutes,
+                ThirtyMinutes,
+                SixtyMinutes,
+            ]
+            .iter()
+            .find(|i| i.to_string() == interval)
+            .map(|i| IntraDay(*i))
+        }
+        Function::TimeSeriesDaily => Some(Daily),
+        Function::TimeSeriesDailyAdjusted => Some(DailyAdjusted),
+        Function::TimeSeriesWeekly => Some(Weekly),
+        Function::TimeSeriesWeeklyAdjusted => Some(WeeklyAdjusted),
+        Function::TimeSeriesMonthly => Some(Monthly),
+        Function::TimeSeriesMonthlyAdjusted => Some(MonthlyAdjusted),
+        _ => None,
+    }
+}
+
+/// Plan how to retrieve time series at each of `intervals`: the intervals to request, and for
+/// each of `intervals`, the index of the requested one it's derived from or retrieved as,
+/// deriving coarser intervals from finer ones if `derive` is set.
+pub(crate) fn plan_timeframes(
+    intervals: &[Interval],
+    derive: bool,
+) -> (Vec<Interval>, Vec<(usize, bool)>) {
+    let derivable =
+        |interval: Interval| derive && intervals.iter().any(|i| interval.derives_from(*i));
+    let mut requested: Vec<Interval> = vec![];
+    for interval in intervals {
+        if !derivable(*interval) && !requested.contains(interval) {
+            requested.push(*interval);
+        }
+    }
+    let sources = intervals
+        .iter()
+        .map(
+            |interval| match requested.iter().position(|r| r == interval) {
+                Some(i) => (i, false),
+                None => {
+                    let i = requested
+                        .iter()
+                        .position(|r| interval.derives_from(*r))
+                        .expect("interval neither requested nor derivable");
+                    (i, true)
+                }
+            },
+        )
+        .collect();
+    (requested, sources)
+}
+
+/// Assemble the time series at each of `intervals` of `symbol` from the `retrieved` ones,
+/// following the `sources` planned by [`plan_timeframes`].
+pub(crate) fn assemble_timeframes(
+    symbol: &str,
+    intervals: &[Interval],
+    sources: &[(usize, bool)],
+    retrieved: &[TimeSeries],
+) -> Timeframes {
+    let timeframes = intervals
+        .iter()
+        .zip(sources)
+        .map(|(interval, (source, derived))| Timeframe {
+            interval: *interval,
+            time_series: if *derived {
+                time_series::resample(&retrieved[*source], *interval)
+            } else {
+                retrieved[*source].clone()
+            },
+            derived: *derived,
+        })
+        .collect();
+    Timeframes {
+        symbol: symbol.to_string(),
+        timeframes,
+    }
+}
+
+/// Amount of daily entries to retrieve to cover the history since `start` as of `today`, the
+/// full history when `start` is open.
+///
+/// Compact responses hold the latest 100 trading days, which span at least 100 calendar days.
+pub(crate) fn history_output_size(start: Option<NaiveDate>, today: NaiveDate) -> OutputSize {
+    match start {
+        Some(start) if (today - start).num_days() < 100 => OutputSize::Compact,
+        _ => OutputSize::Full,
+    }
+}
+
+/// Currencies listed by the API, retrieved once by clients validating currencies.
+#[derive(Debug)]
+pub(crate) struct KnownCurrencies {
+    /// Every digital and physical currency.
+    all: CurrencyList,
+    /// Physical currencies only.
+    physical: CurrencyList,
+}
+
+impl KnownCurrencies {
+    pub(crate) fn new(digital: CurrencyList, physical: CurrencyList) -> KnownCurrencies {
+        let mut all = digital;
+        all.currencies.extend(physical.currencies.iter().cloned());
+        KnownCurrencies { all, physical }
+    }
+
+    /// Fail with [`Error::UnknownCurrency`] if no currency is listed with the given `code`.
+    pub(crate) fn check(&self, code: &str) -> Result<(), Error> {
+        check_currency(&self.all, code)
+    }
+
+    /// Fail with [`Error::UnknownCurrency`] if no physical currency is listed with the given
+    /// `code`.
+    pub(crate) fn check_physical(&self, code: &str) -> Result<(), Error> {
+        check_currency(&self.physical, code)
+    }
+}
+
+/// Fail with [`Error::UnknownCurrency`] if no currency of `list` has the given `code`.
+fn check_currency(list: &CurrencyList, code: &str) -> Result<(), Error> {
+    if list.contains(code) {
+        return Ok(());
+    }
+    Err(Error::UnknownCurrency {
+        code: code.to_string(),
+        close_matches: list
+            .close_matches(code)
+            .iter()
+            .map(|c| c.code.clone())
+            .collect(),
+    })
+}
+
+impl APIRequest<'_> {
+    /// Create an HTTP request to the given `endpoint`.
+    pub(crate) fn to_request(&self, endpoint: &Url) -> Request {
+        Request::new(Method::GET, self.url(endpoint))
+    }
+
+    /// Create a blocking HTTP request to the given `endpoint`.
+    #[cfg(feature = "blocking")]
+    pub(crate) fn to_blocking_request(&self, endpoint: &Url) -> BlockingRequest {
+        BlockingRequest::new(Method::GET, self.url(endpoint))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeframe_plans() {
+        use time_series::IntradayInterval;
+        let five = Interval::Intraday(IntradayInterval::FiveMinutes);
+        let fifteen = Interval::Intraday(IntradayInterval::FifteenMinutes);
+        let intervals = [Interval::Weekly, fifteen, Interval::Daily, five];
+        let (requested, sources) = plan_timeframes(&intervals, true);
+        assert_eq!(requested, vec![Interval::Daily, five]);
+        assert_eq!(sources, vec![(0, true), (1, true), (0, false), (1, false)]);
+        let (requested, _) = plan_timeframes(&intervals, false);
+        assert_eq!(requested.len(), 4);
+        assert!(!Interval::Daily.derives_from(five));
+        assert!(!Interval::Intraday(IntradayInterval::ThirtyMinutes)
+            .derives_from(Interval::Intraday(IntradayInterval::SixtyMinutes)));
+    }
+
+    #[test]
+    fn history_output_sizes() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 5, day).unwrap();
+        assert_eq!(
+            history_output_size(Some(date(1)), date(31)),
+            OutputSize::Compact
+        );
+        let start = NaiveDate::from_ymd_opt(2023, 5, 1).unwrap();
+        assert_eq!(history_output_size(Some(start), date(31)), OutputSize::Full);
+        assert_eq!(history_output_size(None, date(31)), OutputSize::Full);
+    }
+
+    #[test]
+    fn known_currencies() {
+        let list = |codes: &[(&str, &str)]| CurrencyList {
+            currencies: codes
+                .iter()
+                .map(|(code, name)| exchange_rate::Currency {
+                    code: code.to_string(),
+                    name: name.to_string(),
+                })
+                .collect(),
+            warnings: vec![],
+        };
+        let digital = list(&[("BTC", "Bitcoin"), ("BTG", "Bitcoin Gold")]);
+        let physical = list(&[("EUR", "Euro"), ("USD", "United States Dollar")]);
+        let known = KnownCurrencies::new(digital, physical);
+        assert!(known.check("BTC").is_ok());
+        assert!(known.check("usd").is_ok());
+        assert!(known.check_physical("EUR").is_ok());
+        match known.check_physical("BTC") {
+            Err(Error::UnknownCurrency { code, .. }) => assert_eq!(code, "BTC"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        match known.check("BT") {
+            Err(Error::UnknownCurrency {
+                code,
+                close_matches,
+            }) => {
+                assert_eq!(code, "BT");
+                assert_eq!(close_matches, vec!["BTC", "BTG"]);
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn cache_key() {
+        let builder = APIRequestBuilder::new("secret", vec![default_url()]);
+        let params = [("symbol", "MSFT"), ("interval", "5min")];
+        let request = builder.create("TIME_SERIES_INTRADAY", &params);
+        assert_eq!(
+            request.cache_key(),
+            "TIME_SERIES_INTRADAY?interval=5min&symbol=MSFT"
+        );
+        let params = [("interval", "5min"), ("symbol", "MSFT")];
+        let request = builder.create("TIME_SERIES_INTRADAY", &params);
+        assert_eq!(
+            request.cache_key(),
+            "TIME_SERIES_INTRADAY?interval=5min&symbol=MSFT"
+        );
+    }
+
+    #[test]
+    fn url() {
+        let mirror = Url::parse("https://mirror.example.com/alphavantage/query").unwrap();
+        let builder = APIRequestBuilder::new("secret", vec![default_url(), mirror.clone()]);
+        let params = [("symbol", "MSFT")];
+        let request = builder.create("GLOBAL_QUOTE", &params);
+        assert_eq!(request.urls().len(), 2);
+        assert_eq!(
+            request.url(&mirror).as_str(),
+            "https://mirror.example.com/alphavantage/query?function=GLOBAL_QUOTE&apikey=secret&symbol=MSFT"
+        );
+        assert_eq!(
+            request.redacted_url(&mirror).as_str(),
+            "https://mirror.example.com/alphavantage/query?function=GLOBAL_QUOTE&apikey=REDACTED&symbol=MSFT"
+        );
+        let request = builder.create("DIGITAL_CURRENCY_LIST", &[]);
+        assert_eq!(
+            request.url(&default_url()).as_str(),
+            "https://www.alphavantage.co/digital_currency_list/"
+        );
+        assert_eq!(
+            request.url(&mirror).as_str(),
+            "https://mirror.example.com/alphavantage/digital_currency_list/"
+        );
+    }
+
+    #[test]
+    fn check_params() {
+        let builder = APIRequestBuilder::new("secret", vec![default_url()]);
+        let check =
+            |function, params: &[(&str, &str)]| builder.create(function, params).check_params();
+        assert!(check(
+            "TIME_SERIES_INTRADAY",
+            &[("symbol", "MSFT"), ("interval", "5min")]
+        )
+        .is_ok());
+        assert!(check("SMA", &[("interval", "weekly"), ("time_period", "10")]).is_ok());
+        assert!(check(
+            "TIME_SERIES_INTRADAY",
+            &[
+                ("symbol", "MSFT"),
+                ("interval", "5min"),
+                ("month", "2009-01")
+            ]
+        )
+        .is_ok());
+
+        let invalid = [
+            ("TIME_SERIES_INTRADAY", vec![("symbol", "MSFT")]),
+            (
+                "TIME_SERIES_INTRADAY",
+                vec![("symbol", "MSFT"), ("interval", "2min")],
+            ),
+            (
+                "TIME_SERIES_DAILY",
+                vec![("symbol", "MSFT"), ("interval", "5min")],
+            ),
+            ("TIME_SERIES_DAILY", vec![("symbol", "")]),
+            (
+                "TIME_SERIES_DAILY",
+                vec![("symbol", "MSFT"), ("symbol", "AAPL")],
+            ),
+            ("SMA", vec![("time_period", "0")]),
+            (
+                "ANALYTICS_SLIDING_WINDOW",
+                vec![
+                    ("SYMBOLS", "AAPL"),
+                    ("RANGE", "30day"),
+                    ("INTERVAL", "DAILY"),
+                    ("WINDOW_SIZE", "5"),
+                    ("CALCULATIONS", "MEAN"),
+                ],
+            ),
+            ("REAL_GDP", vec![("interval", "monthly")]),
+            (
+                "TIME_SERIES_INTRADAY_EXTENDED",
+                vec![
+                    ("symbol", "MSFT"),
+                    ("interval", "5min"),
+                    ("slice", "year3month1"),
+                ],
+            ),
+            (
+                "TIME_SERIES_INTRADAY",
+                vec![
+                    ("symbol", "MSFT"),
+                    ("interval", "5min"),
+                    ("month", "2009-1"),
+                ],
+            ),
+            (
+                "TIME_SERIES_INTRADAY",
+                vec![
+                    ("symbol", "MSFT"),
+                    ("interval", "5min"),
+                    ("month", "1999-12"),
+                ],
+            ),
+            (
+                "NEWS_SENTIMENT",
+                vec![("time_from", "20240502T0000"), ("time_to", "20240501T0000")],
+            ),
+        ];
+        for (function, params) in invalid.iter() {
+            match check(function, params) {
+                Err(Error::InvalidParams(_)) => {}
+                result => panic!("{} {:?}: unexpected {:?}", function, params, result),
+            }
+        }
+    }
+
+    #[test]
+    fn check_key() {
+        let check = |key| {
+            APIRequestBuilder::new(key, vec![])
+                .create("GLOBAL_QUOTE", &[])
+                .check_key()
+        };
+        assert!(check("DEMO1234ABCD5678").is_ok());
+        assert!(check("").is_err());
+        assert!(check("DEMO 1234").is_err());
+        assert!(check("DEMO1234\n").is_err());
+
+        let error = Error::APIError("the parameter apikey is invalid or missing".into());
+        match classify_key_error(error) {
+            Error::InvalidApiKey(_) => {}
+            error => panic!("unexpected error: {:?}", error),
+        }
+        let error = Error::APIError("Invalid API call.".into());
+        match classify_key_error(error) {
+            Error::APIError(_) => {}
+            error => panic!("unexpected error: {:?}", error),
+        }
+    }
+
+    #[test]
+    fn check_error_responses() {
+        assert!(check_response(br#"{"Error Message": "Invalid API call."}"#).is_err());
+        assert!(check_response(br#"{"Note": "Thank you for using Alpha Vantage!"}"#).is_err());
+        assert!(check_response(br#"{"Meta Data": {"1. Information": "Daily"}}"#).is_ok());
+        assert!(check_response(b"timestamp,open,high,low,close,volume").is_ok());
+    }
+
+    #[test]
+    fn throttled_responses() {
+        let minute =
+            "Thank you for using Alpha Vantage! Our standard API call frequency is 5 calls \
+                      per minute and 500 calls per day.";
+        let day = "We have detected your API key as demo and our standard API rate limit is 25 \
+                   requests per day.";
+        // 12:34:56.5 UTC
+        let now = UNIX_EPOCH + Duration::from_millis((12 * 3600 + 34 * 60 + 56) * 1000 + 500);
+        match classify_message(minute, now) {
+            Error::Throttled { retry_after, .. } => {
+                assert_eq!(retry_after, Duration::from_millis(3500 + 1000))
+            }
+            error => panic!("unexpected error: {:?}", error),
+        }
+        match classify_message(day, now) {
+            Error::Throttled {
+                message,
+                retry_after,
+            } => {
+                assert_eq!(message, day);
+                let until_midnight = (11 * 3600 + 25 * 60 + 3) * 1000 + 500;
+                assert_eq!(retry_after, Duration::from_millis(until_midnight + 1000));
+            }
+            error => panic!("unexpected error: {:?}", error),
+        }
+        match classify_message("Invalid API call.", now) {
+            Error::APIError(message) => assert_eq!(message, "Invalid API call."),
+            error => panic!("unexpected error: {:?}", error),
+        }
     }
 }
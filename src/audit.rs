@@ -0,0 +1,296 @@
+//! Audit log of the calls made by a client
+//!
+//! A client configured with an [`AuditLog`] records how each of its calls was served, e.g. to
+//! a [`JsonlAuditLog`] keeping a tamper-evident record of the data retrieved from the API:
+//!
+//! ```no_run
+//! use alphavantage::audit::JsonlAuditLog;
+//! use alphavantage::Client;
+//!
+//! # fn run() -> Result<(), alphavantage::Error> {
+//! let log = JsonlAuditLog::open("audit.jsonl")?;
+//! let client = Client::builder("MY_SECRET_TOKEN")
+//!     .audit_log(log.clone())
+//!     .build();
+//! // ...
+//! log.check()?;
+//! let records = JsonlAuditLog::verify("audit.jsonl")?;
+//! # Ok(())
+//! # }
+//! ```
+use crate::Error;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Represents how a call was served.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Disposition {
+    /// Issued to the API.
+    Fetched,
+    /// Served from the cache.
+    Cached,
+    /// Served from the cache after its time to live, e.g. while being refreshed in the
+    /// background.
+    Stale,
+    /// Not served, as the client is offline and the response isn't cached.
+    Offline,
+}
+
+/// Represents a call recorded in an audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Time the call was made at.
+    pub time: DateTime<Utc>,
+    /// Function called.
+    pub function: String,
+    /// URL of the last request issued, with the API key redacted. `None` for calls served
+    /// without issuing a request.
+    pub url: Option<String>,
+    /// How the call was served.
+    pub disposition: Disposition,
+    /// HTTP status of the last response received from the API, if any.
+    pub status: Option<u16>,
+    /// Number of endpoints requests were issued to, including fallbacks.
+    pub attempts: usize,
+    /// Size of the response, in bytes.
+    pub size: u64,
+    /// Duration of the call, excluding any delay to respect the rate limit.
+    pub duration: Duration,
+    /// Class of the error the call failed with, if any (see [`Error::class`]).
+    pub error: Option<String>,
+}
+
+/// Records the calls made by a client and its clones.
+///
+/// Closures taking an [`AuditRecord`] can be used as audit logs, e.g. to forward records to
+/// another system. Records are passed synchronously while calls are made, so recording them
+/// should return quickly.
+pub trait AuditLog: Send + Sync {
+    /// Record a call.
+    fn record(&self, record: &AuditRecord);
+}
+
+impl<F> AuditLog for F
+where
+    F: Fn(&AuditRecord) + Send + Sync,
+{
+    fn record(&self, record: &AuditRecord) {
+        self(record)
+    }
+}
+
+/// Tracks the requests issued for a call, to be recorded in an audit log.
+pub(crate) struct Trail {
+    time: DateTime<Utc>,
+    started: Instant,
+    pub(crate) url: Option<String>,
+    pub(crate) status: Option<u16>,
+    pub(crate) attempts: usize,
+}
+
+impl Trail {
+    /// Start tracking a call made just now.
+    pub(crate) fn new() -> Trail {
+        Trail {
+            time: Utc::now(),
+            started: Instant::now(),
+            url: None,
+            status: None,
+            attempts: 0,
+        }
+    }
+
+    /// Restart the duration of the call, e.g. after waiting for the rate limit.
+    pub(crate) fn restart(&mut self) {
+        self.started = Instant::now();
+    }
+
+    /// Record of the call to `function`, served as `disposition` with a response of `size`
+    /// bytes or the `error` it failed with.
+    pub(crate) fn finish(
+        self,
+        function: &str,
+        disposition: Disposition,
+        size: usize,
+        error: Option<&Error>,
+    ) -> AuditRecord {
+        AuditRecord {
+            time: self.time,
+            function: function.to_string(),
+            url: self.url,
+            disposition,
+            status: self.status,
+            attempts: self.attempts,
+            size: size as u64,
+            duration: self.started.elapsed(),
+            error: error.map(|e| e.class().to_string()),
+        }
+    }
+}
+
+/// An audit log appending records to a file, one JSON object per line.
+///
+/// Each line carries a checksum chained to the previous line's, so editing, removing or
+/// reordering lines is detected by [`verify`](JsonlAuditLog::verify). Clones append to the
+/// same file.
+#[derive(Debug, Clone)]
+pub struct JsonlAuditLog {
+    state: Arc<Mutex<JsonlState>>,
+}
+
+#[derive(Debug)]
+struct JsonlState {
+    file: File,
+    checksum: u32,
+    error: Option<String>,
+}
+
+/// Field holding the checksum of each line, which is always last.
+const CHECKSUM_FIELD: &str = ",\"checksum\":\"";
+
+impl JsonlAuditLog {
+    /// Open the log stored at `path`, which is created if needed, failing if its existing
+    /// records don't pass verification.
+    pub fn open(path: impl AsRef<Path>) -> Result<JsonlAuditLog, Error> {
+        let path = path.as_ref();
+        let checksum = if path.exists() {
+            chain(&fs::read_to_string(path)?)?.1
+        } else {
+            0
+        };
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(JsonlAuditLog {
+            state: Arc::new(Mutex::new(JsonlState {
+                file,
+                checksum,
+                error: None,
+            })),
+        })
+    }
+
+    /// Check the checksums of the log stored at `path`, returning its number of records.
+    pub fn verify(path: impl AsRef<Path>) -> Result<usize, Error> {
+        Ok(chain(&fs::read_to_string(path)?)?.0)
+    }
+
+    /// Fail with the first error raised while appending records since the log was opened, if
+    /// any.
+    pub fn check(&self) -> Result<(), Error> {
+        match &self.state.lock().unwrap().error {
+            Some(error) => Err(Error::IOError(error.clone())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl AuditLog for JsonlAuditLog {
+    fn record(&self, record: &AuditRecord) {
+        let mut state = self.state.lock().unwrap();
+        let result = serde_json::to_string(record)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            .and_then(|json| {
+                let checksum = line_checksum(state.checksum, &json);
+                let line = format!(
+                    "{}{}{:08x}\"}}\n",
+                    &json[..json.len() - 1],
+                    CHECKSUM_FIELD,
+                    checksum
+                );
+                state.file.write_all(line.as_bytes())?;
+                state.file.flush()?;
+                state.checksum = checksum;
+                Ok(())
+            });
+        if let Err(error) = result {
+            state.error.get_or_insert(error.to_string());
+        }
+    }
+}
+
+/// Checksum of a line holding the JSON `record`, following a line with the `previous` checksum.
+fn line_checksum(previous: u32, record: &str) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(&previous.to_be_bytes());
+    hasher.update(record.as_bytes());
+    hasher.finalize()
+}
+
+/// Check the checksums of the lines of a log, returning its number of records and last checksum.
+fn chain(log: &str) -> Result<(usize, u32), Error> {
+    let mut checksum = 0;
+    let mut records = 0;
+    for (i, line) in log.lines().enumerate() {
+        let invalid = |message: &str| Error::ParsingError(format!("{} at line {}", message, i + 1));
+        let (record, expected) = line
+            .rfind(CHECKSUM_FIELD)
+            .map(|at| line.split_at(at))
+            .ok_or_else(|| invalid("missing checksum"))?;
+        let expected = expected[CHECKSUM_FIELD.len()..]
+            .strip_suffix("\"}")
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .ok_or_else(|| invalid("invalid checksum"))?;
+        let record = format!("{}}}", record);
+        serde_json::from_str::<AuditRecord>(&record).map_err(|_| invalid("invalid record"))?;
+        checksum = line_checksum(checksum, &record);
+        if checksum != expected {
+            return Err(invalid("checksum mismatch"));
+        }
+        records += 1;
+    }
+    Ok((records, checksum))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(function: &str) -> AuditRecord {
+        let mut trail = Trail::new();
+        trail.url = Some(format!(
+            "https://www.alphavantage.co/query?function={}&apikey=REDACTED",
+            function
+        ));
+        trail.status = Some(200);
+        trail.attempts = 1;
+        trail.finish(function, Disposition::Fetched, 120, None)
+    }
+
+    #[test]
+    fn jsonl_audit_log() {
+        let path =
+            std::env::temp_dir().join(format!("alphavantage-audit-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let log = JsonlAuditLog::open(&path).unwrap();
+        log.record(&record("GLOBAL_QUOTE"));
+        log.record(&record("OVERVIEW"));
+        log.check().unwrap();
+        assert_eq!(JsonlAuditLog::verify(&path).unwrap(), 2);
+
+        // Reopening the log continues the chain.
+        let log = JsonlAuditLog::open(&path).unwrap();
+        log.record(&record("EARNINGS"));
+        assert_eq!(JsonlAuditLog::verify(&path).unwrap(), 3);
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let first: AuditRecord = serde_json::from_str(contents.lines().next().unwrap()).unwrap();
+        assert_eq!(first.function, "GLOBAL_QUOTE");
+        assert_eq!(first.disposition, Disposition::Fetched);
+
+        let lines: Vec<&str> = contents.lines().collect();
+        fs::write(&path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        assert_eq!(
+            JsonlAuditLog::verify(&path).unwrap_err().to_string(),
+            "parsing error: checksum mismatch at line 2"
+        );
+        fs::write(&path, contents.replace("OVERVIEW", "DIVIDENDS")).unwrap();
+        assert!(JsonlAuditLog::verify(&path).is_err());
+        assert!(JsonlAuditLog::open(&path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,353 @@
+use alphavantage::market_movers::Category;
+use alphavantage::time_series::{IntradayInterval, TimeSeries};
+use alphavantage::Client;
+use serde_json::{json, Map, Value};
+use std::env;
+use std::str::FromStr;
+use structopt::StructOpt;
+
+const TOKEN_ENV_KEY: &str = "ALPHAVANTAGE_TOKEN";
+
+#[derive(StructOpt)]
+#[structopt(name = "alphavantage", about = "Query the Alpha Vantage API")]
+struct Cli {
+    #[structopt(
+        short = "t",
+        long = "token",
+        help = "API token (can use the ALPHAVANTAGE_TOKEN env var instead)"
+    )]
+    token: Option<String>,
+    #[structopt(
+        short = "f",
+        long = "format",
+        default_value = "table",
+        help = "output format (table, csv or json)"
+    )]
+    format: Format,
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+#[derive(StructOpt)]
+enum Command {
+    #[structopt(name = "quote", about = "Latest price and volume of a symbol")]
+    Quote {
+        #[structopt(help = "stock symbol (e.g. AAPL)")]
+        symbol: String,
+    },
+    #[structopt(name = "series", about = "Time series of a symbol")]
+    Series {
+        #[structopt(help = "period (1min, 5min, 15min, 30min, hourly, daily, weekly or monthly)")]
+        period: String,
+        #[structopt(help = "stock symbol (e.g. AAPL)")]
+        symbol: String,
+    },
+    #[structopt(name = "fx", about = "Exchange rate between two currencies")]
+    Fx {
+        #[structopt(help = "currency to convert from (e.g. USD)")]
+        from: String,
+        #[structopt(help = "currency to convert to (e.g. EUR)")]
+        to: String,
+        #[structopt(short = "d", long = "daily", help = "retrieve the daily rates instead")]
+        daily: bool,
+    },
+    #[structopt(name = "search", about = "Symbols best matching some keywords")]
+    Search {
+        #[structopt(help = "keywords (e.g. microsoft)")]
+        keywords: String,
+    },
+    #[structopt(
+        name = "movers",
+        about = "Top gainers, losers and most actively traded US tickers"
+    )]
+    Movers,
+}
+
+enum Format {
+    Table,
+    Csv,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Format, String> {
+        match value {
+            "table" => Ok(Format::Table),
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format {}", value)),
+        }
+    }
+}
+
+/// Rows of values under named columns, printable in each output format.
+struct Table {
+    columns: Vec<&'static str>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    fn new(columns: &[&'static str]) -> Table {
+        Table {
+            columns: columns.to_vec(),
+            rows: vec![],
+        }
+    }
+
+    fn push(&mut self, row: Vec<Value>) {
+        self.rows.push(row);
+    }
+
+    fn print(&self, format: &Format) -> Result<(), Box<dyn std::error::Error>> {
+        match format {
+            Format::Table => self.print_table(),
+            Format::Csv => self.print_csv()?,
+            Format::Json => self.print_json()?,
+        }
+        Ok(())
+    }
+
+    fn print_table(&self) {
+        let rows: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(cell).collect())
+            .collect();
+        let widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                rows.iter()
+                    .map(|row| row[i].len())
+                    .chain(std::iter::once(column.len()))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let line = |cells: Vec<String>| -> String {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+        println!(
+            "{}",
+            line(self.columns.iter().map(|c| c.to_string()).collect())
+        );
+        for row in rows {
+            println!("{}", line(row));
+        }
+    }
+
+    fn print_csv(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut writer = csv::Writer::from_writer(std::io::stdout());
+        writer.write_record(&self.columns)?;
+        for row in &self.rows {
+            writer.write_record(row.iter().map(cell))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn print_json(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let rows: Vec<Value> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let object: Map<String, Value> = self
+                    .columns
+                    .iter()
+                    .map(|c| c.to_string())
+                    .zip(row.iter().cloned())
+                    .collect();
+                Value::Object(object)
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+        Ok(())
+    }
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+fn series_table(time_series: TimeSeries) -> Table {
+    let mut table = Table::new(&["date", "open", "high", "low", "close", "volume"]);
+    for entry in time_series.entries {
+        table.push(vec![
+            json!(entry.date.to_string()),
+            json!(entry.open),
+            json!(entry.high),
+            json!(entry.low),
+            json!(entry.close),
+            json!(entry.volume),
+        ]);
+    }
+    table
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Cli::from_args();
+    let token = args
+        .token
+        .or_else(|| env::var(TOKEN_ENV_KEY).ok())
+        .ok_or("missing token")?;
+
+    let client = Client::new(&token);
+
+    let table = match args.command {
+        Command::Quote { symbol } => {
+            let quote = client.get_quote(&symbol).await?;
+            let mut table = Table::new(&[
+                "symbol",
+                "latest_trading_day",
+                "open",
+                "high",
+                "low",
+                "price",
+                "previous_close",
+                "change",
+                "change_percentage",
+                "volume",
+            ]);
+            table.push(vec![
+                json!(quote.symbol),
+                json!(quote.latest_trading_day.to_string()),
+                json!(quote.open),
+                json!(quote.high),
+                json!(quote.low),
+                json!(quote.price),
+                json!(quote.previous_close),
+                json!(quote.change),
+                json!(quote.change_percentage),
+                json!(quote.volume),
+            ]);
+            table
+        }
+        Command::Series { period, symbol } => {
+            let symbol = &symbol;
+            let time_series = match period.as_str() {
+                "1min" => {
+                    client
+                        .get_time_series_intraday(symbol, IntradayInterval::OneMinute)
+                        .await
+                }
+                "5min" => {
+                    client
+                        .get_time_series_intraday(symbol, IntradayInterval::FiveMinutes)
+                        .await
+                }
+                "15min" => {
+                    client
+                        .get_time_series_intraday(symbol, IntradayInterval::FifteenMinutes)
+                        .await
+                }
+                "30min" => {
+                    client
+                        .get_time_series_intraday(symbol, IntradayInterval::ThirtyMinutes)
+                        .await
+                }
+                "hourly" => {
+                    client
+                        .get_time_series_intraday(symbol, IntradayInterval::SixtyMinutes)
+                        .await
+                }
+                "daily" => client.get_time_series_daily(symbol).await,
+                "weekly" => client.get_time_series_weekly(symbol).await,
+                "monthly" => client.get_time_series_monthly(symbol).await,
+                _ => return Err(format!("unknown period {}", period).into()),
+            }?;
+            series_table(time_series)
+        }
+        Command::Fx {
+            from,
+            to,
+            daily: false,
+        } => {
+            let exchange_rate = client.get_exchange_rate(&from, &to).await?;
+            let mut table = Table::new(&["from", "to", "rate", "date"]);
+            table.push(vec![
+                json!(exchange_rate.from.code),
+                json!(exchange_rate.to.code),
+                json!(exchange_rate.rate),
+                json!(exchange_rate.date.to_string()),
+            ]);
+            table
+        }
+        Command::Fx {
+            from,
+            to,
+            daily: true,
+        } => {
+            let forex = client.get_forex_daily(&from, &to).await?;
+            let mut table = Table::new(&["date", "open", "high", "low", "close"]);
+            for entry in forex.entries {
+                table.push(vec![
+                    json!(entry.date.date_naive().to_string()),
+                    json!(entry.open),
+                    json!(entry.high),
+                    json!(entry.low),
+                    json!(entry.close),
+                ]);
+            }
+            table
+        }
+        Command::Search { keywords } => {
+            let matches = client.search_symbol(&keywords).await?;
+            let mut table = Table::new(&["symbol", "name", "type", "region", "currency", "score"]);
+            for m in matches.matches {
+                table.push(vec![
+                    json!(m.symbol),
+                    json!(m.name),
+                    json!(m.asset_type),
+                    json!(m.region),
+                    json!(m.currency),
+                    json!(m.match_score),
+                ]);
+            }
+            table
+        }
+        Command::Movers => {
+            let movers = client.get_top_gainers_losers().await?;
+            let mut table = Table::new(&[
+                "list",
+                "ticker",
+                "price",
+                "change_amount",
+                "change_percentage",
+                "volume",
+            ]);
+            for (category, mover) in movers.iter() {
+                let list = match category {
+                    Category::Gainer => "gainer",
+                    Category::Loser => "loser",
+                    Category::MostActive => "most_active",
+                };
+                table.push(vec![
+                    json!(list),
+                    json!(mover.ticker),
+                    json!(mover.price),
+                    json!(mover.change_amount),
+                    json!(mover.change_percentage),
+                    json!(mover.volume),
+                ]);
+            }
+            table
+        }
+    };
+
+    table.print(&args.format)
+}
@@ -1,34 +1,69 @@
 //! Blocking client implementation
-use crate::api::APIRequestBuilder;
-use crate::error::Error;
+use crate::analytics;
+use crate::api::{self, APIRequest, APIRequestBuilder, KnownCurrencies};
+use crate::asset;
+use crate::audit::{AuditLog, Disposition, Trail};
+use crate::cache::{self, CacheEntry, Refreshes, ResponseCache};
+use crate::client::ClientBuilder;
+use crate::commodities;
+use crate::corporate_actions;
+use crate::crypto;
+use crate::currencies;
+use crate::earnings;
+use crate::economic;
+use crate::etf;
 use crate::exchange_rate;
+use crate::forex;
+use crate::fundamentals;
+use crate::listing;
+use crate::market_movers;
+use crate::news;
+use crate::observer::{Backoff, Observer};
+use crate::planner;
+use crate::quote;
+use crate::rate_limit::{Priority, RateLimiter, Tier};
+use crate::request::{FixedWindowRequest, IntradayRequest, Request, SlidingWindowRequest};
+use crate::response::{self, ApiResponse};
+use crate::returns;
+use crate::search;
+use crate::snapshot;
+use crate::stats;
 use crate::time_series;
-use reqwest;
-use std::io::Read;
-
-/// Controls how much data is returned by the Alpha Vantage API.
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
-pub enum OutputSize {
-    /// Default. Returns the latest 100 datapoints.
-    Compact,
-
-    /// Returns the full-length time series.
-    Full
-}
-
-impl ToString for OutputSize {
-    fn to_string(&self) -> String {
-        match self {
-            OutputSize::Compact => "compact".to_string(),
-            OutputSize::Full => "full".to_string()
-        }
-    }
-}
+pub use crate::time_series::OutputSize;
+use crate::Error;
+use crate::Function;
+use crate::{ParseMode, Parser};
+use alphavantage_types::internal::DATE_FORMAT;
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use reqwest::Url;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 
 /// A blocking client for the Alpha Vantage API.
+#[derive(Clone)]
 pub struct Client {
     builder: APIRequestBuilder,
     client: reqwest::blocking::Client,
+    limiter: Option<Arc<RateLimiter>>,
+    priority: Priority,
+    tier: Option<Tier>,
+    cache: Option<ResponseCache>,
+    observer: Option<Arc<dyn Observer>>,
+    audit_log: Option<Arc<dyn AuditLog>>,
+    parse_mode: ParseMode,
+    stale_after: Option<Duration>,
+    offline: bool,
+    validate_currencies: bool,
+    known_currencies: Arc<Mutex<Option<Arc<KnownCurrencies>>>>,
+    stats: Arc<stats::Recorder>,
+    verify_key: bool,
+    throttle_retries: u32,
+    key_verified: Arc<AtomicBool>,
+    capture: Option<Arc<response::Capture>>,
+    refreshes: Option<Arc<Refreshes>>,
 
     /// Controls the output size of the Alpha Vantage API. Compact by default.
     /// Wrapped in a Cell to allow for interior mutability. Change it like so:
@@ -40,19 +75,146 @@ pub struct Client {
     /// client.output_size.set(OutputSize::Full);
     /// assert_eq!(client.output_size.get(), OutputSize::Full);
     /// ```
-    pub output_size: std::cell::Cell<OutputSize>
+    pub output_size: std::cell::Cell<OutputSize>,
 }
 
 impl Client {
     /// Create a new blocking client using the specified API `key`.
     pub fn new(key: &str) -> Client {
+        Client::builder(key).build_blocking()
+    }
+
+    /// Create a builder to configure a blocking client using the specified API `key`.
+    ///
+    /// Use [`ClientBuilder::build_blocking`](crate::ClientBuilder::build_blocking) to create the client.
+    pub fn builder(key: &str) -> ClientBuilder {
+        crate::Client::builder(key)
+    }
+
+    /// Create a client sharing the configuration and rate limit of this one, issuing its
+    /// requests with the given `priority`.
+    ///
+    /// When the rate limit is saturated, requests with a higher priority are issued first.
+    pub fn with_priority(&self, priority: Priority) -> Client {
+        Client {
+            priority,
+            ..self.clone()
+        }
+    }
+
+    pub(crate) fn from_builder(builder: ClientBuilder) -> Client {
+        let cache_keys = builder.cache_keys;
+        let tier = builder.tier;
         Client {
-            builder: APIRequestBuilder::new(key),
+            builder: APIRequestBuilder::new(&builder.key, builder.urls),
             client: reqwest::blocking::Client::new(),
-            output_size: std::cell::Cell::new(OutputSize::Compact)
+            limiter: builder
+                .rate_limit
+                .or_else(|| tier.map(Tier::rate_limit))
+                .map(|limit| Arc::new(RateLimiter::new(limit))),
+            priority: Priority::default(),
+            tier,
+            cache: builder.cache.map(|cache| cache.with_keys(cache_keys)),
+            observer: builder.observer,
+            audit_log: builder.audit_log,
+            parse_mode: builder.parse_mode,
+            stale_after: builder.stale_after,
+            offline: builder.offline,
+            validate_currencies: builder.validate_currencies,
+            verify_key: builder.verify_key,
+            throttle_retries: builder.throttle_retries,
+            known_currencies: Arc::new(Mutex::new(None)),
+            stats: Arc::new(stats::Recorder::default()),
+            key_verified: Arc::new(AtomicBool::new(false)),
+            capture: None,
+            refreshes: builder
+                .stale_while_revalidate
+                .then(|| Arc::new(Refreshes::default())),
+            output_size: std::cell::Cell::new(builder.output_size),
         }
     }
 
+    /// Usage statistics of the client and its clones since it was built.
+    pub fn stats(&self) -> stats::Stats {
+        self.stats.snapshot()
+    }
+
+    /// Run `call` against a client sharing the configuration of this one, returning its result
+    /// along with metadata about the requests it issued, failing with [`Error::InvalidParams`]
+    /// if it didn't issue any.
+    ///
+    /// Any endpoint call can be wrapped, see [`ApiResponse`] for calls issuing several requests.
+    pub fn with_metadata<T>(
+        &self,
+        call: impl FnOnce(&Client) -> Result<T, Error>,
+    ) -> Result<ApiResponse<T>, Error> {
+        let capture = Arc::new(response::Capture::default());
+        let client = Client {
+            capture: Some(capture.clone()),
+            ..self.clone()
+        };
+        let payload = call(&client)?;
+        capture.finish(payload)
+    }
+
+    /// Issue a typed `request`, e.g. a [`IntradayRequest`].
+    pub fn send<R: Request>(&self, request: R) -> Result<R::Response, Error> {
+        let function = request.function();
+        let params = request.params();
+        let params: Vec<(&str, &str)> = params.iter().map(|(n, v)| (*n, v.as_str())).collect();
+        let response = self.api_call(function.as_str(), &params)?;
+        request.parse(self.parser(function.as_str()), response)
+    }
+
+    /// Plan the requests required by `jobs` against the rate limit of the client, without
+    /// issuing any of them.
+    ///
+    /// Jobs served from the cache don't count towards the plan. Fails with
+    /// [`Error::InvalidParams`] or [`Error::PremiumRequired`] if a job would fail before being
+    /// issued, and with [`Error::QuotaExceeded`] if the requests exceed the remaining daily quota
+    /// of the client, e.g. on the free tier. Only the requests issued by this client and its
+    /// clones count towards the quota.
+    ///
+    /// Jobs for functions the API deprecated are planned for their replacement, as reported to
+    /// [`Observer::on_deprecated`], and jobs for functions it removed fail with
+    /// [`Error::InvalidParams`].
+    pub fn plan(&self, jobs: &[planner::Job]) -> Result<planner::Plan, Error> {
+        let jobs = jobs
+            .iter()
+            .map(|job| self.migrate(job))
+            .collect::<Result<Vec<_>, _>>()?;
+        planner::plan(
+            &jobs,
+            self.limiter.as_deref(),
+            self.cache.is_some(),
+            |job| {
+                let params = job.params();
+                self.builder.create(&job.function, &params).check_params()?;
+                self.check_tier(&job.function)?;
+                Ok(self.is_cached(&job.function, &params))
+            },
+        )
+    }
+
+    /// Freshness of the most recently retrieved response cached for `function` about `symbol`,
+    /// or about any symbol if `None`, whether or not it's past the time to live of the cache.
+    pub fn freshness(&self, function: &str, symbol: Option<&str>) -> Option<cache::Freshness> {
+        self.cache.as_ref()?.freshness(function, symbol)
+    }
+
+    /// Whether the response cached for `function` about `symbol` should be retrieved again under
+    /// `policy`, e.g. before a scheduled job spends its quota on it. Responses missing from the
+    /// cache are stale.
+    pub fn is_stale(
+        &self,
+        function: &str,
+        symbol: Option<&str>,
+        policy: &cache::StalenessPolicy,
+    ) -> bool {
+        self.freshness(function, symbol)
+            .is_none_or(|freshness| freshness.is_stale(policy))
+    }
+
     /// Retrieve intraday time series for the specified `symbol` updated in realtime.
     pub fn get_time_series_intraday(
         &self,
@@ -60,25 +222,97 @@ impl Client {
         interval: time_series::IntradayInterval,
     ) -> Result<time_series::TimeSeries, Error> {
         let function = time_series::Function::IntraDay(interval);
-        self.get_time_series(&function, symbol)
+        self.get_time_series(&function, symbol, self.output_size.get())
+    }
+
+    /// Retrieve a `slice` of the trailing two years of intraday time series for the specified
+    /// `symbol`, from the legacy `TIME_SERIES_INTRADAY_EXTENDED` function.
+    pub fn get_time_series_intraday_extended(
+        &self,
+        symbol: &str,
+        interval: time_series::IntradayInterval,
+        slice: time_series::Slice,
+    ) -> Result<time_series::TimeSeries, Error> {
+        let function = "TIME_SERIES_INTRADAY_EXTENDED";
+        let slice = slice.to_string();
+        let params = [
+            ("symbol", symbol),
+            ("interval", interval.to_string()),
+            ("slice", slice.as_str()),
+        ];
+        let response = self.api_call(function, &params)?;
+        let result = time_series::parser::parse_extended(self.parser(function), symbol, response)?;
+        Ok(result)
     }
 
     /// Retrieve daily time series for the specified `symbol` including up to 20 years of historical data.
     pub fn get_time_series_daily(&self, symbol: &str) -> Result<time_series::TimeSeries, Error> {
         let function = time_series::Function::Daily;
-        self.get_time_series(&function, symbol)
+        self.get_time_series(&function, symbol, self.output_size.get())
+    }
+
+    /// Retrieve daily time series for the specified `symbol` including up to 20 years of historical
+    /// data, with close values adjusted for splits and dividends.
+    pub fn get_time_series_daily_adjusted(
+        &self,
+        symbol: &str,
+    ) -> Result<time_series::TimeSeries, Error> {
+        let function = time_series::Function::DailyAdjusted;
+        self.get_time_series(&function, symbol, self.output_size.get())
     }
 
     /// Retrieve weekly time series for the specified `symbol` including up to 20 years of historical data.
     pub fn get_time_series_weekly(&self, symbol: &str) -> Result<time_series::TimeSeries, Error> {
         let function = time_series::Function::Weekly;
-        self.get_time_series(&function, symbol)
+        self.get_time_series(&function, symbol, self.output_size.get())
+    }
+
+    /// Retrieve weekly time series for the specified `symbol` including up to 20 years of
+    /// historical data, with close values adjusted for splits and dividends.
+    pub fn get_time_series_weekly_adjusted(
+        &self,
+        symbol: &str,
+    ) -> Result<time_series::TimeSeries, Error> {
+        let function = time_series::Function::WeeklyAdjusted;
+        self.get_time_series(&function, symbol, self.output_size.get())
     }
 
     /// Retrieve monthly time series for the specified `symbol` including up to 20 years of historical data.
     pub fn get_time_series_monthly(&self, symbol: &str) -> Result<time_series::TimeSeries, Error> {
         let function = time_series::Function::Monthly;
-        self.get_time_series(&function, symbol)
+        self.get_time_series(&function, symbol, self.output_size.get())
+    }
+
+    /// Retrieve monthly time series for the specified `symbol` including up to 20 years of
+    /// historical data, with close values adjusted for splits and dividends.
+    pub fn get_time_series_monthly_adjusted(
+        &self,
+        symbol: &str,
+    ) -> Result<time_series::TimeSeries, Error> {
+        let function = time_series::Function::MonthlyAdjusted;
+        self.get_time_series(&function, symbol, self.output_size.get())
+    }
+
+    /// Retrieve the time series for the specified `symbol` at each of the given `intervals`.
+    ///
+    /// When the client retrieves full time series (see [`ClientBuilder::output_size`]), which
+    /// cover the same period at every interval, coarser intervals are derived locally from finer
+    /// ones where possible (see [`Interval::derives_from`](time_series::Interval::derives_from))
+    /// instead of being requested.
+    pub fn get_timeframes(
+        &self,
+        symbol: &str,
+        intervals: &[time_series::Interval],
+    ) -> Result<time_series::Timeframes, Error> {
+        let output_size = self.output_size.get();
+        let (requested, sources) = api::plan_timeframes(intervals, output_size == OutputSize::Full);
+        let retrieved = requested
+            .iter()
+            .map(|interval| self.get_time_series(&interval.function(), symbol, output_size))
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(api::assemble_timeframes(
+            symbol, intervals, &sources, &retrieved,
+        ))
     }
 
     /// Retrieve the exchange rate from the currency specified by `from_currency_code` to the
@@ -88,45 +322,1362 @@ impl Client {
         from_currency_code: &str,
         to_currency_code: &str,
     ) -> Result<exchange_rate::ExchangeRate, Error> {
+        self.check_currencies(&[from_currency_code, to_currency_code])?;
         let function = "CURRENCY_EXCHANGE_RATE";
         let params = vec![
             ("from_currency", from_currency_code),
             ("to_currency", to_currency_code),
         ];
         let response = self.api_call(function, &params)?;
-        let result = exchange_rate::parser::parse(response)?;
+        let result = exchange_rate::parser::parse(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the exchange rates between every pair of `currencies`, triangulated through the
+    /// first one, e.g. `USD`, so only the rates from it to each of the others are requested.
+    ///
+    /// Currency codes are compared ignoring case, and duplicates are ignored.
+    pub fn get_rate_matrix(&self, currencies: &[&str]) -> Result<exchange_rate::RateMatrix, Error> {
+        let mut codes: Vec<String> = vec![];
+        for currency in currencies {
+            let code = currency.trim().to_ascii_uppercase();
+            if !codes.contains(&code) {
+                codes.push(code);
+            }
+        }
+        let (pivot, others) = codes
+            .split_first()
+            .ok_or_else(|| Error::InvalidParams("no currencies".into()))?;
+        let rates = others
+            .iter()
+            .map(|to| self.get_exchange_rate(pivot, to))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(exchange_rate::RateMatrix::triangulate(pivot, &rates))
+    }
+
+    /// Retrieve daily time series for the exchange rate from the currency specified by
+    /// `from_currency_code` to the currency specified by `to_currency_code`.
+    ///
+    /// Entries are retrieved at the output size of the client, a
+    /// [`ForexDailyRequest`](crate::request::ForexDailyRequest) sets it for a single request.
+    pub fn get_forex_daily(
+        &self,
+        from_currency_code: &str,
+        to_currency_code: &str,
+    ) -> Result<forex::ForexSeries, Error> {
+        self.get_forex(from_currency_code, to_currency_code, self.output_size.get())
+    }
+
+    /// Retrieve intraday time series for the exchange rate from the currency specified by
+    /// `from_currency_code` to the currency specified by `to_currency_code`, at the given
+    /// `interval`.
+    pub fn get_forex_intraday(
+        &self,
+        from_currency_code: &str,
+        to_currency_code: &str,
+        interval: time_series::IntradayInterval,
+    ) -> Result<forex::ForexSeries, Error> {
+        let function = "FX_INTRADAY";
+        let output_size = self.output_size.get().to_string();
+        let params = vec![
+            ("from_symbol", from_currency_code),
+            ("to_symbol", to_currency_code),
+            ("interval", interval.to_string()),
+            ("outputsize", output_size.as_str()),
+        ];
+        let response = self.api_call(function, &params)?;
+        let result = forex::parser::parse_intraday(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve weekly time series for the exchange rate from the currency specified by
+    /// `from_currency_code` to the currency specified by `to_currency_code`, covering the full
+    /// history of the pair.
+    pub fn get_forex_weekly(
+        &self,
+        from_currency_code: &str,
+        to_currency_code: &str,
+    ) -> Result<forex::ForexSeries, Error> {
+        let function = "FX_WEEKLY";
+        let params = vec![
+            ("from_symbol", from_currency_code),
+            ("to_symbol", to_currency_code),
+        ];
+        let response = self.api_call(function, &params)?;
+        let result = forex::parser::parse_weekly(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve monthly time series for the exchange rate from the currency specified by
+    /// `from_currency_code` to the currency specified by `to_currency_code`, covering the full
+    /// history of the pair.
+    pub fn get_forex_monthly(
+        &self,
+        from_currency_code: &str,
+        to_currency_code: &str,
+    ) -> Result<forex::ForexSeries, Error> {
+        let function = "FX_MONTHLY";
+        let params = vec![
+            ("from_symbol", from_currency_code),
+            ("to_symbol", to_currency_code),
+        ];
+        let response = self.api_call(function, &params)?;
+        let result = forex::parser::parse_monthly(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the exchange rate from the currency specified by `from_currency_code` to the
+    /// currency specified by `to_currency_code` at the close of `date`, or of the most recent
+    /// date before it.
+    ///
+    /// The exchange rate is looked up in the full daily time series of the currency pair.
+    pub fn get_exchange_rate_on(
+        &self,
+        from_currency_code: &str,
+        to_currency_code: &str,
+        date: NaiveDate,
+    ) -> Result<exchange_rate::ExchangeRate, Error> {
+        let forex_series =
+            self.get_forex(from_currency_code, to_currency_code, OutputSize::Full)?;
+        forex_series
+            .rate_on(date)
+            .ok_or_else(|| Error::MissingData(format!("no exchange rate on or before {}", date)))
+    }
+
+    fn get_forex(
+        &self,
+        from_currency_code: &str,
+        to_currency_code: &str,
+        output_size: OutputSize,
+    ) -> Result<forex::ForexSeries, Error> {
+        let function = "FX_DAILY";
+        let output_size = output_size.to_string();
+        let params = vec![
+            ("from_symbol", from_currency_code),
+            ("to_symbol", to_currency_code),
+            ("outputsize", output_size.as_str()),
+        ];
+        let response = self.api_call(function, &params)?;
+        let result = forex::parser::parse(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the daily total return for the specified `symbol`, quoted in the currency
+    /// specified by `from_currency_code`, for an investor in the currency specified by
+    /// `to_currency_code`.
+    pub fn get_total_return_in_currency(
+        &self,
+        symbol: &str,
+        from_currency_code: &str,
+        to_currency_code: &str,
+    ) -> Result<returns::ReturnSeries, Error> {
+        let time_series = self.get_time_series_daily_adjusted(symbol)?;
+        let forex_series = self.get_forex_daily(from_currency_code, to_currency_code)?;
+        Ok(returns::ReturnSeries::total_return_in_currency(
+            &time_series,
+            &forex_series,
+        ))
+    }
+
+    /// Retrieve the latest price and volume information for the specified `symbol`.
+    pub fn get_quote(&self, symbol: &str) -> Result<quote::Quote, Error> {
+        let function = "GLOBAL_QUOTE";
+        let params = vec![("symbol", symbol)];
+        let response = self.api_call(function, &params)?;
+        let result = quote::parser::parse(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the latest price for the specified `symbol`, including pre-market and
+    /// after-hours trading, combining its quote with its latest intraday entries.
+    ///
+    /// See [`ExtendedHoursQuote::new`](quote::ExtendedHoursQuote::new) for how the price is
+    /// chosen.
+    pub fn get_extended_hours_quote(
+        &self,
+        symbol: &str,
+    ) -> Result<quote::ExtendedHoursQuote, Error> {
+        let request = IntradayRequest::builder(time_series::IntradayInterval::OneMinute)
+            .symbol(symbol)
+            .extended_hours(true)
+            .build();
+        let quote = self.get_quote(symbol)?;
+        let time_series = self.send(request)?;
+        Ok(quote::ExtendedHoursQuote::new(&quote, &time_series))
+    }
+
+    /// Retrieve the latest price and volume information for each of `symbols` with individual
+    /// `GLOBAL_QUOTE` requests, e.g. on the free tier which can't call `REALTIME_BULK_QUOTES`.
+    ///
+    /// Requests are issued one at a time, each waiting for the rate limit of the client, so
+    /// they're spread across its budget. With a `max_age`, quotes which can't be requested right
+    /// away are served from the cache instead, past their time to live, if they were retrieved
+    /// less than `max_age` ago. The freshness of each quote is reported by its [`ApiResponse`].
+    pub fn get_quotes(
+        &self,
+        symbols: &[&str],
+        max_age: Option<Duration>,
+    ) -> Result<Vec<ApiResponse<quote::Quote>>, Error> {
+        let mut quotes = Vec::with_capacity(symbols.len());
+        for symbol in symbols {
+            let client = match max_age {
+                Some(max_age) if self.serves_cached_quote(symbol, max_age) => Client {
+                    offline: true,
+                    ..self.clone()
+                },
+                _ => self.clone(),
+            };
+            quotes.push(client.with_metadata(|client| client.get_quote(symbol))?);
+        }
+        Ok(quotes)
+    }
+
+    /// Whether the quote of `symbol` should be served from the cache, as it was cached less than
+    /// `max_age` ago and requesting it would wait for the rate limit.
+    fn serves_cached_quote(&self, symbol: &str, max_age: Duration) -> bool {
+        let (cache, limiter) = match (&self.cache, &self.limiter) {
+            (Some(cache), Some(limiter)) => (cache, limiter),
+            _ => return false,
+        };
+        if limiter.available() {
+            return false;
+        }
+        let params = [("symbol", symbol)];
+        let request = self.builder.create("GLOBAL_QUOTE", &params);
+        cache
+            .get_stale(&cache.key(&request))
+            .is_some_and(|entry| entry.fetched_at.elapsed().unwrap_or_default() <= max_age)
+    }
+
+    /// Retrieve the latest price and volume information for the specified `symbols`, quoting up
+    /// to 100 of them per `REALTIME_BULK_QUOTES` request.
+    ///
+    /// Symbols the API has no quote for are left out.
+    pub fn get_quotes_bulk(&self, symbols: &[&str]) -> Result<quote::BulkQuotes, Error> {
+        let function = "REALTIME_BULK_QUOTES";
+        let mut quotes = quote::BulkQuotes {
+            quotes: Vec::with_capacity(symbols.len()),
+            warnings: vec![],
+        };
+        for batch in symbols.chunks(quote::MAX_BULK_SYMBOLS) {
+            let batch = batch.join(",");
+            let response = self.api_call(function, &[("symbol", batch.as_str())])?;
+            let batch = quote::parser::parse_bulk(self.parser(function), response)?;
+            quotes.quotes.extend(batch.quotes);
+            quotes.warnings.extend(batch.warnings);
+        }
+        Ok(quotes)
+    }
+
+    /// Search for the symbols best matching `keywords`.
+    pub fn search_symbol(&self, keywords: &str) -> Result<search::SymbolMatches, Error> {
+        let function = "SYMBOL_SEARCH";
+        let params = vec![("keywords", keywords)];
+        let response = self.api_call(function, &params)?;
+        let result = search::parser::parse(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the company information and key financial metrics for the specified `symbol`.
+    pub fn get_company_overview(
+        &self,
+        symbol: &str,
+    ) -> Result<fundamentals::CompanyOverview, Error> {
+        let function = "OVERVIEW";
+        let params = vec![("symbol", symbol)];
+        let response = self.api_call(function, &params)?;
+        let result = fundamentals::parser::parse_company_overview(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the annual and quarterly income statements for the specified `symbol`.
+    pub fn get_income_statement(
+        &self,
+        symbol: &str,
+    ) -> Result<fundamentals::IncomeStatement, Error> {
+        let function = "INCOME_STATEMENT";
+        let params = vec![("symbol", symbol)];
+        let response = self.api_call(function, &params)?;
+        let result = fundamentals::parser::parse_income_statement(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the annual and quarterly cash flow statements for the specified `symbol`.
+    pub fn get_cash_flow(&self, symbol: &str) -> Result<fundamentals::CashFlow, Error> {
+        let function = "CASH_FLOW";
+        let params = vec![("symbol", symbol)];
+        let response = self.api_call(function, &params)?;
+        let result = fundamentals::parser::parse_cash_flow(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the intraday time series of the digital currency specified by `symbol` in the
+    /// given `market`, at the given `interval`.
+    ///
+    /// When currency validation is enabled, `market` must be a physical currency listed by the
+    /// API.
+    pub fn get_crypto_intraday(
+        &self,
+        symbol: &str,
+        market: &crypto::Market,
+        interval: time_series::IntradayInterval,
+    ) -> Result<crypto::CryptoSeries, Error> {
+        self.check_currencies(&[symbol])?;
+        self.check_market(market)?;
+        let function = "CRYPTO_INTRADAY";
+        let output_size = self.output_size.get().to_string();
+        let params = vec![
+            ("symbol", symbol),
+            ("market", market.as_str()),
+            ("interval", interval.to_string()),
+            ("outputsize", output_size.as_str()),
+        ];
+        let response = self.api_call(function, &params)?;
+        let result = crypto::parser::parse_intraday(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the daily time series of the digital currency specified by `symbol` in the given
+    /// `market`, with prices quoted in both the market and US dollars.
+    ///
+    /// When currency validation is enabled, `market` must be a physical currency listed by the
+    /// API.
+    pub fn get_crypto_daily(
+        &self,
+        symbol: &str,
+        market: &crypto::Market,
+    ) -> Result<crypto::DigitalCurrencySeries, Error> {
+        self.check_currencies(&[symbol])?;
+        self.check_market(market)?;
+        let function = "DIGITAL_CURRENCY_DAILY";
+        let params = vec![("symbol", symbol), ("market", market.as_str())];
+        let response = self.api_call(function, &params)?;
+        let result = crypto::parser::parse_daily(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the weekly time series of the digital currency specified by `symbol` in the given
+    /// `market`, like [`get_crypto_daily`](Self::get_crypto_daily).
+    pub fn get_crypto_weekly(
+        &self,
+        symbol: &str,
+        market: &crypto::Market,
+    ) -> Result<crypto::DigitalCurrencySeries, Error> {
+        self.check_currencies(&[symbol])?;
+        self.check_market(market)?;
+        let function = "DIGITAL_CURRENCY_WEEKLY";
+        let params = vec![("symbol", symbol), ("market", market.as_str())];
+        let response = self.api_call(function, &params)?;
+        let result = crypto::parser::parse_weekly(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the monthly time series of the digital currency specified by `symbol` in the given
+    /// `market`, like [`get_crypto_daily`](Self::get_crypto_daily).
+    pub fn get_crypto_monthly(
+        &self,
+        symbol: &str,
+        market: &crypto::Market,
+    ) -> Result<crypto::DigitalCurrencySeries, Error> {
+        self.check_currencies(&[symbol])?;
+        self.check_market(market)?;
+        let function = "DIGITAL_CURRENCY_MONTHLY";
+        let params = vec![("symbol", symbol), ("market", market.as_str())];
+        let response = self.api_call(function, &params)?;
+        let result = crypto::parser::parse_monthly(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the profile and holdings of the ETF with the specified `symbol`.
+    pub fn get_etf_profile(&self, symbol: &str) -> Result<etf::EtfProfile, Error> {
+        let function = "ETF_PROFILE";
+        let params = vec![("symbol", symbol)];
+        let response = self.api_call(function, &params)?;
+        let result = etf::parser::parse(self.parser(function), symbol, response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the latest price information together with key company metrics for the
+    /// specified `symbol`.
+    pub fn get_snapshot(&self, symbol: &str) -> Result<snapshot::Snapshot, Error> {
+        let quote = self.get_quote(symbol)?;
+        let overview = self.get_company_overview(symbol)?;
+        Ok(snapshot::Snapshot::new(quote, overview))
+    }
+
+    /// Retrieve the sector and industry classification of the specified `symbols`.
+    pub fn get_classification(
+        &self,
+        symbols: &[&str],
+    ) -> Result<fundamentals::Classification, Error> {
+        let overviews = symbols
+            .iter()
+            .map(|symbol| self.get_company_overview(symbol))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(fundamentals::Classification::new(&overviews))
+    }
+
+    /// Retrieve the fundamental data of the specified `symbols`, recording the errors raised for
+    /// some of them instead of failing altogether.
+    ///
+    /// The company overview of each symbol is always retrieved, while only the given
+    /// `statements` are, leaving the others `None`.
+    pub fn get_fundamentals(
+        &self,
+        symbols: &[&str],
+        statements: &[fundamentals::Statement],
+    ) -> Result<fundamentals::BulkFundamentals, Error> {
+        let mut bulk = fundamentals::BulkFundamentals::default();
+        for symbol in symbols {
+            bulk.insert(symbol, self.get_fundamentals_of(symbol, statements));
+        }
+        Ok(bulk)
+    }
+
+    fn get_fundamentals_of(
+        &self,
+        symbol: &str,
+        statements: &[fundamentals::Statement],
+    ) -> Result<fundamentals::Fundamentals, Error> {
+        let overview = self.get_company_overview(symbol)?;
+        let income_statement = if statements.contains(&fundamentals::Statement::IncomeStatement) {
+            Some(self.get_income_statement(symbol)?)
+        } else {
+            None
+        };
+        let cash_flow = if statements.contains(&fundamentals::Statement::CashFlow) {
+            Some(self.get_cash_flow(symbol)?)
+        } else {
+            None
+        };
+        Ok(fundamentals::Fundamentals {
+            overview,
+            income_statement,
+            cash_flow,
+        })
+    }
+
+    /// Retrieve the yields of US treasuries of the given `maturity`, at the given `interval`:
+    /// daily, weekly or monthly.
+    pub fn get_treasury_yield(
+        &self,
+        interval: economic::Interval,
+        maturity: economic::Maturity,
+    ) -> Result<economic::EconomicSeries, Error> {
+        let params = [
+            ("interval", interval.as_str()),
+            ("maturity", maturity.as_str()),
+        ];
+        self.get_economic_indicator("TREASURY_YIELD", &params)
+    }
+
+    /// Retrieve the federal funds rate (interest rate) of the US, at the given `interval`:
+    /// daily, weekly or monthly.
+    pub fn get_federal_funds_rate(
+        &self,
+        interval: economic::Interval,
+    ) -> Result<economic::EconomicSeries, Error> {
+        let params = [("interval", interval.as_str())];
+        self.get_economic_indicator("FEDERAL_FUNDS_RATE", &params)
+    }
+
+    /// Retrieve the real gross domestic product of the US, at the given `interval`: quarterly
+    /// or annual.
+    pub fn get_real_gdp(
+        &self,
+        interval: economic::Interval,
+    ) -> Result<economic::EconomicSeries, Error> {
+        let params = [("interval", interval.as_str())];
+        self.get_economic_indicator("REAL_GDP", &params)
+    }
+
+    /// Retrieve the consumer price index of the US, at the given `interval`: monthly or
+    /// semiannual.
+    pub fn get_cpi(&self, interval: economic::Interval) -> Result<economic::EconomicSeries, Error> {
+        let params = [("interval", interval.as_str())];
+        self.get_economic_indicator("CPI", &params)
+    }
+
+    /// Retrieve the prices of West Texas Intermediate crude oil, at the given `interval`.
+    pub fn get_wti(
+        &self,
+        interval: commodities::Interval,
+    ) -> Result<commodities::CommoditySeries, Error> {
+        self.get_commodity(commodities::Commodity::Wti, interval)
+    }
+
+    /// Retrieve the prices of Brent crude oil, at the given `interval`.
+    pub fn get_brent(
+        &self,
+        interval: commodities::Interval,
+    ) -> Result<commodities::CommoditySeries, Error> {
+        self.get_commodity(commodities::Commodity::Brent, interval)
+    }
+
+    /// Retrieve the Henry Hub spot prices of natural gas, at the given `interval`, in the unit
+    /// reported by the API.
+    pub fn get_natural_gas(
+        &self,
+        interval: commodities::Interval,
+    ) -> Result<commodities::CommoditySeries, Error> {
+        self.get_commodity(commodities::Commodity::NaturalGas, interval)
+    }
+
+    /// Retrieve the global prices of copper, at the given `interval`: monthly, quarterly or
+    /// annual. Prices the API doesn't report yet are `None`.
+    pub fn get_copper(
+        &self,
+        interval: commodities::Interval,
+    ) -> Result<commodities::CommoditySeries, Error> {
+        self.get_commodity(commodities::Commodity::Copper, interval)
+    }
+
+    /// Retrieve the global prices of aluminum, at the given `interval`: monthly, quarterly or
+    /// annual. Prices the API doesn't report yet are `None`.
+    pub fn get_aluminum(
+        &self,
+        interval: commodities::Interval,
+    ) -> Result<commodities::CommoditySeries, Error> {
+        self.get_commodity(commodities::Commodity::Aluminum, interval)
+    }
+
+    /// Retrieve the prices of any `commodity`, e.g. wheat or coffee, at the given `interval`,
+    /// which must be one of its [`intervals`](commodities::Commodity::intervals).
+    pub fn get_commodity(
+        &self,
+        commodity: commodities::Commodity,
+        interval: commodities::Interval,
+    ) -> Result<commodities::CommoditySeries, Error> {
+        let function = commodity.function();
+        let params = [("interval", interval.as_str())];
+        let response = self.api_call(function, &params)?;
+        let result = commodities::parser::parse(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    fn get_economic_indicator(
+        &self,
+        function: &str,
+        params: &[(&str, &str)],
+    ) -> Result<economic::EconomicSeries, Error> {
+        let response = self.api_call(function, params)?;
+        let result = economic::parser::parse(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the yield curve of US treasuries on `date`, or on the latest date yields were
+    /// reported on for every maturity if `date` is `None`.
+    ///
+    /// Yields missing on `date` are taken from the latest date before it.
+    pub fn get_yield_curve(&self, date: Option<NaiveDate>) -> Result<economic::YieldCurve, Error> {
+        let series = economic::Maturity::all()
+            .iter()
+            .map(|maturity| {
+                Ok((
+                    *maturity,
+                    self.get_treasury_yield(economic::Interval::Daily, *maturity)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        economic::yield_curve(&series, date)
+            .ok_or_else(|| Error::MissingData("missing treasury yields for the yield curve".into()))
+    }
+
+    /// Retrieve the annualized realized volatility of the specified `symbol` over the trailing
+    /// `window`, computed by the API with `ANALYTICS_FIXED_WINDOW` or locally from the daily
+    /// adjusted time series depending on `mode`.
+    pub fn get_realized_volatility(
+        &self,
+        symbol: &str,
+        window: analytics::Window,
+        mode: analytics::VolatilityMode,
+    ) -> Result<analytics::Volatility, Error> {
+        let output_size = window.output_size();
+        let from_server = match mode {
+            analytics::VolatilityMode::Server => true,
+            analytics::VolatilityMode::Local => false,
+            analytics::VolatilityMode::Auto => {
+                let output_size = output_size.to_string();
+                let params = [("symbol", symbol), ("outputsize", output_size.as_str())];
+                !self.is_cached("TIME_SERIES_DAILY_ADJUSTED", &params)
+                    && self.can_issue("ANALYTICS_FIXED_WINDOW")
+            }
+        };
+        if from_server {
+            let function = "ANALYTICS_FIXED_WINDOW";
+            let params = [
+                ("SYMBOLS", symbol),
+                ("RANGE", window.range()),
+                ("INTERVAL", "DAILY"),
+                ("OHLC", "close"),
+                ("CALCULATIONS", "STDDEV(annualized=True)"),
+            ];
+            let result = self.api_call(function, &params).and_then(|response| {
+                analytics::parser::parse_volatility(self.parser(function), symbol, response)
+            });
+            match result {
+                Err(Error::APIError(_)) if mode == analytics::VolatilityMode::Auto => {}
+                result => {
+                    return result.map(|annualized| analytics::Volatility {
+                        symbol: symbol.to_string(),
+                        window,
+                        annualized,
+                        source: analytics::Source::Server,
+                    })
+                }
+            }
+        }
+        let time_series =
+            self.get_time_series(&time_series::Function::DailyAdjusted, symbol, output_size)?;
+        let annualized = analytics::realized_volatility(&time_series, window)
+            .ok_or_else(|| Error::MissingData("not enough entries to compute volatility".into()))?;
+        Ok(analytics::Volatility {
+            symbol: symbol.to_string(),
+            window,
+            annualized,
+            source: analytics::Source::Local,
+        })
+    }
+
+    /// Retrieve analytics over the returns of a set of symbols, like their mean, standard
+    /// deviation or correlation, computed by the API with `ANALYTICS_FIXED_WINDOW`.
+    pub fn get_analytics_fixed_window(
+        &self,
+        request: FixedWindowRequest,
+    ) -> Result<analytics::FixedWindowAnalytics, Error> {
+        self.send(request)
+    }
+
+    /// Retrieve analytics over windows sliding across the returns of a set of symbols, like
+    /// their rolling variance or correlation, computed by the API with
+    /// `ANALYTICS_SLIDING_WINDOW`.
+    pub fn get_analytics_sliding_window(
+        &self,
+        request: SlidingWindowRequest,
+    ) -> Result<analytics::SlidingWindowAnalytics, Error> {
+        self.send(request)
+    }
+
+    /// Retrieve the history of `asset` over the dates of `range`, e.g. `start..=end` or a
+    /// [`DateRange`](asset::DateRange), at the given `granularity`, from the family of functions
+    /// of its type: the close prices of equities and ETFs and of digital currencies, the close
+    /// rates of currency pairs, and the values of commodities and economic indicators.
+    ///
+    /// Intraday histories of equities and ETFs are backfilled one month at a time, i.e. with a
+    /// request per month, so they require `range` to have a start and are planned before issuing
+    /// any request, failing with [`Error::QuotaExceeded`] if the months exceed the remaining
+    /// daily quota of the client. Coarser
+    /// granularities than the ones retrieved are derived from the last value of each period,
+    /// while values are never finer than the ones an asset is reported at, e.g. monthly for the
+    /// consumer price index. Intraday histories of digital currencies are retrieved with
+    /// `CRYPTO_INTRADAY` and of currency pairs with `FX_INTRADAY`, which only cover their latest
+    /// days, while daily, weekly and monthly histories of digital currencies are retrieved with
+    /// `DIGITAL_CURRENCY_DAILY`, `DIGITAL_CURRENCY_WEEKLY` and `DIGITAL_CURRENCY_MONTHLY`, and
+    /// weekly and monthly histories of currency pairs with `FX_WEEKLY` and `FX_MONTHLY`.
+    ///
+    /// Intraday histories of commodities and economic indicators fail with
+    /// [`Error::InvalidParams`] without issuing any request.
+    pub fn get_history(
+        &self,
+        asset: &asset::Asset,
+        range: impl Into<asset::DateRange>,
+        granularity: asset::Granularity,
+    ) -> Result<asset::History, Error> {
+        let range = range.into();
+        let today = Utc::now().date_naive();
+        let output_size = api::history_output_size(range.start, today);
+        let intraday = match granularity {
+            time_series::Interval::Intraday(interval) => Some(interval),
+            _ => None,
+        };
+        let values: Vec<(NaiveDateTime, f64)> = match (asset, intraday) {
+            (asset::Asset::Equity(symbol) | asset::Asset::Etf(symbol), Some(interval)) => {
+                if range.start.is_none() {
+                    return Err(Error::InvalidParams(
+                        "intraday history requires a start date".to_string(),
+                    ));
+                }
+                let requests: Vec<IntradayRequest> = range
+                    .months(api::first_intraday_date(), today)
+                    .iter()
+                    .map(|month| {
+                        IntradayRequest::builder(interval)
+                            .symbol(symbol)
+                            .output_size(OutputSize::Full)
+                            .month(month)
+                            .build()
+                    })
+                    .collect();
+                let jobs: Vec<planner::Job> =
+                    requests.iter().map(planner::Job::from_request).collect();
+                self.plan(&jobs)?;
+                let mut values = vec![];
+                for request in requests {
+                    let time_series = self.send(request)?;
+                    values.extend(
+                        time_series
+                            .entries
+                            .iter()
+                            .map(|e| (e.date.naive_local(), e.close)),
+                    );
+                }
+                values
+            }
+            (asset::Asset::Equity(symbol) | asset::Asset::Etf(symbol), None) => self
+                .get_time_series(&granularity.function(), symbol, output_size)?
+                .entries
+                .iter()
+                .map(|e| (e.date.naive_local(), e.close))
+                .collect(),
+            (asset::Asset::Crypto { symbol, market }, Some(interval)) => self
+                .get_crypto_intraday(symbol, market, interval)?
+                .entries
+                .iter()
+                .map(|e| (e.date.naive_local(), e.close))
+                .collect(),
+            (asset::Asset::Crypto { symbol, market }, None) => {
+                let series = match granularity {
+                    time_series::Interval::Weekly => self.get_crypto_weekly(symbol, market)?,
+                    time_series::Interval::Monthly => self.get_crypto_monthly(symbol, market)?,
+                    _ => self.get_crypto_daily(symbol, market)?,
+                };
+                series
+                    .entries
+                    .iter()
+                    .map(|e| (e.market.date.naive_local(), e.market.close))
+                    .collect()
+            }
+            (asset::Asset::ForexPair(pair), Some(interval)) => self
+                .get_forex_intraday(&pair.from, &pair.to, interval)?
+                .entries
+                .iter()
+                .map(|e| (e.date.naive_local(), e.close))
+                .collect(),
+            (_, Some(_)) => {
+                return Err(Error::InvalidParams(format!(
+                    "intraday history isn't available for {:?}",
+                    asset.asset_type()
+                )))
+            }
+            (asset::Asset::ForexPair(pair), None) => {
+                let forex_series = match granularity {
+                    time_series::Interval::Weekly => self.get_forex_weekly(&pair.from, &pair.to)?,
+                    time_series::Interval::Monthly => {
+                        self.get_forex_monthly(&pair.from, &pair.to)?
+                    }
+                    _ => self.get_forex(&pair.from, &pair.to, output_size)?,
+                };
+                forex_series
+                    .entries
+                    .iter()
+                    .map(|e| (e.date.naive_local(), e.close))
+                    .collect()
+            }
+            (asset::Asset::Commodity(commodity), None) => {
+                // Dates the API doesn't report a price for are left out of the history.
+                self.get_commodity(*commodity, commodity.finest_interval())?
+                    .data
+                    .iter()
+                    .filter_map(|p| Some((p.date.into(), p.value?)))
+                    .collect()
+            }
+            (asset::Asset::EconomicSeries(indicator), None) => {
+                let mut params = vec![("interval", indicator.finest_interval().as_str())];
+                if let economic::Indicator::TreasuryYield(maturity) = indicator {
+                    params.push(("maturity", maturity.as_str()));
+                }
+                self.get_economic_indicator(indicator.function(), &params)?
+                    .data
+                    .iter()
+                    .map(|p| (p.date.into(), p.value))
+                    .collect()
+            }
+        };
+        Ok(asset::History::new(
+            asset.clone(),
+            granularity,
+            values,
+            &range,
+        ))
+    }
+
+    /// Retrieve the top gainers, top losers and most actively traded tickers in the US market.
+    pub fn get_top_gainers_losers(&self) -> Result<market_movers::MarketMovers, Error> {
+        let function = "TOP_GAINERS_LOSERS";
+        let response = self.api_call(function, &[])?;
+        let result = market_movers::parser::parse(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the latest news articles about the specified `tickers`, along with their
+    /// sentiment.
+    pub fn get_news_sentiment(&self, tickers: &[&str]) -> Result<news::NewsFeed, Error> {
+        let function = "NEWS_SENTIMENT";
+        let tickers = tickers.join(",");
+        let params = vec![("tickers", tickers.as_str())];
+        let response = self.api_call(function, &params)?;
+        let result = news::parser::parse(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the market movers matching the filters of `screener`.
+    ///
+    /// Tickers without company overview data are returned without one.
+    pub fn screen_market_movers(
+        &self,
+        screener: &market_movers::Screener,
+    ) -> Result<Vec<market_movers::ScreenedMover>, Error> {
+        let movers = self.get_top_gainers_losers()?;
+        let mut screened = screener.screen(&movers);
+        if screener.wants_overview() {
+            for mover in screened.iter_mut() {
+                mover.overview = match self.get_company_overview(&mover.mover.ticker) {
+                    Ok(overview) => Some(overview),
+                    Err(Error::ParsingError(_)) => None,
+                    Err(e) => return Err(e),
+                };
+            }
+        }
+        Ok(screened)
+    }
+
+    /// Retrieve the dividend history for the specified `symbol`.
+    pub fn get_dividends(&self, symbol: &str) -> Result<corporate_actions::Dividends, Error> {
+        let function = "DIVIDENDS";
+        let params = vec![("symbol", symbol)];
+        let response = self.api_call(function, &params)?;
+        let result = corporate_actions::parser::parse_dividends(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the split history for the specified `symbol`.
+    pub fn get_splits(&self, symbol: &str) -> Result<corporate_actions::Splits, Error> {
+        let function = "SPLITS";
+        let params = vec![("symbol", symbol)];
+        let response = self.api_call(function, &params)?;
+        let result = corporate_actions::parser::parse_splits(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the annual and quarterly earnings history for the specified `symbol`.
+    pub fn get_earnings(&self, symbol: &str) -> Result<earnings::Earnings, Error> {
+        let function = "EARNINGS";
+        let params = vec![("symbol", symbol)];
+        let response = self.api_call(function, &params)?;
+        let result = earnings::parser::parse(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the analyst estimates of the earnings per share and revenue of the specified
+    /// `symbol` for the upcoming fiscal quarters and years.
+    pub fn get_earnings_estimates(
+        &self,
+        symbol: &str,
+    ) -> Result<earnings::EarningsEstimates, Error> {
+        let function = "EARNINGS_ESTIMATES";
+        let params = vec![("symbol", symbol)];
+        let response = self.api_call(function, &params)?;
+        let result = earnings::parser::parse_estimates(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the earnings expected to be reported within `horizon`, by the specified `symbol`
+    /// or by every company if `symbol` is `None`.
+    pub fn get_earnings_calendar(
+        &self,
+        symbol: Option<&str>,
+        horizon: earnings::Horizon,
+    ) -> Result<earnings::EarningsCalendar, Error> {
+        let function = "EARNINGS_CALENDAR";
+        let mut params = vec![("horizon", horizon.as_str())];
+        if let Some(symbol) = symbol {
+            params.push(("symbol", symbol));
+        }
+        let response = self.api_call(function, &params)?;
+        let result = earnings::parser::parse_calendar(self.parser(function), horizon, response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the initial public offerings expected in the coming three months.
+    pub fn get_ipo_calendar(&self) -> Result<listing::IpoCalendar, Error> {
+        let function = "IPO_CALENDAR";
+        let response = self.api_call(function, &[])?;
+        let result = listing::parser::parse_ipo_calendar(self.parser(function), response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the trailing twelve month dividend yield for the specified `symbol`, computed
+    /// from its dividend history and daily time series.
+    pub fn get_dividend_yield(
+        &self,
+        symbol: &str,
+    ) -> Result<corporate_actions::DividendYieldSeries, Error> {
+        let dividends = self.get_dividends(symbol)?;
+        let time_series = self.get_time_series_daily(symbol)?;
+        Ok(dividends.trailing_yield(&time_series))
+    }
+
+    /// Retrieve the symbols in the given listing `state` on `date`, or on the latest trading day
+    /// if `date` is `None`.
+    pub fn get_listing_status(
+        &self,
+        date: Option<NaiveDate>,
+        state: listing::ListingState,
+    ) -> Result<listing::ListingStatus, Error> {
+        let function = "LISTING_STATUS";
+        let date_param = date.map(|d| d.format(DATE_FORMAT).to_string());
+        let mut params = vec![("state", state.as_str())];
+        if let Some(date_param) = &date_param {
+            params.push(("date", date_param.as_str()));
+        }
+        let response = self.api_call(function, &params)?;
+        let result = listing::parser::parse(self.parser(function), date, state, response)?;
+        Ok(result)
+    }
+
+    /// Retrieve the latest snapshots of active and delisted symbols, combined into a
+    /// [`Universe`](listing::Universe) telling which symbols were listed on any past date.
+    pub fn get_universe(&self) -> Result<listing::Universe, Error> {
+        let active = self.get_listing_status(None, listing::ListingState::Active)?;
+        let delisted = self.get_listing_status(None, listing::ListingState::Delisted)?;
+        Ok(listing::Universe::new(&[active, delisted]))
+    }
+
+    /// Retrieve the digital currencies supported by the API.
+    pub fn get_digital_currency_list(&self) -> Result<currencies::CurrencyList, Error> {
+        self.get_currency_list("DIGITAL_CURRENCY_LIST")
+    }
+
+    /// Retrieve the physical currencies supported by the API.
+    pub fn get_physical_currency_list(&self) -> Result<currencies::CurrencyList, Error> {
+        self.get_currency_list("PHYSICAL_CURRENCY_LIST")
+    }
+
+    /// Load a response to `function` with `params`, previously saved from the API to the JSON or
+    /// CSV file at `path`, into the cache.
+    ///
+    /// The response is validated by parsing it as if it was retrieved from the API, and stored
+    /// as retrieved at the modification time of the file. Parameters can be given in any order
+    /// but must otherwise match those of the request, e.g. including `outputsize` for time
+    /// series.
+    ///
+    /// Responses to functions the API deprecated are stored as responses to their replacement.
+    /// Fails with [`Error::Configuration`] if the client has no cache to load the response into.
+    pub fn ingest_file(
+        &self,
+        function: &str,
+        params: &[(&str, &str)],
+        path: &Path,
+    ) -> Result<(), Error> {
+        let cache = self
+            .cache
+            .as_ref()
+            .ok_or_else(|| Error::Configuration("ingesting files requires a cache".to_string()))?;
+        let job = self.migrate(&planner::Job::new(function, params))?;
+        let (function, params) = (job.function.as_str(), &job.params());
+        let body = std::fs::read(path)?;
+        let fetched_at = std::fs::metadata(path)?.modified()?;
+        api::validate(self.parser(function), function, params, &body)?;
+        let cache_key = cache.key(&self.builder.create(function, params));
+        cache.insert_entry(&cache_key, CacheEntry { body, fetched_at });
+        Ok(())
+    }
+
+    fn get_currency_list(&self, function: &str) -> Result<currencies::CurrencyList, Error> {
+        let response = self.api_call(function, &[])?;
+        let result = currencies::parser::parse(self.parser(function), response)?;
         Ok(result)
     }
 
+    /// Fail with [`Error::UnknownCurrency`] if currency validation is enabled and any of
+    /// `codes` isn't listed by the API, retrieving the currency lists the first time.
+    fn check_currencies(&self, codes: &[&str]) -> Result<(), Error> {
+        if !self.validate_currencies {
+            return Ok(());
+        }
+        let known = self.load_known_currencies()?;
+        codes.iter().try_for_each(|code| known.check(code))
+    }
+
+    /// Fail with [`Error::UnknownCurrency`] if currency validation is enabled and `market`
+    /// isn't a physical currency listed by the API.
+    fn check_market(&self, market: &crypto::Market) -> Result<(), Error> {
+        if !self.validate_currencies {
+            return Ok(());
+        }
+        let known = self.load_known_currencies()?;
+        known.check_physical(market.as_str())
+    }
+
+    fn load_known_currencies(&self) -> Result<Arc<KnownCurrencies>, Error> {
+        let known = self.known_currencies.lock().unwrap().clone();
+        match known {
+            Some(known) => Ok(known),
+            None => {
+                let digital = self.get_digital_currency_list()?;
+                let physical = self.get_physical_currency_list()?;
+                let known = Arc::new(KnownCurrencies::new(digital, physical));
+                *self.known_currencies.lock().unwrap() = Some(known.clone());
+                Ok(known)
+            }
+        }
+    }
+
     fn get_time_series(
         &self,
         function: &time_series::Function,
         symbol: &str,
+        output_size: OutputSize,
     ) -> Result<time_series::TimeSeries, Error> {
-        let output_size = self.output_size.get().to_string();
+        let output_size = output_size.to_string();
         let mut params = vec![("symbol", symbol), ("outputsize", output_size.as_str())];
         if let time_series::Function::IntraDay(interval) = function {
             params.push(("interval", interval.to_string()));
         }
         let response = self.api_call(function.into(), &params)?;
-        let result = time_series::parser::parse(function, response)?;
+        let result = time_series::parser::parse(self.parser(function.into()), function, response)?;
         Ok(result)
     }
 
+    fn parser(&self, function: &str) -> Parser {
+        let mut parser = Parser::new(self.parse_mode);
+        if let Some(max_age) = self.stale_after {
+            parser = parser.stale_after(max_age);
+        }
+        match &self.observer {
+            Some(observer) => {
+                let (function, observer) = (function.to_string(), observer.clone());
+                parser.on_warnings(move |warnings| observer.on_parse_warnings(&function, warnings))
+            }
+            None => parser,
+        }
+    }
+
+    /// Whether a response to `function` with `params` is cached, so it can be served without
+    /// issuing a request.
+    fn is_cached(&self, function: &str, params: &[(&str, &str)]) -> bool {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return false,
+        };
+        let key = cache.key(&self.builder.create(function, params));
+        if self.offline || self.refreshes.is_some() {
+            cache.get_stale(&key).is_some()
+        } else {
+            cache.get(&key).is_some()
+        }
+    }
+
     fn api_call(&self, function: &str, params: &[(&str, &str)]) -> Result<impl Read, Error> {
         let request = self.builder.create(function, params);
-        let response = self.client.execute(request.into())?;
+        request.check_params()?;
+        let trail = Trail::new();
+        let cache_key = match &self.cache {
+            Some(cache) => cache.key(&request),
+            None => request.cache_key(),
+        };
+        let cached = match &self.cache {
+            Some(cache) if self.offline || self.refreshes.is_some() => {
+                cache.get_stale(&cache_key).map(|entry| {
+                    let stale = !cache.is_fresh(&entry);
+                    (entry, stale)
+                })
+            }
+            Some(cache) => cache.get(&cache_key).map(|entry| (entry, false)),
+            None => None,
+        };
+        if let Some((entry, stale)) = cached {
+            self.stats.cache_hit(function);
+            if let Some(observer) = &self.observer {
+                observer.on_cache_hit(function);
+            }
+            if let Some(capture) = &self.capture {
+                if stale {
+                    capture.record_stale(function, entry.fetched_at, entry.body.len());
+                } else {
+                    capture.record(function, entry.fetched_at, true, entry.body.len());
+                }
+            }
+            let disposition = if stale {
+                Disposition::Stale
+            } else {
+                Disposition::Cached
+            };
+            self.audit(trail, function, disposition, entry.body.len(), None);
+            if stale && !self.offline {
+                self.refresh(&request, cache_key);
+            }
+            return Ok(Cursor::new(entry.body));
+        }
+        if self.offline {
+            let error = Error::Offline(cache_key);
+            self.stats.response(function, Err(&error));
+            self.audit(trail, function, Disposition::Offline, 0, Some(&error));
+            return Err(error);
+        }
+        let body = self.fetch(request, &cache_key, trail)?;
+        if let Some(capture) = &self.capture {
+            capture.record(function, SystemTime::now(), false, body.len());
+        }
+        Ok(Cursor::new(body))
+    }
+
+    /// Refresh the stale entry cached for `request` in the background, unless it's already
+    /// being refreshed.
+    fn refresh(&self, request: &APIRequest, cache_key: String) {
+        let refreshes = match &self.refreshes {
+            Some(refreshes) if refreshes.start(&cache_key) => refreshes.clone(),
+            _ => return,
+        };
+        let client = Client {
+            capture: None,
+            ..self.clone()
+        };
+        let function = request.function().to_string();
+        let params: Vec<(String, String)> = request
+            .params()
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        std::thread::spawn(move || {
+            let params: Vec<(&str, &str)> = params
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str()))
+                .collect();
+            let request = client.builder.create(&function, &params);
+            // Failures are reported to the observer and statistics, keeping the stale entry.
+            let _ = client.fetch(request, &cache_key, Trail::new());
+            refreshes.finish(&cache_key);
+        });
+    }
+
+    /// Issue `request` to the API, subject to the rate limit, caching its response under
+    /// `cache_key` and recording the call tracked by `trail` in the audit log.
+    fn fetch(
+        &self,
+        request: APIRequest,
+        cache_key: &str,
+        mut trail: Trail,
+    ) -> Result<Vec<u8>, Error> {
+        let function = request.function();
+        request.check_key()?;
+        self.check_tier(function)?;
+        let mut started;
+        let mut retries = self.throttle_retries;
+        let result = loop {
+            self.acquire(function);
+            started = Instant::now();
+            trail.restart();
+            match self.execute(&request, &mut trail) {
+                Err(Error::Throttled { retry_after, .. }) if retries > 0 => {
+                    retries -= 1;
+                    self.wait(function, retry_after, retries);
+                }
+                result => break result,
+            }
+        };
+        let result = self.verify_key(result);
+        if let Some(observer) = &self.observer {
+            observer.on_response(function, started.elapsed(), result.as_ref().err());
+        }
+        self.stats
+            .response(function, result.as_ref().map(|body| body.as_slice()));
+        let size = result.as_ref().map_or(0, |body| body.len());
+        self.audit(
+            trail,
+            function,
+            Disposition::Fetched,
+            size,
+            result.as_ref().err(),
+        );
+        let body = result?;
+        if let Some(cache) = &self.cache {
+            cache.insert(cache_key, body.clone());
+        }
+        Ok(body)
+    }
+
+    /// Wait for a slot of the rate limit, if any, to issue a request for `function`.
+    fn acquire(&self, function: &str) {
+        let limiter = match &self.limiter {
+            Some(limiter) => limiter,
+            None => return,
+        };
+        let ticket = limiter.ticket(self.priority);
+        let mut waited = Duration::from_secs(0);
+        let used = loop {
+            match ticket.try_acquire() {
+                Ok(used) => break used,
+                Err(wait) => {
+                    self.backoff(function, wait, Backoff::RateLimit);
+                    std::thread::sleep(wait);
+                    waited += wait;
+                }
+            }
+        };
+        if waited > Duration::from_secs(0) {
+            self.record_wait(function, waited);
+        }
+        let limit = limiter.limit();
+        if used == limit.warning_threshold() {
+            if let Some(observer) = &self.observer {
+                observer.on_quota_warning(function, used, limit);
+            }
+        }
+    }
+
+    /// Wait for `duration` before retrying a throttled request for `function`, with `retries`
+    /// left afterwards.
+    fn wait(&self, function: &str, duration: Duration, retries: u32) {
+        let reason = Backoff::Throttled {
+            retries_left: retries,
+        };
+        self.backoff(function, duration, reason);
+        std::thread::sleep(duration);
+        self.record_wait(function, duration);
+    }
+
+    fn backoff(&self, function: &str, wait: Duration, reason: Backoff) {
+        if let Some(observer) = &self.observer {
+            observer.on_backoff(function, wait, reason);
+        }
+    }
+
+    fn record_wait(&self, function: &str, waited: Duration) {
+        self.stats.rate_limit_wait(waited);
+        if let Some(observer) = &self.observer {
+            observer.on_rate_limit_wait(function, waited);
+        }
+    }
+
+    /// Replace `job` by a job for the replacement of its function if the API deprecated it,
+    /// notifying the observer.
+    fn migrate(&self, job: &planner::Job) -> Result<planner::Job, Error> {
+        match job.migrate()? {
+            Some((replacement, deprecation)) => {
+                if let Some(observer) = &self.observer {
+                    observer.on_deprecated(&job.function, deprecation);
+                }
+                Ok(replacement)
+            }
+            None => Ok(job.clone()),
+        }
+    }
+
+    /// Fail with [`Error::PremiumRequired`] if the client is configured with a tier which can't
+    /// call `function`.
+    fn check_tier(&self, function: &str) -> Result<(), Error> {
+        match self.tier {
+            Some(tier) if !tier.can_access(&Function::from(function)) => {
+                Err(Error::PremiumRequired(function.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether a request for `function` could be issued right away, within the tier and the
+    /// remaining quota of the rate limit of the client.
+    fn can_issue(&self, function: &str) -> bool {
+        self.check_tier(function).is_ok()
+            && self
+                .limiter
+                .as_ref()
+                .is_none_or(|limiter| limiter.available())
+    }
+
+    /// Record the call to `function` tracked by `trail` in the audit log, if any.
+    fn audit(
+        &self,
+        trail: Trail,
+        function: &str,
+        disposition: Disposition,
+        size: usize,
+        error: Option<&Error>,
+    ) {
+        if let Some(audit_log) = &self.audit_log {
+            audit_log.record(&trail.finish(function, disposition, size, error));
+        }
+    }
+
+    /// Classify errors returned until a response is successfully received as
+    /// [`Error::InvalidApiKey`] when they report an invalid key, if configured to.
+    fn verify_key(&self, result: Result<Vec<u8>, Error>) -> Result<Vec<u8>, Error> {
+        if !self.verify_key || self.key_verified.load(Ordering::Relaxed) {
+            return result;
+        }
+        match result {
+            Ok(body) => {
+                self.key_verified.store(true, Ordering::Relaxed);
+                Ok(body)
+            }
+            Err(error) => Err(api::classify_key_error(error)),
+        }
+    }
+
+    /// Issue `request` to each of the configured endpoints in turn, until one of them can be
+    /// connected to, tracking the attempts in `trail`.
+    fn execute(&self, request: &APIRequest, trail: &mut Trail) -> Result<Vec<u8>, Error> {
+        let mut result = Err(Error::ConnectionError("no endpoint configured".into()));
+        for endpoint in request.urls() {
+            trail.url = Some(request.redacted_url(endpoint).to_string());
+            trail.status = None;
+            trail.attempts += 1;
+            result = self.execute_at(request, endpoint, trail);
+            if let Err(Error::ConnectionError(_)) = result {
+                continue;
+            }
+            break;
+        }
+        result
+    }
+
+    fn execute_at(
+        &self,
+        request: &APIRequest,
+        endpoint: &Url,
+        trail: &mut Trail,
+    ) -> Result<Vec<u8>, Error> {
+        let response = self
+            .client
+            .execute(request.to_blocking_request(endpoint))
+            .map_err(api::connection_error)?;
         let status = response.status();
+        trail.status = Some(status.as_u16());
         if status != reqwest::StatusCode::OK {
             return Err(Error::ServerError(status.as_u16()));
         }
-        Ok(response)
+        let body = response.bytes().map_err(api::connection_error)?;
+        api::check_response(&body)?;
+        Ok(body.to_vec())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::OutputSize;
+    use super::{Client, OutputSize};
+    use crate::analytics::{Source, VolatilityMode, Window};
+    use crate::asset::{Asset, DateRange, HistoryPoint};
+    use crate::audit::{AuditRecord, Disposition};
+    use crate::cache::{Cache, CacheEntry, MemoryCache};
+    use crate::commodities::{self, Commodity};
+    use crate::crypto::Market;
+    use crate::economic::{Indicator, Maturity};
+    use crate::exchange_rate::CurrencyPair;
+    use crate::fundamentals::Statement;
+    use crate::observer::{Backoff, Observer};
+    use crate::rate_limit::{RateLimit, Tier};
+    use crate::time_series::{Interval, IntradayInterval};
+    use crate::Error;
+    use crate::Function;
+    use crate::{ParseWarning, ParseWarningKind};
+    use chrono::NaiveDate;
+    use reqwest::Url;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
 
     #[test]
     fn test_output_size_strings() {
@@ -136,4 +1687,708 @@ mod tests {
         assert_eq!(a.to_string(), "compact");
         assert_eq!(b.to_string(), "full");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn fallback_url() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mirror = format!("http://{}/query", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            let body = include_str!("../tests/json/global_quote.json");
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+
+        // Nothing listens on port 1, so connecting to the first endpoint fails.
+        let client = Client::builder("key")
+            .urls(&[Url::parse("http://127.0.0.1:1/query").unwrap()])
+            .fallback_url(Url::parse(&mirror).unwrap())
+            .build_blocking();
+        let quote = client.get_quote("MSFT").unwrap();
+        assert_eq!(quote.symbol, "MSFT");
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn offline() {
+        let cache = MemoryCache::new();
+        let mut entry = CacheEntry::new(include_bytes!("../tests/json/global_quote.json").to_vec());
+        entry.fetched_at -= Duration::from_secs(7 * 24 * 60 * 60);
+        let fetched_at = entry.fetched_at;
+        cache.insert("GLOBAL_QUOTE?symbol=MSFT", entry);
+
+        // Nothing listens on port 1, so any request issued to the API would fail to connect.
+        let client = Client::builder("key")
+            .urls(&[Url::parse("http://127.0.0.1:1/query").unwrap()])
+            .cache(cache, Duration::from_secs(60))
+            .offline(true)
+            .build_blocking();
+        let quote = client.get_quote("MSFT").unwrap();
+        assert_eq!(quote.symbol, "MSFT");
+        match client.get_quote("AAPL") {
+            Err(Error::Offline(key)) => assert_eq!(key, "GLOBAL_QUOTE?symbol=AAPL"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        let stats = client.stats();
+        assert_eq!(stats.functions["GLOBAL_QUOTE"].calls, 2);
+        assert_eq!(stats.cache_hit_rate(), Some(0.5));
+        assert_eq!(stats.errors["offline"], 1);
+
+        let response = client
+            .with_metadata(|client| client.get_quote("MSFT"))
+            .unwrap();
+        assert_eq!(response.payload.symbol, "MSFT");
+        assert_eq!(response.function, Function::GlobalQuote);
+        assert_eq!(response.fetched_at, fetched_at);
+        assert!(response.from_cache);
+        assert!(response.stale);
+        assert_eq!(response.requests, 1);
+    }
+
+    #[test]
+    fn audit_log() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mirror = format!("http://{}/query", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            let body = include_str!("../tests/json/global_quote.json");
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+
+        let records = Arc::new(Mutex::new(vec![]));
+        let recorded = records.clone();
+        let client = Client::builder("secret")
+            .urls(&[Url::parse("http://127.0.0.1:1/query").unwrap()])
+            .fallback_url(Url::parse(&mirror).unwrap())
+            .cache(MemoryCache::new(), Duration::from_secs(60))
+            .audit_log(move |record: &AuditRecord| recorded.lock().unwrap().push(record.clone()))
+            .build_blocking();
+        client.get_quote("MSFT").unwrap();
+        client.get_quote("MSFT").unwrap();
+        server.join().unwrap();
+
+        let records = records.lock().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].function, "GLOBAL_QUOTE");
+        assert_eq!(records[0].disposition, Disposition::Fetched);
+        assert_eq!(
+            records[0].url.as_deref(),
+            Some(
+                format!(
+                    "{}?function=GLOBAL_QUOTE&apikey=REDACTED&symbol=MSFT",
+                    mirror
+                )
+                .as_str()
+            )
+        );
+        assert_eq!(records[0].status, Some(200));
+        assert_eq!(records[0].attempts, 2);
+        assert_eq!(
+            records[0].size,
+            include_bytes!("../tests/json/global_quote.json").len() as u64
+        );
+        assert_eq!(records[0].error, None);
+        assert_eq!(records[1].disposition, Disposition::Cached);
+        assert_eq!(records[1].url, None);
+        assert_eq!(records[1].attempts, 0);
+        assert_eq!(records[1].size, records[0].size);
+    }
+
+    #[test]
+    fn warnings() {
+        #[derive(Default)]
+        struct Recorder {
+            warnings: Mutex<Vec<(String, ParseWarningKind)>>,
+            quota: Mutex<Vec<(String, u32)>>,
+        }
+
+        impl Observer for Arc<Recorder> {
+            fn on_parse_warnings(&self, function: &str, warnings: &[ParseWarning]) {
+                let mut recorded = self.warnings.lock().unwrap();
+                recorded.extend(warnings.iter().map(|w| (function.to_string(), w.kind)));
+            }
+
+            fn on_quota_warning(&self, function: &str, used: u32, _limit: RateLimit) {
+                self.quota
+                    .lock()
+                    .unwrap()
+                    .push((function.to_string(), used));
+            }
+        }
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/query", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            let body = include_str!("../tests/json/time_series_daily.json");
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+
+        let recorder = Arc::new(Recorder::default());
+        let client = Client::builder("key")
+            .urls(&[Url::parse(&url).unwrap()])
+            .rate_limit(RateLimit::per_minute(1))
+            .stale_after(Duration::from_secs(7 * 24 * 60 * 60))
+            .observer(recorder.clone())
+            .build_blocking();
+        let time_series = client.get_time_series_daily("MSFT").unwrap();
+        server.join().unwrap();
+
+        assert_eq!(time_series.warnings.len(), 1);
+        assert_eq!(time_series.warnings[0].kind, ParseWarningKind::StaleData);
+        assert_eq!(
+            *recorder.warnings.lock().unwrap(),
+            vec![("TIME_SERIES_DAILY".to_string(), ParseWarningKind::StaleData)]
+        );
+        assert_eq!(
+            *recorder.quota.lock().unwrap(),
+            vec![("TIME_SERIES_DAILY".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn backoff() {
+        #[derive(Default)]
+        struct Recorder {
+            backoffs: Mutex<Vec<(String, Backoff)>>,
+            waited: Mutex<Duration>,
+        }
+
+        impl Observer for Arc<Recorder> {
+            fn on_backoff(&self, function: &str, wait: Duration, reason: Backoff) {
+                assert!(wait <= Duration::from_millis(200));
+                let mut backoffs = self.backoffs.lock().unwrap();
+                backoffs.push((function.to_string(), reason));
+            }
+
+            fn on_rate_limit_wait(&self, _function: &str, wait: Duration) {
+                *self.waited.lock().unwrap() += wait;
+            }
+        }
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/query", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request).unwrap();
+                let body = include_str!("../tests/json/global_quote.json");
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        let recorder = Arc::new(Recorder::default());
+        let client = Client::builder("key")
+            .urls(&[Url::parse(&url).unwrap()])
+            .rate_limit(RateLimit::new(1, Duration::from_millis(200)))
+            .observer(recorder.clone())
+            .build_blocking();
+        client.get_quote("MSFT").unwrap();
+        assert!(recorder.backoffs.lock().unwrap().is_empty());
+        client.get_quote("IBM").unwrap();
+        server.join().unwrap();
+
+        let backoffs = recorder.backoffs.lock().unwrap();
+        assert!(!backoffs.is_empty());
+        assert!(backoffs
+            .iter()
+            .all(|b| *b == ("GLOBAL_QUOTE".to_string(), Backoff::RateLimit)));
+        assert!(*recorder.waited.lock().unwrap() > Duration::from_millis(0));
+    }
+
+    #[test]
+    fn throttled() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/query", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            let body = r#"{"Note": "Our standard API call frequency is 5 calls per minute."}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+
+        let client = Client::builder("key")
+            .urls(&[Url::parse(&url).unwrap()])
+            .build_blocking();
+        match client.get_quote("MSFT") {
+            Err(Error::Throttled { retry_after, .. }) => {
+                assert!(retry_after <= Duration::from_secs(62))
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+        server.join().unwrap();
+        assert_eq!(client.stats().errors["throttled"], 1);
+    }
+
+    #[test]
+    fn quotes_within_rate_limit() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/query", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            let body = include_str!("../tests/json/global_quote.json");
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+
+        let cache = MemoryCache::new();
+        let mut entry = CacheEntry::new(include_bytes!("../tests/json/global_quote.json").to_vec());
+        entry.fetched_at -= Duration::from_secs(2 * 60 * 60);
+        let fetched_at = entry.fetched_at;
+        cache.insert("GLOBAL_QUOTE?symbol=MSFT", entry);
+
+        // The first quote takes the only request allowed by the rate limit, so the second one
+        // is served from the cache past its time to live.
+        let client = Client::builder("key")
+            .urls(&[Url::parse(&url).unwrap()])
+            .cache(cache, Duration::from_secs(60))
+            .rate_limit(RateLimit::per_minute(1))
+            .build_blocking();
+        let day = Duration::from_secs(24 * 60 * 60);
+        let quotes = client.get_quotes(&["AAPL", "MSFT"], Some(day)).unwrap();
+        server.join().unwrap();
+        assert_eq!(quotes.len(), 2);
+        assert!(!quotes[0].from_cache);
+        assert!(quotes[1].from_cache);
+        assert!(quotes[1].stale);
+        assert_eq!(quotes[1].fetched_at, fetched_at);
+        assert_eq!(quotes[1].payload.symbol, "MSFT");
+    }
+
+    #[test]
+    fn stale_while_revalidate() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/query", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            let body = include_str!("../tests/json/global_quote.json");
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+
+        let cache = Arc::new(MemoryCache::new());
+        let mut entry = CacheEntry::new(include_bytes!("../tests/json/global_quote.json").to_vec());
+        entry.fetched_at -= Duration::from_secs(120);
+        let fetched_at = entry.fetched_at;
+        cache.insert("GLOBAL_QUOTE?symbol=MSFT", entry);
+
+        let client = Client::builder("key")
+            .urls(&[Url::parse(&url).unwrap()])
+            .cache(cache.clone(), Duration::from_secs(60))
+            .stale_while_revalidate(true)
+            .build_blocking();
+        let response = client
+            .with_metadata(|client| client.get_quote("MSFT"))
+            .unwrap();
+        assert_eq!(response.payload.symbol, "MSFT");
+        assert_eq!(response.fetched_at, fetched_at);
+        assert!(response.from_cache);
+        assert!(response.stale);
+
+        // The stale entry is replaced once the background refresh completes.
+        server.join().unwrap();
+        let mut refreshed = false;
+        for _ in 0..100 {
+            let entry = cache.get("GLOBAL_QUOTE?symbol=MSFT").unwrap();
+            if entry.fetched_at > fetched_at {
+                refreshed = true;
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(refreshed);
+        let response = client
+            .with_metadata(|client| client.get_quote("MSFT"))
+            .unwrap();
+        assert!(response.from_cache);
+        assert!(!response.stale);
+    }
+
+    #[test]
+    fn tier() {
+        let cache = MemoryCache::new();
+        let body = include_bytes!("../tests/json/realtime_bulk_quotes.json").to_vec();
+        cache.insert("REALTIME_BULK_QUOTES?symbol=MSFT", CacheEntry::new(body));
+
+        // Nothing listens on port 1, so any request issued to the API would fail to connect.
+        let client = Client::builder("key")
+            .urls(&[Url::parse("http://127.0.0.1:1/query").unwrap()])
+            .cache(cache, Duration::from_secs(60))
+            .tier(Tier::Free)
+            .build_blocking();
+        assert!(!client.get_quotes_bulk(&["MSFT"]).unwrap().quotes.is_empty());
+        match client.get_quotes_bulk(&["AAPL"]) {
+            Err(Error::PremiumRequired(function)) => assert_eq!(function, "REALTIME_BULK_QUOTES"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn get_realized_volatility() {
+        let client = Client::builder("key")
+            .cache(MemoryCache::new(), Duration::from_secs(60))
+            .offline(true)
+            .build_blocking();
+        let params = [("symbol", "MSFT"), ("outputsize", "compact")];
+        let path = Path::new("tests/json/time_series_daily_adjusted.json");
+        client
+            .ingest_file("TIME_SERIES_DAILY_ADJUSTED", &params, path)
+            .unwrap();
+
+        // The cached daily time series is used instead of requesting the volatility.
+        let volatility = client
+            .get_realized_volatility("MSFT", Window::ThirtyDays, VolatilityMode::Auto)
+            .unwrap();
+        assert_eq!(volatility.source, Source::Local);
+        assert!(volatility.annualized > 0.0);
+        match client.get_realized_volatility("MSFT", Window::ThirtyDays, VolatilityMode::Server) {
+            Err(Error::Offline(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn realized_volatility_within_rate_limit() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/query", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let bodies = [
+                include_str!("../tests/json/global_quote.json"),
+                include_str!("../tests/json/time_series_daily_adjusted.json"),
+            ];
+            let mut functions = vec![];
+            for body in bodies {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0; 1024];
+                let read = stream.read(&mut request).unwrap();
+                let request = String::from_utf8_lossy(&request[..read]).to_string();
+                functions.push(request.contains("function=TIME_SERIES_DAILY_ADJUSTED"));
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+            functions
+        });
+
+        // The quote takes the only request allowed by the rate limit, so the volatility is
+        // computed locally instead of waiting to request it from the API.
+        let client = Client::builder("key")
+            .urls(&[Url::parse(&url).unwrap()])
+            .rate_limit(RateLimit::new(1, Duration::from_secs(1)))
+            .build_blocking();
+        client.get_quote("MSFT").unwrap();
+        let volatility = client
+            .get_realized_volatility("MSFT", Window::ThirtyDays, VolatilityMode::Auto)
+            .unwrap();
+        assert_eq!(server.join().unwrap(), vec![false, true]);
+        assert_eq!(volatility.source, Source::Local);
+    }
+
+    #[test]
+    fn get_commodity() {
+        let cache = MemoryCache::new();
+        let body = include_bytes!("../tests/json/coffee.json").to_vec();
+        cache.insert("COFFEE?interval=monthly", CacheEntry::new(body));
+        let client = Client::builder("key")
+            .cache(cache, Duration::from_secs(60))
+            .offline(true)
+            .build_blocking();
+        let coffee = client
+            .get_commodity(Commodity::Coffee, commodities::Interval::Monthly)
+            .unwrap();
+        assert_eq!(coffee.name, "Global Price of Coffee");
+        match client.get_commodity(Commodity::Coffee, commodities::Interval::Daily) {
+            Err(Error::InvalidParams(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn get_fundamentals() {
+        let cache = MemoryCache::new();
+        let body = include_bytes!("../tests/json/company_overview.json").to_vec();
+        cache.insert("OVERVIEW?symbol=IBM", CacheEntry::new(body));
+        let body = include_bytes!("../tests/json/income_statement.json").to_vec();
+        cache.insert("INCOME_STATEMENT?symbol=IBM", CacheEntry::new(body));
+        let client = Client::builder("key")
+            .cache(cache, Duration::from_secs(60))
+            .offline(true)
+            .build_blocking();
+        let bulk = client.get_fundamentals(&["IBM", "MSFT"], &[]).unwrap();
+        assert!(!bulk.is_complete());
+        assert_eq!(
+            bulk.fundamentals["IBM"].overview.name,
+            "International Business Machines"
+        );
+        assert_eq!(bulk.fundamentals["IBM"].income_statement, None);
+        assert_eq!(bulk.fundamentals["IBM"].cash_flow, None);
+        match &bulk.failures["MSFT"] {
+            Error::Offline(_) => {}
+            error => panic!("unexpected error: {:?}", error),
+        }
+
+        // Only the requested statements are retrieved, and the cash flow statement isn't cached.
+        let bulk = client
+            .get_fundamentals(&["IBM"], &[Statement::IncomeStatement])
+            .unwrap();
+        assert!(bulk.is_complete());
+        assert!(bulk.fundamentals["IBM"].income_statement.is_some());
+        assert_eq!(bulk.fundamentals["IBM"].cash_flow, None);
+        let bulk = client
+            .get_fundamentals(&["IBM"], &[Statement::CashFlow])
+            .unwrap();
+        match &bulk.failures["IBM"] {
+            Error::Offline(_) => {}
+            error => panic!("unexpected error: {:?}", error),
+        }
+    }
+
+    #[test]
+    fn ingest_file() {
+        let client = Client::builder("key")
+            .cache(MemoryCache::new(), Duration::from_secs(60))
+            .offline(true)
+            .build_blocking();
+        let path = Path::new("tests/json/time_series_intraday_1min.json");
+        let params = [
+            ("interval", "1min"),
+            ("outputsize", "compact"),
+            ("symbol", "MSFT"),
+        ];
+        client
+            .ingest_file("TIME_SERIES_INTRADAY", &params, path)
+            .unwrap();
+        let time_series = client
+            .get_time_series_intraday("MSFT", IntradayInterval::OneMinute)
+            .unwrap();
+        assert_eq!(time_series.symbol, "MSFT");
+
+        let path = Path::new("tests/json/global_quote.json");
+        assert!(client
+            .ingest_file("OVERVIEW", &[("symbol", "MSFT")], path)
+            .is_err());
+        assert!(client.get_company_overview("MSFT").is_err());
+        match client.ingest_file("GLOBAL_QUOTE", &[("symbol", "MSFT")], Path::new("missing")) {
+            Err(Error::IOError(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn ingest_file_without_cache() {
+        let client = Client::builder("key").build_blocking();
+        let path = Path::new("tests/json/global_quote.json");
+        match client.ingest_file("GLOBAL_QUOTE", &[("symbol", "MSFT")], path) {
+            Err(Error::Configuration(message)) => assert!(message.contains("requires a cache")),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn validate_currencies() {
+        let client = Client::builder("key")
+            .cache(MemoryCache::new(), Duration::from_secs(60))
+            .offline(true)
+            .validate_currencies(true)
+            .build_blocking();
+        let lists = [
+            (
+                "DIGITAL_CURRENCY_LIST",
+                "tests/csv/digital_currency_list.csv",
+            ),
+            (
+                "PHYSICAL_CURRENCY_LIST",
+                "tests/csv/physical_currency_list.csv",
+            ),
+        ];
+        for (function, path) in lists.iter() {
+            client.ingest_file(function, &[], Path::new(path)).unwrap();
+        }
+
+        match client.get_exchange_rate("BT", "USD") {
+            Err(Error::UnknownCurrency {
+                code,
+                close_matches,
+            }) => {
+                assert_eq!(code, "BT");
+                assert_eq!(close_matches, vec!["BTC", "BTG"]);
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+        // Known currencies go on to request the exchange rate, which isn't cached.
+        match client.get_exchange_rate("BTC", "USD") {
+            Err(Error::Offline(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+        // Markets are restricted to physical currencies.
+        let interval = IntradayInterval::FiveMinutes;
+        match client.get_crypto_intraday("ETH", &Market::new("btc"), interval) {
+            Err(Error::UnknownCurrency { code, .. }) => assert_eq!(code, "BTC"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        match client.get_crypto_intraday("ETH", &Market::new("usd"), interval) {
+            Err(Error::Offline(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn history() {
+        let cache = MemoryCache::new();
+        cache.insert(
+            "TIME_SERIES_DAILY?outputsize=full&symbol=MSFT",
+            CacheEntry::new(include_bytes!("../tests/json/time_series_daily.json").to_vec()),
+        );
+        cache.insert(
+            "TREASURY_YIELD?interval=daily&maturity=10year",
+            CacheEntry::new(include_bytes!("../tests/json/treasury_yield.json").to_vec()),
+        );
+        cache.insert(
+            "FX_WEEKLY?from_symbol=EUR&to_symbol=USD",
+            CacheEntry::new(include_bytes!("../tests/json/fx_weekly.json").to_vec()),
+        );
+        cache.insert(
+            "FX_INTRADAY?from_symbol=EUR&interval=5min&outputsize=compact&to_symbol=USD",
+            CacheEntry::new(include_bytes!("../tests/json/fx_intraday.json").to_vec()),
+        );
+        cache.insert(
+            "DIGITAL_CURRENCY_DAILY?market=EUR&symbol=BTC",
+            CacheEntry::new(include_bytes!("../tests/json/digital_currency_daily.json").to_vec()),
+        );
+        cache.insert(
+            "DIGITAL_CURRENCY_MONTHLY?market=USD&symbol=BTC",
+            CacheEntry::new(include_bytes!("../tests/json/digital_currency_monthly.json").to_vec()),
+        );
+        cache.insert(
+            "COPPER?interval=monthly",
+            CacheEntry::new(include_bytes!("../tests/json/copper.json").to_vec()),
+        );
+        let client = Client::builder("key")
+            .urls(&[Url::parse("http://127.0.0.1:1/query").unwrap()])
+            .cache(cache, Duration::from_secs(60))
+            .offline(true)
+            .build_blocking();
+        let date = |date: &str| NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+
+        let asset = Asset::Equity("MSFT".to_string());
+        let range = DateRange::new(date("2018-06-06"), date("2018-06-08"));
+        let history = client.get_history(&asset, range, Interval::Daily).unwrap();
+        assert_eq!(history.asset, asset);
+        assert_eq!(history.points.len(), 3);
+        assert_eq!(
+            history.points.last(),
+            Some(&HistoryPoint {
+                date: date("2018-06-08").into(),
+                value: 101.63
+            })
+        );
+
+        let asset = Asset::ForexPair(CurrencyPair::new("EUR", "USD"));
+        let range = DateRange::new(date("2024-05-03"), date("2024-05-03"));
+        let five = Interval::Intraday(IntradayInterval::FiveMinutes);
+        let history = client.get_history(&asset, range, five).unwrap();
+        assert_eq!(history.points.len(), 4);
+        assert_eq!(history.points[3].value, 1.07641);
+        let range = DateRange::new(date("2024-04-22"), date("2024-05-31"));
+        let history = client.get_history(&asset, range, Interval::Weekly).unwrap();
+        let values: Vec<f64> = history.points.iter().map(|p| p.value).collect();
+        assert_eq!(values, vec![1.06930, 1.07610]);
+
+        let asset = Asset::Crypto {
+            symbol: "BTC".to_string(),
+            market: Market::new("EUR"),
+        };
+        let history = client.get_history(&asset, .., Interval::Daily).unwrap();
+        let values: Vec<f64> = history.points.iter().map(|p| p.value).collect();
+        assert_eq!(values, vec![55190.12, 55770.4]);
+        let asset = Asset::Crypto {
+            symbol: "BTC".to_string(),
+            market: Market::new("USD"),
+        };
+        let history = client.get_history(&asset, .., Interval::Monthly).unwrap();
+        let values: Vec<f64> = history.points.iter().map(|p| p.value).collect();
+        assert_eq!(values, vec![71291.28, 60609.50, 59823.45]);
+
+        // Unreported prices are left out.
+        let asset = Asset::Commodity(Commodity::Copper);
+        let history = client.get_history(&asset, .., Interval::Monthly).unwrap();
+        let values: Vec<f64> = history.points.iter().map(|p| p.value).collect();
+        assert_eq!(values, vec![8474.22, 8315.35]);
+
+        let asset = Asset::EconomicSeries(Indicator::TreasuryYield(Maturity::TenYears));
+        let range = DateRange::new(date("2024-04-30"), date("2024-05-02"));
+        let history = client.get_history(&asset, range, Interval::Daily).unwrap();
+        let values: Vec<f64> = history.points.iter().map(|p| p.value).collect();
+        assert_eq!(values, vec![4.69, 4.59]);
+        let hourly = Interval::Intraday(IntradayInterval::SixtyMinutes);
+        match client.get_history(&asset, range, hourly) {
+            Err(Error::InvalidParams(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        // Intraday histories of equities are planned before backfilling any month.
+        let asset = Asset::Equity("MSFT".to_string());
+        match client.get_history(&asset, .., hourly) {
+            Err(Error::InvalidParams(message)) => assert!(message.contains("start date")),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        let client = Client::builder("key")
+            .urls(&[Url::parse("http://127.0.0.1:1/query").unwrap()])
+            .rate_limit(RateLimit::new(25, Duration::from_secs(24 * 60 * 60)))
+            .build_blocking();
+        match client.get_history(&asset, date("2022-01-01")..=date("2024-06-30"), hourly) {
+            Err(Error::QuotaExceeded {
+                required,
+                remaining,
+            }) => assert_eq!((required, remaining), (30, 25)),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+}
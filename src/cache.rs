@@ -0,0 +1,760 @@
+//! Caching of API responses
+use crate::api::APIRequest;
+use crate::Function;
+use alphavantage_types::internal::parse_date;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// Represents a response stored in a cache.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CacheEntry {
+    /// Body of the response.
+    pub body: Vec<u8>,
+    /// Time the response was retrieved from the API.
+    pub fetched_at: SystemTime,
+}
+
+impl CacheEntry {
+    /// Create an entry for a response `body` retrieved just now.
+    pub fn new(body: Vec<u8>) -> CacheEntry {
+        CacheEntry {
+            body,
+            fetched_at: SystemTime::now(),
+        }
+    }
+
+    /// Whether the entry was retrieved less than `ttl` ago.
+    pub fn is_fresh(&self, ttl: Duration) -> bool {
+        self.fetched_at
+            .elapsed()
+            .map(|age| age < ttl)
+            .unwrap_or(true)
+    }
+}
+
+/// Storage for API responses, keyed by request.
+///
+/// Keys identify the function and parameters of a request, never including the API key.
+pub trait Cache: Send + Sync {
+    /// Retrieve the entry stored for `key`.
+    fn get(&self, key: &str) -> Option<CacheEntry>;
+
+    /// Store `entry` for `key`, replacing any existing entry.
+    fn insert(&self, key: &str, entry: CacheEntry);
+
+    /// Keys of every stored entry, in no particular order.
+    fn keys(&self) -> Vec<String>;
+}
+
+impl<C: Cache + ?Sized> Cache for Arc<C> {
+    fn get(&self, key: &str) -> Option<CacheEntry> {
+        (**self).get(key)
+    }
+
+    fn insert(&self, key: &str, entry: CacheEntry) {
+        (**self).insert(key, entry)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        (**self).keys()
+    }
+}
+
+/// Computes the keys identifying requests in a cache, controlling which requests are served
+/// the same cached responses.
+///
+/// Keys are expected to start with the function, followed by `?` and the parameters (e.g.
+/// `GLOBAL_QUOTE?symbol=MSFT`), for responses to be selected and exported. Closures taking the
+/// function and parameters of requests can be used as strategies, e.g. to normalize symbols:
+///
+/// ```
+/// use alphavantage::cache::{DefaultKeyStrategy, KeyStrategy};
+///
+/// let strategy = |function: &str, params: &[(&str, &str)]| {
+///     let symbols: Vec<String> = params.iter().map(|p| p.1.to_uppercase()).collect();
+///     let params: Vec<(&str, &str)> =
+///         params.iter().zip(&symbols).map(|(p, s)| (p.0, s.as_str())).collect();
+///     DefaultKeyStrategy.key(function, &params)
+/// };
+/// assert_eq!(strategy.key("GLOBAL_QUOTE", &[("symbol", "msft")]), "GLOBAL_QUOTE?symbol=MSFT");
+/// ```
+pub trait KeyStrategy: Send + Sync {
+    /// Key identifying a request to `function` with `params`, which don't include the API key.
+    fn key(&self, function: &str, params: &[(&str, &str)]) -> String;
+}
+
+impl<F> KeyStrategy for F
+where
+    F: Fn(&str, &[(&str, &str)]) -> String + Send + Sync,
+{
+    fn key(&self, function: &str, params: &[(&str, &str)]) -> String {
+        self(function, params)
+    }
+}
+
+/// Strategy used by default, keying requests by function and parameters sorted by name, so keys
+/// don't depend on the order parameters were given in.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultKeyStrategy;
+
+impl KeyStrategy for DefaultKeyStrategy {
+    fn key(&self, function: &str, params: &[(&str, &str)]) -> String {
+        let mut params = params.to_vec();
+        params.sort_unstable();
+        let mut key = String::from(function);
+        for (i, param) in params.iter().enumerate() {
+            key.push(if i == 0 { '?' } else { '&' });
+            key.push_str(param.0);
+            key.push('=');
+            key.push_str(param.1);
+        }
+        key
+    }
+}
+
+/// A cache keeping responses in memory for the lifetime of the client.
+#[derive(Debug, Default)]
+pub struct MemoryCache {
+    entries: Mutex<HashMap<String, CacheEntry>>,
+}
+
+impl MemoryCache {
+    /// Create an empty cache.
+    pub fn new() -> MemoryCache {
+        MemoryCache::default()
+    }
+}
+
+impl Cache for MemoryCache {
+    fn get(&self, key: &str) -> Option<CacheEntry> {
+        self.entries.lock().unwrap().get(key).cloned()
+    }
+
+    fn insert(&self, key: &str, entry: CacheEntry) {
+        self.entries.lock().unwrap().insert(key.to_string(), entry);
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.entries.lock().unwrap().keys().cloned().collect()
+    }
+}
+
+/// Magic bytes starting gzip streams.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// A cache storing responses gzip compressed in another cache, e.g. one persisting them to
+/// disk, where full-size time series for many symbols would otherwise take up a lot of space.
+///
+/// Responses are decompressed when retrieved, so they can be exported uncompressed. Entries
+/// stored uncompressed in the underlying cache before it was wrapped are served as is.
+#[derive(Debug, Default)]
+pub struct CompressedCache<C> {
+    inner: C,
+    level: Compression,
+}
+
+impl<C: Cache> CompressedCache<C> {
+    /// Compress the responses stored in `inner` with the default compression level.
+    pub fn new(inner: C) -> CompressedCache<C> {
+        CompressedCache::with_level(inner, 6)
+    }
+
+    /// Compress the responses stored in `inner` with the given compression `level`, from 0
+    /// (fastest) to 9 (smallest).
+    pub fn with_level(inner: C, level: u32) -> CompressedCache<C> {
+        CompressedCache {
+            inner,
+            level: Compression::new(level.min(9)),
+        }
+    }
+
+    /// Cache the compressed responses are stored in.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+}
+
+impl<C: Cache> Cache for CompressedCache<C> {
+    fn get(&self, key: &str) -> Option<CacheEntry> {
+        let entry = self.inner.get(key)?;
+        if !entry.body.starts_with(&GZIP_MAGIC) {
+            return Some(entry);
+        }
+        let mut body = vec![];
+        GzDecoder::new(entry.body.as_slice())
+            .read_to_end(&mut body)
+            .ok()?;
+        Some(CacheEntry { body, ..entry })
+    }
+
+    fn insert(&self, key: &str, entry: CacheEntry) {
+        let mut encoder = GzEncoder::new(vec![], self.level);
+        let body = encoder
+            .write_all(&entry.body)
+            .and_then(|_| encoder.finish());
+        if let Ok(body) = body {
+            self.inner.insert(key, CacheEntry { body, ..entry });
+        }
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.inner.keys()
+    }
+}
+
+/// Selection of cached responses by API function and symbol.
+///
+/// An empty selection matches every response.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Selection {
+    functions: Vec<String>,
+    symbols: Vec<String>,
+}
+
+impl Selection {
+    /// Create a selection matching every response.
+    pub fn all() -> Selection {
+        Selection::default()
+    }
+
+    /// Restrict the selection to the responses to `function`, in addition to any other
+    /// functions selected.
+    pub fn function(mut self, function: &str) -> Selection {
+        self.functions.push(function.to_string());
+        self
+    }
+
+    /// Restrict the selection to the responses about `symbol`, in addition to any other
+    /// symbols selected.
+    pub fn symbol(mut self, symbol: &str) -> Selection {
+        self.symbols.push(symbol.to_string());
+        self
+    }
+
+    /// Whether the response stored for `key` is selected.
+    pub fn matches(&self, key: &str) -> bool {
+        let (function, params) = split_key(key);
+        let function_matches =
+            self.functions.is_empty() || self.functions.iter().any(|f| f == function);
+        let symbol_matches = self.symbols.is_empty()
+            || params
+                .split('&')
+                .filter_map(|param| param.split_once('='))
+                .any(|(name, value)| {
+                    name.ends_with("symbol") && self.symbols.iter().any(|s| s == value)
+                });
+        function_matches && symbol_matches
+    }
+}
+
+/// Export the responses of `cache` matching `selection` to `dir`, returning how many were
+/// exported.
+///
+/// Each response is written verbatim to a file named after its parameters, in a directory
+/// named after its function (e.g. `GLOBAL_QUOTE/symbol=MSFT.json`), with the time it was
+/// retrieved as modification time.
+pub fn export(cache: &dyn Cache, dir: &Path, selection: &Selection) -> io::Result<usize> {
+    let mut exported = 0;
+    for key in cache.keys() {
+        if !selection.matches(&key) {
+            continue;
+        }
+        let entry = match cache.get(&key) {
+            Some(entry) => entry,
+            None => continue,
+        };
+        let (function, params) = split_key(&key);
+        let function_dir = dir.join(encode(function));
+        fs::create_dir_all(&function_dir)?;
+        let name = match params {
+            "" => String::from("_"),
+            params => encode(params),
+        };
+        let extension = match entry.body.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'{') | Some(b'[') => "json",
+            _ => "csv",
+        };
+        let mut file = File::create(function_dir.join(format!("{}.{}", name, extension)))?;
+        file.write_all(&entry.body)?;
+        file.set_modified(entry.fetched_at)?;
+        exported += 1;
+    }
+    Ok(exported)
+}
+
+/// Import the responses previously exported to `dir` into `cache`, returning how many were
+/// imported.
+///
+/// Responses keep the time they were retrieved, replacing any entry already stored for the
+/// same request.
+pub fn import(cache: &dyn Cache, dir: &Path) -> io::Result<usize> {
+    let mut imported = 0;
+    for function_dir in fs::read_dir(dir)? {
+        let function_dir = function_dir?;
+        if !function_dir.file_type()?.is_dir() {
+            continue;
+        }
+        let function = decode(&function_dir.file_name().to_string_lossy())?;
+        for file in fs::read_dir(function_dir.path())? {
+            let file = file?;
+            let path = file.path();
+            let params = match path.file_stem() {
+                Some(stem) if file.file_type()?.is_file() => decode(&stem.to_string_lossy())?,
+                _ => continue,
+            };
+            let key = match params.as_str() {
+                "_" => function.clone(),
+                params => format!("{}?{}", function, params),
+            };
+            let entry = CacheEntry {
+                body: fs::read(&path)?,
+                fetched_at: file.metadata()?.modified()?,
+            };
+            cache.insert(&key, entry);
+            imported += 1;
+        }
+    }
+    Ok(imported)
+}
+
+/// Represents how fresh the response cached for a request, or a series persisted by a
+/// [`DataStore`](crate::store::DataStore), is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Freshness {
+    /// Key the response or series is stored for.
+    pub key: String,
+    /// Function called.
+    pub function: Function,
+    /// Symbol the response is about, from the first parameter naming one (e.g. `symbol` or
+    /// `from_symbol`), if any.
+    pub symbol: Option<String>,
+    /// Time the response was retrieved from the API.
+    pub fetched_at: SystemTime,
+    /// Time the data was last refreshed, according to the metadata of the response, if it
+    /// reports it.
+    pub last_refreshed: Option<DateTime<Tz>>,
+}
+
+impl Freshness {
+    /// Freshness of the cached `entry` stored for `key`.
+    pub fn new(key: &str, entry: &CacheEntry) -> Freshness {
+        let (function, params) = split_key(key);
+        let symbol = params
+            .split('&')
+            .filter_map(|param| param.split_once('='))
+            .find(|(name, _)| name.ends_with("symbol"))
+            .map(|(_, value)| value.to_string());
+        Freshness {
+            key: key.to_string(),
+            function: Function::from(function),
+            symbol,
+            fetched_at: entry.fetched_at,
+            last_refreshed: last_refreshed(&entry.body),
+        }
+    }
+
+    /// Whether the response should be retrieved again under `policy`.
+    pub fn is_stale(&self, policy: &StalenessPolicy) -> bool {
+        let fetched = CacheEntry {
+            body: vec![],
+            fetched_at: self.fetched_at,
+        };
+        if !fetched.is_fresh(policy.max_age) {
+            return true;
+        }
+        let max_data_age = match policy
+            .max_data_age
+            .and_then(|age| chrono::Duration::from_std(age).ok())
+        {
+            Some(max_data_age) => max_data_age,
+            None => return false,
+        };
+        match &self.last_refreshed {
+            Some(last_refreshed) => {
+                Utc::now().signed_duration_since(*last_refreshed) > max_data_age
+            }
+            None => false,
+        }
+    }
+}
+
+/// Policy deciding when cached responses should be retrieved again, e.g. by a scheduled job
+/// before spending its quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StalenessPolicy {
+    max_age: Duration,
+    max_data_age: Option<Duration>,
+}
+
+impl StalenessPolicy {
+    /// Consider responses retrieved longer than `max_age` ago stale.
+    pub fn new(max_age: Duration) -> StalenessPolicy {
+        StalenessPolicy {
+            max_age,
+            max_data_age: None,
+        }
+    }
+
+    /// Also consider responses whose data was last refreshed longer than `max_age` ago stale,
+    /// e.g. a daily time series retrieved before the market closed.
+    pub fn max_data_age(mut self, max_age: Duration) -> StalenessPolicy {
+        self.max_data_age = Some(max_age);
+        self
+    }
+}
+
+/// Freshness of the responses of `cache` matching `selection`, sorted by key.
+pub fn freshness(cache: &dyn Cache, selection: &Selection) -> Vec<Freshness> {
+    let mut keys: Vec<String> = cache
+        .keys()
+        .into_iter()
+        .filter(|key| selection.matches(key))
+        .collect();
+    keys.sort_unstable();
+    keys.iter()
+        .filter_map(|key| Some(Freshness::new(key, &cache.get(key)?)))
+        .collect()
+}
+
+/// Time the data of a JSON response was last refreshed, from the `Last Refreshed` field of its
+/// metadata, e.g. `Meta Data.3. Last Refreshed` for time series.
+fn last_refreshed(body: &[u8]) -> Option<DateTime<Tz>> {
+    if body.iter().find(|b| !b.is_ascii_whitespace()) != Some(&b'{') {
+        return None;
+    }
+    let response: serde_json::Value = serde_json::from_slice(body).ok()?;
+    response
+        .as_object()?
+        .values()
+        .filter_map(|value| value.as_object())
+        .find_map(|metadata| {
+            let field = |suffix: &str| {
+                metadata
+                    .iter()
+                    .find(|(name, _)| name.ends_with(suffix))
+                    .and_then(|(_, value)| value.as_str())
+            };
+            let time_zone = field("Time Zone")
+                .and_then(|name| name.parse().ok())
+                .unwrap_or(Tz::UTC);
+            parse_date(field("Last Refreshed")?, time_zone).ok()
+        })
+}
+
+/// Split a cache `key` into its function and parameters.
+fn split_key(key: &str) -> (&str, &str) {
+    key.split_once('?').unwrap_or((key, ""))
+}
+
+/// Percent-encode the characters of `name` which may not be portable in file names.
+pub(crate) fn encode(name: &str) -> String {
+    let mut encoded = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-' | b'.' | b'=' | b'&' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Decode a file `name` percent-encoded by [`encode`].
+pub(crate) fn decode(name: &str) -> io::Result<String> {
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid name: {}", name),
+        )
+    };
+    let mut bytes = Vec::with_capacity(name.len());
+    let mut iter = name.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hex = [
+                iter.next().ok_or_else(invalid)?,
+                iter.next().ok_or_else(invalid)?,
+            ];
+            let hex = std::str::from_utf8(&hex).map_err(|_| invalid())?;
+            bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+/// Cache configured on a client, along with how long its entries remain fresh.
+#[derive(Clone)]
+pub(crate) struct ResponseCache {
+    cache: Arc<dyn Cache>,
+    ttl: Duration,
+    keys: Arc<dyn KeyStrategy>,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(cache: Arc<dyn Cache>, ttl: Duration) -> ResponseCache {
+        ResponseCache {
+            cache,
+            ttl,
+            keys: Arc::new(DefaultKeyStrategy),
+        }
+    }
+
+    /// Key the entries of the cache with `keys` instead of the default strategy.
+    pub(crate) fn with_keys(self, keys: Arc<dyn KeyStrategy>) -> ResponseCache {
+        ResponseCache { keys, ..self }
+    }
+
+    /// Key identifying `request` in the cache.
+    pub(crate) fn key(&self, request: &APIRequest) -> String {
+        self.keys.key(request.function(), request.params())
+    }
+
+    /// Retrieve the entry stored for `key` if it's still fresh.
+    pub(crate) fn get(&self, key: &str) -> Option<CacheEntry> {
+        self.cache.get(key).filter(|entry| entry.is_fresh(self.ttl))
+    }
+
+    /// Retrieve the entry stored for `key`, however old it is.
+    pub(crate) fn get_stale(&self, key: &str) -> Option<CacheEntry> {
+        self.cache.get(key)
+    }
+
+    /// Whether `entry` is still fresh.
+    pub(crate) fn is_fresh(&self, entry: &CacheEntry) -> bool {
+        entry.is_fresh(self.ttl)
+    }
+
+    pub(crate) fn insert(&self, key: &str, body: Vec<u8>) {
+        self.cache.insert(key, CacheEntry::new(body));
+    }
+
+    pub(crate) fn insert_entry(&self, key: &str, entry: CacheEntry) {
+        self.cache.insert(key, entry);
+    }
+
+    /// Freshness of the most recently retrieved response to `function` about `symbol`, or
+    /// about any symbol if `None`.
+    ///
+    /// Only the body of the most recently retrieved response is parsed for its metadata.
+    pub(crate) fn freshness(&self, function: &str, symbol: Option<&str>) -> Option<Freshness> {
+        let mut selection = Selection::all().function(function);
+        if let Some(symbol) = symbol {
+            selection = selection.symbol(symbol);
+        }
+        let (key, entry) = self
+            .cache
+            .keys()
+            .into_iter()
+            .filter(|key| selection.matches(key))
+            .filter_map(|key| {
+                let entry = self.cache.get(&key)?;
+                Some((key, entry))
+            })
+            .max_by_key(|(_, entry)| entry.fetched_at)?;
+        Some(Freshness::new(&key, &entry))
+    }
+}
+
+/// Tracks the entries refreshed in the background by a client and its clones, so each entry is
+/// only refreshed once at a time.
+#[derive(Debug, Default)]
+pub(crate) struct Refreshes {
+    keys: Mutex<HashSet<String>>,
+}
+
+impl Refreshes {
+    /// Mark the entry stored for `key` as being refreshed, returning whether it wasn't already.
+    pub(crate) fn start(&self, key: &str) -> bool {
+        self.keys.lock().unwrap().insert(key.to_string())
+    }
+
+    /// Mark the entry stored for `key` as refreshed, whether successfully or not.
+    pub(crate) fn finish(&self, key: &str) {
+        self.keys.lock().unwrap().remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_cache() {
+        let cache = MemoryCache::new();
+        assert_eq!(cache.get("GLOBAL_QUOTE?symbol=MSFT"), None);
+        let entry = CacheEntry::new(b"{}".to_vec());
+        cache.insert("GLOBAL_QUOTE?symbol=MSFT", entry.clone());
+        assert_eq!(cache.get("GLOBAL_QUOTE?symbol=MSFT"), Some(entry));
+        assert_eq!(cache.get("GLOBAL_QUOTE?symbol=AAPL"), None);
+    }
+
+    #[test]
+    fn compressed_cache() {
+        let cache = CompressedCache::new(MemoryCache::new());
+        let body = br#"{"Global Quote": {"01. symbol": "MSFT"}}"#.repeat(100);
+        let entry = CacheEntry::new(body.clone());
+        cache.insert("GLOBAL_QUOTE?symbol=MSFT", entry.clone());
+        assert_eq!(cache.get("GLOBAL_QUOTE?symbol=MSFT"), Some(entry));
+        let stored = cache.inner().get("GLOBAL_QUOTE?symbol=MSFT").unwrap();
+        assert!(stored.body.len() < body.len() / 10);
+        assert_eq!(cache.keys(), vec!["GLOBAL_QUOTE?symbol=MSFT"]);
+
+        let entry = CacheEntry::new(b"{}".to_vec());
+        cache
+            .inner()
+            .insert("GLOBAL_QUOTE?symbol=AAPL", entry.clone());
+        assert_eq!(cache.get("GLOBAL_QUOTE?symbol=AAPL"), Some(entry));
+    }
+
+    #[test]
+    fn key_strategies() {
+        let params = [("symbol", "MSFT"), ("entitlement", "delayed")];
+        assert_eq!(
+            DefaultKeyStrategy.key("GLOBAL_QUOTE", &params),
+            "GLOBAL_QUOTE?entitlement=delayed&symbol=MSFT"
+        );
+        let strategy = |function: &str, params: &[(&str, &str)]| {
+            let params: Vec<(&str, &str)> = params
+                .iter()
+                .filter(|p| p.0 != "entitlement")
+                .cloned()
+                .collect();
+            DefaultKeyStrategy.key(function, &params)
+        };
+        assert_eq!(
+            strategy.key("GLOBAL_QUOTE", &params),
+            "GLOBAL_QUOTE?symbol=MSFT"
+        );
+    }
+
+    #[test]
+    fn freshness() {
+        let mut entry = CacheEntry::new(vec![]);
+        assert!(entry.is_fresh(Duration::from_secs(60)));
+        entry.fetched_at -= Duration::from_secs(120);
+        assert!(!entry.is_fresh(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn refreshes() {
+        let refreshes = Refreshes::default();
+        assert!(refreshes.start("GLOBAL_QUOTE?symbol=MSFT"));
+        assert!(!refreshes.start("GLOBAL_QUOTE?symbol=MSFT"));
+        assert!(refreshes.start("GLOBAL_QUOTE?symbol=AAPL"));
+        refreshes.finish("GLOBAL_QUOTE?symbol=MSFT");
+        assert!(refreshes.start("GLOBAL_QUOTE?symbol=MSFT"));
+    }
+
+    #[test]
+    fn selection() {
+        assert!(Selection::all().matches("TOP_GAINERS_LOSERS"));
+        let quotes = Selection::all().function("GLOBAL_QUOTE");
+        assert!(quotes.matches("GLOBAL_QUOTE?symbol=MSFT"));
+        assert!(!quotes.matches("OVERVIEW?symbol=MSFT"));
+        let msft = Selection::all().symbol("MSFT").symbol("EUR");
+        assert!(msft.matches("OVERVIEW?symbol=MSFT"));
+        assert!(msft.matches("FX_DAILY?from_symbol=EUR&outputsize=compact&to_symbol=USD"));
+        assert!(!msft.matches("OVERVIEW?symbol=AAPL"));
+        assert!(!msft.matches("TOP_GAINERS_LOSERS"));
+    }
+
+    #[test]
+    fn export_import() {
+        let dir = std::env::temp_dir().join(format!("alphavantage-export-{}", std::process::id()));
+        let cache = MemoryCache::new();
+        let quote = CacheEntry::new(br#"{"Global Quote": {}}"#.to_vec());
+        cache.insert("GLOBAL_QUOTE?symbol=BRK/B", quote.clone());
+        let movers = CacheEntry::new(b"{}".to_vec());
+        cache.insert("TOP_GAINERS_LOSERS", movers.clone());
+        let listings = CacheEntry::new(b"symbol,name\nMSFT,Microsoft".to_vec());
+        cache.insert("LISTING_STATUS?state=active", listings.clone());
+        cache.insert("OVERVIEW?symbol=MSFT", CacheEntry::new(b"{}".to_vec()));
+
+        let selection = Selection::all()
+            .function("GLOBAL_QUOTE")
+            .function("TOP_GAINERS_LOSERS")
+            .function("LISTING_STATUS");
+        assert_eq!(export(&cache, &dir, &selection).unwrap(), 3);
+        assert!(dir.join("GLOBAL_QUOTE/symbol=BRK%2FB.json").is_file());
+        assert!(dir.join("TOP_GAINERS_LOSERS/_.json").is_file());
+        assert!(dir.join("LISTING_STATUS/state=active.csv").is_file());
+
+        let imported = MemoryCache::new();
+        assert_eq!(import(&imported, &dir).unwrap(), 3);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(imported.get("GLOBAL_QUOTE?symbol=BRK/B"), Some(quote));
+        assert_eq!(imported.get("TOP_GAINERS_LOSERS"), Some(movers));
+        assert_eq!(imported.get("LISTING_STATUS?state=active"), Some(listings));
+        assert_eq!(imported.get("OVERVIEW?symbol=MSFT"), None);
+    }
+
+    #[test]
+    fn response_freshness() {
+        let cache = MemoryCache::new();
+        let daily: &[u8] = include_bytes!("../tests/json/time_series_daily.json");
+        let mut entry = CacheEntry::new(daily.to_vec());
+        entry.fetched_at -= Duration::from_secs(2 * 60 * 60);
+        cache.insert("TIME_SERIES_DAILY?symbol=MSFT", entry.clone());
+        let rate: &[u8] = include_bytes!("../tests/json/currency_exchange_rate.json");
+        cache.insert(
+            "CURRENCY_EXCHANGE_RATE?from_currency=EUR&to_currency=USD",
+            CacheEntry::new(rate.to_vec()),
+        );
+        cache.insert(
+            "LISTING_STATUS?state=active",
+            CacheEntry::new(b"symbol,name".to_vec()),
+        );
+
+        let all = super::freshness(&cache, &Selection::all());
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].function, Function::CurrencyExchangeRate);
+        assert_eq!(all[0].symbol, None);
+        assert_eq!(
+            all[0].last_refreshed.map(|d| d.to_rfc3339()),
+            Some("2018-06-23T10:27:49+00:00".to_string())
+        );
+        assert_eq!(all[1].last_refreshed, None);
+
+        let msft = &super::freshness(&cache, &Selection::all().symbol("MSFT"))[0];
+        assert_eq!(msft.symbol.as_deref(), Some("MSFT"));
+        assert_eq!(msft.fetched_at, entry.fetched_at);
+        assert_eq!(
+            msft.last_refreshed.map(|d| d.to_rfc3339()),
+            Some("2018-06-08T00:00:00-04:00".to_string())
+        );
+
+        let hourly = StalenessPolicy::new(Duration::from_secs(60 * 60));
+        let daily = StalenessPolicy::new(Duration::from_secs(24 * 60 * 60));
+        assert!(msft.is_stale(&hourly));
+        assert!(!msft.is_stale(&daily));
+        assert!(msft.is_stale(&daily.max_data_age(Duration::from_secs(7 * 24 * 60 * 60))));
+        assert!(!all[1].is_stale(&hourly.max_data_age(Duration::from_secs(60))));
+
+        let cache = Arc::new(cache);
+        cache.insert(
+            "TIME_SERIES_DAILY?outputsize=full&symbol=MSFT",
+            CacheEntry::new(entry.body.clone()),
+        );
+        let responses = ResponseCache::new(cache, Duration::from_secs(60));
+        let latest = responses
+            .freshness("TIME_SERIES_DAILY", Some("MSFT"))
+            .unwrap();
+        assert_eq!(latest.key, "TIME_SERIES_DAILY?outputsize=full&symbol=MSFT");
+        assert!(latest.last_refreshed.is_some());
+        assert!(responses
+            .freshness("TIME_SERIES_DAILY", Some("AAPL"))
+            .is_none());
+    }
+}
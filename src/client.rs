@@ -1,34 +1,233 @@
-use crate::api::{APIRequest, APIRequestBuilder};
-use crate::error::Error;
+use crate::analytics;
+use crate::api::{self, APIRequest, APIRequestBuilder, KnownCurrencies};
+use crate::asset;
+use crate::audit::{AuditLog, Disposition, Trail};
+use crate::cache::{
+    self, Cache, CacheEntry, DefaultKeyStrategy, KeyStrategy, Refreshes, ResponseCache,
+};
+use crate::calendar;
+use crate::commodities;
+use crate::corporate_actions;
+use crate::crypto;
+use crate::currencies;
+use crate::earnings;
+use crate::economic;
+use crate::etf;
 use crate::exchange_rate;
-use crate::time_series;
-use reqwest;
+use crate::forex;
+use crate::fundamentals;
+use crate::listing;
+use crate::market_movers;
+use crate::news;
+use crate::observer::{Backoff, Observer};
+use crate::planner;
+use crate::quote;
+use crate::rate_limit::{Priority, RateLimit, RateLimiter, Tier};
+use crate::request::{FixedWindowRequest, IntradayRequest, Request, SlidingWindowRequest};
+use crate::response::{self, ApiResponse};
+use crate::returns;
+use crate::search;
+use crate::snapshot;
+use crate::stats;
+use crate::time_series::{self, OutputSize};
+use crate::Error;
+use crate::Function;
+use crate::{ParseMode, Parser};
+use alphavantage_types::internal::DATE_FORMAT;
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use futures::future::try_join_all;
+use futures::stream::{self, Stream};
+use reqwest::Url;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
 use std::io::Cursor;
-use std::io::Read;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Number of requests issued concurrently by operations spanning several symbols.
+pub(crate) const BATCH_SIZE: usize = 5;
+
+/// Size of the responses parsed on the blocking thread pool by default, in bytes.
+const BLOCKING_PARSE_THRESHOLD: usize = 1024 * 1024;
 
 /// An asynchronous client for the Alpha Vantage API.
+#[derive(Clone)]
 pub struct Client {
     builder: APIRequestBuilder,
     client: reqwest::Client,
+    limiter: Option<Arc<RateLimiter>>,
+    priority: Priority,
+    tier: Option<Tier>,
+    cache: Option<ResponseCache>,
+    observer: Option<Arc<dyn Observer>>,
+    audit_log: Option<Arc<dyn AuditLog>>,
+    parse_mode: ParseMode,
+    stale_after: Option<Duration>,
+    offline: bool,
+    validate_currencies: bool,
+    known_currencies: Arc<Mutex<Option<Arc<KnownCurrencies>>>>,
+    stats: Arc<stats::Recorder>,
+    verify_key: bool,
+    throttle_retries: u32,
+    key_verified: Arc<AtomicBool>,
+    capture: Option<Arc<response::Capture>>,
+    refreshes: Option<Arc<Refreshes>>,
+    output_size: OutputSize,
+    blocking_parse_threshold: Option<usize>,
 }
 
 impl Client {
     /// Create a new client using the specified API `key`.
     pub fn new(key: &str) -> Client {
+        Client::builder(key).build()
+    }
+
+    /// Create a builder to configure a client using the specified API `key`.
+    pub fn builder(key: &str) -> ClientBuilder {
+        ClientBuilder::new(key)
+    }
+
+    /// Create a client sharing the configuration and rate limit of this one, issuing its
+    /// requests with the given `priority`.
+    ///
+    /// When the rate limit is saturated, requests with a higher priority are issued first.
+    pub fn with_priority(&self, priority: Priority) -> Client {
         Client {
-            builder: APIRequestBuilder::new(key),
-            client: reqwest::Client::new(),
+            priority,
+            ..self.clone()
         }
     }
 
+    /// Usage statistics of the client and its clones since it was built.
+    pub fn stats(&self) -> stats::Stats {
+        self.stats.snapshot()
+    }
+
+    /// Run `call` against a client sharing the configuration of this one, returning its result
+    /// along with metadata about the requests it issued, failing with [`Error::InvalidParams`]
+    /// if it didn't issue any.
+    ///
+    /// Any endpoint call can be wrapped, see [`ApiResponse`] for calls issuing several requests.
+    pub async fn with_metadata<T, F, Fut>(&self, call: F) -> Result<ApiResponse<T>, Error>
+    where
+        F: FnOnce(Client) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let capture = Arc::new(response::Capture::default());
+        let client = Client {
+            capture: Some(capture.clone()),
+            ..self.clone()
+        };
+        let payload = call(client).await?;
+        capture.finish(payload)
+    }
+
+    /// Issue a typed `request`, e.g. a [`IntradayRequest`].
+    pub async fn send<R>(&self, request: R) -> Result<R::Response, Error>
+    where
+        R: Request + Send + 'static,
+        R::Response: Send,
+    {
+        let function = request.function();
+        let params = request.params();
+        let params: Vec<(&str, &str)> = params.iter().map(|(n, v)| (*n, v.as_str())).collect();
+        let api_request = self.builder.create(function.as_str(), &params);
+        let response = self.api_call(api_request).await?;
+        self.parse_response(function.as_str(), response, move |parser, response| {
+            request.parse(parser, response)
+        })
+        .await
+    }
+
+    /// Plan the requests required by `jobs` against the rate limit of the client, without
+    /// issuing any of them.
+    ///
+    /// Jobs served from the cache don't count towards the plan. Fails with
+    /// [`Error::InvalidParams`] or [`Error::PremiumRequired`] if a job would fail before being
+    /// issued, and with [`Error::QuotaExceeded`] if the requests exceed the remaining daily quota
+    /// of the client, e.g. on the free tier. Only the requests issued by this client and its
+    /// clones count towards the quota.
+    ///
+    /// Jobs for functions the API deprecated are planned for their replacement, as reported to
+    /// [`Observer::on_deprecated`], and jobs for functions it removed fail with
+    /// [`Error::InvalidParams`].
+    pub fn plan(&self, jobs: &[planner::Job]) -> Result<planner::Plan, Error> {
+        let jobs = jobs
+            .iter()
+            .map(|job| self.migrate(job))
+            .collect::<Result<Vec<_>, _>>()?;
+        planner::plan(
+            &jobs,
+            self.limiter.as_deref(),
+            self.cache.is_some(),
+            |job| {
+                let params = job.params();
+                self.builder.create(&job.function, &params).check_params()?;
+                self.check_tier(&job.function)?;
+                Ok(self.is_cached(&job.function, &params))
+            },
+        )
+    }
+
+    /// Freshness of the most recently retrieved response cached for `function` about `symbol`,
+    /// or about any symbol if `None`, whether or not it's past the time to live of the cache.
+    pub fn freshness(&self, function: &str, symbol: Option<&str>) -> Option<cache::Freshness> {
+        self.cache.as_ref()?.freshness(function, symbol)
+    }
+
+    /// Whether the response cached for `function` about `symbol` should be retrieved again under
+    /// `policy`, e.g. before a scheduled job spends its quota on it. Responses missing from the
+    /// cache are stale.
+    pub fn is_stale(
+        &self,
+        function: &str,
+        symbol: Option<&str>,
+        policy: &cache::StalenessPolicy,
+    ) -> bool {
+        self.freshness(function, symbol)
+            .is_none_or(|freshness| freshness.is_stale(policy))
+    }
+
     /// Retrieve intraday time series for the specified `symbol` updated in realtime.
     pub async fn get_time_series_intraday(
         &self,
         symbol: &str,
         interval: time_series::IntradayInterval,
     ) -> Result<time_series::TimeSeries, Error> {
-        self.get_time_series(&time_series::Function::IntraDay(interval), symbol)
-            .await
+        self.get_time_series(
+            &time_series::Function::IntraDay(interval),
+            symbol,
+            self.output_size,
+        )
+        .await
+    }
+
+    /// Retrieve a `slice` of the trailing two years of intraday time series for the specified
+    /// `symbol`, from the legacy `TIME_SERIES_INTRADAY_EXTENDED` function.
+    pub async fn get_time_series_intraday_extended(
+        &self,
+        symbol: &str,
+        interval: time_series::IntradayInterval,
+        slice: time_series::Slice,
+    ) -> Result<time_series::TimeSeries, Error> {
+        let function = "TIME_SERIES_INTRADAY_EXTENDED";
+        let slice = slice.to_string();
+        let params = [
+            ("symbol", symbol),
+            ("interval", interval.to_string()),
+            ("slice", slice.as_str()),
+        ];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let symbol = symbol.to_string();
+        let result = self
+            .parse_response(function, response, move |parser, response| {
+                time_series::parser::parse_extended(parser, &symbol, response)
+            })
+            .await?;
+        Ok(result)
     }
 
     /// Retrieve daily time series for the specified `symbol` including up to 20 years of historical data.
@@ -36,28 +235,96 @@ impl Client {
         &self,
         symbol: &str,
     ) -> Result<time_series::TimeSeries, Error> {
-        self.get_time_series(&time_series::Function::Daily, symbol)
+        self.get_time_series(&time_series::Function::Daily, symbol, self.output_size)
             .await
     }
 
+    /// Retrieve daily time series for the specified `symbol` including up to 20 years of historical
+    /// data, with close values adjusted for splits and dividends.
+    pub async fn get_time_series_daily_adjusted(
+        &self,
+        symbol: &str,
+    ) -> Result<time_series::TimeSeries, Error> {
+        self.get_time_series(
+            &time_series::Function::DailyAdjusted,
+            symbol,
+            self.output_size,
+        )
+        .await
+    }
+
     /// Retrieve weekly time series for the specified `symbol` including up to 20 years of historical data.
     pub async fn get_time_series_weekly(
         &self,
         symbol: &str,
     ) -> Result<time_series::TimeSeries, Error> {
-        self.get_time_series(&time_series::Function::Weekly, symbol)
+        self.get_time_series(&time_series::Function::Weekly, symbol, self.output_size)
             .await
     }
 
+    /// Retrieve weekly time series for the specified `symbol` including up to 20 years of
+    /// historical data, with close values adjusted for splits and dividends.
+    pub async fn get_time_series_weekly_adjusted(
+        &self,
+        symbol: &str,
+    ) -> Result<time_series::TimeSeries, Error> {
+        self.get_time_series(
+            &time_series::Function::WeeklyAdjusted,
+            symbol,
+            self.output_size,
+        )
+        .await
+    }
+
     /// Retrieve monthly time series for the specified `symbol` including up to 20 years of historical data.
     pub async fn get_time_series_monthly(
         &self,
         symbol: &str,
     ) -> Result<time_series::TimeSeries, Error> {
-        self.get_time_series(&time_series::Function::Monthly, symbol)
+        self.get_time_series(&time_series::Function::Monthly, symbol, self.output_size)
             .await
     }
 
+    /// Retrieve monthly time series for the specified `symbol` including up to 20 years of
+    /// historical data, with close values adjusted for splits and dividends.
+    pub async fn get_time_series_monthly_adjusted(
+        &self,
+        symbol: &str,
+    ) -> Result<time_series::TimeSeries, Error> {
+        self.get_time_series(
+            &time_series::Function::MonthlyAdjusted,
+            symbol,
+            self.output_size,
+        )
+        .await
+    }
+
+    /// Retrieve the time series for the specified `symbol` at each of the given `intervals`,
+    /// concurrently.
+    ///
+    /// When the client retrieves full time series (see [`ClientBuilder::output_size`]), which
+    /// cover the same period at every interval, coarser intervals are derived locally from finer
+    /// ones where possible (see [`Interval::derives_from`](time_series::Interval::derives_from))
+    /// instead of being requested.
+    pub async fn get_timeframes(
+        &self,
+        symbol: &str,
+        intervals: &[time_series::Interval],
+    ) -> Result<time_series::Timeframes, Error> {
+        let derive = self.output_size == OutputSize::Full;
+        let (requested, sources) = api::plan_timeframes(intervals, derive);
+        let functions: Vec<_> = requested.iter().map(|i| i.function()).collect();
+        let retrieved = try_join_all(
+            functions
+                .iter()
+                .map(|function| self.get_time_series(function, symbol, self.output_size)),
+        )
+        .await?;
+        Ok(api::assemble_timeframes(
+            symbol, intervals, &sources, &retrieved,
+        ))
+    }
+
     /// Retrieve the exchange rate from the currency specified by `from_currency_code` to the
     /// currency specified by `to_currency_code`.
     pub async fn get_exchange_rate(
@@ -65,6 +332,8 @@ impl Client {
         from_currency_code: &str,
         to_currency_code: &str,
     ) -> Result<exchange_rate::ExchangeRate, Error> {
+        self.check_currencies(&[from_currency_code, to_currency_code])
+            .await?;
         let function = "CURRENCY_EXCHANGE_RATE";
         let params = vec![
             ("from_currency", from_currency_code),
@@ -72,32 +341,1972 @@ impl Client {
         ];
         let request = self.builder.create(function, &params);
         let response = self.api_call(request).await?;
-        let result = exchange_rate::parser::parse(response)?;
+        let result = self
+            .parse_response(function, response, exchange_rate::parser::parse)
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the exchange rates between every pair of `currencies`, triangulated through the
+    /// first one, e.g. `USD`, so only the rates from it to each of the others are requested.
+    ///
+    /// Currency codes are compared ignoring case, and duplicates are ignored.
+    pub async fn get_rate_matrix(
+        &self,
+        currencies: &[&str],
+    ) -> Result<exchange_rate::RateMatrix, Error> {
+        let mut codes: Vec<String> = vec![];
+        for currency in currencies {
+            let code = currency.trim().to_ascii_uppercase();
+            if !codes.contains(&code) {
+                codes.push(code);
+            }
+        }
+        let (pivot, others) = codes
+            .split_first()
+            .ok_or_else(|| Error::InvalidParams("no currencies".into()))?;
+        let others: Vec<&str> = others.iter().map(String::as_str).collect();
+        let rates = fetch_batched(&others, |to| self.get_exchange_rate(pivot, to)).await?;
+        Ok(exchange_rate::RateMatrix::triangulate(pivot, &rates))
+    }
+
+    /// Poll the exchange rates of `pairs` every `interval`, yielding an update for each pair
+    /// whose rate changed from one poll to the next.
+    ///
+    /// The pairs are polled one after the other, subject to any rate limit and cache configured
+    /// on the client. Every pair is reported on the first poll, and failures are reported as
+    /// updates of the pair they occurred for without ending the stream.
+    pub fn fx_stream(
+        &self,
+        pairs: &[exchange_rate::CurrencyPair],
+        interval: Duration,
+    ) -> impl Stream<Item = exchange_rate::FxUpdate> + '_ {
+        let state: (
+            Vec<exchange_rate::CurrencyPair>,
+            HashMap<exchange_rate::CurrencyPair, exchange_rate::ExchangeRate>,
+            VecDeque<exchange_rate::FxUpdate>,
+            bool,
+        ) = (pairs.to_vec(), HashMap::new(), VecDeque::new(), false);
+        stream::unfold(
+            state,
+            move |(pairs, mut previous, mut pending, mut polled)| async move {
+                loop {
+                    if let Some(update) = pending.pop_front() {
+                        return Some((update, (pairs, previous, pending, polled)));
+                    }
+                    if pairs.is_empty() {
+                        return None;
+                    }
+                    if polled {
+                        tokio::time::delay_for(interval).await;
+                    }
+                    polled = true;
+                    for pair in &pairs {
+                        let result = match self.get_exchange_rate(&pair.from, &pair.to).await {
+                            Ok(rate) if !rate.changed_from(previous.get(pair)) => continue,
+                            Ok(rate) => {
+                                previous.insert(pair.clone(), rate.clone());
+                                Ok(rate)
+                            }
+                            Err(e) => Err(e),
+                        };
+                        pending.push_back(exchange_rate::FxUpdate {
+                            pair: pair.clone(),
+                            result,
+                        });
+                    }
+                }
+            },
+        )
+    }
+
+    /// Retrieve daily time series for the exchange rate from the currency specified by
+    /// `from_currency_code` to the currency specified by `to_currency_code`.
+    ///
+    /// Entries are retrieved at the output size of the client, a
+    /// [`ForexDailyRequest`](crate::request::ForexDailyRequest) sets it for a single request.
+    pub async fn get_forex_daily(
+        &self,
+        from_currency_code: &str,
+        to_currency_code: &str,
+    ) -> Result<forex::ForexSeries, Error> {
+        self.get_forex(from_currency_code, to_currency_code, self.output_size)
+            .await
+    }
+
+    /// Retrieve intraday time series for the exchange rate from the currency specified by
+    /// `from_currency_code` to the currency specified by `to_currency_code`, at the given
+    /// `interval`.
+    pub async fn get_forex_intraday(
+        &self,
+        from_currency_code: &str,
+        to_currency_code: &str,
+        interval: time_series::IntradayInterval,
+    ) -> Result<forex::ForexSeries, Error> {
+        let function = "FX_INTRADAY";
+        let output_size = self.output_size.to_string();
+        let params = vec![
+            ("from_symbol", from_currency_code),
+            ("to_symbol", to_currency_code),
+            ("interval", interval.to_string()),
+            ("outputsize", output_size.as_str()),
+        ];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, forex::parser::parse_intraday)
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve weekly time series for the exchange rate from the currency specified by
+    /// `from_currency_code` to the currency specified by `to_currency_code`, covering the full
+    /// history of the pair.
+    pub async fn get_forex_weekly(
+        &self,
+        from_currency_code: &str,
+        to_currency_code: &str,
+    ) -> Result<forex::ForexSeries, Error> {
+        let function = "FX_WEEKLY";
+        let params = vec![
+            ("from_symbol", from_currency_code),
+            ("to_symbol", to_currency_code),
+        ];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, forex::parser::parse_weekly)
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve monthly time series for the exchange rate from the currency specified by
+    /// `from_currency_code` to the currency specified by `to_currency_code`, covering the full
+    /// history of the pair.
+    pub async fn get_forex_monthly(
+        &self,
+        from_currency_code: &str,
+        to_currency_code: &str,
+    ) -> Result<forex::ForexSeries, Error> {
+        let function = "FX_MONTHLY";
+        let params = vec![
+            ("from_symbol", from_currency_code),
+            ("to_symbol", to_currency_code),
+        ];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, forex::parser::parse_monthly)
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the exchange rate from the currency specified by `from_currency_code` to the
+    /// currency specified by `to_currency_code` at the close of `date`, or of the most recent
+    /// date before it.
+    ///
+    /// The exchange rate is looked up in the full daily time series of the currency pair.
+    pub async fn get_exchange_rate_on(
+        &self,
+        from_currency_code: &str,
+        to_currency_code: &str,
+        date: NaiveDate,
+    ) -> Result<exchange_rate::ExchangeRate, Error> {
+        let forex_series = self
+            .get_forex(from_currency_code, to_currency_code, OutputSize::Full)
+            .await?;
+        forex_series
+            .rate_on(date)
+            .ok_or_else(|| Error::MissingData(format!("no exchange rate on or before {}", date)))
+    }
+
+    async fn get_forex(
+        &self,
+        from_currency_code: &str,
+        to_currency_code: &str,
+        output_size: OutputSize,
+    ) -> Result<forex::ForexSeries, Error> {
+        let function = "FX_DAILY";
+        let output_size = output_size.to_string();
+        let params = vec![
+            ("from_symbol", from_currency_code),
+            ("to_symbol", to_currency_code),
+            ("outputsize", output_size.as_str()),
+        ];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, forex::parser::parse)
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the daily total return for the specified `symbol`, quoted in the currency
+    /// specified by `from_currency_code`, for an investor in the currency specified by
+    /// `to_currency_code`.
+    pub async fn get_total_return_in_currency(
+        &self,
+        symbol: &str,
+        from_currency_code: &str,
+        to_currency_code: &str,
+    ) -> Result<returns::ReturnSeries, Error> {
+        let (time_series, forex_series) = futures::try_join!(
+            self.get_time_series_daily_adjusted(symbol),
+            self.get_forex_daily(from_currency_code, to_currency_code)
+        )?;
+        Ok(returns::ReturnSeries::total_return_in_currency(
+            &time_series,
+            &forex_series,
+        ))
+    }
+
+    /// Retrieve the latest price and volume information for the specified `symbol`.
+    pub async fn get_quote(&self, symbol: &str) -> Result<quote::Quote, Error> {
+        let function = "GLOBAL_QUOTE";
+        let params = vec![("symbol", symbol)];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, quote::parser::parse)
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the latest price for the specified `symbol`, including pre-market and
+    /// after-hours trading, combining its quote with its latest intraday entries.
+    ///
+    /// See [`ExtendedHoursQuote::new`](quote::ExtendedHoursQuote::new) for how the price is
+    /// chosen.
+    pub async fn get_extended_hours_quote(
+        &self,
+        symbol: &str,
+    ) -> Result<quote::ExtendedHoursQuote, Error> {
+        let request = IntradayRequest::builder(time_series::IntradayInterval::OneMinute)
+            .symbol(symbol)
+            .extended_hours(true)
+            .build();
+        let (quote, time_series) = futures::try_join!(self.get_quote(symbol), self.send(request))?;
+        Ok(quote::ExtendedHoursQuote::new(&quote, &time_series))
+    }
+
+    /// Retrieve the latest price and volume information for each of `symbols` with individual
+    /// `GLOBAL_QUOTE` requests, e.g. on the free tier which can't call `REALTIME_BULK_QUOTES`.
+    ///
+    /// Requests are issued one at a time, each waiting for the rate limit of the client, so
+    /// they're spread across its budget. With a `max_age`, quotes which can't be requested right
+    /// away are served from the cache instead, past their time to live, if they were retrieved
+    /// less than `max_age` ago. The freshness of each quote is reported by its [`ApiResponse`].
+    pub async fn get_quotes(
+        &self,
+        symbols: &[&str],
+        max_age: Option<Duration>,
+    ) -> Result<Vec<ApiResponse<quote::Quote>>, Error> {
+        let mut quotes = Vec::with_capacity(symbols.len());
+        for symbol in symbols {
+            let client = match max_age {
+                Some(max_age) if self.serves_cached_quote(symbol, max_age) => Client {
+                    offline: true,
+                    ..self.clone()
+                },
+                _ => self.clone(),
+            };
+            let quote = client
+                .with_metadata(|client| async move { client.get_quote(symbol).await })
+                .await?;
+            quotes.push(quote);
+        }
+        Ok(quotes)
+    }
+
+    /// Whether the quote of `symbol` should be served from the cache, as it was cached less than
+    /// `max_age` ago and requesting it would wait for the rate limit.
+    fn serves_cached_quote(&self, symbol: &str, max_age: Duration) -> bool {
+        let (cache, limiter) = match (&self.cache, &self.limiter) {
+            (Some(cache), Some(limiter)) => (cache, limiter),
+            _ => return false,
+        };
+        if limiter.available() {
+            return false;
+        }
+        let params = [("symbol", symbol)];
+        let request = self.builder.create("GLOBAL_QUOTE", &params);
+        cache
+            .get_stale(&cache.key(&request))
+            .is_some_and(|entry| entry.fetched_at.elapsed().unwrap_or_default() <= max_age)
+    }
+
+    /// Retrieve the latest price and volume information for the specified `symbols`, quoting up
+    /// to 100 of them per `REALTIME_BULK_QUOTES` request.
+    ///
+    /// Symbols the API has no quote for are left out.
+    pub async fn get_quotes_bulk(&self, symbols: &[&str]) -> Result<quote::BulkQuotes, Error> {
+        let function = "REALTIME_BULK_QUOTES";
+        let mut quotes = quote::BulkQuotes {
+            quotes: Vec::with_capacity(symbols.len()),
+            warnings: vec![],
+        };
+        for batch in symbols.chunks(quote::MAX_BULK_SYMBOLS) {
+            let batch = batch.join(",");
+            let params = vec![("symbol", batch.as_str())];
+            let request = self.builder.create(function, &params);
+            let response = self.api_call(request).await?;
+            let batch = self
+                .parse_response(function, response, quote::parser::parse_bulk)
+                .await?;
+            quotes.quotes.extend(batch.quotes);
+            quotes.warnings.extend(batch.warnings);
+        }
+        Ok(quotes)
+    }
+
+    /// Search for the symbols best matching `keywords`.
+    pub async fn search_symbol(&self, keywords: &str) -> Result<search::SymbolMatches, Error> {
+        let function = "SYMBOL_SEARCH";
+        let params = vec![("keywords", keywords)];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, search::parser::parse)
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the company information and key financial metrics for the specified `symbol`.
+    pub async fn get_company_overview(
+        &self,
+        symbol: &str,
+    ) -> Result<fundamentals::CompanyOverview, Error> {
+        let function = "OVERVIEW";
+        let params = vec![("symbol", symbol)];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(
+                function,
+                response,
+                fundamentals::parser::parse_company_overview,
+            )
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the annual and quarterly income statements for the specified `symbol`.
+    pub async fn get_income_statement(
+        &self,
+        symbol: &str,
+    ) -> Result<fundamentals::IncomeStatement, Error> {
+        let function = "INCOME_STATEMENT";
+        let params = vec![("symbol", symbol)];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(
+                function,
+                response,
+                fundamentals::parser::parse_income_statement,
+            )
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the annual and quarterly cash flow statements for the specified `symbol`.
+    pub async fn get_cash_flow(&self, symbol: &str) -> Result<fundamentals::CashFlow, Error> {
+        let function = "CASH_FLOW";
+        let params = vec![("symbol", symbol)];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, fundamentals::parser::parse_cash_flow)
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the intraday time series of the digital currency specified by `symbol` in the
+    /// given `market`, at the given `interval`.
+    ///
+    /// When currency validation is enabled, `market` must be a physical currency listed by the
+    /// API.
+    pub async fn get_crypto_intraday(
+        &self,
+        symbol: &str,
+        market: &crypto::Market,
+        interval: time_series::IntradayInterval,
+    ) -> Result<crypto::CryptoSeries, Error> {
+        self.check_currencies(&[symbol]).await?;
+        self.check_market(market).await?;
+        let function = "CRYPTO_INTRADAY";
+        let output_size = self.output_size.to_string();
+        let params = vec![
+            ("symbol", symbol),
+            ("market", market.as_str()),
+            ("interval", interval.to_string()),
+            ("outputsize", output_size.as_str()),
+        ];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, crypto::parser::parse_intraday)
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the daily time series of the digital currency specified by `symbol` in the given
+    /// `market`, with prices quoted in both the market and US dollars.
+    ///
+    /// When currency validation is enabled, `market` must be a physical currency listed by the
+    /// API.
+    pub async fn get_crypto_daily(
+        &self,
+        symbol: &str,
+        market: &crypto::Market,
+    ) -> Result<crypto::DigitalCurrencySeries, Error> {
+        self.check_currencies(&[symbol]).await?;
+        self.check_market(market).await?;
+        let function = "DIGITAL_CURRENCY_DAILY";
+        let params = vec![("symbol", symbol), ("market", market.as_str())];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, crypto::parser::parse_daily)
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the weekly time series of the digital currency specified by `symbol` in the given
+    /// `market`, like [`get_crypto_daily`](Self::get_crypto_daily).
+    pub async fn get_crypto_weekly(
+        &self,
+        symbol: &str,
+        market: &crypto::Market,
+    ) -> Result<crypto::DigitalCurrencySeries, Error> {
+        self.check_currencies(&[symbol]).await?;
+        self.check_market(market).await?;
+        let function = "DIGITAL_CURRENCY_WEEKLY";
+        let params = vec![("symbol", symbol), ("market", market.as_str())];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, crypto::parser::parse_weekly)
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the monthly time series of the digital currency specified by `symbol` in the given
+    /// `market`, like [`get_crypto_daily`](Self::get_crypto_daily).
+    pub async fn get_crypto_monthly(
+        &self,
+        symbol: &str,
+        market: &crypto::Market,
+    ) -> Result<crypto::DigitalCurrencySeries, Error> {
+        self.check_currencies(&[symbol]).await?;
+        self.check_market(market).await?;
+        let function = "DIGITAL_CURRENCY_MONTHLY";
+        let params = vec![("symbol", symbol), ("market", market.as_str())];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, crypto::parser::parse_monthly)
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the profile and holdings of the ETF with the specified `symbol`.
+    pub async fn get_etf_profile(&self, symbol: &str) -> Result<etf::EtfProfile, Error> {
+        let function = "ETF_PROFILE";
+        let params = vec![("symbol", symbol)];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let symbol = symbol.to_string();
+        let result = self
+            .parse_response(function, response, move |parser, response| {
+                etf::parser::parse(parser, &symbol, response)
+            })
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the latest price information together with key company metrics for the
+    /// specified `symbol`.
+    pub async fn get_snapshot(&self, symbol: &str) -> Result<snapshot::Snapshot, Error> {
+        let (quote, overview) =
+            futures::try_join!(self.get_quote(symbol), self.get_company_overview(symbol))?;
+        Ok(snapshot::Snapshot::new(quote, overview))
+    }
+
+    /// Retrieve the sector and industry classification of the specified `symbols`.
+    ///
+    /// Company overviews are requested in small concurrent batches, subject to any rate limit
+    /// and cache configured on the client.
+    pub async fn get_classification(
+        &self,
+        symbols: &[&str],
+    ) -> Result<fundamentals::Classification, Error> {
+        let overviews = fetch_batched(symbols, |symbol| self.get_company_overview(symbol)).await?;
+        Ok(fundamentals::Classification::new(&overviews))
+    }
+
+    /// Retrieve the fundamental data of the specified `symbols`, recording the errors raised for
+    /// some of them instead of failing altogether.
+    ///
+    /// The company overview of each symbol is always retrieved, while only the given
+    /// `statements` are, leaving the others `None`. Symbols are requested in small concurrent
+    /// batches, subject to any rate limit and cache configured on the client.
+    pub async fn get_fundamentals(
+        &self,
+        symbols: &[&str],
+        statements: &[fundamentals::Statement],
+    ) -> Result<fundamentals::BulkFundamentals, Error> {
+        let results = fetch_batched(symbols, |symbol| async move {
+            Ok(self.get_fundamentals_of(symbol, statements).await)
+        })
+        .await?;
+        let mut bulk = fundamentals::BulkFundamentals::default();
+        for (symbol, result) in symbols.iter().zip(results) {
+            bulk.insert(symbol, result);
+        }
+        Ok(bulk)
+    }
+
+    async fn get_fundamentals_of(
+        &self,
+        symbol: &str,
+        statements: &[fundamentals::Statement],
+    ) -> Result<fundamentals::Fundamentals, Error> {
+        let overview = self.get_company_overview(symbol).await?;
+        let income_statement = if statements.contains(&fundamentals::Statement::IncomeStatement) {
+            Some(self.get_income_statement(symbol).await?)
+        } else {
+            None
+        };
+        let cash_flow = if statements.contains(&fundamentals::Statement::CashFlow) {
+            Some(self.get_cash_flow(symbol).await?)
+        } else {
+            None
+        };
+        Ok(fundamentals::Fundamentals {
+            overview,
+            income_statement,
+            cash_flow,
+        })
+    }
+
+    /// Retrieve the yields of US treasuries of the given `maturity`, at the given `interval`:
+    /// daily, weekly or monthly.
+    pub async fn get_treasury_yield(
+        &self,
+        interval: economic::Interval,
+        maturity: economic::Maturity,
+    ) -> Result<economic::EconomicSeries, Error> {
+        let params = [
+            ("interval", interval.as_str()),
+            ("maturity", maturity.as_str()),
+        ];
+        self.get_economic_indicator("TREASURY_YIELD", &params).await
+    }
+
+    /// Retrieve the federal funds rate (interest rate) of the US, at the given `interval`:
+    /// daily, weekly or monthly.
+    pub async fn get_federal_funds_rate(
+        &self,
+        interval: economic::Interval,
+    ) -> Result<economic::EconomicSeries, Error> {
+        let params = [("interval", interval.as_str())];
+        self.get_economic_indicator("FEDERAL_FUNDS_RATE", &params)
+            .await
+    }
+
+    /// Retrieve the real gross domestic product of the US, at the given `interval`: quarterly
+    /// or annual.
+    pub async fn get_real_gdp(
+        &self,
+        interval: economic::Interval,
+    ) -> Result<economic::EconomicSeries, Error> {
+        let params = [("interval", interval.as_str())];
+        self.get_economic_indicator("REAL_GDP", &params).await
+    }
+
+    /// Retrieve the consumer price index of the US, at the given `interval`: monthly or
+    /// semiannual.
+    pub async fn get_cpi(
+        &self,
+        interval: economic::Interval,
+    ) -> Result<economic::EconomicSeries, Error> {
+        let params = [("interval", interval.as_str())];
+        self.get_economic_indicator("CPI", &params).await
+    }
+
+    /// Retrieve the prices of West Texas Intermediate crude oil, at the given `interval`.
+    pub async fn get_wti(
+        &self,
+        interval: commodities::Interval,
+    ) -> Result<commodities::CommoditySeries, Error> {
+        self.get_commodity(commodities::Commodity::Wti, interval)
+            .await
+    }
+
+    /// Retrieve the prices of Brent crude oil, at the given `interval`.
+    pub async fn get_brent(
+        &self,
+        interval: commodities::Interval,
+    ) -> Result<commodities::CommoditySeries, Error> {
+        self.get_commodity(commodities::Commodity::Brent, interval)
+            .await
+    }
+
+    /// Retrieve the Henry Hub spot prices of natural gas, at the given `interval`, in the unit
+    /// reported by the API.
+    pub async fn get_natural_gas(
+        &self,
+        interval: commodities::Interval,
+    ) -> Result<commodities::CommoditySeries, Error> {
+        self.get_commodity(commodities::Commodity::NaturalGas, interval)
+            .await
+    }
+
+    /// Retrieve the global prices of copper, at the given `interval`: monthly, quarterly or
+    /// annual. Prices the API doesn't report yet are `None`.
+    pub async fn get_copper(
+        &self,
+        interval: commodities::Interval,
+    ) -> Result<commodities::CommoditySeries, Error> {
+        self.get_commodity(commodities::Commodity::Copper, interval)
+            .await
+    }
+
+    /// Retrieve the global prices of aluminum, at the given `interval`: monthly, quarterly or
+    /// annual. Prices the API doesn't report yet are `None`.
+    pub async fn get_aluminum(
+        &self,
+        interval: commodities::Interval,
+    ) -> Result<commodities::CommoditySeries, Error> {
+        self.get_commodity(commodities::Commodity::Aluminum, interval)
+            .await
+    }
+
+    /// Retrieve the prices of any `commodity`, e.g. wheat or coffee, at the given `interval`,
+    /// which must be one of its [`intervals`](commodities::Commodity::intervals).
+    pub async fn get_commodity(
+        &self,
+        commodity: commodities::Commodity,
+        interval: commodities::Interval,
+    ) -> Result<commodities::CommoditySeries, Error> {
+        let function = commodity.function();
+        let params = [("interval", interval.as_str())];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, commodities::parser::parse)
+            .await?;
+        Ok(result)
+    }
+
+    async fn get_economic_indicator(
+        &self,
+        function: &str,
+        params: &[(&str, &str)],
+    ) -> Result<economic::EconomicSeries, Error> {
+        let request = self.builder.create(function, params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, economic::parser::parse)
+            .await?;
         Ok(result)
     }
 
-    async fn get_time_series(
+    /// Retrieve the yield curve of US treasuries on `date`, or on the latest date yields were
+    /// reported on for every maturity if `date` is `None`.
+    ///
+    /// The yields of each maturity are requested concurrently. Yields missing on `date` are
+    /// taken from the latest date before it.
+    pub async fn get_yield_curve(
+        &self,
+        date: Option<NaiveDate>,
+    ) -> Result<economic::YieldCurve, Error> {
+        let maturities = economic::Maturity::all();
+        let series = try_join_all(
+            maturities
+                .iter()
+                .map(|maturity| self.get_treasury_yield(economic::Interval::Daily, *maturity)),
+        )
+        .await?;
+        let series: Vec<_> = maturities.iter().copied().zip(series).collect();
+        economic::yield_curve(&series, date)
+            .ok_or_else(|| Error::MissingData("missing treasury yields for the yield curve".into()))
+    }
+
+    /// Retrieve the annualized realized volatility of the specified `symbol` over the trailing
+    /// `window`, computed by the API with `ANALYTICS_FIXED_WINDOW` or locally from the daily
+    /// adjusted time series depending on `mode`.
+    pub async fn get_realized_volatility(
+        &self,
+        symbol: &str,
+        window: analytics::Window,
+        mode: analytics::VolatilityMode,
+    ) -> Result<analytics::Volatility, Error> {
+        let output_size = window.output_size();
+        let from_server = match mode {
+            analytics::VolatilityMode::Server => true,
+            analytics::VolatilityMode::Local => false,
+            analytics::VolatilityMode::Auto => {
+                let output_size = output_size.to_string();
+                let params = [("symbol", symbol), ("outputsize", output_size.as_str())];
+                !self.is_cached("TIME_SERIES_DAILY_ADJUSTED", &params)
+                    && self.can_issue("ANALYTICS_FIXED_WINDOW")
+            }
+        };
+        if from_server {
+            let function = "ANALYTICS_FIXED_WINDOW";
+            let params = [
+                ("SYMBOLS", symbol),
+                ("RANGE", window.range()),
+                ("INTERVAL", "DAILY"),
+                ("OHLC", "close"),
+                ("CALCULATIONS", "STDDEV(annualized=True)"),
+            ];
+            let request = self.builder.create(function, &params);
+            let result = match self.api_call(request).await {
+                Ok(response) => {
+                    analytics::parser::parse_volatility(self.parser(function), symbol, response)
+                }
+                Err(error) => Err(error),
+            };
+            match result {
+                Err(Error::APIError(_)) if mode == analytics::VolatilityMode::Auto => {}
+                result => {
+                    return result.map(|annualized| analytics::Volatility {
+                        symbol: symbol.to_string(),
+                        window,
+                        annualized,
+                        source: analytics::Source::Server,
+                    })
+                }
+            }
+        }
+        let time_series = self
+            .get_time_series(&time_series::Function::DailyAdjusted, symbol, output_size)
+            .await?;
+        let annualized = analytics::realized_volatility(&time_series, window)
+            .ok_or_else(|| Error::MissingData("not enough entries to compute volatility".into()))?;
+        Ok(analytics::Volatility {
+            symbol: symbol.to_string(),
+            window,
+            annualized,
+            source: analytics::Source::Local,
+        })
+    }
+
+    /// Retrieve analytics over the returns of a set of symbols, like their mean, standard
+    /// deviation or correlation, computed by the API with `ANALYTICS_FIXED_WINDOW`.
+    pub async fn get_analytics_fixed_window(
+        &self,
+        request: FixedWindowRequest,
+    ) -> Result<analytics::FixedWindowAnalytics, Error> {
+        self.send(request).await
+    }
+
+    /// Retrieve analytics over windows sliding across the returns of a set of symbols, like
+    /// their rolling variance or correlation, computed by the API with
+    /// `ANALYTICS_SLIDING_WINDOW`.
+    pub async fn get_analytics_sliding_window(
+        &self,
+        request: SlidingWindowRequest,
+    ) -> Result<analytics::SlidingWindowAnalytics, Error> {
+        self.send(request).await
+    }
+
+    /// Retrieve the history of `asset` over the dates of `range`, e.g. `start..=end` or a
+    /// [`DateRange`](asset::DateRange), at the given `granularity`, from the family of functions
+    /// of its type: the close prices of equities and ETFs and of digital currencies, the close
+    /// rates of currency pairs, and the values of commodities and economic indicators.
+    ///
+    /// Intraday histories of equities and ETFs are backfilled one month at a time, i.e. with a
+    /// request per month, so they require `range` to have a start and are planned before issuing
+    /// any request, failing with [`Error::QuotaExceeded`] if the months exceed the remaining
+    /// daily quota of the client. Coarser
+    /// granularities than the ones retrieved are derived from the last value of each period,
+    /// while values are never finer than the ones an asset is reported at, e.g. monthly for the
+    /// consumer price index. Intraday histories of digital currencies are retrieved with
+    /// `CRYPTO_INTRADAY` and of currency pairs with `FX_INTRADAY`, which only cover their latest
+    /// days, while daily, weekly and monthly histories of digital currencies are retrieved with
+    /// `DIGITAL_CURRENCY_DAILY`, `DIGITAL_CURRENCY_WEEKLY` and `DIGITAL_CURRENCY_MONTHLY`, and
+    /// weekly and monthly histories of currency pairs with `FX_WEEKLY` and `FX_MONTHLY`.
+    ///
+    /// Intraday histories of commodities and economic indicators fail with
+    /// [`Error::InvalidParams`] without issuing any request.
+    pub async fn get_history(
+        &self,
+        asset: &asset::Asset,
+        range: impl Into<asset::DateRange>,
+        granularity: asset::Granularity,
+    ) -> Result<asset::History, Error> {
+        let range = range.into();
+        let today = Utc::now().date_naive();
+        let output_size = api::history_output_size(range.start, today);
+        let intraday = match granularity {
+            time_series::Interval::Intraday(interval) => Some(interval),
+            _ => None,
+        };
+        let values: Vec<(NaiveDateTime, f64)> = match (asset, intraday) {
+            (asset::Asset::Equity(symbol) | asset::Asset::Etf(symbol), Some(interval)) => {
+                if range.start.is_none() {
+                    return Err(Error::InvalidParams(
+                        "intraday history requires a start date".to_string(),
+                    ));
+                }
+                let requests: Vec<IntradayRequest> = range
+                    .months(api::first_intraday_date(), today)
+                    .iter()
+                    .map(|month| {
+                        IntradayRequest::builder(interval)
+                            .symbol(symbol)
+                            .output_size(OutputSize::Full)
+                            .month(month)
+                            .build()
+                    })
+                    .collect();
+                let jobs: Vec<planner::Job> =
+                    requests.iter().map(planner::Job::from_request).collect();
+                self.plan(&jobs)?;
+                let mut values = vec![];
+                for request in requests {
+                    let time_series = self.send(request).await?;
+                    values.extend(
+                        time_series
+                            .entries
+                            .iter()
+                            .map(|e| (e.date.naive_local(), e.close)),
+                    );
+                }
+                values
+            }
+            (asset::Asset::Equity(symbol) | asset::Asset::Etf(symbol), None) => self
+                .get_time_series(&granularity.function(), symbol, output_size)
+                .await?
+                .entries
+                .iter()
+                .map(|e| (e.date.naive_local(), e.close))
+                .collect(),
+            (asset::Asset::Crypto { symbol, market }, Some(interval)) => self
+                .get_crypto_intraday(symbol, market, interval)
+                .await?
+                .entries
+                .iter()
+                .map(|e| (e.date.naive_local(), e.close))
+                .collect(),
+            (asset::Asset::Crypto { symbol, market }, None) => {
+                let series = match granularity {
+                    time_series::Interval::Weekly => self.get_crypto_weekly(symbol, market).await?,
+                    time_series::Interval::Monthly => {
+                        self.get_crypto_monthly(symbol, market).await?
+                    }
+                    _ => self.get_crypto_daily(symbol, market).await?,
+                };
+                series
+                    .entries
+                    .iter()
+                    .map(|e| (e.market.date.naive_local(), e.market.close))
+                    .collect()
+            }
+            (asset::Asset::ForexPair(pair), Some(interval)) => self
+                .get_forex_intraday(&pair.from, &pair.to, interval)
+                .await?
+                .entries
+                .iter()
+                .map(|e| (e.date.naive_local(), e.close))
+                .collect(),
+            (_, Some(_)) => {
+                return Err(Error::InvalidParams(format!(
+                    "intraday history isn't available for {:?}",
+                    asset.asset_type()
+                )))
+            }
+            (asset::Asset::ForexPair(pair), None) => {
+                let forex_series = match granularity {
+                    time_series::Interval::Weekly => {
+                        self.get_forex_weekly(&pair.from, &pair.to).await?
+                    }
+                    time_series::Interval::Monthly => {
+                        self.get_forex_monthly(&pair.from, &pair.to).await?
+                    }
+                    _ => self.get_forex(&pair.from, &pair.to, output_size).await?,
+                };
+                forex_series
+                    .entries
+                    .iter()
+                    .map(|e| (e.date.naive_local(), e.close))
+                    .collect()
+            }
+            (asset::Asset::Commodity(commodity), None) => {
+                // Dates the API doesn't report a price for are left out of the history.
+                self.get_commodity(*commodity, commodity.finest_interval())
+                    .await?
+                    .data
+                    .iter()
+                    .filter_map(|p| Some((p.date.into(), p.value?)))
+                    .collect()
+            }
+            (asset::Asset::EconomicSeries(indicator), None) => {
+                let mut params = vec![("interval", indicator.finest_interval().as_str())];
+                if let economic::Indicator::TreasuryYield(maturity) = indicator {
+                    params.push(("maturity", maturity.as_str()));
+                }
+                self.get_economic_indicator(indicator.function(), &params)
+                    .await?
+                    .data
+                    .iter()
+                    .map(|p| (p.date.into(), p.value))
+                    .collect()
+            }
+        };
+        Ok(asset::History::new(
+            asset.clone(),
+            granularity,
+            values,
+            &range,
+        ))
+    }
+
+    /// Retrieve the top gainers, top losers and most actively traded tickers in the US market.
+    pub async fn get_top_gainers_losers(&self) -> Result<market_movers::MarketMovers, Error> {
+        let function = "TOP_GAINERS_LOSERS";
+        let request = self.builder.create(function, &[]);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, market_movers::parser::parse)
+            .await?;
+        Ok(result)
+    }
+
+    /// Poll the top gainers, losers and most actively traded tickers every `interval`, yielding
+    /// the changes from one poll to the next.
+    ///
+    /// Every market mover of the first poll is reported as entering its list. Polls without
+    /// changes, e.g. when served from the cache, don't yield anything.
+    pub fn watch_market_movers(
+        &self,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<Vec<market_movers::MoverEvent>, Error>> + '_ {
+        let state: (Option<market_movers::MarketMovers>, bool) = (None, false);
+        stream::unfold(state, move |(mut previous, mut polled)| async move {
+            loop {
+                if polled {
+                    tokio::time::delay_for(interval).await;
+                }
+                polled = true;
+                let movers = match self.get_top_gainers_losers().await {
+                    Ok(movers) => movers,
+                    Err(e) => return Some((Err(e), (previous, polled))),
+                };
+                let events = match &previous {
+                    Some(previous) => previous.diff(&movers),
+                    None => movers.empty().diff(&movers),
+                };
+                previous = Some(movers);
+                if !events.is_empty() {
+                    return Some((Ok(events), (previous, polled)));
+                }
+            }
+        })
+    }
+
+    /// Retrieve the latest news articles about the specified `tickers`, along with their
+    /// sentiment.
+    pub async fn get_news_sentiment(&self, tickers: &[&str]) -> Result<news::NewsFeed, Error> {
+        let function = "NEWS_SENTIMENT";
+        let tickers = tickers.join(",");
+        let params = vec![("tickers", tickers.as_str())];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, news::parser::parse)
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the market movers matching the filters of `screener`.
+    ///
+    /// When the screener requests company overviews, these are requested in small concurrent
+    /// batches, subject to any rate limit and cache configured on the client. Tickers without
+    /// company overview data are returned without one.
+    pub async fn screen_market_movers(
+        &self,
+        screener: &market_movers::Screener,
+    ) -> Result<Vec<market_movers::ScreenedMover>, Error> {
+        let movers = self.get_top_gainers_losers().await?;
+        let mut screened = screener.screen(&movers);
+        if screener.wants_overview() {
+            let mut tickers: Vec<&str> = screened.iter().map(|m| m.mover.ticker.as_str()).collect();
+            tickers.sort_unstable();
+            tickers.dedup();
+            let overviews = fetch_batched(&tickers, |ticker| async move {
+                match self.get_company_overview(ticker).await {
+                    Ok(overview) => Ok(Some(overview)),
+                    Err(Error::ParsingError(_)) => Ok(None),
+                    Err(e) => Err(e),
+                }
+            })
+            .await?;
+            let overviews: Vec<fundamentals::CompanyOverview> =
+                overviews.into_iter().flatten().collect();
+            for mover in screened.iter_mut() {
+                mover.overview = overviews
+                    .iter()
+                    .find(|o| o.symbol == mover.mover.ticker)
+                    .cloned();
+            }
+        }
+        Ok(screened)
+    }
+
+    /// Retrieve the dividend history for the specified `symbol`.
+    pub async fn get_dividends(&self, symbol: &str) -> Result<corporate_actions::Dividends, Error> {
+        let function = "DIVIDENDS";
+        let params = vec![("symbol", symbol)];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(
+                function,
+                response,
+                corporate_actions::parser::parse_dividends,
+            )
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the split history for the specified `symbol`.
+    pub async fn get_splits(&self, symbol: &str) -> Result<corporate_actions::Splits, Error> {
+        let function = "SPLITS";
+        let params = vec![("symbol", symbol)];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, corporate_actions::parser::parse_splits)
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the annual and quarterly earnings history for the specified `symbol`.
+    pub async fn get_earnings(&self, symbol: &str) -> Result<earnings::Earnings, Error> {
+        let function = "EARNINGS";
+        let params = vec![("symbol", symbol)];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, earnings::parser::parse)
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the analyst estimates of the earnings per share and revenue of the specified
+    /// `symbol` for the upcoming fiscal quarters and years.
+    pub async fn get_earnings_estimates(
+        &self,
+        symbol: &str,
+    ) -> Result<earnings::EarningsEstimates, Error> {
+        let function = "EARNINGS_ESTIMATES";
+        let params = vec![("symbol", symbol)];
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, earnings::parser::parse_estimates)
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the earnings expected to be reported within `horizon`, by the specified `symbol`
+    /// or by every company if `symbol` is `None`.
+    pub async fn get_earnings_calendar(
+        &self,
+        symbol: Option<&str>,
+        horizon: earnings::Horizon,
+    ) -> Result<earnings::EarningsCalendar, Error> {
+        let function = "EARNINGS_CALENDAR";
+        let mut params = vec![("horizon", horizon.as_str())];
+        if let Some(symbol) = symbol {
+            params.push(("symbol", symbol));
+        }
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, move |parser, response| {
+                earnings::parser::parse_calendar(parser, horizon, response)
+            })
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the initial public offerings expected in the coming three months.
+    pub async fn get_ipo_calendar(&self) -> Result<listing::IpoCalendar, Error> {
+        let function = "IPO_CALENDAR";
+        let request = self.builder.create(function, &[]);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, listing::parser::parse_ipo_calendar)
+            .await?;
+        Ok(result)
+    }
+
+    /// Poll the earnings calendar within `horizon` and the IPO calendar every `interval`,
+    /// yielding the entries added, removed or rescheduled from one poll to the next.
+    ///
+    /// Every entry of the first poll is reported as added. Polls without changes, e.g. when
+    /// served from the cache, don't yield anything.
+    pub fn watch_calendars(
+        &self,
+        horizon: earnings::Horizon,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<calendar::CalendarChanges, Error>> + '_ {
+        let state = (
+            earnings::EarningsCalendar {
+                horizon,
+                entries: vec![],
+                warnings: vec![],
+            },
+            listing::IpoCalendar {
+                entries: vec![],
+                warnings: vec![],
+            },
+            false,
+        );
+        stream::unfold(
+            state,
+            move |(mut earnings, mut ipos, mut polled)| async move {
+                loop {
+                    if polled {
+                        tokio::time::delay_for(interval).await;
+                    }
+                    polled = true;
+                    let (next_earnings, next_ipos) = match futures::try_join!(
+                        self.get_earnings_calendar(None, horizon),
+                        self.get_ipo_calendar()
+                    ) {
+                        Ok(calendars) => calendars,
+                        Err(e) => return Some((Err(e), (earnings, ipos, polled))),
+                    };
+                    let changes = calendar::CalendarChanges {
+                        earnings: earnings.diff(&next_earnings),
+                        ipos: ipos.diff(&next_ipos),
+                    };
+                    earnings = next_earnings;
+                    ipos = next_ipos;
+                    if !changes.is_empty() {
+                        return Some((Ok(changes), (earnings, ipos, polled)));
+                    }
+                }
+            },
+        )
+    }
+
+    /// Retrieve the trailing twelve month dividend yield for the specified `symbol`, computed
+    /// from its dividend history and daily time series.
+    pub async fn get_dividend_yield(
+        &self,
+        symbol: &str,
+    ) -> Result<corporate_actions::DividendYieldSeries, Error> {
+        let (dividends, time_series) = futures::try_join!(
+            self.get_dividends(symbol),
+            self.get_time_series_daily(symbol)
+        )?;
+        Ok(dividends.trailing_yield(&time_series))
+    }
+
+    /// Retrieve the symbols in the given listing `state` on `date`, or on the latest trading day
+    /// if `date` is `None`.
+    pub async fn get_listing_status(
+        &self,
+        date: Option<NaiveDate>,
+        state: listing::ListingState,
+    ) -> Result<listing::ListingStatus, Error> {
+        let function = "LISTING_STATUS";
+        let date_param = date.map(|d| d.format(DATE_FORMAT).to_string());
+        let mut params = vec![("state", state.as_str())];
+        if let Some(date_param) = &date_param {
+            params.push(("date", date_param.as_str()));
+        }
+        let request = self.builder.create(function, &params);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, move |parser, response| {
+                listing::parser::parse(parser, date, state, response)
+            })
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve the latest snapshots of active and delisted symbols, combined into a
+    /// [`Universe`](listing::Universe) telling which symbols were listed on any past date.
+    pub async fn get_universe(&self) -> Result<listing::Universe, Error> {
+        let (active, delisted) = futures::try_join!(
+            self.get_listing_status(None, listing::ListingState::Active),
+            self.get_listing_status(None, listing::ListingState::Delisted)
+        )?;
+        Ok(listing::Universe::new(&[active, delisted]))
+    }
+
+    /// Retrieve the digital currencies supported by the API.
+    pub async fn get_digital_currency_list(&self) -> Result<currencies::CurrencyList, Error> {
+        self.get_currency_list("DIGITAL_CURRENCY_LIST").await
+    }
+
+    /// Retrieve the physical currencies supported by the API.
+    pub async fn get_physical_currency_list(&self) -> Result<currencies::CurrencyList, Error> {
+        self.get_currency_list("PHYSICAL_CURRENCY_LIST").await
+    }
+
+    /// Load a response to `function` with `params`, previously saved from the API to the JSON or
+    /// CSV file at `path`, into the cache.
+    ///
+    /// The response is validated by parsing it as if it was retrieved from the API, and stored
+    /// as retrieved at the modification time of the file. Parameters can be given in any order
+    /// but must otherwise match those of the request, e.g. including `outputsize` for time
+    /// series.
+    ///
+    /// Responses to functions the API deprecated are stored as responses to their replacement.
+    /// Fails with [`Error::Configuration`] if the client has no cache to load the response into.
+    pub fn ingest_file(
+        &self,
+        function: &str,
+        params: &[(&str, &str)],
+        path: &Path,
+    ) -> Result<(), Error> {
+        let cache = self
+            .cache
+            .as_ref()
+            .ok_or_else(|| Error::Configuration("ingesting files requires a cache".to_string()))?;
+        let job = self.migrate(&planner::Job::new(function, params))?;
+        let (function, params) = (job.function.as_str(), &job.params());
+        let body = std::fs::read(path)?;
+        let fetched_at = std::fs::metadata(path)?.modified()?;
+        api::validate(self.parser(function), function, params, &body)?;
+        let cache_key = cache.key(&self.builder.create(function, params));
+        cache.insert_entry(&cache_key, CacheEntry { body, fetched_at });
+        Ok(())
+    }
+
+    async fn get_currency_list(&self, function: &str) -> Result<currencies::CurrencyList, Error> {
+        let request = self.builder.create(function, &[]);
+        let response = self.api_call(request).await?;
+        let result = self
+            .parse_response(function, response, currencies::parser::parse)
+            .await?;
+        Ok(result)
+    }
+
+    /// Fail with [`Error::UnknownCurrency`] if currency validation is enabled and any of
+    /// `codes` isn't listed by the API, retrieving the currency lists the first time.
+    async fn check_currencies(&self, codes: &[&str]) -> Result<(), Error> {
+        if !self.validate_currencies {
+            return Ok(());
+        }
+        let known = self.load_known_currencies().await?;
+        codes.iter().try_for_each(|code| known.check(code))
+    }
+
+    /// Fail with [`Error::UnknownCurrency`] if currency validation is enabled and `market`
+    /// isn't a physical currency listed by the API.
+    async fn check_market(&self, market: &crypto::Market) -> Result<(), Error> {
+        if !self.validate_currencies {
+            return Ok(());
+        }
+        let known = self.load_known_currencies().await?;
+        known.check_physical(market.as_str())
+    }
+
+    async fn load_known_currencies(&self) -> Result<Arc<KnownCurrencies>, Error> {
+        let known = self.known_currencies.lock().unwrap().clone();
+        match known {
+            Some(known) => Ok(known),
+            None => {
+                let (digital, physical) = futures::try_join!(
+                    self.get_digital_currency_list(),
+                    self.get_physical_currency_list()
+                )?;
+                let known = Arc::new(KnownCurrencies::new(digital, physical));
+                *self.known_currencies.lock().unwrap() = Some(known.clone());
+                Ok(known)
+            }
+        }
+    }
+
+    pub(crate) async fn get_time_series(
         &self,
         function: &time_series::Function,
         symbol: &str,
+        output_size: OutputSize,
     ) -> Result<time_series::TimeSeries, Error> {
-        let mut params = vec![("symbol", symbol)];
+        let output_size = output_size.to_string();
+        let mut params = vec![("symbol", symbol), ("outputsize", output_size.as_str())];
         if let time_series::Function::IntraDay(interval) = function {
             params.push(("interval", interval.to_string()));
         }
         let request = self.builder.create(function.into(), &params);
         let response = self.api_call(request).await?;
-        let result = time_series::parser::parse(function, response)?;
+        let name = function.into();
+        let function = function.clone();
+        let result = self
+            .parse_response(name, response, move |parser, response| {
+                time_series::parser::parse(parser, &function, response)
+            })
+            .await?;
+        Ok(result)
+    }
+
+    /// Retrieve a time series like `get_time_series`, bypassing the cache so any cached response
+    /// is replaced.
+    pub(crate) async fn refetch_time_series(
+        &self,
+        function: &time_series::Function,
+        symbol: &str,
+        output_size: OutputSize,
+    ) -> Result<time_series::TimeSeries, Error> {
+        let output_size = output_size.to_string();
+        let mut params = vec![("symbol", symbol), ("outputsize", output_size.as_str())];
+        if let time_series::Function::IntraDay(interval) = function {
+            params.push(("interval", interval.to_string()));
+        }
+        let request = self.builder.create(function.into(), &params);
+        let response = self.refetch(request).await?;
+        let name = function.into();
+        let function = function.clone();
+        let result = self
+            .parse_response(name, response, move |parser, response| {
+                time_series::parser::parse(parser, &function, response)
+            })
+            .await?;
         Ok(result)
     }
 
-    async fn api_call<'a>(&self, request: APIRequest<'a>) -> Result<impl Read, Error> {
-        let response = self.client.execute(request.into()).await?;
+    /// Parse a `response` to `function` with `parse`, on the blocking thread pool if it's at
+    /// least as large as the configured threshold, so parsing doesn't stall the runtime.
+    async fn parse_response<T, F>(
+        &self,
+        function: &str,
+        response: Cursor<Vec<u8>>,
+        parse: F,
+    ) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(Parser, Cursor<Vec<u8>>) -> Result<T, Error> + Send + 'static,
+    {
+        let parser = self.parser(function);
+        match self.blocking_parse_threshold {
+            Some(threshold) if response.get_ref().len() >= threshold => {
+                tokio::task::spawn_blocking(move || parse(parser, response))
+                    .await
+                    .map_err(|e| Error::ParsingError(format!("parsing failed: {}", e)))?
+            }
+            _ => parse(parser, response),
+        }
+    }
+
+    fn parser(&self, function: &str) -> Parser {
+        let mut parser = Parser::new(self.parse_mode);
+        if let Some(max_age) = self.stale_after {
+            parser = parser.stale_after(max_age);
+        }
+        match &self.observer {
+            Some(observer) => {
+                let (function, observer) = (function.to_string(), observer.clone());
+                parser.on_warnings(move |warnings| observer.on_parse_warnings(&function, warnings))
+            }
+            None => parser,
+        }
+    }
+
+    /// Whether a response to `function` with `params` is cached, so it can be served without
+    /// issuing a request.
+    fn is_cached(&self, function: &str, params: &[(&str, &str)]) -> bool {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return false,
+        };
+        let key = cache.key(&self.builder.create(function, params));
+        if self.offline || self.refreshes.is_some() {
+            cache.get_stale(&key).is_some()
+        } else {
+            cache.get(&key).is_some()
+        }
+    }
+
+    async fn api_call<'a>(&self, request: APIRequest<'a>) -> Result<Cursor<Vec<u8>>, Error> {
+        request.check_params()?;
+        let trail = Trail::new();
+        let function = request.function();
+        let cache_key = match &self.cache {
+            Some(cache) => cache.key(&request),
+            None => request.cache_key(),
+        };
+        let cached = match &self.cache {
+            Some(cache) if self.offline || self.refreshes.is_some() => {
+                cache.get_stale(&cache_key).map(|entry| {
+                    let stale = !cache.is_fresh(&entry);
+                    (entry, stale)
+                })
+            }
+            Some(cache) => cache.get(&cache_key).map(|entry| (entry, false)),
+            None => None,
+        };
+        if let Some((entry, stale)) = cached {
+            self.stats.cache_hit(function);
+            if let Some(observer) = &self.observer {
+                observer.on_cache_hit(function);
+            }
+            if let Some(capture) = &self.capture {
+                if stale {
+                    capture.record_stale(function, entry.fetched_at, entry.body.len());
+                } else {
+                    capture.record(function, entry.fetched_at, true, entry.body.len());
+                }
+            }
+            let disposition = if stale {
+                Disposition::Stale
+            } else {
+                Disposition::Cached
+            };
+            self.audit(trail, function, disposition, entry.body.len(), None);
+            if stale && !self.offline {
+                self.refresh(&request, cache_key);
+            }
+            return Ok(Cursor::new(entry.body));
+        }
+        if self.offline {
+            let error = Error::Offline(cache_key);
+            self.stats.response(function, Err(&error));
+            self.audit(trail, function, Disposition::Offline, 0, Some(&error));
+            return Err(error);
+        }
+        let body = self.fetch(request, &cache_key, trail).await?;
+        if let Some(capture) = &self.capture {
+            capture.record(function, SystemTime::now(), false, body.len());
+        }
+        Ok(Cursor::new(body))
+    }
+
+    /// Issue `request` to the API without looking it up in the cache, replacing the cached
+    /// response, if any.
+    async fn refetch<'a>(&self, request: APIRequest<'a>) -> Result<Cursor<Vec<u8>>, Error> {
+        request.check_params()?;
+        let trail = Trail::new();
+        let function = request.function();
+        let cache_key = match &self.cache {
+            Some(cache) => cache.key(&request),
+            None => request.cache_key(),
+        };
+        if self.offline {
+            let error = Error::Offline(cache_key);
+            self.stats.response(function, Err(&error));
+            self.audit(trail, function, Disposition::Offline, 0, Some(&error));
+            return Err(error);
+        }
+        let body = self.fetch(request, &cache_key, trail).await?;
+        if let Some(capture) = &self.capture {
+            capture.record(function, SystemTime::now(), false, body.len());
+        }
+        Ok(Cursor::new(body))
+    }
+
+    /// Refresh the stale entry cached for `request` in the background, unless it's already
+    /// being refreshed.
+    fn refresh(&self, request: &APIRequest, cache_key: String) {
+        let refreshes = match &self.refreshes {
+            Some(refreshes) if refreshes.start(&cache_key) => refreshes.clone(),
+            _ => return,
+        };
+        let client = Client {
+            capture: None,
+            ..self.clone()
+        };
+        let function = request.function().to_string();
+        let params: Vec<(String, String)> = request
+            .params()
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        tokio::spawn(async move {
+            let params: Vec<(&str, &str)> = params
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str()))
+                .collect();
+            let request = client.builder.create(&function, &params);
+            // Failures are reported to the observer and statistics, keeping the stale entry.
+            let _ = client.fetch(request, &cache_key, Trail::new()).await;
+            refreshes.finish(&cache_key);
+        });
+    }
+
+    /// Issue `request` to the API, subject to the rate limit, caching its response under
+    /// `cache_key` and recording the call tracked by `trail` in the audit log.
+    async fn fetch<'a>(
+        &self,
+        request: APIRequest<'a>,
+        cache_key: &str,
+        mut trail: Trail,
+    ) -> Result<Vec<u8>, Error> {
+        let function = request.function();
+        request.check_key()?;
+        self.check_tier(function)?;
+        let mut started;
+        let mut retries = self.throttle_retries;
+        let result = loop {
+            self.acquire(function).await;
+            started = Instant::now();
+            trail.restart();
+            match self.execute(&request, &mut trail).await {
+                Err(Error::Throttled { retry_after, .. }) if retries > 0 => {
+                    retries -= 1;
+                    self.wait(function, retry_after, retries).await;
+                }
+                result => break result,
+            }
+        };
+        let result = self.verify_key(result);
+        if let Some(observer) = &self.observer {
+            observer.on_response(function, started.elapsed(), result.as_ref().err());
+        }
+        self.stats
+            .response(function, result.as_ref().map(|body| body.as_slice()));
+        let size = result.as_ref().map_or(0, |body| body.len());
+        self.audit(
+            trail,
+            function,
+            Disposition::Fetched,
+            size,
+            result.as_ref().err(),
+        );
+        let body = result?;
+        if let Some(cache) = &self.cache {
+            cache.insert(cache_key, body.clone());
+        }
+        Ok(body)
+    }
+
+    /// Wait for a slot of the rate limit, if any, to issue a request for `function`.
+    async fn acquire(&self, function: &str) {
+        let limiter = match &self.limiter {
+            Some(limiter) => limiter,
+            None => return,
+        };
+        let ticket = limiter.ticket(self.priority);
+        let mut waited = Duration::from_secs(0);
+        let used = loop {
+            match ticket.try_acquire() {
+                Ok(used) => break used,
+                Err(wait) => {
+                    self.backoff(function, wait, Backoff::RateLimit);
+                    tokio::time::delay_for(wait).await;
+                    waited += wait;
+                }
+            }
+        };
+        if waited > Duration::from_secs(0) {
+            self.record_wait(function, waited);
+        }
+        let limit = limiter.limit();
+        if used == limit.warning_threshold() {
+            if let Some(observer) = &self.observer {
+                observer.on_quota_warning(function, used, limit);
+            }
+        }
+    }
+
+    /// Wait for `duration` before retrying a throttled request for `function`, with `retries`
+    /// left afterwards.
+    async fn wait(&self, function: &str, duration: Duration, retries: u32) {
+        let reason = Backoff::Throttled {
+            retries_left: retries,
+        };
+        self.backoff(function, duration, reason);
+        tokio::time::delay_for(duration).await;
+        self.record_wait(function, duration);
+    }
+
+    fn backoff(&self, function: &str, wait: Duration, reason: Backoff) {
+        if let Some(observer) = &self.observer {
+            observer.on_backoff(function, wait, reason);
+        }
+    }
+
+    fn record_wait(&self, function: &str, waited: Duration) {
+        self.stats.rate_limit_wait(waited);
+        if let Some(observer) = &self.observer {
+            observer.on_rate_limit_wait(function, waited);
+        }
+    }
+
+    /// Replace `job` by a job for the replacement of its function if the API deprecated it,
+    /// notifying the observer.
+    fn migrate(&self, job: &planner::Job) -> Result<planner::Job, Error> {
+        match job.migrate()? {
+            Some((replacement, deprecation)) => {
+                if let Some(observer) = &self.observer {
+                    observer.on_deprecated(&job.function, deprecation);
+                }
+                Ok(replacement)
+            }
+            None => Ok(job.clone()),
+        }
+    }
+
+    /// Fail with [`Error::PremiumRequired`] if the client is configured with a tier which can't
+    /// call `function`.
+    fn check_tier(&self, function: &str) -> Result<(), Error> {
+        match self.tier {
+            Some(tier) if !tier.can_access(&Function::from(function)) => {
+                Err(Error::PremiumRequired(function.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether a request for `function` could be issued right away, within the tier and the
+    /// remaining quota of the rate limit of the client.
+    fn can_issue(&self, function: &str) -> bool {
+        self.check_tier(function).is_ok()
+            && self
+                .limiter
+                .as_ref()
+                .is_none_or(|limiter| limiter.available())
+    }
+
+    /// Record the call to `function` tracked by `trail` in the audit log, if any.
+    fn audit(
+        &self,
+        trail: Trail,
+        function: &str,
+        disposition: Disposition,
+        size: usize,
+        error: Option<&Error>,
+    ) {
+        if let Some(audit_log) = &self.audit_log {
+            audit_log.record(&trail.finish(function, disposition, size, error));
+        }
+    }
+
+    /// Classify errors returned until a response is successfully received as
+    /// [`Error::InvalidApiKey`] when they report an invalid key, if configured to.
+    fn verify_key(&self, result: Result<Vec<u8>, Error>) -> Result<Vec<u8>, Error> {
+        if !self.verify_key || self.key_verified.load(Ordering::Relaxed) {
+            return result;
+        }
+        match result {
+            Ok(body) => {
+                self.key_verified.store(true, Ordering::Relaxed);
+                Ok(body)
+            }
+            Err(error) => Err(api::classify_key_error(error)),
+        }
+    }
+
+    /// Issue `request` to each of the configured endpoints in turn, until one of them can be
+    /// connected to, tracking the attempts in `trail`.
+    async fn execute<'a>(
+        &self,
+        request: &APIRequest<'a>,
+        trail: &mut Trail,
+    ) -> Result<Vec<u8>, Error> {
+        let mut result = Err(Error::ConnectionError("no endpoint configured".into()));
+        for endpoint in request.urls() {
+            trail.url = Some(request.redacted_url(endpoint).to_string());
+            trail.status = None;
+            trail.attempts += 1;
+            result = self.execute_at(request, endpoint, trail).await;
+            if let Err(Error::ConnectionError(_)) = result {
+                continue;
+            }
+            break;
+        }
+        result
+    }
+
+    async fn execute_at<'a>(
+        &self,
+        request: &APIRequest<'a>,
+        endpoint: &Url,
+        trail: &mut Trail,
+    ) -> Result<Vec<u8>, Error> {
+        let response = self
+            .client
+            .execute(request.to_request(endpoint))
+            .await
+            .map_err(api::connection_error)?;
         let status = response.status();
+        trail.status = Some(status.as_u16());
         if status != reqwest::StatusCode::OK {
             return Err(Error::ServerError(status.as_u16()));
         }
-        let reader = Cursor::new(response.bytes().await?);
-        Ok(reader)
+        let body = response.bytes().await.map_err(api::connection_error)?;
+        api::check_response(&body)?;
+        Ok(body.to_vec())
+    }
+}
+
+/// Call `fetch` for each of `symbols` in concurrent batches, preserving their order.
+pub(crate) async fn fetch_batched<'a, T, F, R>(
+    symbols: &[&'a str],
+    fetch: F,
+) -> Result<Vec<T>, Error>
+where
+    F: Fn(&'a str) -> R,
+    R: Future<Output = Result<T, Error>>,
+{
+    let mut results = Vec::with_capacity(symbols.len());
+    for batch in symbols.chunks(BATCH_SIZE) {
+        let batch = try_join_all(batch.iter().map(|symbol| fetch(symbol))).await?;
+        results.extend(batch);
+    }
+    Ok(results)
+}
+
+/// A builder to configure a [`Client`](crate::Client).
+pub struct ClientBuilder {
+    pub(crate) key: String,
+    pub(crate) urls: Vec<Url>,
+    pub(crate) rate_limit: Option<RateLimit>,
+    pub(crate) tier: Option<Tier>,
+    pub(crate) cache: Option<ResponseCache>,
+    pub(crate) cache_keys: Arc<dyn KeyStrategy>,
+    pub(crate) observer: Option<Arc<dyn Observer>>,
+    pub(crate) audit_log: Option<Arc<dyn AuditLog>>,
+    pub(crate) parse_mode: ParseMode,
+    pub(crate) stale_after: Option<Duration>,
+    pub(crate) offline: bool,
+    pub(crate) stale_while_revalidate: bool,
+    pub(crate) validate_currencies: bool,
+    pub(crate) verify_key: bool,
+    pub(crate) throttle_retries: u32,
+    pub(crate) output_size: OutputSize,
+    pub(crate) blocking_parse_threshold: Option<usize>,
+}
+
+impl ClientBuilder {
+    fn new(key: &str) -> ClientBuilder {
+        ClientBuilder {
+            key: String::from(key),
+            urls: vec![api::default_url()],
+            rate_limit: None,
+            tier: None,
+            cache: None,
+            cache_keys: Arc::new(DefaultKeyStrategy),
+            observer: None,
+            audit_log: None,
+            parse_mode: ParseMode::Standard,
+            stale_after: None,
+            offline: false,
+            stale_while_revalidate: false,
+            validate_currencies: false,
+            verify_key: false,
+            throttle_retries: 0,
+            output_size: OutputSize::Compact,
+            blocking_parse_threshold: Some(BLOCKING_PARSE_THRESHOLD),
+        }
+    }
+
+    /// Issue requests to the given endpoint `urls` instead of the Alpha Vantage API, e.g. to go
+    /// through a proxy or mirror.
+    ///
+    /// Each request is issued to the first endpoint that can be connected to, in order. The
+    /// default endpoint is only used if included in `urls`.
+    pub fn urls(mut self, urls: &[Url]) -> ClientBuilder {
+        if !urls.is_empty() {
+            self.urls = urls.to_vec();
+        }
+        self
+    }
+
+    /// Fall back to the given endpoint `url` when the previously configured endpoints can't be
+    /// connected to.
+    pub fn fallback_url(mut self, url: Url) -> ClientBuilder {
+        self.urls.push(url);
+        self
+    }
+
+    /// Limit the rate at which the client issues requests to the API.
+    ///
+    /// Requests exceeding the limit are delayed until they can be issued.
+    pub fn rate_limit(mut self, limit: RateLimit) -> ClientBuilder {
+        self.rate_limit = Some(limit);
+        self
+    }
+
+    /// Configure the client for an account of the given `tier`, limiting its rate to the tier's
+    /// unless a rate limit is set explicitly.
+    ///
+    /// Requests for functions the tier can't call fail with [`Error::PremiumRequired`] without
+    /// being issued, though they can still be served from the cache.
+    pub fn tier(mut self, tier: Tier) -> ClientBuilder {
+        self.tier = Some(tier);
+        self
+    }
+
+    /// Serve responses from `cache` for up to `ttl` after they were retrieved, instead of
+    /// issuing the same request to the API again.
+    pub fn cache(mut self, cache: impl Cache + 'static, ttl: Duration) -> ClientBuilder {
+        self.cache = Some(ResponseCache::new(Arc::new(cache), ttl));
+        self
+    }
+
+    /// Compute the keys identifying requests in the cache with `strategy`, e.g. to serve the
+    /// same responses to requests differing only by the case of their symbols.
+    pub fn cache_keys(mut self, strategy: impl KeyStrategy + 'static) -> ClientBuilder {
+        self.cache_keys = Arc::new(strategy);
+        self
+    }
+
+    /// Serve responses exclusively from the cache, regardless of their age, when `offline`.
+    ///
+    /// An offline client never issues requests to the API, failing with [`Error::Offline`]
+    /// when a response isn't cached instead, e.g. to run deterministic backtests.
+    pub fn offline(mut self, offline: bool) -> ClientBuilder {
+        self.offline = offline;
+        self
+    }
+
+    /// Serve responses from the cache after their time to live when `revalidate` is set,
+    /// refreshing them in the background so later calls are served fresh responses.
+    ///
+    /// Only a request missing from the cache waits for the API. Responses served past their time
+    /// to live are reported as stale by `with_metadata`. Background refreshes are subject to the
+    /// rate limit, and the async client issues them as tasks on the current tokio runtime.
+    pub fn stale_while_revalidate(mut self, revalidate: bool) -> ClientBuilder {
+        self.stale_while_revalidate = revalidate;
+        self
+    }
+
+    /// Check that the currencies of exchange rate and digital currency requests are listed by
+    /// the API before issuing them when `validate` is set, failing with
+    /// [`Error::UnknownCurrency`] otherwise. Markets must be physical currencies.
+    ///
+    /// The lists of digital and physical currencies are retrieved by the first request
+    /// validated, then shared by the client and its clones.
+    pub fn validate_currencies(mut self, validate: bool) -> ClientBuilder {
+        self.validate_currencies = validate;
+        self
+    }
+
+    /// Classify the errors returned until a first response is successfully received as
+    /// [`Error::InvalidApiKey`] when `verify` is set and they report the key to be invalid, so a
+    /// misconfigured key fails loudly instead of surfacing as an [`Error::APIError`].
+    ///
+    /// Malformed keys always fail with [`Error::InvalidApiKey`] without being issued.
+    pub fn verify_key_on_first_use(mut self, verify: bool) -> ClientBuilder {
+        self.verify_key = verify;
+        self
+    }
+
+    /// Retry requests throttled by the API up to `retries` times, waiting until the start of the
+    /// next minute, or day once the daily quota is exceeded, plus some jitter. Not retried by
+    /// default, failing with [`Error::Throttled`] instead.
+    pub fn retry_throttled(mut self, retries: u32) -> ClientBuilder {
+        self.throttle_retries = retries;
+        self
+    }
+
+    /// Notify `observer` about the requests issued by the client.
+    pub fn observer(mut self, observer: impl Observer + 'static) -> ClientBuilder {
+        self.observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Record the calls made by the client and its clones in `audit_log`, including those
+    /// served from the cache, with the API key redacted from the URLs recorded.
+    pub fn audit_log(mut self, audit_log: impl AuditLog + 'static) -> ClientBuilder {
+        self.audit_log = Some(Arc::new(audit_log));
+        self
+    }
+
+    /// Set how strictly responses are parsed. [`ParseMode::Standard`] by default.
+    pub fn parse_mode(mut self, mode: ParseMode) -> ClientBuilder {
+        self.parse_mode = mode;
+        self
+    }
+
+    /// Warn about time series and exchange rates last refreshed longer than `max_age` ago,
+    /// reporting them as [`ParseWarningKind::StaleData`](crate::ParseWarningKind)
+    /// warnings to the observer and on the parsed results, in every parse mode.
+    pub fn stale_after(mut self, max_age: Duration) -> ClientBuilder {
+        self.stale_after = Some(max_age);
+        self
+    }
+
+    /// Set the output size used when retrieving time series. Compact by default.
+    pub fn output_size(mut self, output_size: OutputSize) -> ClientBuilder {
+        self.output_size = output_size;
+        self
+    }
+
+    /// Parse responses of at least `threshold` bytes on the blocking thread pool, so parsing
+    /// large payloads, such as full time series, doesn't stall the runtime, or parse every
+    /// response on the runtime if `threshold` is `None`. 1 MiB by default.
+    ///
+    /// The blocking client parses every response on the calling thread.
+    pub fn blocking_parse_threshold(mut self, threshold: Option<usize>) -> ClientBuilder {
+        self.blocking_parse_threshold = threshold;
+        self
+    }
+
+    /// Create a client using the current configuration.
+    pub fn build(self) -> Client {
+        let cache_keys = self.cache_keys;
+        let tier = self.tier;
+        Client {
+            builder: APIRequestBuilder::new(&self.key, self.urls),
+            client: reqwest::Client::new(),
+            limiter: self
+                .rate_limit
+                .or_else(|| tier.map(Tier::rate_limit))
+                .map(|limit| Arc::new(RateLimiter::new(limit))),
+            priority: Priority::default(),
+            tier,
+            cache: self.cache.map(|cache| cache.with_keys(cache_keys)),
+            observer: self.observer,
+            audit_log: self.audit_log,
+            parse_mode: self.parse_mode,
+            stale_after: self.stale_after,
+            offline: self.offline,
+            validate_currencies: self.validate_currencies,
+            verify_key: self.verify_key,
+            throttle_retries: self.throttle_retries,
+            known_currencies: Arc::new(Mutex::new(None)),
+            stats: Arc::new(stats::Recorder::default()),
+            key_verified: Arc::new(AtomicBool::new(false)),
+            capture: None,
+            refreshes: self
+                .stale_while_revalidate
+                .then(|| Arc::new(Refreshes::default())),
+            output_size: self.output_size,
+            blocking_parse_threshold: self.blocking_parse_threshold,
+        }
+    }
+
+    /// Create a blocking client using the current configuration.
+    #[cfg(feature = "blocking")]
+    pub fn build_blocking(self) -> crate::blocking::Client {
+        crate::blocking::Client::from_builder(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::MemoryCache;
+
+    #[tokio::test]
+    async fn blocking_parse() {
+        let cache = Arc::new(MemoryCache::new());
+        let body = include_bytes!("../tests/json/time_series_daily.json").to_vec();
+        cache.insert(
+            "TIME_SERIES_DAILY?outputsize=compact&symbol=MSFT",
+            CacheEntry::new(body.clone()),
+        );
+        for threshold in &[Some(0), Some(body.len() + 1), None] {
+            let client = Client::builder("key")
+                .cache(cache.clone(), Duration::from_secs(60))
+                .offline(true)
+                .blocking_parse_threshold(*threshold)
+                .build();
+            let time_series = client.get_time_series_daily("MSFT").await.unwrap();
+            assert_eq!(time_series.symbol, "MSFT");
+        }
     }
 }
@@ -0,0 +1,251 @@
+//! Configuration of a client from the configuration files of an application
+//!
+//! A [`ClientConfig`] can be deserialized from any format supported by `serde`, e.g. a section
+//! of a TOML file:
+//!
+//! ```toml
+//! [alphavantage]
+//! key = { env = "ALPHAVANTAGE_TOKEN" }
+//! tier = "free"
+//! retry_throttled = 2
+//!
+//! [alphavantage.cache]
+//! ttl_secs = 3600
+//! compressed = true
+//! ```
+//!
+//! ```no_run
+//! # fn run(config: alphavantage::config::ClientConfig) -> Result<(), alphavantage::Error> {
+//! let client = config.build()?;
+//! # Ok(())
+//! # }
+//! ```
+use crate::cache::{self, Cache, CompressedCache, MemoryCache};
+use crate::client::{Client, ClientBuilder};
+use crate::rate_limit::{RateLimit, Tier};
+use crate::time_series::OutputSize;
+use crate::Error;
+use crate::ParseMode;
+use reqwest::Url;
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Environment variable the API key is read from by default.
+const DEFAULT_KEY_ENV: &str = "ALPHAVANTAGE_TOKEN";
+
+/// Configuration of a [`Client`], mirroring the settings of a [`ClientBuilder`].
+///
+/// Every field is optional, leaving the corresponding setting to its default.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClientConfig {
+    /// Where to read the API key from, the `ALPHAVANTAGE_TOKEN` environment variable by
+    /// default.
+    pub key: KeySource,
+    /// Endpoints to issue requests to, in order, the Alpha Vantage API by default.
+    pub urls: Vec<String>,
+    /// Tier of the account, see [`ClientBuilder::tier`].
+    pub tier: Option<Tier>,
+    /// Rate limit, see [`ClientBuilder::rate_limit`].
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Cache of responses, none by default.
+    pub cache: Option<CacheConfig>,
+    /// Number of times throttled requests are retried, see [`ClientBuilder::retry_throttled`].
+    pub retry_throttled: u32,
+    /// How strictly responses are parsed.
+    pub parse_mode: ParseMode,
+    /// Output size used when retrieving time series.
+    pub output_size: Option<OutputSize>,
+}
+
+/// Source of an API key.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    /// The key itself.
+    Key(String),
+    /// Name of an environment variable holding the key.
+    Env(String),
+    /// Path of a file holding the key, ignoring surrounding whitespace.
+    File(PathBuf),
+}
+
+impl Default for KeySource {
+    fn default() -> KeySource {
+        KeySource::Env(DEFAULT_KEY_ENV.to_string())
+    }
+}
+
+impl KeySource {
+    /// Read the key, failing with [`Error::InvalidApiKey`] if the environment variable isn't
+    /// set or with [`Error::IOError`] if the file can't be read.
+    pub fn read(&self) -> Result<String, Error> {
+        match self {
+            KeySource::Key(key) => Ok(key.clone()),
+            KeySource::Env(name) => std::env::var(name).map_err(|_| {
+                Error::InvalidApiKey(format!("environment variable {} isn't set", name))
+            }),
+            KeySource::File(path) => Ok(fs::read_to_string(path)?.trim().to_string()),
+        }
+    }
+}
+
+/// Configuration of a [`RateLimit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitConfig {
+    /// Number of requests allowed per period.
+    pub requests: u32,
+    /// Length of the period in seconds, a minute by default.
+    #[serde(default = "default_period_secs")]
+    pub period_secs: u64,
+}
+
+fn default_period_secs() -> u64 {
+    60
+}
+
+impl From<RateLimitConfig> for RateLimit {
+    fn from(config: RateLimitConfig) -> RateLimit {
+        RateLimit::new(config.requests, Duration::from_secs(config.period_secs))
+    }
+}
+
+/// Configuration of an in-memory cache of responses.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CacheConfig {
+    /// Time to live of the cached responses, in seconds.
+    pub ttl_secs: u64,
+    /// Whether responses are stored gzip compressed, see [`CompressedCache`].
+    #[serde(default)]
+    pub compressed: bool,
+    /// Directory of responses previously exported with [`cache::export`] to load into the cache.
+    #[serde(default)]
+    pub import: Option<PathBuf>,
+    /// Whether to serve responses from the cache only, see [`ClientBuilder::offline`].
+    #[serde(default)]
+    pub offline: bool,
+    /// Whether to refresh stale responses in the background, see
+    /// [`ClientBuilder::stale_while_revalidate`].
+    #[serde(default)]
+    pub stale_while_revalidate: bool,
+}
+
+impl ClientConfig {
+    /// Create a builder configured accordingly, reading the API key and importing cached
+    /// responses.
+    ///
+    /// Fails with [`Error::InvalidParams`] if a URL is malformed.
+    pub fn builder(&self) -> Result<ClientBuilder, Error> {
+        let mut builder = Client::builder(&self.key.read()?)
+            .retry_throttled(self.retry_throttled)
+            .parse_mode(self.parse_mode);
+        if !self.urls.is_empty() {
+            let urls = self
+                .urls
+                .iter()
+                .map(|url| {
+                    Url::parse(url)
+                        .map_err(|e| Error::InvalidParams(format!("invalid URL {}: {}", url, e)))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            builder = builder.urls(&urls);
+        }
+        if let Some(tier) = self.tier {
+            builder = builder.tier(tier);
+        }
+        if let Some(rate_limit) = self.rate_limit {
+            builder = builder.rate_limit(rate_limit.into());
+        }
+        if let Some(output_size) = self.output_size {
+            builder = builder.output_size(output_size);
+        }
+        if let Some(config) = &self.cache {
+            let cache: Arc<dyn Cache> = if config.compressed {
+                Arc::new(CompressedCache::new(MemoryCache::new()))
+            } else {
+                Arc::new(MemoryCache::new())
+            };
+            if let Some(dir) = &config.import {
+                cache::import(cache.as_ref(), dir)?;
+            }
+            builder = builder
+                .cache(cache, Duration::from_secs(config.ttl_secs))
+                .offline(config.offline)
+                .stale_while_revalidate(config.stale_while_revalidate);
+        }
+        Ok(builder)
+    }
+
+    /// Create a client configured accordingly, see [`ClientConfig::builder`].
+    pub fn build(&self) -> Result<Client, Error> {
+        Ok(self.builder()?.build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        let config: ClientConfig = serde_json::from_str(
+            r#"{
+                "key": {"key": "demo"},
+                "urls": ["https://mirror.example.com/query"],
+                "tier": "premium75",
+                "rate_limit": {"requests": 30},
+                "cache": {"ttl_secs": 3600, "compressed": true},
+                "retry_throttled": 2,
+                "parse_mode": "lenient",
+                "output_size": "full"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.key, KeySource::Key("demo".to_string()));
+        assert_eq!(config.tier, Some(Tier::Premium75));
+        assert_eq!(
+            RateLimit::from(config.rate_limit.unwrap()),
+            RateLimit::per_minute(30)
+        );
+        assert_eq!(config.cache.as_ref().unwrap().ttl_secs, 3600);
+        assert!(config.cache.as_ref().unwrap().compressed);
+        assert_eq!(config.retry_throttled, 2);
+        assert_eq!(config.parse_mode, ParseMode::Lenient);
+        assert_eq!(config.output_size, Some(OutputSize::Full));
+        assert!(config.build().is_ok());
+
+        let config: ClientConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config, ClientConfig::default());
+        assert_eq!(config.key, KeySource::Env("ALPHAVANTAGE_TOKEN".to_string()));
+
+        assert!(serde_json::from_str::<ClientConfig>(r#"{"tier": "gold"}"#).is_err());
+        assert!(serde_json::from_str::<ClientConfig>(r#"{"retries": 2}"#).is_err());
+    }
+
+    #[test]
+    fn invalid_settings() {
+        let config = ClientConfig {
+            key: KeySource::Env("ALPHAVANTAGE_CONFIG_TEST_UNSET".to_string()),
+            ..ClientConfig::default()
+        };
+        match config.build() {
+            Err(Error::InvalidApiKey(_)) => {}
+            result => panic!("unexpected result: {:?}", result.err()),
+        }
+
+        let config = ClientConfig {
+            key: KeySource::Key("demo".to_string()),
+            urls: vec!["not a url".to_string()],
+            ..ClientConfig::default()
+        };
+        match config.build() {
+            Err(Error::InvalidParams(_)) => {}
+            result => panic!("unexpected result: {:?}", result.err()),
+        }
+    }
+}
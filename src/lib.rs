@@ -4,22 +4,90 @@
 //!
 //! Currently supports the following operations:
 //! - [TIME_SERIES_INTRADAY](https://www.alphavantage.co/documentation/#intraday)
+//! - [TIME_SERIES_INTRADAY_EXTENDED](https://www.alphavantage.co/documentation/#intraday-extended)
 //! - [TIME_SERIES_DAILY](https://www.alphavantage.co/documentation/#daily)
+//! - [TIME_SERIES_DAILY_ADJUSTED](https://www.alphavantage.co/documentation/#dailyadj)
 //! - [TIME_SERIES_WEEKLY](https://www.alphavantage.co/documentation/#weekly)
+//! - [TIME_SERIES_WEEKLY_ADJUSTED](https://www.alphavantage.co/documentation/#weeklyadj)
 //! - [TIME_SERIES_MONTHLY](https://www.alphavantage.co/documentation/#monthly)
+//! - [TIME_SERIES_MONTHLY_ADJUSTED](https://www.alphavantage.co/documentation/#monthlyadj)
 //! - [CURRENCY_EXCHANGE_RATE](https://www.alphavantage.co/documentation/#crypto-exchange)
+//! - [CRYPTO_INTRADAY](https://www.alphavantage.co/documentation/#crypto-intraday)
+//! - [DIGITAL_CURRENCY_DAILY](https://www.alphavantage.co/documentation/#currency-daily)
+//! - [DIGITAL_CURRENCY_WEEKLY](https://www.alphavantage.co/documentation/#currency-weekly)
+//! - [DIGITAL_CURRENCY_MONTHLY](https://www.alphavantage.co/documentation/#currency-monthly)
+//! - [FX_INTRADAY](https://www.alphavantage.co/documentation/#fx-intraday)
+//! - [FX_DAILY](https://www.alphavantage.co/documentation/#fx-daily)
+//! - [FX_WEEKLY](https://www.alphavantage.co/documentation/#fx-weekly)
+//! - [FX_MONTHLY](https://www.alphavantage.co/documentation/#fx-monthly)
+//! - [GLOBAL_QUOTE](https://www.alphavantage.co/documentation/#latestprice)
+//! - [REALTIME_BULK_QUOTES](https://www.alphavantage.co/documentation/#realtime-bulk-quotes)
+//! - [SYMBOL_SEARCH](https://www.alphavantage.co/documentation/#symbolsearch)
+//! - [OVERVIEW](https://www.alphavantage.co/documentation/#company-overview)
+//! - [ETF_PROFILE](https://www.alphavantage.co/documentation/#etf-profile)
+//! - [DIVIDENDS](https://www.alphavantage.co/documentation/#dividends)
+//! - [SPLITS](https://www.alphavantage.co/documentation/#splits)
+//! - [EARNINGS](https://www.alphavantage.co/documentation/#earnings)
+//! - [EARNINGS_ESTIMATES](https://www.alphavantage.co/documentation/#earnings-estimates)
+//! - [EARNINGS_CALENDAR](https://www.alphavantage.co/documentation/#earnings-calendar)
+//! - [IPO_CALENDAR](https://www.alphavantage.co/documentation/#ipo-calendar)
+//! - [INCOME_STATEMENT](https://www.alphavantage.co/documentation/#income-statement)
+//! - [CASH_FLOW](https://www.alphavantage.co/documentation/#cash-flow)
+//! - [WTI](https://www.alphavantage.co/documentation/#wti)
+//! - [BRENT](https://www.alphavantage.co/documentation/#brent)
+//! - [NATURAL_GAS](https://www.alphavantage.co/documentation/#natural-gas)
+//! - [COPPER](https://www.alphavantage.co/documentation/#copper)
+//! - [ALUMINUM](https://www.alphavantage.co/documentation/#aluminum)
+//! - [WHEAT](https://www.alphavantage.co/documentation/#wheat)
+//! - [CORN](https://www.alphavantage.co/documentation/#corn)
+//! - [COTTON](https://www.alphavantage.co/documentation/#cotton)
+//! - [SUGAR](https://www.alphavantage.co/documentation/#sugar)
+//! - [COFFEE](https://www.alphavantage.co/documentation/#coffee)
+//! - [TREASURY_YIELD](https://www.alphavantage.co/documentation/#treasury-yield)
+//! - [FEDERAL_FUNDS_RATE](https://www.alphavantage.co/documentation/#interest-rate)
+//! - [REAL_GDP](https://www.alphavantage.co/documentation/#real-gdp)
+//! - [CPI](https://www.alphavantage.co/documentation/#cpi)
+//! - [ANALYTICS_FIXED_WINDOW](https://www.alphavantage.co/documentation/#analytics-fixed-window)
+//! - [ANALYTICS_SLIDING_WINDOW](https://www.alphavantage.co/documentation/#analytics-sliding-window)
+//! - [TOP_GAINERS_LOSERS](https://www.alphavantage.co/documentation/#gainer-loser)
+//! - [LISTING_STATUS](https://www.alphavantage.co/documentation/#listing-status)
+//! - [NEWS_SENTIMENT](https://www.alphavantage.co/documentation/#news-sentiment)
 //!
 //! The default [`alphavantage::Client`](client) is asynchronous but a
 //! blocking client is also available through the optional `blocking` feature.
+//!
+//! Response types and their parsers are provided by the `alphavantage-types` crate, which
+//! doesn't depend on any HTTP client or async runtime, and are re-exported here.
 
 mod api;
 mod client;
-mod deserialize;
-mod error;
+mod rate_limit;
+
+use alphavantage_types::Parser;
 
+pub mod audit;
 #[cfg(feature = "blocking")]
 pub mod blocking;
-pub mod exchange_rate;
-pub mod time_series;
-pub use crate::client::Client;
-pub use crate::error::Error;
+pub mod cache;
+pub mod config;
+pub mod observer;
+pub mod planner;
+pub mod portfolio;
+pub mod request;
+pub mod response;
+pub mod search;
+pub mod stats;
+pub mod store;
+pub use crate::client::{Client, ClientBuilder};
+pub use crate::rate_limit::{Priority, RateLimit, Tier};
+#[cfg(feature = "test-util")]
+pub use alphavantage_types::approx;
+pub use alphavantage_types::{
+    analytics, asset, calendar, commodities, corporate_actions, crypto, currencies, earnings,
+    economic, etf, exchange_rate, forex, format, fundamentals, listing, market_movers, news,
+    options, patterns, quote, rate, returns, snapshot, symbol, time_series,
+};
+pub use alphavantage_types::{
+    Deprecation, Error, Function, ParseMode, ParseWarning, ParseWarningKind,
+};
+pub use reqwest::Url;
@@ -0,0 +1,182 @@
+//! Observation of the requests issued by a client
+use crate::rate_limit::RateLimit;
+use crate::Deprecation;
+use crate::Error;
+use crate::ParseWarning;
+use std::time::Duration;
+
+/// Represents the reason a client sleeps before issuing a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Backoff {
+    /// Waiting for a slot of the rate limit of the client.
+    RateLimit,
+    /// Waiting to retry a request throttled by the API, with the number of retries left after
+    /// this one.
+    Throttled {
+        /// Retries left once the request is retried.
+        retries_left: u32,
+    },
+}
+
+/// Receives events about the requests issued by a client, e.g. to record metrics.
+///
+/// Every method does nothing by default, so implementations only need to handle the events
+/// they're interested in. Methods are called synchronously while requests are issued and
+/// should return quickly.
+pub trait Observer: Send + Sync {
+    /// Called when a request for `function` is served from the cache.
+    fn on_cache_hit(&self, _function: &str) {}
+
+    /// Called when a request for `function` is delayed by `wait` to respect the rate limit.
+    fn on_rate_limit_wait(&self, _function: &str, _wait: Duration) {}
+
+    /// Called before the client sleeps for `wait` ahead of a request for `function`, with the
+    /// `reason` it backs off for, e.g. to report the progress of long running jobs instead of
+    /// appearing hung.
+    ///
+    /// A request waiting for the rate limit can back off several times when other requests take
+    /// the slot it was waiting for first. The total time it waited is reported to
+    /// [`Observer::on_rate_limit_wait`] once it goes ahead.
+    fn on_backoff(&self, _function: &str, _wait: Duration, _reason: Backoff) {}
+
+    /// Called when a request for `function` completes after `latency`, with the `error` it
+    /// failed with, if any.
+    fn on_response(&self, _function: &str, _latency: Duration, _error: Option<&Error>) {}
+
+    /// Called with the `warnings` raised while parsing a response to `function`, e.g. fields
+    /// which aren't captured by the parsed types yet or stale data.
+    fn on_parse_warnings(&self, _function: &str, _warnings: &[ParseWarning]) {}
+
+    /// Called when a request for `function` brings the number of requests issued over the
+    /// period of the rate `limit` to its [`RateLimit::warning_threshold`], e.g. to slow down
+    /// bulk jobs before the daily quota of the free tier runs out.
+    fn on_quota_warning(&self, _function: &str, _used: u32, _limit: RateLimit) {}
+
+    /// Called when a request for `function`, which the API deprecated, is issued for its
+    /// replacement instead, e.g. to log the `deprecation` until callers are updated.
+    fn on_deprecated(&self, _function: &str, _deprecation: &Deprecation) {}
+}
+
+#[cfg(feature = "prometheus")]
+pub use self::prometheus_observer::PrometheusObserver;
+
+#[cfg(feature = "prometheus")]
+mod prometheus_observer {
+    use super::*;
+    use prometheus::{Histogram, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry};
+
+    /// An observer exporting request counts, error counts by class, latencies and rate limit
+    /// wait times as Prometheus metrics.
+    ///
+    /// Every metric is prefixed with `alphavantage_` and, except for rate limit wait times,
+    /// labelled with the API function called.
+    #[derive(Clone)]
+    pub struct PrometheusObserver {
+        requests: IntCounterVec,
+        errors: IntCounterVec,
+        cache_hits: IntCounterVec,
+        latency: HistogramVec,
+        rate_limit_wait: Histogram,
+    }
+
+    impl PrometheusObserver {
+        /// Create an observer registering its metrics with `registry`.
+        pub fn new(registry: &Registry) -> Result<PrometheusObserver, prometheus::Error> {
+            let observer = PrometheusObserver {
+                requests: IntCounterVec::new(
+                    Opts::new("alphavantage_requests_total", "Requests issued to the API"),
+                    &["function"],
+                )?,
+                errors: IntCounterVec::new(
+                    Opts::new("alphavantage_errors_total", "Requests which failed"),
+                    &["function", "class"],
+                )?,
+                cache_hits: IntCounterVec::new(
+                    Opts::new(
+                        "alphavantage_cache_hits_total",
+                        "Requests served from the cache",
+                    ),
+                    &["function"],
+                )?,
+                latency: HistogramVec::new(
+                    HistogramOpts::new(
+                        "alphavantage_request_duration_seconds",
+                        "Time taken by the API to respond",
+                    ),
+                    &["function"],
+                )?,
+                rate_limit_wait: Histogram::with_opts(
+                    HistogramOpts::new(
+                        "alphavantage_rate_limit_wait_seconds",
+                        "Time requests were delayed to respect the rate limit",
+                    )
+                    .buckets(vec![0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0]),
+                )?,
+            };
+            registry.register(Box::new(observer.requests.clone()))?;
+            registry.register(Box::new(observer.errors.clone()))?;
+            registry.register(Box::new(observer.cache_hits.clone()))?;
+            registry.register(Box::new(observer.latency.clone()))?;
+            registry.register(Box::new(observer.rate_limit_wait.clone()))?;
+            Ok(observer)
+        }
+    }
+
+    impl Observer for PrometheusObserver {
+        fn on_cache_hit(&self, function: &str) {
+            self.cache_hits.with_label_values(&[function]).inc();
+        }
+
+        fn on_rate_limit_wait(&self, _function: &str, wait: Duration) {
+            self.rate_limit_wait.observe(wait.as_secs_f64());
+        }
+
+        fn on_response(&self, function: &str, latency: Duration, error: Option<&Error>) {
+            self.requests.with_label_values(&[function]).inc();
+            self.latency
+                .with_label_values(&[function])
+                .observe(latency.as_secs_f64());
+            if let Some(error) = error {
+                self.errors
+                    .with_label_values(&[function, error.class()])
+                    .inc();
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "prometheus"))]
+mod tests {
+    use super::*;
+    use prometheus::{Encoder, Registry, TextEncoder};
+
+    #[test]
+    fn prometheus_observer() {
+        let registry = Registry::new();
+        let observer = PrometheusObserver::new(&registry).unwrap();
+        observer.on_cache_hit("GLOBAL_QUOTE");
+        observer.on_rate_limit_wait("GLOBAL_QUOTE", Duration::from_millis(1500));
+        observer.on_response("GLOBAL_QUOTE", Duration::from_millis(200), None);
+        observer.on_response(
+            "GLOBAL_QUOTE",
+            Duration::from_millis(300),
+            Some(&Error::ServerError(503)),
+        );
+
+        let mut buffer = vec![];
+        TextEncoder::new()
+            .encode(&registry.gather(), &mut buffer)
+            .unwrap();
+        let metrics = String::from_utf8(buffer).unwrap();
+        assert!(metrics.contains("alphavantage_requests_total{function=\"GLOBAL_QUOTE\"} 2"));
+        assert!(metrics
+            .contains("alphavantage_errors_total{class=\"server\",function=\"GLOBAL_QUOTE\"} 1"));
+        assert!(metrics.contains("alphavantage_cache_hits_total{function=\"GLOBAL_QUOTE\"} 1"));
+        assert!(metrics.contains("alphavantage_rate_limit_wait_seconds_count 1"));
+        assert!(metrics
+            .contains("alphavantage_request_duration_seconds_count{function=\"GLOBAL_QUOTE\"} 2"));
+
+        assert!(PrometheusObserver::new(&registry).is_err());
+    }
+}
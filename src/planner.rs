@@ -0,0 +1,273 @@
+//! Planning of batches of requests against the rate limit and quota of a client
+//!
+//! A scheduled job can check that its requests fit in the remaining quota of the client before
+//! issuing any of them:
+//!
+//! ```no_run
+//! use alphavantage::planner::Job;
+//! use alphavantage::{Client, Tier};
+//!
+//! # fn run() -> Result<(), alphavantage::Error> {
+//! let client = Client::builder("MY_SECRET_TOKEN").tier(Tier::Free).build();
+//! let jobs: Vec<Job> = ["IBM", "MSFT", "AAPL"]
+//!     .iter()
+//!     .map(|symbol| Job::new("TIME_SERIES_DAILY", &[("symbol", symbol)]))
+//!     .collect();
+//! let plan = client.plan(&jobs)?;
+//! println!("{} requests, issued in {:?}", plan.cost(), plan.duration);
+//! # Ok(())
+//! # }
+//! ```
+use crate::cache::{DefaultKeyStrategy, KeyStrategy};
+use crate::rate_limit::RateLimiter;
+use crate::request::Request;
+use crate::Error;
+use crate::{Deprecation, Function};
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// Period from which a rate limit is a quota, whose exhaustion fails a plan rather than
+/// delaying it.
+const QUOTA_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Represents a request to plan, identified by its function and parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Job {
+    /// Function called by the request.
+    pub function: String,
+    /// Parameters of the request, excluding the function and API key.
+    pub params: Vec<(String, String)>,
+}
+
+impl Job {
+    /// Create a job calling `function` with `params`.
+    pub fn new(function: &str, params: &[(&str, &str)]) -> Job {
+        Job {
+            function: function.to_string(),
+            params: params
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Create a job issuing a typed `request`, e.g. a
+    /// [`DailyRequest`](crate::request::DailyRequest).
+    pub fn from_request<R: Request>(request: &R) -> Job {
+        Job {
+            function: request.function().as_str().to_string(),
+            params: request
+                .params()
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+        }
+    }
+
+    /// Job calling the replacement of the function, along with its deprecation, if the API
+    /// deprecated or renamed it.
+    ///
+    /// Fails with [`Error::InvalidParams`] if the function was removed without any replacement.
+    pub fn migrate(&self) -> Result<Option<(Job, &'static Deprecation)>, Error> {
+        let deprecation = match Function::deprecation(&self.function) {
+            Some(deprecation) => deprecation,
+            None => return Ok(None),
+        };
+        match &deprecation.replacement {
+            Some(replacement) => {
+                let job = Job::new(replacement.as_str(), &deprecation.params(&self.params()));
+                Ok(Some((job, deprecation)))
+            }
+            None => Err(Error::InvalidParams(deprecation.to_string())),
+        }
+    }
+
+    pub(crate) fn params(&self) -> Vec<(&str, &str)> {
+        self.params
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect()
+    }
+}
+
+/// Represents how a batch of jobs would be executed by a client.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    /// Jobs requiring a request to the API, in order.
+    pub requests: Vec<Job>,
+    /// Jobs served from the cache, including repeated jobs when the client has a cache.
+    pub cached: Vec<Job>,
+    /// Estimated time to issue every request within the rate limit of the client.
+    pub duration: Duration,
+    /// Number of requests left over the current period of the rate limit once every request is
+    /// issued, `None` without a rate limit.
+    pub remaining: Option<u32>,
+}
+
+impl Plan {
+    /// Number of requests to the API, i.e. the quota used by the plan.
+    pub fn cost(&self) -> u32 {
+        self.requests.len() as u32
+    }
+}
+
+/// Plan `jobs` for a client with the given rate `limiter`, where `check` validates each job and
+/// returns whether it's cached.
+///
+/// Fails with [`Error::QuotaExceeded`] if the requests exceed the remaining quota of a daily or
+/// longer rate limit, e.g. on the free tier.
+pub(crate) fn plan(
+    jobs: &[Job],
+    limiter: Option<&RateLimiter>,
+    has_cache: bool,
+    mut check: impl FnMut(&Job) -> Result<bool, Error>,
+) -> Result<Plan, Error> {
+    let mut seen = HashSet::new();
+    let mut requests = vec![];
+    let mut cached = vec![];
+    for job in jobs {
+        let cache_hit = check(job)?;
+        let repeated = !seen.insert(DefaultKeyStrategy.key(&job.function, &job.params()));
+        if cache_hit || (repeated && has_cache) {
+            cached.push(job.clone());
+        } else {
+            requests.push(job.clone());
+        }
+    }
+    let required = requests.len() as u32;
+    let (duration, remaining) = match limiter {
+        Some(limiter) => {
+            let limit = limiter.limit();
+            let remaining = limiter.remaining();
+            if limit.period >= QUOTA_PERIOD && required > remaining {
+                return Err(Error::QuotaExceeded {
+                    required,
+                    remaining,
+                });
+            }
+            // Requests beyond the remaining slots wait for whole periods of the limit.
+            let overflow = required.saturating_sub(remaining);
+            let periods = overflow.div_ceil(limit.requests.max(1));
+            let left = match overflow {
+                0 => remaining - required,
+                _ => periods * limit.requests - overflow,
+            };
+            (limit.period * periods, Some(left))
+        }
+        None => (Duration::ZERO, None),
+    };
+    Ok(Plan {
+        requests,
+        cached,
+        duration,
+        remaining,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::{Cache, CacheEntry, MemoryCache};
+    use crate::client::Client;
+    use crate::rate_limit::{Priority, RateLimit, Tier};
+
+    fn jobs(symbols: &[&str]) -> Vec<Job> {
+        symbols
+            .iter()
+            .map(|symbol| Job::new("TIME_SERIES_DAILY", &[("symbol", symbol)]))
+            .collect()
+    }
+
+    #[test]
+    fn rate_limited() {
+        let limiter = RateLimiter::new(RateLimit::per_minute(2));
+        let _ = limiter.ticket(Priority::default()).try_acquire();
+        let plan = super::plan(&jobs(&["A", "B", "C", "D"]), Some(&limiter), false, |_| {
+            Ok(false)
+        })
+        .unwrap();
+        assert_eq!(plan.cost(), 4);
+        // One slot is left in the current minute, then two in each of the next two.
+        assert_eq!(plan.duration, Duration::from_secs(120));
+        assert_eq!(plan.remaining, Some(1));
+
+        let plan = super::plan(&jobs(&["A"]), Some(&limiter), false, |_| Ok(false)).unwrap();
+        assert_eq!(plan.duration, Duration::ZERO);
+        assert_eq!(plan.remaining, Some(0));
+    }
+
+    #[test]
+    fn repeated_jobs() {
+        let jobs = jobs(&["A", "B", "A"]);
+        let plan = super::plan(&jobs, None, true, |job| Ok(job.params[0].1 == "B")).unwrap();
+        assert_eq!(plan.requests, vec![jobs[0].clone()]);
+        assert_eq!(plan.cached, vec![jobs[1].clone(), jobs[2].clone()]);
+        assert_eq!(plan.remaining, None);
+
+        let plan = super::plan(&jobs, None, false, |_| Ok(false)).unwrap();
+        assert_eq!(plan.cost(), 3);
+    }
+
+    #[test]
+    fn client_plan() {
+        let cache = MemoryCache::new();
+        cache.insert(
+            "TIME_SERIES_DAILY?symbol=MSFT",
+            CacheEntry::new(b"{}".to_vec()),
+        );
+        let client = Client::builder("key")
+            .tier(Tier::Free)
+            .cache(cache, Duration::from_secs(60))
+            .build();
+        let symbols: Vec<String> = (0..25).map(|i| format!("S{}", i)).collect();
+        let mut symbols: Vec<&str> = symbols.iter().map(String::as_str).collect();
+        symbols.push("MSFT");
+        let plan = client.plan(&jobs(&symbols)).unwrap();
+        assert_eq!(plan.cost(), 25);
+        assert_eq!(plan.cached.len(), 1);
+        assert_eq!(plan.remaining, Some(0));
+
+        symbols.push("IBM");
+        match client.plan(&jobs(&symbols)) {
+            Err(Error::QuotaExceeded {
+                required: 26,
+                remaining: 25,
+            }) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        let premium = [Job::new("REALTIME_BULK_QUOTES", &[("symbol", "MSFT")])];
+        match client.plan(&premium) {
+            Err(Error::PremiumRequired(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+        let invalid = [Job::new("TIME_SERIES_DAILY", &[])];
+        match client.plan(&invalid) {
+            Err(Error::InvalidParams(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn deprecated_jobs() {
+        let client = Client::new("key");
+        let jobs = [
+            Job::new(
+                "DIGITAL_CURRENCY_INTRADAY",
+                &[("symbol", "ETH"), ("market", "USD")],
+            ),
+            Job::new("TIME_SERIES_DAILY", &[("symbol", "MSFT")]),
+        ];
+        let plan = client.plan(&jobs).unwrap();
+        let expected = Job::new(
+            "CRYPTO_INTRADAY",
+            &[("symbol", "ETH"), ("market", "USD"), ("interval", "5min")],
+        );
+        assert_eq!(plan.requests, vec![expected, jobs[1].clone()]);
+
+        match client.plan(&[Job::new("SECTOR", &[])]) {
+            Err(Error::InvalidParams(message)) => assert!(message.starts_with("SECTOR")),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+}
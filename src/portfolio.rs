@@ -0,0 +1,406 @@
+//! Portfolio related operations
+use crate::calendar::{self, DateRange};
+use crate::client::{fetch_batched, Client};
+use crate::quote::Quote;
+use crate::time_series::{Function, OutputSize, TimeSeries};
+use crate::Error;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+
+/// Represents a set of positions, mapping each symbol to the quantity held.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Portfolio {
+    positions: BTreeMap<String, f64>,
+}
+
+/// Represents the market value of a single position.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PositionValue {
+    /// Symbol of the position.
+    pub symbol: String,
+    /// Quantity held.
+    pub quantity: f64,
+    /// Price the position was valued at.
+    pub price: f64,
+    /// Market value of the position.
+    pub value: f64,
+    /// Trading day the price corresponds to.
+    pub date: NaiveDate,
+}
+
+/// Represents the market value of a portfolio.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Valuation {
+    /// Value of each position, sorted by symbol.
+    pub positions: Vec<PositionValue>,
+    /// Total market value of the portfolio.
+    pub total: f64,
+    /// Most recent trading day among the prices used, if the portfolio isn't empty.
+    pub date: Option<NaiveDate>,
+}
+
+/// Represents the value of a portfolio at the close of a trading day.
+#[derive(Debug, PartialEq, Clone)]
+pub struct HistoryEntry {
+    /// Trading day.
+    pub date: NaiveDate,
+    /// Total market value of the portfolio.
+    pub value: f64,
+    /// Change in value since the previous trading day.
+    pub daily_pnl: f64,
+    /// Change in value since the first trading day in the history.
+    pub total_pnl: f64,
+}
+
+/// Represents the daily value and profit and loss of a portfolio over a range of dates.
+#[derive(Debug, PartialEq, Clone)]
+pub struct History {
+    /// Entries in the history, one per trading day sorted by ascending dates.
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl Portfolio {
+    /// Create an empty portfolio.
+    pub fn new() -> Portfolio {
+        Portfolio::default()
+    }
+
+    /// Add `quantity` units of `symbol` to the portfolio, on top of any quantity already held.
+    pub fn add(&mut self, symbol: &str, quantity: f64) {
+        *self.positions.entry(symbol.to_string()).or_insert(0.0) += quantity;
+    }
+
+    /// Remove the position for `symbol`, returning the quantity which was held.
+    pub fn remove(&mut self, symbol: &str) -> Option<f64> {
+        self.positions.remove(symbol)
+    }
+
+    /// Quantity held for `symbol`.
+    pub fn quantity(&self, symbol: &str) -> Option<f64> {
+        self.positions.get(symbol).copied()
+    }
+
+    /// Iterate over the positions in the portfolio, sorted by symbol.
+    pub fn positions(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.positions.iter().map(|(s, q)| (s.as_str(), *q))
+    }
+
+    /// Number of positions in the portfolio.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Whether the portfolio has no positions.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Value the portfolio at the latest prices retrieved using `client`.
+    ///
+    /// Quotes are requested in small concurrent batches, subject to any rate limit
+    /// configured on the client.
+    pub async fn value_with(&self, client: &Client) -> Result<Valuation, Error> {
+        let quotes = fetch_batched(&self.symbols(), |symbol| client.get_quote(symbol)).await?;
+        self.value(&quotes)
+    }
+
+    /// Value the portfolio using previously retrieved `quotes`.
+    pub fn value(&self, quotes: &[Quote]) -> Result<Valuation, Error> {
+        let mut positions = Vec::with_capacity(self.positions.len());
+        for (symbol, quantity) in self.positions() {
+            let quote = quotes
+                .iter()
+                .find(|q| q.symbol.eq_ignore_ascii_case(symbol))
+                .ok_or_else(|| Error::MissingData(format!("missing quote for {}", symbol)))?;
+            positions.push(PositionValue {
+                symbol: symbol.to_string(),
+                quantity,
+                price: quote.price,
+                value: quantity * quote.price,
+                date: quote.latest_trading_day,
+            });
+        }
+
+        let total = positions.iter().map(|p| p.value).sum();
+        let date = positions.iter().map(|p| p.date).max();
+        Ok(Valuation {
+            positions,
+            total,
+            date,
+        })
+    }
+
+    /// Compute the daily value and profit and loss of the portfolio over `range` using the daily
+    /// adjusted time series of each position retrieved using `client`, e.g. `start..=end`.
+    ///
+    /// Time series are requested in small concurrent batches, subject to any rate limit
+    /// configured on the client.
+    pub async fn history(
+        &self,
+        client: &Client,
+        range: impl Into<DateRange>,
+    ) -> Result<History, Error> {
+        let series = fetch_batched(&self.symbols(), |symbol| {
+            client.get_time_series(&Function::DailyAdjusted, symbol, OutputSize::Full)
+        })
+        .await?;
+        self.history_from(&series, range)
+    }
+
+    /// Compute the daily value and profit and loss of the portfolio over `range` using
+    /// previously retrieved daily time series.
+    ///
+    /// Positions are valued at their adjusted close when available. Trading days missing from a
+    /// time series are valued at the most recent price before them, while trading days before
+    /// every position has a price are left out of the history. Open ends of `range` are bounded
+    /// by the first and last dates of the time series.
+    pub fn history_from(
+        &self,
+        series: &[TimeSeries],
+        range: impl Into<DateRange>,
+    ) -> Result<History, Error> {
+        let mut prices = Vec::with_capacity(self.positions.len());
+        for (symbol, quantity) in self.positions() {
+            let time_series = series
+                .iter()
+                .find(|s| s.symbol.eq_ignore_ascii_case(symbol))
+                .ok_or_else(|| Error::MissingData(format!("missing time series for {}", symbol)))?;
+            let closes: BTreeMap<NaiveDate, f64> = time_series
+                .entries
+                .iter()
+                .map(|e| (e.date.date_naive(), e.adjusted_close.unwrap_or(e.close)))
+                .collect();
+            prices.push((quantity, closes));
+        }
+
+        let range = range.into();
+        let dates = prices.iter().flat_map(|(_, closes)| closes.keys().copied());
+        let (start, end) = match (
+            range.start.or(dates.clone().min()),
+            range.end.or(dates.max()),
+        ) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return Ok(History { entries: vec![] }),
+        };
+        let mut entries: Vec<HistoryEntry> = vec![];
+        for date in calendar::trading_days(start, end) {
+            let value: Option<f64> = prices
+                .iter()
+                .map(|(quantity, closes)| {
+                    closes
+                        .range(..=date)
+                        .next_back()
+                        .map(|(_, close)| quantity * close)
+                })
+                .sum();
+            if let Some(value) = value {
+                let daily_pnl = entries.last().map_or(0.0, |e| value - e.value);
+                let total_pnl = entries.first().map_or(0.0, |e| value - e.value);
+                entries.push(HistoryEntry {
+                    date,
+                    value,
+                    daily_pnl,
+                    total_pnl,
+                });
+            }
+        }
+        Ok(History { entries })
+    }
+
+    fn symbols(&self) -> Vec<&str> {
+        self.positions.keys().map(String::as_str).collect()
+    }
+}
+
+impl<'a> FromIterator<(&'a str, f64)> for Portfolio {
+    fn from_iter<I: IntoIterator<Item = (&'a str, f64)>>(iter: I) -> Self {
+        let mut portfolio = Portfolio::new();
+        for (symbol, quantity) in iter {
+            portfolio.add(symbol, quantity);
+        }
+        portfolio
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::{Cache, CacheEntry, MemoryCache};
+    use crate::client::BATCH_SIZE;
+    use crate::time_series::Entry;
+    use alphavantage_types::internal::parse_date;
+    use chrono::Datelike;
+    use chrono_tz::US::Eastern;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    fn quote(symbol: &str, price: f64, day: u32) -> Quote {
+        Quote {
+            symbol: symbol.to_string(),
+            open: price,
+            high: price,
+            low: price,
+            price,
+            volume: 0,
+            latest_trading_day: NaiveDate::from_ymd_opt(2018, 6, day).unwrap(),
+            previous_close: price,
+            change: 0.0,
+            change_percentage: 0.0,
+            warnings: vec![],
+        }
+    }
+
+    #[test]
+    fn add_positions() {
+        let mut portfolio: Portfolio = vec![("MSFT", 10.0), ("AAPL", 5.0)].into_iter().collect();
+        portfolio.add("MSFT", 2.5);
+        assert_eq!(portfolio.len(), 2);
+        assert_eq!(portfolio.quantity("MSFT"), Some(12.5));
+        assert_eq!(portfolio.remove("AAPL"), Some(5.0));
+        assert_eq!(portfolio.quantity("AAPL"), None);
+    }
+
+    #[test]
+    fn value() {
+        let portfolio: Portfolio = vec![("MSFT", 10.0), ("aapl", 2.0)].into_iter().collect();
+        let quotes = vec![quote("MSFT", 101.5, 8), quote("AAPL", 190.0, 7)];
+        let valuation = portfolio.value(&quotes).expect("failed to value portfolio");
+        assert_eq!(valuation.positions.len(), 2);
+        assert_eq!(valuation.positions[0].symbol, "MSFT");
+        assert_eq!(valuation.positions[0].value, 1015.0);
+        assert_eq!(valuation.positions[1].value, 380.0);
+        assert_eq!(valuation.total, 1395.0);
+        assert_eq!(valuation.date, NaiveDate::from_ymd_opt(2018, 6, 8));
+    }
+
+    fn series(symbol: &str, closes: &[(u32, f64)]) -> TimeSeries {
+        let date = |day| parse_date(&format!("2018-06-{:02}", day), Eastern).unwrap();
+        TimeSeries {
+            symbol: symbol.to_string(),
+            last_refreshed: date(closes.last().unwrap().0),
+            entries: closes
+                .iter()
+                .map(|(day, close)| Entry {
+                    date: date(*day),
+                    open: *close,
+                    high: *close,
+                    low: *close,
+                    close: *close,
+                    volume: 0,
+                    adjusted_close: None,
+                    dividend_amount: None,
+                    split_coefficient: None,
+                })
+                .collect(),
+            warnings: vec![],
+        }
+    }
+
+    #[test]
+    fn history() {
+        let portfolio: Portfolio = vec![("MSFT", 10.0), ("AAPL", 2.0)].into_iter().collect();
+        let series = vec![
+            series("MSFT", &[(4, 100.0), (5, 101.0), (6, 99.0)]),
+            series("AAPL", &[(5, 190.0), (7, 195.0)]),
+        ];
+        let start = NaiveDate::from_ymd_opt(2018, 6, 2).unwrap();
+        let end = NaiveDate::from_ymd_opt(2018, 6, 7).unwrap();
+        let history = portfolio
+            .history_from(&series, start..=end)
+            .expect("failed to compute history");
+        let values: Vec<(u32, f64, f64, f64)> = history
+            .entries
+            .iter()
+            .map(|e| (e.date.day(), e.value, e.daily_pnl, e.total_pnl))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                (5, 1390.0, 0.0, 0.0),
+                (6, 1370.0, -20.0, -20.0),
+                (7, 1380.0, 10.0, -10.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn value_missing_quote() {
+        let portfolio: Portfolio = vec![("MSFT", 10.0)].into_iter().collect();
+        match portfolio.value(&[]) {
+            Err(Error::MissingData(message)) => assert_eq!(message, "missing quote for MSFT"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        match portfolio.history_from(&[], ..) {
+            Err(Error::MissingData(message)) => assert_eq!(message, "missing time series for MSFT"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    /// Tracks the symbols fetched by [`fetch`] and how many were fetched concurrently.
+    #[derive(Default)]
+    struct Fetches {
+        started: Mutex<Vec<String>>,
+        running: AtomicUsize,
+        max_running: AtomicUsize,
+    }
+
+    /// Fetch `symbol`, failing if it's `failing`. Later symbols complete sooner, so that
+    /// results come back out of order within a batch.
+    async fn fetch(fetches: &Fetches, symbol: &str, failing: &str) -> Result<String, Error> {
+        fetches.started.lock().unwrap().push(symbol.to_string());
+        let running = fetches.running.fetch_add(1, Ordering::SeqCst) + 1;
+        fetches.max_running.fetch_max(running, Ordering::SeqCst);
+        let index: u64 = symbol[1..].parse().unwrap();
+        tokio::time::delay_for(Duration::from_millis(20 - index)).await;
+        fetches.running.fetch_sub(1, Ordering::SeqCst);
+        if symbol == failing {
+            return Err(Error::InvalidParams(format!("unknown symbol {}", symbol)));
+        }
+        Ok(symbol.to_string())
+    }
+
+    #[tokio::test]
+    async fn fetch_in_batches() {
+        let symbols: Vec<String> = (0..12).map(|i| format!("S{}", i)).collect();
+        let symbols: Vec<&str> = symbols.iter().map(String::as_str).collect();
+
+        let fetches = Fetches::default();
+        let results = fetch_batched(&symbols, |symbol| fetch(&fetches, symbol, ""))
+            .await
+            .unwrap();
+        assert_eq!(results, symbols);
+        assert_eq!(fetches.max_running.load(Ordering::SeqCst), BATCH_SIZE);
+
+        // The batch after the one which failed isn't fetched.
+        let fetches = Fetches::default();
+        match fetch_batched(&symbols, |symbol| fetch(&fetches, symbol, "S6")).await {
+            Err(Error::InvalidParams(message)) => assert_eq!(message, "unknown symbol S6"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert_eq!(fetches.started.lock().unwrap().len(), 2 * BATCH_SIZE);
+    }
+
+    #[tokio::test]
+    async fn value_with() {
+        let cache = MemoryCache::new();
+        let body = include_bytes!("../tests/json/global_quote.json").to_vec();
+        cache.insert("GLOBAL_QUOTE?symbol=MSFT", CacheEntry::new(body));
+        let client = Client::builder("key")
+            .cache(cache, Duration::from_secs(60))
+            .offline(true)
+            .build();
+
+        let portfolio: Portfolio = vec![("MSFT", 10.0)].into_iter().collect();
+        let valuation = portfolio.value_with(&client).await.unwrap();
+        assert_eq!(valuation.positions[0].price, 101.63);
+        assert!((valuation.total - 1016.3).abs() < 1e-9);
+
+        let portfolio: Portfolio = vec![("MSFT", 10.0), ("AAPL", 5.0)].into_iter().collect();
+        match portfolio.value_with(&client).await {
+            Err(Error::Offline(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+}
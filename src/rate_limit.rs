@@ -0,0 +1,302 @@
+//! Client side rate limiting
+use crate::Function;
+use serde::Deserialize;
+use std::collections::{BTreeSet, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Maximum number of requests a client is allowed to issue over a period of time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Number of requests allowed per period.
+    pub requests: u32,
+    /// Length of the period.
+    pub period: Duration,
+}
+
+impl RateLimit {
+    /// Create a rate limit allowing `requests` requests per `period`.
+    pub fn new(requests: u32, period: Duration) -> RateLimit {
+        RateLimit { requests, period }
+    }
+
+    /// Create a rate limit allowing `requests` requests per minute.
+    pub fn per_minute(requests: u32) -> RateLimit {
+        RateLimit::new(requests, Duration::from_secs(60))
+    }
+
+    /// Number of requests issued over a period from which a client is nearing the limit, 80% of
+    /// it.
+    pub fn warning_threshold(self) -> u32 {
+        (self.requests * 4).div_ceil(5)
+    }
+}
+
+/// Tier of an Alpha Vantage account, determining its rate limit and the functions it can call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tier {
+    /// Free tier, limited to 25 requests per day.
+    Free,
+    /// Premium tier allowing 75 requests per minute.
+    Premium75,
+    /// Premium tier allowing 150 requests per minute.
+    Premium150,
+    /// Premium tier allowing 300 requests per minute.
+    Premium300,
+    /// Premium tier allowing 600 requests per minute.
+    Premium600,
+    /// Premium tier allowing 1200 requests per minute.
+    Premium1200,
+}
+
+impl Tier {
+    /// Rate limit of the tier, which is the daily quota for the free tier.
+    pub fn rate_limit(self) -> RateLimit {
+        match self {
+            Tier::Free => RateLimit::new(25, Duration::from_secs(24 * 60 * 60)),
+            Tier::Premium75 => RateLimit::per_minute(75),
+            Tier::Premium150 => RateLimit::per_minute(150),
+            Tier::Premium300 => RateLimit::per_minute(300),
+            Tier::Premium600 => RateLimit::per_minute(600),
+            Tier::Premium1200 => RateLimit::per_minute(1200),
+        }
+    }
+
+    /// Whether the tier is a premium one.
+    pub fn is_premium(self) -> bool {
+        self != Tier::Free
+    }
+
+    /// Whether the tier can call `function`, see [`Function::is_premium`].
+    pub fn can_access(self, function: &Function) -> bool {
+        self.is_premium() || !function.is_premium()
+    }
+}
+
+/// Priority of the requests issued by a client, used to order requests waiting for the rate
+/// limit.
+///
+/// When the rate limit is saturated, waiting requests are issued by ascending priority levels,
+/// then in the order they were made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Priority {
+    /// Latency sensitive requests, e.g. a quote requested by a user. Default.
+    #[default]
+    Interactive,
+    /// Periodic requests, e.g. refreshing the latest data.
+    Scheduled,
+    /// Bulk requests, e.g. backfilling historical data.
+    Backfill,
+}
+
+/// Sliding window rate limiter handing out request slots.
+///
+/// Callers queue up with a [`Ticket`] and take a slot as soon as one is available and no
+/// caller ahead of them in the queue is still waiting for it.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Times at which the slots used during the last period were taken, in ascending order.
+    slots: VecDeque<Instant>,
+    /// Callers waiting for a slot, ordered by priority then by arrival.
+    waiting: BTreeSet<(Priority, u64)>,
+    next_ticket: u64,
+}
+
+/// A caller queued for a slot, leaving the queue when dropped.
+pub(crate) struct Ticket<'a> {
+    limiter: &'a RateLimiter,
+    key: (Priority, u64),
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limit: RateLimit) -> RateLimiter {
+        RateLimiter {
+            limit,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Queue up for a slot with the given `priority`.
+    pub(crate) fn ticket(&self, priority: Priority) -> Ticket<'_> {
+        let mut state = self.state.lock().unwrap();
+        let key = (priority, state.next_ticket);
+        state.next_ticket += 1;
+        state.waiting.insert(key);
+        Ticket { limiter: self, key }
+    }
+
+    pub(crate) fn limit(&self) -> RateLimit {
+        self.limit
+    }
+
+    /// Whether a request could be issued right away, with a slot available and nobody waiting
+    /// for one.
+    pub(crate) fn available(&self) -> bool {
+        self.available_at(Instant::now())
+    }
+
+    fn available_at(&self, now: Instant) -> bool {
+        let state = self.state.lock().unwrap();
+        state.waiting.is_empty() && self.used(&state, now) < self.limit.requests.max(1)
+    }
+
+    /// Number of slots which aren't used over the current period.
+    pub(crate) fn remaining(&self) -> u32 {
+        self.remaining_at(Instant::now())
+    }
+
+    fn remaining_at(&self, now: Instant) -> u32 {
+        let state = self.state.lock().unwrap();
+        self.limit.requests.saturating_sub(self.used(&state, now))
+    }
+
+    fn used(&self, state: &State, now: Instant) -> u32 {
+        state
+            .slots
+            .iter()
+            .filter(|slot| **slot + self.limit.period > now)
+            .count() as u32
+    }
+}
+
+impl Ticket<'_> {
+    /// Take a slot if one is available to the caller, returning the number of slots used over
+    /// the period including it, otherwise return how long to wait before trying again.
+    pub(crate) fn try_acquire(&self) -> Result<u32, Duration> {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&self, now: Instant) -> Result<u32, Duration> {
+        let limit = self.limiter.limit;
+        let capacity = limit.requests.max(1) as usize;
+        let mut state = self.limiter.state.lock().unwrap();
+        while state
+            .slots
+            .front()
+            .is_some_and(|slot| *slot + limit.period <= now)
+        {
+            state.slots.pop_front();
+        }
+        let free = capacity.saturating_sub(state.slots.len());
+        let ahead = state.waiting.range(..self.key).count();
+        if ahead < free {
+            state.slots.push_back(now);
+            state.waiting.remove(&self.key);
+            return Ok(state.slots.len() as u32);
+        }
+        // Wait until enough slots are freed for every caller ahead and this one.
+        let wait = match state.slots.get(ahead - free) {
+            Some(slot) => *slot + limit.period - now,
+            None => limit.period,
+        };
+        Err(wait)
+    }
+}
+
+impl Drop for Ticket<'_> {
+    fn drop(&mut self) {
+        self.limiter.state.lock().unwrap().waiting.remove(&self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tier() {
+        assert_eq!(Tier::Premium150.rate_limit(), RateLimit::per_minute(150));
+        assert_eq!(Tier::Free.rate_limit().requests, 25);
+        assert_eq!(Tier::Free.rate_limit().warning_threshold(), 20);
+        assert_eq!(RateLimit::per_minute(1).warning_threshold(), 1);
+        assert!(Tier::Free.can_access(&Function::GlobalQuote));
+        assert!(!Tier::Free.can_access(&Function::RealtimeBulkQuotes));
+        assert!(Tier::Premium75.can_access(&Function::RealtimeBulkQuotes));
+        assert!(Tier::Free.can_access(&Function::from("INSIDER_TRANSACTIONS")));
+    }
+
+    #[test]
+    fn acquire_within_limit() {
+        let limiter = RateLimiter::new(RateLimit::per_minute(3));
+        let now = Instant::now();
+        for used in 1..=3 {
+            assert_eq!(
+                limiter.ticket(Priority::default()).try_acquire_at(now),
+                Ok(used)
+            );
+        }
+    }
+
+    #[test]
+    fn acquire_over_limit() {
+        let limiter = RateLimiter::new(RateLimit::per_minute(2));
+        let now = Instant::now();
+        assert_eq!(
+            limiter.ticket(Priority::default()).try_acquire_at(now),
+            Ok(1)
+        );
+        let later = now + Duration::from_secs(10);
+        assert_eq!(
+            limiter.ticket(Priority::default()).try_acquire_at(later),
+            Ok(2)
+        );
+
+        let first = limiter.ticket(Priority::default());
+        let second = limiter.ticket(Priority::default());
+        assert_eq!(first.try_acquire_at(later), Err(Duration::from_secs(50)));
+        assert_eq!(second.try_acquire_at(later), Err(Duration::from_secs(60)));
+
+        let later = now + Duration::from_secs(60);
+        assert_eq!(second.try_acquire_at(later), Err(Duration::from_secs(10)));
+        assert_eq!(first.try_acquire_at(later), Ok(2));
+        assert_eq!(
+            second.try_acquire_at(later + Duration::from_secs(10)),
+            Ok(2)
+        );
+    }
+
+    #[test]
+    fn available() {
+        let limiter = RateLimiter::new(RateLimit::per_minute(1));
+        let now = Instant::now();
+        assert!(limiter.available_at(now));
+        let ticket = limiter.ticket(Priority::default());
+        assert!(!limiter.available_at(now));
+        assert_eq!(limiter.remaining_at(now), 1);
+        assert_eq!(ticket.try_acquire_at(now), Ok(1));
+        drop(ticket);
+        assert!(!limiter.available_at(now));
+        assert_eq!(limiter.remaining_at(now), 0);
+        assert!(limiter.available_at(now + Duration::from_secs(60)));
+        assert_eq!(limiter.remaining_at(now + Duration::from_secs(60)), 1);
+    }
+
+    #[test]
+    fn acquire_by_priority() {
+        let limiter = RateLimiter::new(RateLimit::per_minute(1));
+        let now = Instant::now();
+        assert_eq!(
+            limiter.ticket(Priority::Backfill).try_acquire_at(now),
+            Ok(1)
+        );
+
+        let backfill = limiter.ticket(Priority::Backfill);
+        let scheduled = limiter.ticket(Priority::Scheduled);
+        let interactive = limiter.ticket(Priority::Interactive);
+        let later = now + Duration::from_secs(60);
+        assert!(backfill.try_acquire_at(later).is_err());
+        assert!(scheduled.try_acquire_at(later).is_err());
+        assert_eq!(interactive.try_acquire_at(later), Ok(1));
+
+        drop(scheduled);
+        let later = later + Duration::from_secs(60);
+        assert_eq!(backfill.try_acquire_at(later), Ok(1));
+    }
+}
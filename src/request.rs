@@ -0,0 +1,638 @@
+//! Typed requests to the API, built from their parameters and issued with `Client::send`
+//!
+//! ```no_run
+//! use alphavantage::request::IntradayRequest;
+//! use alphavantage::time_series::{IntradayInterval, OutputSize};
+//! use alphavantage::Client;
+//!
+//! # async fn run() -> Result<(), alphavantage::Error> {
+//! let client = Client::new("MY_SECRET_TOKEN");
+//! let request = IntradayRequest::builder(IntradayInterval::FiveMinutes)
+//!     .symbol("AAPL")
+//!     .output_size(OutputSize::Full)
+//!     .adjusted(false)
+//!     .build();
+//! let time_series = client.send(request).await?;
+//! # Ok(())
+//! # }
+//! ```
+use crate::analytics::{
+    self, Calculation, FixedWindowAnalytics, PriceField, Range, SlidingWindowAnalytics, Window,
+};
+use crate::forex::{self, ForexSeries};
+use crate::time_series::{self, Interval, IntradayInterval, OutputSize, TimeSeries};
+use crate::Error;
+use crate::Function;
+use crate::Parser;
+use std::io::Read;
+
+/// Represents a request to a function of the API.
+pub trait Request: private::Sealed {
+    /// Type the response to the request is parsed into.
+    type Response;
+
+    /// Function called by the request.
+    fn function(&self) -> Function;
+
+    /// Parameters of the request, excluding the function and API key.
+    fn params(&self) -> Vec<(&'static str, String)>;
+}
+
+pub(crate) mod private {
+    use super::*;
+
+    pub trait Sealed {
+        /// Parse the response to the request.
+        fn parse(&self, parser: Parser, reader: impl Read) -> Result<Self::Response, Error>
+        where
+            Self: Request;
+    }
+}
+
+/// Request for an intraday time series (`TIME_SERIES_INTRADAY`).
+#[derive(Debug, Clone)]
+pub struct IntradayRequest {
+    symbol: String,
+    interval: IntradayInterval,
+    output_size: Option<OutputSize>,
+    adjusted: Option<bool>,
+    extended_hours: Option<bool>,
+    month: Option<String>,
+}
+
+impl IntradayRequest {
+    /// Create a builder to configure a request for an intraday time series with the given
+    /// `interval` between its entries.
+    pub fn builder(interval: IntradayInterval) -> IntradayRequestBuilder {
+        IntradayRequestBuilder {
+            symbol: String::new(),
+            interval,
+            output_size: None,
+            adjusted: None,
+            extended_hours: None,
+            month: None,
+        }
+    }
+
+    fn time_series_function(&self) -> time_series::Function {
+        time_series::Function::IntraDay(self.interval)
+    }
+}
+
+/// Builder for an [`IntradayRequest`].
+///
+/// Parameters which aren't set are left for the API to default.
+#[derive(Debug, Clone)]
+pub struct IntradayRequestBuilder {
+    symbol: String,
+    interval: IntradayInterval,
+    output_size: Option<OutputSize>,
+    adjusted: Option<bool>,
+    extended_hours: Option<bool>,
+    month: Option<String>,
+}
+
+impl IntradayRequestBuilder {
+    /// Symbol to retrieve the time series of.
+    pub fn symbol(mut self, symbol: &str) -> IntradayRequestBuilder {
+        self.symbol = symbol.to_string();
+        self
+    }
+
+    /// Amount of entries to retrieve.
+    pub fn output_size(mut self, output_size: OutputSize) -> IntradayRequestBuilder {
+        self.output_size = Some(output_size);
+        self
+    }
+
+    /// Whether values are adjusted for splits and dividends, which they are by default.
+    pub fn adjusted(mut self, adjusted: bool) -> IntradayRequestBuilder {
+        self.adjusted = Some(adjusted);
+        self
+    }
+
+    /// Whether entries outside of regular trading hours are included, which they are by
+    /// default.
+    pub fn extended_hours(mut self, extended_hours: bool) -> IntradayRequestBuilder {
+        self.extended_hours = Some(extended_hours);
+        self
+    }
+
+    /// Month to retrieve the entries of instead of the latest ones, formatted as `YYYY-MM`.
+    pub fn month(mut self, month: &str) -> IntradayRequestBuilder {
+        self.month = Some(month.to_string());
+        self
+    }
+
+    /// Build the request.
+    pub fn build(self) -> IntradayRequest {
+        IntradayRequest {
+            symbol: self.symbol,
+            interval: self.interval,
+            output_size: self.output_size,
+            adjusted: self.adjusted,
+            extended_hours: self.extended_hours,
+            month: self.month,
+        }
+    }
+}
+
+impl Request for IntradayRequest {
+    type Response = TimeSeries;
+
+    fn function(&self) -> Function {
+        Function::TimeSeriesIntraday
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![
+            ("symbol", self.symbol.clone()),
+            ("interval", String::from(self.interval.to_string())),
+        ];
+        if let Some(output_size) = self.output_size {
+            params.push(("outputsize", output_size.to_string()));
+        }
+        if let Some(adjusted) = self.adjusted {
+            params.push(("adjusted", adjusted.to_string()));
+        }
+        if let Some(extended_hours) = self.extended_hours {
+            params.push(("extended_hours", extended_hours.to_string()));
+        }
+        if let Some(month) = &self.month {
+            params.push(("month", month.clone()));
+        }
+        params
+    }
+}
+
+impl private::Sealed for IntradayRequest {
+    fn parse(&self, parser: Parser, reader: impl Read) -> Result<TimeSeries, Error> {
+        time_series::parser::parse(parser, &self.time_series_function(), reader)
+    }
+}
+
+/// Request for a daily time series (`TIME_SERIES_DAILY` or `TIME_SERIES_DAILY_ADJUSTED`).
+#[derive(Debug, Clone)]
+pub struct DailyRequest {
+    symbol: String,
+    output_size: Option<OutputSize>,
+    adjusted: bool,
+}
+
+impl DailyRequest {
+    /// Create a builder to configure a daily time series request.
+    pub fn builder() -> DailyRequestBuilder {
+        DailyRequestBuilder::default()
+    }
+
+    fn time_series_function(&self) -> time_series::Function {
+        if self.adjusted {
+            time_series::Function::DailyAdjusted
+        } else {
+            time_series::Function::Daily
+        }
+    }
+}
+
+/// Builder for a [`DailyRequest`].
+///
+/// Parameters which aren't set are left for the API to default.
+#[derive(Debug, Clone, Default)]
+pub struct DailyRequestBuilder {
+    symbol: String,
+    output_size: Option<OutputSize>,
+    adjusted: bool,
+}
+
+impl DailyRequestBuilder {
+    /// Symbol to retrieve the time series of.
+    pub fn symbol(mut self, symbol: &str) -> DailyRequestBuilder {
+        self.symbol = symbol.to_string();
+        self
+    }
+
+    /// Amount of entries to retrieve.
+    pub fn output_size(mut self, output_size: OutputSize) -> DailyRequestBuilder {
+        self.output_size = Some(output_size);
+        self
+    }
+
+    /// Whether close values adjusted for splits and dividends are included, which they aren't
+    /// by default.
+    pub fn adjusted(mut self, adjusted: bool) -> DailyRequestBuilder {
+        self.adjusted = adjusted;
+        self
+    }
+
+    /// Build the request.
+    pub fn build(self) -> DailyRequest {
+        DailyRequest {
+            symbol: self.symbol,
+            output_size: self.output_size,
+            adjusted: self.adjusted,
+        }
+    }
+}
+
+impl Request for DailyRequest {
+    type Response = TimeSeries;
+
+    fn function(&self) -> Function {
+        (&self.time_series_function()).into()
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("symbol", self.symbol.clone())];
+        if let Some(output_size) = self.output_size {
+            params.push(("outputsize", output_size.to_string()));
+        }
+        params
+    }
+}
+
+impl private::Sealed for DailyRequest {
+    fn parse(&self, parser: Parser, reader: impl Read) -> Result<TimeSeries, Error> {
+        time_series::parser::parse(parser, &self.time_series_function(), reader)
+    }
+}
+
+/// Request for the daily exchange rates of a currency pair (`FX_DAILY`).
+#[derive(Debug, Clone)]
+pub struct ForexDailyRequest {
+    from_symbol: String,
+    to_symbol: String,
+    output_size: Option<OutputSize>,
+}
+
+impl ForexDailyRequest {
+    /// Create a builder to configure a daily forex time series request.
+    pub fn builder() -> ForexDailyRequestBuilder {
+        ForexDailyRequestBuilder::default()
+    }
+}
+
+/// Builder for a [`ForexDailyRequest`].
+///
+/// Parameters which aren't set are left for the API to default.
+#[derive(Debug, Clone, Default)]
+pub struct ForexDailyRequestBuilder {
+    from_symbol: String,
+    to_symbol: String,
+    output_size: Option<OutputSize>,
+}
+
+impl ForexDailyRequestBuilder {
+    /// Code of the currency to retrieve the exchange rates for.
+    pub fn from_symbol(mut self, from_symbol: &str) -> ForexDailyRequestBuilder {
+        self.from_symbol = from_symbol.to_string();
+        self
+    }
+
+    /// Code of the currency the exchange rates are quoted in.
+    pub fn to_symbol(mut self, to_symbol: &str) -> ForexDailyRequestBuilder {
+        self.to_symbol = to_symbol.to_string();
+        self
+    }
+
+    /// Amount of entries to retrieve.
+    pub fn output_size(mut self, output_size: OutputSize) -> ForexDailyRequestBuilder {
+        self.output_size = Some(output_size);
+        self
+    }
+
+    /// Build the request.
+    pub fn build(self) -> ForexDailyRequest {
+        ForexDailyRequest {
+            from_symbol: self.from_symbol,
+            to_symbol: self.to_symbol,
+            output_size: self.output_size,
+        }
+    }
+}
+
+impl Request for ForexDailyRequest {
+    type Response = ForexSeries;
+
+    fn function(&self) -> Function {
+        Function::FxDaily
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![
+            ("from_symbol", self.from_symbol.clone()),
+            ("to_symbol", self.to_symbol.clone()),
+        ];
+        if let Some(output_size) = self.output_size {
+            params.push(("outputsize", output_size.to_string()));
+        }
+        params
+    }
+}
+
+impl private::Sealed for ForexDailyRequest {
+    fn parse(&self, parser: Parser, reader: impl Read) -> Result<ForexSeries, Error> {
+        forex::parser::parse(parser, reader)
+    }
+}
+
+/// Parameters shared by the analytics functions.
+#[derive(Debug, Clone, Default)]
+struct AnalyticsParams {
+    symbols: Vec<String>,
+    range: Option<Range>,
+    interval: Option<Interval>,
+    ohlc: PriceField,
+    calculations: Vec<Calculation>,
+}
+
+impl AnalyticsParams {
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let range = self.range.unwrap_or(Range::Window(Window::ThirtyDays));
+        let interval = match self.interval.unwrap_or(Interval::Daily) {
+            Interval::Intraday(interval) => interval.to_string().to_ascii_uppercase(),
+            Interval::Daily => "DAILY".to_string(),
+            Interval::Weekly => "WEEKLY".to_string(),
+            Interval::Monthly => "MONTHLY".to_string(),
+        };
+        let calculations: Vec<&str> = self.calculations.iter().map(|c| c.to_string()).collect();
+        vec![
+            ("SYMBOLS", self.symbols.join(",")),
+            ("RANGE", range.to_param()),
+            ("INTERVAL", interval),
+            ("OHLC", self.ohlc.to_string().to_string()),
+            ("CALCULATIONS", calculations.join(",")),
+        ]
+    }
+}
+
+/// Request for analytics over the returns of a set of symbols (`ANALYTICS_FIXED_WINDOW`).
+#[derive(Debug, Clone)]
+pub struct FixedWindowRequest {
+    params: AnalyticsParams,
+}
+
+impl FixedWindowRequest {
+    /// Create a builder for the request, computing analytics over the daily close values of the
+    /// last 30 days by default.
+    pub fn builder() -> FixedWindowRequestBuilder {
+        FixedWindowRequestBuilder::default()
+    }
+}
+
+/// Builder of a [`FixedWindowRequest`].
+#[derive(Debug, Default)]
+pub struct FixedWindowRequestBuilder {
+    params: AnalyticsParams,
+}
+
+impl FixedWindowRequestBuilder {
+    /// Symbols to compute the analytics of.
+    pub fn symbols(mut self, symbols: &[&str]) -> FixedWindowRequestBuilder {
+        self.params.symbols = symbols.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    /// Range of time to compute the analytics over.
+    pub fn range(mut self, range: Range) -> FixedWindowRequestBuilder {
+        self.params.range = Some(range);
+        self
+    }
+
+    /// Interval between the entries returns are computed from.
+    pub fn interval(mut self, interval: Interval) -> FixedWindowRequestBuilder {
+        self.params.interval = Some(interval);
+        self
+    }
+
+    /// Value of each entry returns are computed from.
+    pub fn ohlc(mut self, ohlc: PriceField) -> FixedWindowRequestBuilder {
+        self.params.ohlc = ohlc;
+        self
+    }
+
+    /// Calculations to perform, in addition to the ones previously added.
+    pub fn calculations(mut self, calculations: &[Calculation]) -> FixedWindowRequestBuilder {
+        self.params.calculations.extend_from_slice(calculations);
+        self
+    }
+
+    /// Build the request.
+    pub fn build(self) -> FixedWindowRequest {
+        FixedWindowRequest {
+            params: self.params,
+        }
+    }
+}
+
+impl Request for FixedWindowRequest {
+    type Response = FixedWindowAnalytics;
+
+    fn function(&self) -> Function {
+        Function::AnalyticsFixedWindow
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        self.params.params()
+    }
+}
+
+impl private::Sealed for FixedWindowRequest {
+    fn parse(&self, parser: Parser, reader: impl Read) -> Result<FixedWindowAnalytics, Error> {
+        analytics::parser::parse_fixed_window(parser, reader)
+    }
+}
+
+/// Request for analytics over a window sliding across the returns of a set of symbols
+/// (`ANALYTICS_SLIDING_WINDOW`).
+#[derive(Debug, Clone)]
+pub struct SlidingWindowRequest {
+    params: AnalyticsParams,
+    window_size: u32,
+}
+
+impl SlidingWindowRequest {
+    /// Create a builder for the request, computing analytics over windows of 20 daily close
+    /// values of the last 30 days by default.
+    pub fn builder() -> SlidingWindowRequestBuilder {
+        SlidingWindowRequestBuilder::default()
+    }
+}
+
+/// Builder of a [`SlidingWindowRequest`].
+#[derive(Debug, Default)]
+pub struct SlidingWindowRequestBuilder {
+    params: AnalyticsParams,
+    window_size: Option<u32>,
+}
+
+impl SlidingWindowRequestBuilder {
+    /// Symbols to compute the analytics of.
+    pub fn symbols(mut self, symbols: &[&str]) -> SlidingWindowRequestBuilder {
+        self.params.symbols = symbols.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    /// Range of time to compute the analytics over.
+    pub fn range(mut self, range: Range) -> SlidingWindowRequestBuilder {
+        self.params.range = Some(range);
+        self
+    }
+
+    /// Interval between the entries returns are computed from.
+    pub fn interval(mut self, interval: Interval) -> SlidingWindowRequestBuilder {
+        self.params.interval = Some(interval);
+        self
+    }
+
+    /// Value of each entry returns are computed from.
+    pub fn ohlc(mut self, ohlc: PriceField) -> SlidingWindowRequestBuilder {
+        self.params.ohlc = ohlc;
+        self
+    }
+
+    /// Number of entries in each window, at least 10.
+    pub fn window_size(mut self, window_size: u32) -> SlidingWindowRequestBuilder {
+        self.window_size = Some(window_size);
+        self
+    }
+
+    /// Calculations to perform, in addition to the ones previously added.
+    ///
+    /// Only the mean, median, cumulative return, variance, standard deviation, covariance and
+    /// correlation are computed over sliding windows.
+    pub fn calculations(mut self, calculations: &[Calculation]) -> SlidingWindowRequestBuilder {
+        self.params.calculations.extend_from_slice(calculations);
+        self
+    }
+
+    /// Build the request.
+    pub fn build(self) -> SlidingWindowRequest {
+        SlidingWindowRequest {
+            params: self.params,
+            window_size: self.window_size.unwrap_or(20),
+        }
+    }
+}
+
+impl Request for SlidingWindowRequest {
+    type Response = SlidingWindowAnalytics;
+
+    fn function(&self) -> Function {
+        Function::AnalyticsSlidingWindow
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = self.params.params();
+        let calculations = params.pop();
+        params.push(("WINDOW_SIZE", self.window_size.to_string()));
+        params.extend(calculations);
+        params
+    }
+}
+
+impl private::Sealed for SlidingWindowRequest {
+    fn parse(&self, parser: Parser, reader: impl Read) -> Result<SlidingWindowAnalytics, Error> {
+        analytics::parser::parse_sliding_window(parser, reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intraday_params() {
+        let request = IntradayRequest::builder(IntradayInterval::FiveMinutes)
+            .symbol("AAPL")
+            .output_size(OutputSize::Full)
+            .adjusted(false)
+            .build();
+        assert_eq!(request.function(), Function::TimeSeriesIntraday);
+        assert_eq!(
+            request.params(),
+            vec![
+                ("symbol", "AAPL".to_string()),
+                ("interval", "5min".to_string()),
+                ("outputsize", "full".to_string()),
+                ("adjusted", "false".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn daily_params() {
+        let request = DailyRequest::builder().symbol("AAPL").build();
+        assert_eq!(request.function(), Function::TimeSeriesDaily);
+        assert_eq!(request.params(), vec![("symbol", "AAPL".to_string())]);
+
+        let request = DailyRequest::builder()
+            .symbol("AAPL")
+            .adjusted(true)
+            .build();
+        assert_eq!(request.function(), Function::TimeSeriesDailyAdjusted);
+    }
+
+    #[test]
+    fn forex_daily_params() {
+        let request = ForexDailyRequest::builder()
+            .from_symbol("EUR")
+            .to_symbol("USD")
+            .output_size(OutputSize::Full)
+            .build();
+        assert_eq!(request.function(), Function::FxDaily);
+        assert_eq!(
+            request.params(),
+            vec![
+                ("from_symbol", "EUR".to_string()),
+                ("to_symbol", "USD".to_string()),
+                ("outputsize", "full".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn fixed_window_params() {
+        let request = FixedWindowRequest::builder()
+            .symbols(&["AAPL", "MSFT"])
+            .range(Range::Years(2))
+            .interval(Interval::Intraday(IntradayInterval::FifteenMinutes))
+            .calculations(&[Calculation::Mean, Calculation::AnnualizedStdDev])
+            .calculations(&[Calculation::Correlation])
+            .build();
+        assert_eq!(request.function(), Function::AnalyticsFixedWindow);
+        assert_eq!(
+            request.params(),
+            vec![
+                ("SYMBOLS", "AAPL,MSFT".to_string()),
+                ("RANGE", "2year".to_string()),
+                ("INTERVAL", "15MIN".to_string()),
+                ("OHLC", "close".to_string()),
+                (
+                    "CALCULATIONS",
+                    "MEAN,STDDEV(annualized=True),CORRELATION".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn sliding_window_params() {
+        let request = SlidingWindowRequest::builder()
+            .symbols(&["AAPL", "IBM"])
+            .window_size(30)
+            .calculations(&[Calculation::Mean, Calculation::Correlation])
+            .build();
+        assert_eq!(request.function(), Function::AnalyticsSlidingWindow);
+        assert_eq!(
+            request.params(),
+            vec![
+                ("SYMBOLS", "AAPL,IBM".to_string()),
+                ("RANGE", "30day".to_string()),
+                ("INTERVAL", "DAILY".to_string()),
+                ("OHLC", "close".to_string()),
+                ("WINDOW_SIZE", "30".to_string()),
+                ("CALCULATIONS", "MEAN,CORRELATION".to_string()),
+            ]
+        );
+    }
+}
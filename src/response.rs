@@ -0,0 +1,166 @@
+//! Parsed responses along with metadata about the requests they were retrieved with
+//!
+//! Any endpoint call can be wrapped with `Client::with_metadata` to report where its result
+//! came from, e.g. to audit the freshness of cached data:
+//!
+//! ```no_run
+//! use alphavantage::Client;
+//!
+//! # async fn run() -> Result<(), alphavantage::Error> {
+//! let client = Client::new("MY_SECRET_TOKEN");
+//! let response = client
+//!     .with_metadata(|client| async move { client.get_quote("MSFT").await })
+//!     .await?;
+//! println!("{} ({} bytes, cached: {})", response.function, response.size, response.from_cache);
+//! let quote = response.payload;
+//! # Ok(())
+//! # }
+//! ```
+use crate::Error;
+use crate::Function;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Represents the parsed payload of an endpoint call along with metadata about the requests
+/// it's made of.
+///
+/// Calls combining several requests (e.g. `get_snapshot`) report the function of the first
+/// one, the oldest retrieval time and the total size, and are only reported as served from the
+/// cache if each of their requests was, and as stale if any of them was.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct ApiResponse<T> {
+    /// Parsed payload.
+    pub payload: T,
+    /// Function called.
+    pub function: Function,
+    /// Time the response was retrieved from the API, which is earlier than the call for
+    /// responses served from the cache.
+    pub fetched_at: SystemTime,
+    /// Whether the response was served from the cache.
+    pub from_cache: bool,
+    /// Whether the response was served from the cache after its time to live, e.g. by an offline
+    /// client or while being refreshed in the background.
+    pub stale: bool,
+    /// Size of the raw response, in bytes.
+    pub size: u64,
+    /// Number of requests the call is made of.
+    pub requests: usize,
+}
+
+impl<T> ApiResponse<T> {
+    /// Convert the payload with `f`, keeping the metadata.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> ApiResponse<U> {
+        ApiResponse {
+            payload: f(self.payload),
+            function: self.function,
+            fetched_at: self.fetched_at,
+            from_cache: self.from_cache,
+            stale: self.stale,
+            size: self.size,
+            requests: self.requests,
+        }
+    }
+}
+
+/// Metadata of a response recorded by a [`Capture`].
+#[derive(Debug)]
+struct Recorded {
+    function: Function,
+    fetched_at: SystemTime,
+    from_cache: bool,
+    stale: bool,
+    size: u64,
+}
+
+/// Records the responses received by a client wrapped by `with_metadata`.
+#[derive(Debug, Default)]
+pub(crate) struct Capture {
+    responses: Mutex<Vec<Recorded>>,
+}
+
+impl Capture {
+    /// Record a response to `function` of `size` bytes retrieved at `fetched_at`.
+    pub(crate) fn record(
+        &self,
+        function: &str,
+        fetched_at: SystemTime,
+        from_cache: bool,
+        size: usize,
+    ) {
+        self.record_response(function, fetched_at, from_cache, false, size);
+    }
+
+    /// Record a response to `function` of `size` bytes served from the cache after its time to
+    /// live.
+    pub(crate) fn record_stale(&self, function: &str, fetched_at: SystemTime, size: usize) {
+        self.record_response(function, fetched_at, true, true, size);
+    }
+
+    fn record_response(
+        &self,
+        function: &str,
+        fetched_at: SystemTime,
+        from_cache: bool,
+        stale: bool,
+        size: usize,
+    ) {
+        self.responses.lock().unwrap().push(Recorded {
+            function: Function::from(function),
+            fetched_at,
+            from_cache,
+            stale,
+            size: size as u64,
+        });
+    }
+
+    /// Wrap `payload` with the metadata of the recorded responses, failing with
+    /// [`Error::InvalidParams`] if none was recorded.
+    pub(crate) fn finish<T>(&self, payload: T) -> Result<ApiResponse<T>, Error> {
+        let responses = self.responses.lock().unwrap();
+        let first = responses
+            .first()
+            .ok_or_else(|| Error::InvalidParams("no request was issued".into()))?;
+        Ok(ApiResponse {
+            payload,
+            function: first.function.clone(),
+            fetched_at: responses.iter().map(|r| r.fetched_at).min().unwrap(),
+            from_cache: responses.iter().all(|r| r.from_cache),
+            stale: responses.iter().any(|r| r.stale),
+            size: responses.iter().map(|r| r.size).sum(),
+            requests: responses.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn finish() {
+        let capture = Capture::default();
+        assert!(capture.finish(()).is_err());
+
+        let now = SystemTime::now();
+        let earlier = now - Duration::from_secs(60);
+        capture.record("GLOBAL_QUOTE", now, false, 120);
+        capture.record("OVERVIEW", earlier, true, 800);
+        let response = capture.finish(2).unwrap().map(|n| n * 2);
+        assert_eq!(response.payload, 4);
+        assert_eq!(response.function, Function::GlobalQuote);
+        assert_eq!(response.fetched_at, earlier);
+        assert!(!response.from_cache);
+        assert!(!response.stale);
+        assert_eq!(response.size, 920);
+        assert_eq!(response.requests, 2);
+
+        let capture = Capture::default();
+        capture.record("GLOBAL_QUOTE", now, true, 120);
+        capture.record_stale("OVERVIEW", earlier, 800);
+        let response = capture.finish(()).unwrap();
+        assert!(response.from_cache);
+        assert!(response.stale);
+    }
+}
@@ -0,0 +1,214 @@
+//! Symbol search related operations
+use crate::client::Client;
+use crate::Error;
+use alphavantage_types::internal::{normalize, score};
+pub use alphavantage_types::search::*;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+/// Minimum local score for a lookup to be resolved without searching the API.
+const DEFAULT_MIN_LOCAL_SCORE: f64 = 0.8;
+
+/// Represents a symbol resolved by a [`SymbolResolver`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct ResolvedSymbol {
+    /// Symbol.
+    pub symbol: String,
+    /// Name of the company or fund.
+    pub name: String,
+    /// How well the symbol matches the lookup, between 0 and 1.
+    pub score: f64,
+}
+
+/// Resolves symbols from partial symbols or names, caching the symbols found by previous
+/// searches so lookups can usually be resolved locally instead of searching the API.
+///
+/// Lookups are first matched against every symbol known to the resolver. The API is only
+/// searched when no known symbol scores at least the resolver's minimum local score, and each
+/// distinct lookup is searched at most once.
+#[derive(Debug)]
+pub struct SymbolResolver {
+    min_local_score: f64,
+    symbols: Mutex<BTreeMap<String, String>>,
+    searches: Mutex<HashMap<String, Vec<ResolvedSymbol>>>,
+}
+
+impl Default for SymbolResolver {
+    fn default() -> SymbolResolver {
+        SymbolResolver::new()
+    }
+}
+
+impl SymbolResolver {
+    /// Create a resolver without any known symbols.
+    pub fn new() -> SymbolResolver {
+        SymbolResolver {
+            min_local_score: DEFAULT_MIN_LOCAL_SCORE,
+            symbols: Mutex::new(BTreeMap::new()),
+            searches: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set the minimum score, between 0 and 1, a known symbol must reach for a lookup to be
+    /// resolved locally.
+    pub fn min_local_score(mut self, score: f64) -> SymbolResolver {
+        self.min_local_score = score;
+        self
+    }
+
+    /// Make `symbol`, with the company or fund `name`, known to the resolver (e.g. from a
+    /// listing of every active symbol).
+    pub fn insert(&self, symbol: &str, name: &str) {
+        self.symbols
+            .lock()
+            .unwrap()
+            .insert(symbol.to_string(), name.to_string());
+    }
+
+    /// Number of symbols known to the resolver.
+    pub fn len(&self) -> usize {
+        self.symbols.lock().unwrap().len()
+    }
+
+    /// Whether no symbols are known to the resolver.
+    pub fn is_empty(&self) -> bool {
+        self.symbols.lock().unwrap().is_empty()
+    }
+
+    /// Resolve `keywords` against the symbols known to the resolver, sorted by descending scores.
+    pub fn resolve_local(&self, keywords: &str) -> Vec<ResolvedSymbol> {
+        let query = normalize(keywords);
+        let mut resolved: Vec<ResolvedSymbol> = self
+            .symbols
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(symbol, name)| ResolvedSymbol {
+                symbol: symbol.clone(),
+                name: name.clone(),
+                score: score(&query, symbol, name),
+            })
+            .filter(|r| r.score > 0.0)
+            .collect();
+        sort(&mut resolved);
+        resolved
+    }
+
+    /// Resolve `keywords`, searching the API using `client` only when they can't be resolved
+    /// locally.
+    pub async fn resolve(
+        &self,
+        client: &Client,
+        keywords: &str,
+    ) -> Result<Vec<ResolvedSymbol>, Error> {
+        if let Some(resolved) = self.cached(keywords) {
+            return Ok(resolved);
+        }
+        let matches = client.search_symbol(keywords).await?;
+        Ok(self.store(keywords, matches.matches))
+    }
+
+    /// Resolve `keywords`, searching the API using the blocking `client` only when they can't
+    /// be resolved locally.
+    #[cfg(feature = "blocking")]
+    pub fn resolve_blocking(
+        &self,
+        client: &crate::blocking::Client,
+        keywords: &str,
+    ) -> Result<Vec<ResolvedSymbol>, Error> {
+        if let Some(resolved) = self.cached(keywords) {
+            return Ok(resolved);
+        }
+        let matches = client.search_symbol(keywords)?;
+        Ok(self.store(keywords, matches.matches))
+    }
+
+    fn cached(&self, keywords: &str) -> Option<Vec<ResolvedSymbol>> {
+        if let Some(resolved) = self.searches.lock().unwrap().get(&normalize(keywords)) {
+            return Some(resolved.clone());
+        }
+        let resolved = self.resolve_local(keywords);
+        match resolved.first() {
+            Some(best) if best.score >= self.min_local_score => Some(resolved),
+            _ => None,
+        }
+    }
+
+    fn store(&self, keywords: &str, matches: Vec<SymbolMatch>) -> Vec<ResolvedSymbol> {
+        let query = normalize(keywords);
+        let mut resolved: Vec<ResolvedSymbol> = matches
+            .into_iter()
+            .map(|m| {
+                self.insert(&m.symbol, &m.name);
+                ResolvedSymbol {
+                    score: score(&query, &m.symbol, &m.name).max(m.match_score),
+                    symbol: m.symbol,
+                    name: m.name,
+                }
+            })
+            .collect();
+        sort(&mut resolved);
+        self.searches
+            .lock()
+            .unwrap()
+            .insert(query, resolved.clone());
+        resolved
+    }
+}
+
+fn sort(resolved: &mut [ResolvedSymbol]) {
+    resolved.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.symbol.len().cmp(&b.symbol.len()))
+            .then_with(|| a.symbol.cmp(&b.symbol))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+    use std::io::BufReader;
+
+    fn matches() -> Vec<SymbolMatch> {
+        let data: &[u8] = include_bytes!("../tests/json/symbol_search.json");
+        parser::parse(Parser::default(), BufReader::new(data))
+            .expect("failed to parse symbol search")
+            .matches
+    }
+
+    #[test]
+    fn resolve_cached_search() {
+        let resolver = SymbolResolver::new();
+        assert_eq!(resolver.cached("tesco"), None);
+        let resolved = resolver.store("tesco", matches());
+        assert_eq!(resolved.len(), 3);
+        assert_eq!(resolver.len(), 3);
+        assert_eq!(resolver.cached(" Tesco "), Some(resolved));
+    }
+
+    #[test]
+    fn resolve_locally() {
+        let resolver = SymbolResolver::new();
+        resolver.insert("MSFT", "Microsoft Corporation");
+        resolver.insert("MSTR", "MicroStrategy Inc");
+        resolver.insert("AAPL", "Apple Inc");
+
+        let symbols = |keywords: &str| -> Vec<String> {
+            resolver
+                .resolve_local(keywords)
+                .into_iter()
+                .map(|r| r.symbol)
+                .collect()
+        };
+        assert_eq!(symbols("msft"), vec!["MSFT"]);
+        assert_eq!(symbols("MS"), vec!["MSFT", "MSTR"]);
+        assert_eq!(symbols("micro"), vec!["MSFT", "MSTR"]);
+        assert_eq!(symbols("apple"), vec!["AAPL"]);
+        assert!(resolver.cached("micro").is_some());
+        assert!(resolver.cached("crosoft").is_none());
+        assert!(resolver.cached("tesco").is_none());
+    }
+}
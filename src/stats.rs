@@ -0,0 +1,126 @@
+//! Usage statistics of a client
+use crate::Error;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Usage statistics of a client and its clones since it was built.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    /// Statistics of each function called, by name.
+    pub functions: BTreeMap<String, FunctionStats>,
+    /// Number of failed calls, by class of error (see [`Error::class`]).
+    pub errors: BTreeMap<String, u64>,
+    /// Total size of the responses downloaded from the API, in bytes.
+    pub bytes_downloaded: u64,
+    /// Total time requests were delayed for to respect the rate limit.
+    pub rate_limit_wait: Duration,
+}
+
+/// Usage statistics of a function of the API.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FunctionStats {
+    /// Number of calls, including the ones served from the cache.
+    pub calls: u64,
+    /// Number of calls served from the cache.
+    pub cache_hits: u64,
+    /// Number of failed calls.
+    pub errors: u64,
+    /// Size of the responses downloaded from the API, in bytes.
+    pub bytes_downloaded: u64,
+}
+
+impl Stats {
+    /// Number of calls to every function.
+    pub fn calls(&self) -> u64 {
+        self.functions.values().map(|f| f.calls).sum()
+    }
+
+    /// Number of calls to every function served from the cache.
+    pub fn cache_hits(&self) -> u64 {
+        self.functions.values().map(|f| f.cache_hits).sum()
+    }
+
+    /// Share of calls served from the cache, if any call was made.
+    pub fn cache_hit_rate(&self) -> Option<f64> {
+        match self.calls() {
+            0 => None,
+            calls => Some(self.cache_hits() as f64 / calls as f64),
+        }
+    }
+}
+
+/// Records the statistics shared by a client and its clones.
+#[derive(Debug, Default)]
+pub(crate) struct Recorder {
+    stats: Mutex<Stats>,
+}
+
+impl Recorder {
+    pub(crate) fn snapshot(&self) -> Stats {
+        self.stats.lock().unwrap().clone()
+    }
+
+    /// Record a call to `function` served from the cache.
+    pub(crate) fn cache_hit(&self, function: &str) {
+        let mut stats = self.stats.lock().unwrap();
+        let function = stats.functions.entry(function.to_string()).or_default();
+        function.calls += 1;
+        function.cache_hits += 1;
+    }
+
+    /// Record a request delayed by `wait` to respect the rate limit.
+    pub(crate) fn rate_limit_wait(&self, wait: Duration) {
+        self.stats.lock().unwrap().rate_limit_wait += wait;
+    }
+
+    /// Record a call to `function` completed with `result`, the response body if successful.
+    pub(crate) fn response(&self, function: &str, result: Result<&[u8], &Error>) {
+        let mut stats = self.stats.lock().unwrap();
+        let stats = &mut *stats;
+        let function = stats.functions.entry(function.to_string()).or_default();
+        function.calls += 1;
+        match result {
+            Ok(body) => {
+                function.bytes_downloaded += body.len() as u64;
+                stats.bytes_downloaded += body.len() as u64;
+            }
+            Err(error) => {
+                function.errors += 1;
+                *stats.errors.entry(error.class().to_string()).or_default() += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record() {
+        let recorder = Recorder::default();
+        assert_eq!(recorder.snapshot().cache_hit_rate(), None);
+        recorder.response("GLOBAL_QUOTE", Ok(b"{}"));
+        recorder.cache_hit("GLOBAL_QUOTE");
+        recorder.response("OVERVIEW", Err(&Error::ServerError(500)));
+        recorder.rate_limit_wait(Duration::from_secs(2));
+
+        let stats = recorder.snapshot();
+        assert_eq!(
+            stats.functions["GLOBAL_QUOTE"],
+            FunctionStats {
+                calls: 2,
+                cache_hits: 1,
+                errors: 0,
+                bytes_downloaded: 2,
+            }
+        );
+        assert_eq!(stats.functions["OVERVIEW"].errors, 1);
+        assert_eq!(stats.errors["server"], 1);
+        assert_eq!(stats.calls(), 3);
+        assert_eq!(stats.cache_hit_rate(), Some(1.0 / 3.0));
+        assert_eq!(stats.bytes_downloaded, 2);
+        assert_eq!(stats.rate_limit_wait, Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,798 @@
+//! Storage of time series combining persisted data with API requests
+//!
+//! A [`DataStore`] answers queries such as "daily bars for AAPL from 2019-01-01" from the
+//! entries persisted in a [`Store`], only requesting the API when entries are missing from the
+//! queried range, then persisting the merged result.
+use crate::cache::{decode, encode, Freshness, StalenessPolicy};
+use crate::calendar::{self, DateRange};
+use crate::client::{fetch_batched, Client};
+use crate::corporate_actions::{Dividend, Dividends, Split, Splits};
+use crate::time_series::{Entry, Function, OutputSize};
+use crate::Error;
+use alphavantage_types::internal::{parse_date_with_time_zone, DATETIME_FORMAT};
+use chrono::{Duration, NaiveDate, Utc};
+use chrono_tz::US::Eastern;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Number of entries returned by the API for compact time series.
+const COMPACT_SIZE: usize = 100;
+
+/// Represents the entries of a time series persisted for a symbol.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct StoredSeries {
+    /// Entries, sorted by ascending dates.
+    pub entries: Vec<Entry>,
+    /// Whether the entries start with the earliest ones available from the API.
+    pub complete: bool,
+    /// Time the entries were last retrieved from the API, if known.
+    pub fetched_at: Option<SystemTime>,
+}
+
+/// Represents a corporate action changing the adjusted values of a time series.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CorporateAction {
+    /// A dividend, from its ex-dividend date.
+    Dividend(Dividend),
+    /// A split, from its effective date.
+    Split(Split),
+}
+
+impl CorporateAction {
+    /// Date from which the corporate action changes the adjusted values.
+    pub fn date(&self) -> NaiveDate {
+        match self {
+            CorporateAction::Dividend(dividend) => dividend.ex_dividend_date,
+            CorporateAction::Split(split) => split.effective_date,
+        }
+    }
+}
+
+/// Represents the refresh of the daily adjusted series persisted for a symbol, following
+/// corporate actions missing from it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdjustmentEvent {
+    /// Symbol the series refers to.
+    pub symbol: String,
+    /// Corporate actions missing from the series before it was refreshed, sorted by ascending
+    /// dates.
+    pub actions: Vec<CorporateAction>,
+    /// Entries of the refreshed series, sorted by ascending dates.
+    pub entries: Vec<Entry>,
+}
+
+/// Persistent storage for time series, keyed by function and symbol (e.g.
+/// `TIME_SERIES_DAILY/AAPL`).
+pub trait Store: Send + Sync {
+    /// Retrieve the series stored for `key`.
+    fn load(&self, key: &str) -> Result<Option<StoredSeries>, Error>;
+
+    /// Store `series` for `key`, replacing any existing series.
+    fn save(&self, key: &str, series: &StoredSeries) -> Result<(), Error>;
+}
+
+/// A store keeping time series in memory for its lifetime.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    series: Mutex<HashMap<String, StoredSeries>>,
+}
+
+impl MemoryStore {
+    /// Create an empty store.
+    pub fn new() -> MemoryStore {
+        MemoryStore::default()
+    }
+}
+
+impl Store for MemoryStore {
+    fn load(&self, key: &str) -> Result<Option<StoredSeries>, Error> {
+        Ok(self.series.lock().unwrap().get(key).cloned())
+    }
+
+    fn save(&self, key: &str, series: &StoredSeries) -> Result<(), Error> {
+        self.series
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), series.clone());
+        Ok(())
+    }
+}
+
+/// Represents an integrity issue found in a series persisted by a [`DirectoryStore`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IntegrityIssue {
+    /// Key of the offending series (e.g. `TIME_SERIES_DAILY/AAPL`).
+    pub key: String,
+    /// Description of the issue.
+    pub message: String,
+}
+
+/// A store persisting each time series to a CSV file in a directory named after its function
+/// (e.g. `TIME_SERIES_DAILY/AAPL.csv`).
+///
+/// Each file is accompanied by a manifest recording its row count and checksum (e.g.
+/// `TIME_SERIES_DAILY/AAPL.manifest.json`), which are checked whenever the series is loaded
+/// so truncated or corrupted files are reported as errors instead of returning partial data.
+#[derive(Debug, Clone)]
+pub struct DirectoryStore {
+    dir: PathBuf,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    rows: usize,
+    checksum: u32,
+    complete: bool,
+    #[serde(default)]
+    fetched_at: Option<SystemTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Row {
+    date: String,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    volume: u64,
+    adjusted_close: Option<f64>,
+    dividend_amount: Option<f64>,
+    split_coefficient: Option<f64>,
+}
+
+const CSV_EXTENSION: &str = ".csv";
+const MANIFEST_EXTENSION: &str = ".manifest.json";
+
+impl DirectoryStore {
+    /// Create a store persisting time series to `dir`, which is created if needed.
+    pub fn new(dir: impl Into<PathBuf>) -> DirectoryStore {
+        DirectoryStore { dir: dir.into() }
+    }
+
+    /// Check the row counts and checksums of every series in the store, returning the issues
+    /// found.
+    pub fn verify(&self) -> Result<Vec<IntegrityIssue>, Error> {
+        if !self.dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut keys = BTreeSet::new();
+        for function_dir in fs::read_dir(&self.dir)? {
+            let function_dir = function_dir?;
+            if !function_dir.file_type()?.is_dir() {
+                continue;
+            }
+            let function = decode(&function_dir.file_name().to_string_lossy())?;
+            for file in fs::read_dir(function_dir.path())? {
+                let name = file?.file_name().to_string_lossy().into_owned();
+                let symbol = match name
+                    .strip_suffix(MANIFEST_EXTENSION)
+                    .or_else(|| name.strip_suffix(CSV_EXTENSION))
+                {
+                    Some(symbol) => decode(symbol)?,
+                    None => continue,
+                };
+                keys.insert(format!("{}/{}", function, symbol));
+            }
+        }
+        let mut issues = vec![];
+        for key in keys {
+            if let Err(message) = self.read(&key) {
+                issues.push(IntegrityIssue { key, message });
+            }
+        }
+        Ok(issues)
+    }
+
+    fn paths(&self, key: &str) -> (PathBuf, PathBuf) {
+        let (function, symbol) = key.split_once('/').unwrap_or(("", key));
+        let dir = self.dir.join(encode(function));
+        let symbol = encode(symbol);
+        (
+            dir.join(format!("{}{}", symbol, CSV_EXTENSION)),
+            dir.join(format!("{}{}", symbol, MANIFEST_EXTENSION)),
+        )
+    }
+
+    /// Read the series stored for `key` if any, checking it against its manifest.
+    fn read(&self, key: &str) -> Result<Option<StoredSeries>, String> {
+        let (data_path, manifest_path) = self.paths(key);
+        if !data_path.exists() && !manifest_path.exists() {
+            return Ok(None);
+        }
+        let manifest = fs::read(&manifest_path).map_err(|e| format!("missing manifest: {}", e))?;
+        let manifest: Manifest =
+            serde_json::from_slice(&manifest).map_err(|e| format!("invalid manifest: {}", e))?;
+        let data = fs::read(&data_path).map_err(|e| format!("missing data: {}", e))?;
+        let checksum = crc32fast::hash(&data);
+        if checksum != manifest.checksum {
+            return Err(format!(
+                "checksum mismatch: expected {:08x}, found {:08x}",
+                manifest.checksum, checksum
+            ));
+        }
+        let mut entries = vec![];
+        for row in csv::Reader::from_reader(data.as_slice()).deserialize() {
+            let row: Row = row.map_err(|e| format!("invalid data: {}", e))?;
+            entries.push(Entry {
+                date: parse_date_with_time_zone(&row.date).map_err(|e| e.to_string())?,
+                open: row.open,
+                high: row.high,
+                low: row.low,
+                close: row.close,
+                volume: row.volume,
+                adjusted_close: row.adjusted_close,
+                dividend_amount: row.dividend_amount,
+                split_coefficient: row.split_coefficient,
+            });
+        }
+        if entries.len() != manifest.rows {
+            return Err(format!(
+                "row count mismatch: expected {}, found {}",
+                manifest.rows,
+                entries.len()
+            ));
+        }
+        Ok(Some(StoredSeries {
+            entries,
+            complete: manifest.complete,
+            fetched_at: manifest.fetched_at,
+        }))
+    }
+}
+
+impl Store for DirectoryStore {
+    fn load(&self, key: &str) -> Result<Option<StoredSeries>, Error> {
+        self.read(key)
+            .map_err(|message| Error::ParsingError(format!("{} at `{}`", message, key)))
+    }
+
+    fn save(&self, key: &str, series: &StoredSeries) -> Result<(), Error> {
+        let mut writer = csv::Writer::from_writer(vec![]);
+        for entry in &series.entries {
+            writer.serialize(Row {
+                date: format!(
+                    "{} {}",
+                    entry.date.format(DATETIME_FORMAT),
+                    entry.date.timezone().name()
+                ),
+                open: entry.open,
+                high: entry.high,
+                low: entry.low,
+                close: entry.close,
+                volume: entry.volume,
+                adjusted_close: entry.adjusted_close,
+                dividend_amount: entry.dividend_amount,
+                split_coefficient: entry.split_coefficient,
+            })?;
+        }
+        let data = writer
+            .into_inner()
+            .map_err(|e| Error::IOError(e.to_string()))?;
+        let manifest = Manifest {
+            rows: series.entries.len(),
+            checksum: crc32fast::hash(&data),
+            complete: series.complete,
+            fetched_at: series.fetched_at,
+        };
+        let (data_path, manifest_path) = self.paths(key);
+        if let Some(dir) = data_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&data_path, data)?;
+        fs::write(&manifest_path, serde_json::to_vec(&manifest)?)?;
+        Ok(())
+    }
+}
+
+/// Answers time series queries from a [`Store`], requesting the API through a [`Client`] for
+/// the missing entries only.
+#[derive(Clone)]
+pub struct DataStore {
+    client: Client,
+    store: Arc<dyn Store>,
+}
+
+impl DataStore {
+    /// Create a data store persisting time series to `store` and requesting missing entries
+    /// with `client`.
+    pub fn new(client: Client, store: impl Store + 'static) -> DataStore {
+        DataStore {
+            client,
+            store: Arc::new(store),
+        }
+    }
+
+    /// Retrieve the daily entries for the specified `symbol` within `range`, e.g. `start..` for
+    /// every entry from `start` to the latest one.
+    pub async fn get_daily(
+        &self,
+        symbol: &str,
+        range: impl Into<DateRange>,
+    ) -> Result<Vec<Entry>, Error> {
+        self.get(&Function::Daily, symbol, range.into()).await
+    }
+
+    /// Retrieve the daily entries for the specified `symbol` within `range`, with close values
+    /// adjusted for splits and dividends.
+    pub async fn get_daily_adjusted(
+        &self,
+        symbol: &str,
+        range: impl Into<DateRange>,
+    ) -> Result<Vec<Entry>, Error> {
+        self.get(&Function::DailyAdjusted, symbol, range.into())
+            .await
+    }
+
+    /// Check the dividends and splits of each of `symbols` for corporate actions missing from its
+    /// persisted daily adjusted series, replacing the series of the affected symbols, and their
+    /// cached responses, with the full series refetched from the API.
+    ///
+    /// Corporate actions change the adjusted values of the whole history, so entries persisted
+    /// before them can't be merged with entries retrieved after them. Call this periodically,
+    /// e.g. daily after the close, and recompute anything derived from the series reported by
+    /// the returned events. Symbols without a persisted series are skipped.
+    pub async fn refresh_adjusted(&self, symbols: &[&str]) -> Result<Vec<AdjustmentEvent>, Error> {
+        let function_name: &str = (&Function::DailyAdjusted).into();
+        let mut stored = vec![];
+        for symbol in symbols {
+            let key = format!("{}/{}", function_name, symbol);
+            if let Some(series) = self.store.load(&key)? {
+                stored.push((*symbol, key, series));
+            }
+        }
+        let watched: Vec<&str> = stored.iter().map(|(symbol, _, _)| *symbol).collect();
+        let dividends = fetch_batched(&watched, |symbol| self.client.get_dividends(symbol)).await?;
+        let splits = fetch_batched(&watched, |symbol| self.client.get_splits(symbol)).await?;
+        let today = Utc::now().with_timezone(&Eastern).date_naive();
+        let mut events = vec![];
+        for (((symbol, key, series), dividends), splits) in
+            stored.into_iter().zip(dividends).zip(splits)
+        {
+            let actions = unapplied(&series, &dividends, &splits, today);
+            if actions.is_empty() {
+                continue;
+            }
+            let fetched = self
+                .client
+                .refetch_time_series(&Function::DailyAdjusted, symbol, OutputSize::Full)
+                .await?;
+            let refreshed = StoredSeries {
+                entries: fetched.entries,
+                complete: true,
+                fetched_at: Some(SystemTime::now()),
+            };
+            self.store.save(&key, &refreshed)?;
+            events.push(AdjustmentEvent {
+                symbol: symbol.to_string(),
+                actions,
+                entries: refreshed.entries,
+            });
+        }
+        Ok(events)
+    }
+
+    /// Freshness of the series of `function` persisted for `symbol`, if any, last refreshed at
+    /// the date of its latest entry.
+    ///
+    /// Series persisted without the time they were retrieved are reported as retrieved at the
+    /// Unix epoch, so they're always stale.
+    pub fn freshness(&self, function: &Function, symbol: &str) -> Result<Option<Freshness>, Error> {
+        let function_name: &str = function.into();
+        let key = format!("{}/{}", function_name, symbol);
+        let series = match self.store.load(&key)? {
+            Some(series) => series,
+            None => return Ok(None),
+        };
+        Ok(Some(Freshness {
+            function: crate::Function::from(function_name),
+            symbol: Some(symbol.to_string()),
+            fetched_at: series.fetched_at.unwrap_or(SystemTime::UNIX_EPOCH),
+            last_refreshed: series.entries.last().map(|e| e.date),
+            key,
+        }))
+    }
+
+    /// Whether the series of `function` persisted for `symbol` should be retrieved again under
+    /// `policy`, e.g. before a scheduled job spends its quota on it. Missing series are stale.
+    pub fn is_stale(
+        &self,
+        function: &Function,
+        symbol: &str,
+        policy: &StalenessPolicy,
+    ) -> Result<bool, Error> {
+        Ok(self
+            .freshness(function, symbol)?
+            .is_none_or(|freshness| freshness.is_stale(policy)))
+    }
+
+    async fn get(
+        &self,
+        function: &Function,
+        symbol: &str,
+        range: DateRange,
+    ) -> Result<Vec<Entry>, Error> {
+        let function_name: &str = function.into();
+        let key = format!("{}/{}", function_name, symbol);
+        let mut series = self.store.load(&key)?;
+        let today = Utc::now().with_timezone(&Eastern).date_naive();
+        if let Some(output_size) = missing(series.as_ref(), &range, today) {
+            let fetched = self
+                .client
+                .get_time_series(function, symbol, output_size)
+                .await?;
+            let merged = StoredSeries {
+                fetched_at: Some(SystemTime::now()),
+                ..merge(series, fetched.entries, output_size == OutputSize::Full)
+            };
+            self.store.save(&key, &merged)?;
+            series = Some(merged);
+        }
+        let entries = series.map(|s| s.entries).unwrap_or_default();
+        Ok(entries
+            .into_iter()
+            .filter(|e| range.contains(e.date.date_naive()))
+            .collect())
+    }
+}
+
+/// Output size of the request needed to complete the `stored` series over `range`, if any, as
+/// of `today`.
+///
+/// Today's entry is never expected, as the market may not have closed yet.
+fn missing(
+    stored: Option<&StoredSeries>,
+    range: &DateRange,
+    today: NaiveDate,
+) -> Option<OutputSize> {
+    let stored = match stored {
+        Some(stored) => stored,
+        None => return Some(OutputSize::Full),
+    };
+    let (first, last) = match (stored.entries.first(), stored.entries.last()) {
+        (Some(first), Some(last)) => (first.date.date_naive(), last.date.date_naive()),
+        _ => return Some(OutputSize::Full),
+    };
+    if range.start.is_none_or(|start| first > start) && !stored.complete {
+        return Some(OutputSize::Full);
+    }
+    let until = range
+        .end
+        .map_or(today, |end| (end + Duration::days(1)).min(today));
+    let expected = calendar::previous_trading_day(until);
+    if last >= expected {
+        return None;
+    }
+    let gap = calendar::trading_days(last + Duration::days(1), expected).len();
+    if gap < COMPACT_SIZE {
+        Some(OutputSize::Compact)
+    } else {
+        Some(OutputSize::Full)
+    }
+}
+
+/// Corporate actions among `dividends` and `splits` missing from the `stored` series, as of
+/// `today`, sorted by ascending dates.
+///
+/// A dividend is missing when it went ex-dividend after the first stored entry but no stored
+/// entry on its ex-dividend date records it, and likewise for a split and its effective date.
+/// Today's corporate actions aren't expected, as the market may not have closed yet.
+fn unapplied(
+    stored: &StoredSeries,
+    dividends: &Dividends,
+    splits: &Splits,
+    today: NaiveDate,
+) -> Vec<CorporateAction> {
+    let first = match stored.entries.first() {
+        Some(first) => first.date.date_naive(),
+        None => return vec![],
+    };
+    let paid: BTreeSet<NaiveDate> = stored
+        .entries
+        .iter()
+        .filter(|e| e.dividend_amount.is_some_and(|amount| amount > 0.0))
+        .map(|e| e.date.date_naive())
+        .collect();
+    let split: BTreeSet<NaiveDate> = stored
+        .entries
+        .iter()
+        .filter(|e| {
+            e.split_coefficient
+                .is_some_and(|coefficient| coefficient != 1.0)
+        })
+        .map(|e| e.date.date_naive())
+        .collect();
+    let mut actions: Vec<CorporateAction> = dividends
+        .dividends
+        .iter()
+        .filter(|d| !paid.contains(&d.ex_dividend_date))
+        .map(|d| CorporateAction::Dividend(d.clone()))
+        .chain(
+            splits
+                .splits
+                .iter()
+                .filter(|s| !split.contains(&s.effective_date))
+                .map(|s| CorporateAction::Split(s.clone())),
+        )
+        .filter(|a| a.date() > first && a.date() < today)
+        .collect();
+    actions.sort_by_key(CorporateAction::date);
+    actions
+}
+
+/// Merge the `fetched` entries into the `stored` series, replacing stored entries for the same
+/// dates.
+fn merge(stored: Option<StoredSeries>, fetched: Vec<Entry>, full: bool) -> StoredSeries {
+    let stored = stored.unwrap_or_default();
+    let mut entries: BTreeMap<NaiveDate, Entry> = stored
+        .entries
+        .into_iter()
+        .map(|e| (e.date.date_naive(), e))
+        .collect();
+    for entry in fetched {
+        entries.insert(entry.date.date_naive(), entry);
+    }
+    StoredSeries {
+        entries: entries.into_values().collect(),
+        complete: stored.complete || full,
+        fetched_at: stored.fetched_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alphavantage_types::internal::parse_date;
+
+    fn entry(date: &str, close: f64) -> Entry {
+        Entry::builder(parse_date(date, Eastern).unwrap())
+            .price(close)
+            .build()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn unapplied_dividends() {
+        let dividend = |date: &str| Dividend {
+            ex_dividend_date: self::date(date),
+            declaration_date: None,
+            record_date: None,
+            payment_date: None,
+            amount: 0.5,
+        };
+        let dividends = Dividends {
+            symbol: "MSFT".to_string(),
+            dividends: vec![
+                dividend("2024-02-14"),
+                dividend("2024-05-01"),
+                dividend("2024-05-06"),
+                dividend("2024-05-08"),
+            ],
+            warnings: vec![],
+        };
+        let paid = Entry {
+            dividend_amount: Some(0.5),
+            ..entry("2024-05-01", 2.0)
+        };
+        let stored = StoredSeries {
+            entries: vec![entry("2024-04-30", 1.0), paid, entry("2024-05-03", 2.0)],
+            complete: false,
+            fetched_at: None,
+        };
+        let splits = Splits {
+            symbol: "MSFT".to_string(),
+            splits: vec![],
+            warnings: vec![],
+        };
+        let actions = unapplied(&stored, &dividends, &splits, date("2024-05-08"));
+        assert_eq!(
+            actions,
+            vec![CorporateAction::Dividend(dividend("2024-05-06"))]
+        );
+        assert!(unapplied(
+            &StoredSeries::default(),
+            &dividends,
+            &splits,
+            date("2024-05-08")
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn unapplied_splits() {
+        let split = |date: &str| Split {
+            effective_date: self::date(date),
+            split_factor: 2.0,
+        };
+        let dividends = Dividends {
+            symbol: "NVDA".to_string(),
+            dividends: vec![],
+            warnings: vec![],
+        };
+        let split_entry = Entry {
+            split_coefficient: Some(2.0),
+            ..entry("2024-05-01", 2.0)
+        };
+        let stored = StoredSeries {
+            entries: vec![
+                entry("2024-04-30", 1.0),
+                split_entry,
+                entry("2024-05-03", 2.0),
+            ],
+            complete: false,
+            fetched_at: None,
+        };
+        let mut splits = Splits {
+            symbol: "NVDA".to_string(),
+            splits: vec![split("2024-05-01")],
+            warnings: vec![],
+        };
+        assert!(unapplied(&stored, &dividends, &splits, date("2024-05-08")).is_empty());
+
+        // A split announced after the series was stored.
+        splits.splits.push(split("2024-05-06"));
+        assert_eq!(
+            unapplied(&stored, &dividends, &splits, date("2024-05-08")),
+            vec![CorporateAction::Split(split("2024-05-06"))]
+        );
+        assert!(unapplied(&stored, &dividends, &splits, date("2024-05-06")).is_empty());
+    }
+
+    #[test]
+    fn missing_entries() {
+        let today = date("2024-05-08");
+        let start = date("2024-05-01");
+        assert_eq!(
+            missing(None, &DateRange::since(start), today),
+            Some(OutputSize::Full)
+        );
+
+        let stored = StoredSeries {
+            entries: vec![entry("2024-05-01", 1.0), entry("2024-05-03", 2.0)],
+            complete: false,
+            fetched_at: None,
+        };
+        assert_eq!(
+            missing(Some(&stored), &(start..=date("2024-05-03")).into(), today),
+            None
+        );
+        assert_eq!(
+            missing(Some(&stored), &(start..=date("2024-05-05")).into(), today),
+            None
+        );
+        assert_eq!(
+            missing(Some(&stored), &(start..).into(), today),
+            Some(OutputSize::Compact)
+        );
+        assert_eq!(
+            missing(Some(&stored), &(start..).into(), date("2025-01-01")),
+            Some(OutputSize::Full)
+        );
+        // Ranges without a start need the earliest entries available.
+        assert_eq!(
+            missing(Some(&stored), &(..=date("2024-05-03")).into(), today),
+            Some(OutputSize::Full)
+        );
+        assert_eq!(
+            missing(
+                Some(&stored),
+                &(date("2024-04-01")..=date("2024-05-03")).into(),
+                today
+            ),
+            Some(OutputSize::Full)
+        );
+
+        let stored = StoredSeries {
+            complete: true,
+            ..stored
+        };
+        assert_eq!(
+            missing(
+                Some(&stored),
+                &(date("2024-04-01")..=date("2024-05-03")).into(),
+                today
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn merge_entries() {
+        let stored = StoredSeries {
+            entries: vec![entry("2024-05-01", 1.0), entry("2024-05-02", 2.0)],
+            complete: false,
+            fetched_at: None,
+        };
+        let fetched = vec![entry("2024-05-02", 3.0), entry("2024-05-03", 4.0)];
+        assert_eq!(
+            merge(Some(stored), fetched, false),
+            StoredSeries {
+                entries: vec![
+                    entry("2024-05-01", 1.0),
+                    entry("2024-05-02", 3.0),
+                    entry("2024-05-03", 4.0)
+                ],
+                complete: false,
+                fetched_at: None,
+            }
+        );
+        assert!(merge(None, vec![entry("2024-05-01", 1.0)], true).complete);
+    }
+
+    #[test]
+    fn series_freshness() {
+        let store = DataStore::new(Client::builder("key").build(), MemoryStore::new());
+        assert_eq!(store.freshness(&Function::Daily, "MSFT").unwrap(), None);
+        let policy = StalenessPolicy::new(std::time::Duration::from_secs(60 * 60));
+        assert!(store.is_stale(&Function::Daily, "MSFT", &policy).unwrap());
+
+        let series = StoredSeries {
+            entries: vec![entry("2024-05-02", 1.0), entry("2024-05-03", 2.0)],
+            complete: false,
+            fetched_at: Some(SystemTime::now()),
+        };
+        store.store.save("TIME_SERIES_DAILY/MSFT", &series).unwrap();
+        let freshness = store.freshness(&Function::Daily, "MSFT").unwrap().unwrap();
+        assert_eq!(freshness.function, crate::Function::TimeSeriesDaily);
+        assert_eq!(freshness.symbol.as_deref(), Some("MSFT"));
+        assert_eq!(freshness.fetched_at, series.fetched_at.unwrap());
+        assert_eq!(freshness.last_refreshed, Some(series.entries[1].date));
+        assert!(!store.is_stale(&Function::Daily, "MSFT", &policy).unwrap());
+        assert!(store
+            .is_stale(
+                &Function::Daily,
+                "MSFT",
+                &policy.max_data_age(std::time::Duration::from_secs(24 * 60 * 60))
+            )
+            .unwrap());
+
+        let series = StoredSeries {
+            fetched_at: None,
+            ..series
+        };
+        store.store.save("TIME_SERIES_DAILY/MSFT", &series).unwrap();
+        assert!(store.is_stale(&Function::Daily, "MSFT", &policy).unwrap());
+    }
+
+    #[test]
+    fn directory_store() {
+        let dir = std::env::temp_dir().join(format!("alphavantage-store-{}", std::process::id()));
+        let store = DirectoryStore::new(&dir);
+        let mut adjusted = entry("2024-05-03", 2.5);
+        adjusted.adjusted_close = Some(2.25);
+        let series = StoredSeries {
+            entries: vec![entry("2024-05-02", 1.0), adjusted],
+            complete: true,
+            fetched_at: Some(
+                SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_714_762_800),
+            ),
+        };
+        assert_eq!(store.load("TIME_SERIES_DAILY/BRK.B").unwrap(), None);
+        store.save("TIME_SERIES_DAILY/BRK.B", &series).unwrap();
+        store.save("TIME_SERIES_DAILY/MSFT", &series).unwrap();
+        assert_eq!(
+            store.load("TIME_SERIES_DAILY/BRK.B").unwrap(),
+            Some(series.clone())
+        );
+        assert_eq!(store.verify().unwrap(), vec![]);
+
+        let data = dir.join("TIME_SERIES_DAILY/MSFT.csv");
+        let truncated = fs::read_to_string(&data).unwrap();
+        fs::write(&data, &truncated[..truncated.len() - 10]).unwrap();
+        fs::remove_file(dir.join("TIME_SERIES_DAILY/BRK.B.manifest.json")).unwrap();
+        let issues = store.verify().unwrap();
+        assert!(store.load("TIME_SERIES_DAILY/MSFT").is_err());
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].key, "TIME_SERIES_DAILY/BRK.B");
+        assert!(issues[0].message.starts_with("missing manifest"));
+        assert_eq!(issues[1].key, "TIME_SERIES_DAILY/MSFT");
+        assert!(issues[1].message.starts_with("checksum mismatch"));
+    }
+}